@@ -0,0 +1,315 @@
+//! Generates the tool registry from `src/tools/registry.json`.
+//!
+//! The JSON file is the source of truth for categories and the groups
+//! they nest under, tools, their schemas and tags, and search synonyms.
+//! This script checks it for consistency (unknown categories, groups, or
+//! tags, duplicate names, malformed schemas, categories with neither tools
+//! nor planned tools) and fails the build with a message naming the
+//! problem, then writes `registry_data.rs` for `tools::registry` to
+//! include: the `Category` enum, and each category's tool count computed
+//! from the tools in it.
+
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+const REGISTRY: &str = "src/tools/registry.json";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", REGISTRY);
+    let text = std::fs::read_to_string(REGISTRY).unwrap_or_else(|e| fail(format!("can't read {}: {}", REGISTRY, e)));
+    let registry: Value = serde_json::from_str(&text).unwrap_or_else(|e| fail(format!("{} is not valid JSON: {}", REGISTRY, e)));
+
+    let generated = generate(&registry).unwrap_or_else(|e| fail(e));
+    let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("registry_data.rs");
+    std::fs::write(out, generated).unwrap();
+}
+
+fn fail(message: String) -> ! {
+    panic!("{}: {}", REGISTRY, message)
+}
+
+fn generate(registry: &Value) -> Result<String, String> {
+    let categories = array(registry, "categories", "registry")?;
+    let tools = array(registry, "tools", "registry")?;
+    let output_schemas = registry
+        .get("output_schemas")
+        .and_then(Value::as_object)
+        .ok_or("registry is missing \"output_schemas\"")?;
+    for (name, schema) in output_schemas {
+        check_object_schema(schema, &format!("output schema \"{}\"", name))?;
+    }
+
+    let tags = registry
+        .get("tags")
+        .and_then(Value::as_object)
+        .ok_or("registry is missing \"tags\"")?;
+
+    let mut category_ids = Vec::new();
+    for category in categories {
+        let id = string(category, "id", "category")?;
+        if category_ids.contains(&id) {
+            return Err(format!("category \"{}\" is defined twice", id));
+        }
+        // Each id becomes a `Category` variant, so it must be snake_case words
+        let is_word = |part: &str| {
+            part.starts_with(|c: char| c.is_ascii_lowercase())
+                && part.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        };
+        if !id.split('_').all(is_word) {
+            return Err(format!("category \"{}\" must be a snake_case id", id));
+        }
+        category_ids.push(id);
+    }
+
+    // Groups of categories, each with at least one child
+    let groups = array(registry, "category_groups", "registry")?;
+    let mut group_ids = Vec::new();
+    for group in groups {
+        let id = string(group, "id", "category group")?;
+        if group_ids.contains(&id) || category_ids.contains(&id) {
+            return Err(format!("category group \"{}\" is defined twice, or as a category", id));
+        }
+        if !categories.iter().any(|c| c.get("parent").and_then(Value::as_str) == Some(id)) {
+            return Err(format!("category group \"{}\" has no categories", id));
+        }
+        group_ids.push(id);
+    }
+
+    let mut out = String::from("// Generated by build.rs from src/tools/registry.json. Do not edit.\n\n");
+
+    // The Category enum, with a variant per category in file order
+    let _ = writeln!(out, "/// Tool category identifier");
+    let _ = writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]");
+    let _ = writeln!(out, "pub enum Category {{");
+    for id in &category_ids {
+        let _ = writeln!(out, "    #[serde(rename = {:?})]", id);
+        let _ = writeln!(out, "    {},", variant(id));
+    }
+    let _ = writeln!(out, "}}\n");
+    let _ = writeln!(out, "impl Category {{");
+    let _ = writeln!(out, "    pub fn as_str(&self) -> &'static str {{");
+    let _ = writeln!(out, "        match self {{");
+    for id in &category_ids {
+        let _ = writeln!(out, "            Self::{} => {:?},", variant(id), id);
+    }
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "    }}\n");
+    let _ = writeln!(out, "    pub fn all() -> &'static [Category] {{");
+    let _ = writeln!(out, "        &[");
+    for id in &category_ids {
+        let _ = writeln!(out, "            Self::{},", variant(id));
+    }
+    let _ = writeln!(out, "        ]");
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "}}\n");
+    let _ = writeln!(out, "impl std::str::FromStr for Category {{");
+    let _ = writeln!(out, "    type Err = String;\n");
+    let _ = writeln!(out, "    fn from_str(s: &str) -> Result<Self, Self::Err> {{");
+    let _ = writeln!(out, "        match s {{");
+    for id in &category_ids {
+        let _ = writeln!(out, "            {:?} => Ok(Self::{}),", id, variant(id));
+    }
+    let _ = writeln!(out, "            _ => Err(format!(\"Unknown category: {{}}\", s)),");
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "}}\n");
+
+    // Tools
+    let mut names = BTreeSet::new();
+    let mut counts = vec![0usize; category_ids.len()];
+    let mut entries = String::new();
+    for tool in tools {
+        let name = string(tool, "name", "tool")?;
+        let context = format!("tool \"{}\"", name);
+        if !names.insert(name) {
+            return Err(format!("{} is defined twice", context));
+        }
+        let category = string(tool, "category", &context)?;
+        let position = category_ids
+            .iter()
+            .position(|id| *id == category)
+            .ok_or_else(|| format!("{} has unknown category \"{}\"", context, category))?;
+        counts[position] += 1;
+
+        let tool_tags = strings(tool, "tags", &context)?;
+        if let Some(unknown) = tool_tags.iter().find(|tag| !tags.contains_key(**tag)) {
+            return Err(format!("{} has unknown tag \"{}\"", context, unknown));
+        }
+
+        let input_schema = tool.get("input_schema").ok_or_else(|| format!("{} has no input_schema", context))?;
+        check_input_schema(input_schema, &context)?;
+        let output_schema = match tool.get("output_schema") {
+            None | Some(Value::Null) => None,
+            Some(Value::String(named)) => Some(
+                output_schemas
+                    .get(named)
+                    .ok_or_else(|| format!("{} uses unknown output schema \"{}\"", context, named))?,
+            ),
+            Some(schema) => {
+                check_object_schema(schema, &format!("{} output schema", context))?;
+                Some(schema)
+            }
+        };
+
+        let _ = writeln!(entries, "    {:?} => Tool {{", name);
+        let _ = writeln!(entries, "        name: {:?},", name);
+        let _ = writeln!(entries, "        category: Category::{},", variant(category));
+        let _ = writeln!(entries, "        description: {:?},", string(tool, "description", &context)?);
+        let _ = writeln!(entries, "        keywords: &{:?},", strings(tool, "keywords", &context)?);
+        let _ = writeln!(entries, "        examples: &{:?},", strings(tool, "examples", &context)?);
+        let _ = writeln!(entries, "        tags: &{:?},", tool_tags);
+        let _ = writeln!(entries, "        input_schema_json: {:?},", input_schema.to_string());
+        let _ = writeln!(entries, "        output_schema_json: {:?},", output_schema.map(Value::to_string));
+        let _ = writeln!(entries, "    }},");
+    }
+
+    // Categories, with their computed tool counts
+    let _ = writeln!(out, "/// Categories in [`Category::all`] order");
+    let _ = writeln!(out, "static CATEGORIES: [ToolCategory; {}] = [", categories.len());
+    for (category, count) in categories.iter().zip(&counts) {
+        let id = string(category, "id", "category")?;
+        let context = format!("category \"{}\"", id);
+        // Tools announced for the category that don't exist yet
+        let mut planned = Vec::new();
+        for tool in category.get("planned").and_then(Value::as_array).into_iter().flatten() {
+            let name = string(tool, "name", &format!("{} planned tool", context))?;
+            if names.contains(name) {
+                return Err(format!("{} plans \"{}\", which already exists", context, name));
+            }
+            planned.push((name, string(tool, "description", &format!("{} planned tool \"{}\"", context, name))?));
+        }
+        if *count == 0 && planned.is_empty() {
+            return Err(format!("{} has no tools", context));
+        }
+        let _ = writeln!(out, "    ToolCategory {{");
+        let _ = writeln!(out, "        id: Category::{},", variant(id));
+        let _ = writeln!(out, "        name: {:?},", string(category, "name", &context)?);
+        let _ = writeln!(out, "        description: {:?},", string(category, "description", &context)?);
+        let _ = writeln!(out, "        tool_count: {},", count);
+        let _ = writeln!(out, "        planned_tools: &{:?},", planned);
+        let parent = category.get("parent").and_then(Value::as_str);
+        if let Some(parent) = parent.filter(|parent| !group_ids.contains(parent)) {
+            return Err(format!("{} has unknown parent \"{}\"", context, parent));
+        }
+        let _ = writeln!(out, "        parent: {:?},", parent);
+        let _ = writeln!(out, "        example_queries: &{:?},", strings(category, "example_queries", &context)?);
+        let _ = writeln!(out, "    }},");
+    }
+    let _ = writeln!(out, "];\n");
+
+    let _ = writeln!(out, "static CATEGORY_GROUPS: [CategoryGroup; {}] = [", groups.len());
+    for group in groups {
+        let id = string(group, "id", "category group")?;
+        let context = format!("category group \"{}\"", id);
+        let _ = writeln!(out, "    CategoryGroup {{");
+        let _ = writeln!(out, "        id: {:?},", id);
+        let _ = writeln!(out, "        name: {:?},", string(group, "name", &context)?);
+        let _ = writeln!(out, "        description: {:?},", string(group, "description", &context)?);
+        let _ = writeln!(out, "    }},");
+    }
+    let _ = writeln!(out, "];\n");
+
+    let _ = writeln!(out, "static TOOLS: phf::Map<&'static str, Tool> = phf::phf_map! {{");
+    out.push_str(&entries);
+    let _ = writeln!(out, "}};\n");
+
+    // Tags
+    let _ = writeln!(out, "/// Tags tools can carry, with what each means");
+    let _ = writeln!(out, "pub static TAGS: &[(&str, &str)] = &[");
+    for (tag, description) in tags {
+        let description = description
+            .as_str()
+            .ok_or_else(|| format!("tag \"{}\" must be described by a string", tag))?;
+        let _ = writeln!(out, "    ({:?}, {:?}),", tag, description);
+    }
+    let _ = writeln!(out, "];\n");
+
+    // Synonyms
+    let synonyms = registry
+        .get("synonyms")
+        .and_then(Value::as_object)
+        .ok_or("registry is missing \"synonyms\"")?;
+    let _ = writeln!(out, "/// Phrases people use for a concept, and the terms the registry uses for");
+    let _ = writeln!(out, "/// it. A query containing a phrase (as whole words) also matches tools by");
+    let _ = writeln!(out, "/// its terms.");
+    let _ = writeln!(out, "pub static SYNONYMS: &[(&str, &[&str])] = &[");
+    for (phrase, terms) in synonyms {
+        let context = format!("synonym \"{}\"", phrase);
+        if *phrase != phrase.to_lowercase() {
+            return Err(format!("{} must be lowercase", context));
+        }
+        let terms = string_array(terms, &context)?;
+        if terms.is_empty() {
+            return Err(format!("{} has no terms", context));
+        }
+        let _ = writeln!(out, "    ({:?}, &{:?}),", phrase, terms);
+    }
+    let _ = writeln!(out, "];");
+    Ok(out)
+}
+
+/// `Category` variant for a snake_case id
+fn variant(id: &str) -> String {
+    id.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect()
+}
+
+fn check_object_schema(schema: &Value, context: &str) -> Result<(), String> {
+    if schema.get("type").and_then(Value::as_str) != Some("object") {
+        return Err(format!("{} must have \"type\": \"object\"", context));
+    }
+    Ok(())
+}
+
+fn check_input_schema(schema: &Value, context: &str) -> Result<(), String> {
+    check_object_schema(schema, &format!("{} input schema", context))?;
+    let properties: &Map<String, Value> = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .ok_or_else(|| format!("{} input schema has no properties", context))?;
+    if let Some(required) = schema.get("required") {
+        for name in string_array(required, &format!("{} required", context))? {
+            if !properties.contains_key(name) {
+                return Err(format!("{} requires \"{}\", which isn't one of its properties", context, name));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn array<'a>(value: &'a Value, key: &str, context: &str) -> Result<&'a Vec<Value>, String> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("{} is missing \"{}\"", context, key))
+}
+
+fn string<'a>(value: &'a Value, key: &str, context: &str) -> Result<&'a str, String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| format!("{} is missing \"{}\"", context, key))
+}
+
+fn strings<'a>(value: &'a Value, key: &str, context: &str) -> Result<Vec<&'a str>, String> {
+    let list = value
+        .get(key)
+        .ok_or_else(|| format!("{} is missing \"{}\"", context, key))?;
+    string_array(list, &format!("{} {}", context, key))
+}
+
+fn string_array<'a>(value: &'a Value, context: &str) -> Result<Vec<&'a str>, String> {
+    value
+        .as_array()
+        .ok_or_else(|| format!("{} must be a list", context))?
+        .iter()
+        .map(|v| v.as_str().ok_or_else(|| format!("{} must only contain strings", context)))
+        .collect()
+}
@@ -3,12 +3,19 @@
 //! Async HTTP client for the FilingExplorer API with authentication
 //! and error handling.
 
-use reqwest::{Client, Response, StatusCode};
+use crate::call_stats::{self, Source};
+use crate::cancellation;
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, Request, RequestBuilder, Response, StatusCode};
 use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use thiserror::Error;
+use tracing::{Instrument, Span};
 
 /// Base URL for the FilingExplorer API
 const API_BASE_URL: &str = "https://api.filingexplorer.com/v1";
@@ -19,6 +26,13 @@ const DEFAULT_TIMEOUT_SECS: u64 = 30;
 /// User-Agent header value
 const USER_AGENT: &str = "Giant Octopus, LLC hello@giantoctopus.ink";
 
+/// Remaining share of the request budget below which it counts as nearly
+/// exhausted
+const RATE_LIMIT_WARNING_FRACTION: f64 = 0.1;
+
+/// Reset values below this are seconds from now; above it, a Unix timestamp
+const RATE_LIMIT_RESET_EPOCH_THRESHOLD: i64 = 1_000_000_000;
+
 #[derive(Error, Debug)]
 pub enum ApiError {
     #[error("HTTP request failed: {0}")]
@@ -37,7 +51,94 @@ pub enum ApiError {
     NotFound,
 
     #[error("Rate limited - please slow down requests")]
-    RateLimited,
+    RateLimited {
+        /// Seconds to wait before retrying, from the `Retry-After` header
+        retry_after: Option<u64>,
+    },
+
+    #[error("Request cancelled")]
+    Cancelled,
+}
+
+impl ApiError {
+    /// Whether the failure is transient and the request may succeed if retried
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } => true,
+            Self::ApiError { status, .. } => *status >= 500,
+            Self::RequestError(e) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+}
+
+/// Request budget reported by the API's rate-limit headers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RateLimitStatus {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// When the budget resets
+    pub reset_at: Option<DateTime<Utc>>,
+    /// When the headers were received
+    pub observed_at: DateTime<Utc>,
+}
+
+impl RateLimitStatus {
+    /// Read `X-RateLimit-*` (or `RateLimit-*`) headers; None if the
+    /// response carried neither a limit nor a remaining count
+    fn from_headers(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Self> {
+        let number = |name: &str| -> Option<i64> {
+            [format!("x-ratelimit-{}", name), format!("ratelimit-{}", name)]
+                .iter()
+                .find_map(|key| headers.get(key.as_str()))
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse().ok())
+        };
+        let limit = number("limit").and_then(|n| u64::try_from(n).ok());
+        let remaining = number("remaining").and_then(|n| u64::try_from(n).ok());
+        if limit.is_none() && remaining.is_none() {
+            return None;
+        }
+        let reset_at = number("reset").and_then(|reset| {
+            if reset >= RATE_LIMIT_RESET_EPOCH_THRESHOLD {
+                DateTime::from_timestamp(reset, 0)
+            } else {
+                Some(now + chrono::Duration::seconds(reset))
+            }
+        });
+        Some(Self {
+            limit,
+            remaining,
+            reset_at,
+            observed_at: now,
+        })
+    }
+
+    /// Whether the remaining budget is used up or nearly so
+    pub fn is_nearly_exhausted(&self) -> bool {
+        match (self.remaining, self.limit) {
+            (Some(0), _) => true,
+            (Some(remaining), Some(limit)) => (remaining as f64) < limit as f64 * RATE_LIMIT_WARNING_FRACTION,
+            _ => false,
+        }
+    }
+
+    /// A warning to show alongside results when the budget is nearly
+    /// exhausted
+    pub fn warning(&self) -> Option<String> {
+        if !self.is_nearly_exhausted() {
+            return None;
+        }
+        let budget = match (self.remaining, self.limit) {
+            (Some(remaining), Some(limit)) => format!("{} of {} API requests remaining", remaining, limit),
+            (Some(remaining), None) => format!("{} API requests remaining", remaining),
+            _ => "API request budget nearly exhausted".to_string(),
+        };
+        Some(match self.reset_at {
+            Some(reset_at) => format!("{}; resets at {}", budget, reset_at.format("%Y-%m-%d %H:%M:%S UTC")),
+            None => budget,
+        })
+    }
 }
 
 /// FilingExplorer API client
@@ -46,6 +147,8 @@ pub struct ApiClient {
     client: Client,
     api_token: String,
     base_url: String,
+    /// Latest rate-limit headers, shared by clones of this client
+    rate_limit: Arc<Mutex<Option<RateLimitStatus>>>,
 }
 
 impl ApiClient {
@@ -62,6 +165,7 @@ impl ApiClient {
             client,
             api_token: api_token.into(),
             base_url: API_BASE_URL.to_string(),
+            rate_limit: Arc::default(),
         })
     }
 
@@ -80,6 +184,7 @@ impl ApiClient {
         params: Option<HashMap<String, String>>,
     ) -> Result<T, ApiError> {
         let url = format!("{}/{}", self.base_url, endpoint.trim_start_matches('/'));
+        call_stats::record(Source::Api);
 
         let mut request = self
             .client
@@ -90,8 +195,7 @@ impl ApiClient {
             request = request.query(&params);
         }
 
-        let response = request.send().await?;
-        self.handle_response(response).await
+        self.send(request).await
     }
 
     /// Make a GET request and return raw JSON Value
@@ -110,6 +214,7 @@ impl ApiClient {
         body: Option<&Value>,
     ) -> Result<T, ApiError> {
         let url = format!("{}/{}", self.base_url, endpoint.trim_start_matches('/'));
+        call_stats::record(Source::Api);
 
         let mut request = self
             .client
@@ -121,8 +226,7 @@ impl ApiClient {
             request = request.json(body);
         }
 
-        let response = request.send().await?;
-        self.handle_response(response).await
+        self.send(request).await
     }
 
     /// Make a PATCH request to the API
@@ -132,6 +236,7 @@ impl ApiClient {
         body: Option<&Value>,
     ) -> Result<T, ApiError> {
         let url = format!("{}/{}", self.base_url, endpoint.trim_start_matches('/'));
+        call_stats::record(Source::Api);
 
         let mut request = self
             .client
@@ -143,28 +248,36 @@ impl ApiClient {
             request = request.json(body);
         }
 
-        let response = request.send().await?;
-        self.handle_response(response).await
+        self.send(request).await
     }
 
     /// Make a DELETE request to the API
     pub async fn delete(&self, endpoint: &str) -> Result<(), ApiError> {
         let url = format!("{}/{}", self.base_url, endpoint.trim_start_matches('/'));
+        call_stats::record(Source::Api);
 
-        let response = self
+        let request = self
             .client
             .delete(&url)
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .send()
-            .await?;
-
-        let status = response.status();
+            .header("Authorization", format!("Bearer {}", self.api_token));
 
-        if status.is_success() || status == StatusCode::NO_CONTENT {
-            Ok(())
-        } else {
-            Err(self.error_from_response(response).await)
-        }
+        let request = request.build()?;
+        let span = request_span(&request);
+        cancellation::guard(async {
+            let response = self.client.execute(request).await?;
+            self.record_rate_limit(response.headers());
+            let status = response.status();
+            Span::current().record("status", status.as_u16());
+
+            if status.is_success() || status == StatusCode::NO_CONTENT {
+                Ok(())
+            } else {
+                Err(self.error_from_response(response).await)
+            }
+        })
+        .instrument(span)
+        .await
+        .unwrap_or(Err(ApiError::Cancelled))
     }
 
     /// Validate the API token by making a test request
@@ -179,6 +292,33 @@ impl ApiClient {
         }
     }
 
+    /// The request budget from the most recent response that reported one
+    pub fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.rate_limit.lock().unwrap().clone()
+    }
+
+    fn record_rate_limit(&self, headers: &HeaderMap) {
+        if let Some(status) = RateLimitStatus::from_headers(headers, Utc::now()) {
+            *self.rate_limit.lock().unwrap() = Some(status);
+        }
+    }
+
+    /// Send a request and parse the response, abandoning it if the caller's
+    /// cancellation scope is cancelled
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ApiError> {
+        let request = request.build()?;
+        let span = request_span(&request);
+        cancellation::guard(async {
+            let response = self.client.execute(request).await?;
+            self.record_rate_limit(response.headers());
+            Span::current().record("status", response.status().as_u16());
+            self.handle_response(response).await
+        })
+        .instrument(span)
+        .await
+        .unwrap_or(Err(ApiError::Cancelled))
+    }
+
     /// Handle API response, converting to typed result or error
     async fn handle_response<T: DeserializeOwned>(&self, response: Response) -> Result<T, ApiError> {
         let status = response.status();
@@ -199,7 +339,13 @@ impl ApiClient {
         match status {
             StatusCode::UNAUTHORIZED => ApiError::Unauthorized,
             StatusCode::NOT_FOUND => ApiError::NotFound,
-            StatusCode::TOO_MANY_REQUESTS => ApiError::RateLimited,
+            StatusCode::TOO_MANY_REQUESTS => ApiError::RateLimited {
+                retry_after: response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse().ok()),
+            },
             _ => {
                 let message = response
                     .text()
@@ -214,6 +360,16 @@ impl ApiClient {
     }
 }
 
+/// Span for one outbound API request, nested under the caller's span
+fn request_span(request: &Request) -> Span {
+    tracing::debug_span!(
+        "api_request",
+        method = %request.method(),
+        path = request.url().path(),
+        status = tracing::field::Empty,
+    )
+}
+
 /// Helper to build query parameters, filtering out None values
 pub fn build_params<I, K, V>(pairs: I) -> HashMap<String, String>
 where
@@ -243,7 +399,7 @@ mod tests {
 
         assert_eq!(params.get("limit"), Some(&"10".to_string()));
         assert_eq!(params.get("page"), Some(&"1".to_string()));
-        assert!(params.get("offset").is_none());
+        assert!(!params.contains_key("offset"));
     }
 
     #[test]
@@ -442,6 +598,55 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_rate_limit_headers_recorded() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/lists"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!([]))
+                    .insert_header("X-RateLimit-Limit", "1000")
+                    .insert_header("X-RateLimit-Remaining", "42")
+                    .insert_header("X-RateLimit-Reset", "1735689600"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = ApiClient::with_base_url("test_token", mock_server.uri()).unwrap();
+        assert!(client.rate_limit_status().is_none());
+        let _: Value = client.get("lists", None).await.unwrap();
+
+        let status = client.clone().rate_limit_status().unwrap();
+        assert_eq!(status.limit, Some(1000));
+        assert_eq!(status.remaining, Some(42));
+        assert_eq!(status.reset_at, DateTime::from_timestamp(1_735_689_600, 0));
+        assert!(status.is_nearly_exhausted());
+        assert_eq!(
+            status.warning().unwrap(),
+            "42 of 1000 API requests remaining; resets at 2025-01-01 00:00:00 UTC"
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_status_from_headers() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut headers = HeaderMap::new();
+        assert!(RateLimitStatus::from_headers(&headers, now).is_none());
+
+        headers.insert("ratelimit-limit", "100".parse().unwrap());
+        headers.insert("ratelimit-remaining", "50".parse().unwrap());
+        headers.insert("ratelimit-reset", "60".parse().unwrap());
+        let status = RateLimitStatus::from_headers(&headers, now).unwrap();
+        assert_eq!(status.reset_at, Some(now + chrono::Duration::seconds(60)));
+        assert!(!status.is_nearly_exhausted());
+        assert!(status.warning().is_none());
+
+        headers.insert("ratelimit-remaining", "0".parse().unwrap());
+        assert!(RateLimitStatus::from_headers(&headers, now).unwrap().is_nearly_exhausted());
+    }
+
     #[tokio::test]
     async fn test_error_unauthorized() {
         let mock_server = MockServer::start().await;
@@ -487,7 +692,28 @@ mod tests {
         let client = ApiClient::with_base_url("test_token", mock_server.uri()).unwrap();
         let result: Result<Value, _> = client.get("limited", None).await;
 
-        assert!(matches!(result, Err(ApiError::RateLimited)));
+        assert!(matches!(
+            result,
+            Err(ApiError::RateLimited { retry_after: None })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_error_rate_limited_retry_after() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/limited"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "30"))
+            .mount(&mock_server)
+            .await;
+
+        let client = ApiClient::with_base_url("test_token", mock_server.uri()).unwrap();
+        let result: Result<Value, _> = client.get("limited", None).await;
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, ApiError::RateLimited { retry_after: Some(30) }));
+        assert!(err.is_retryable());
     }
 
     #[tokio::test]
@@ -614,6 +840,22 @@ mod tests {
         assert!(matches!(result, Err(ApiError::ParseError(_))));
     }
 
+    #[test]
+    fn test_api_error_is_retryable() {
+        let server_error = ApiError::ApiError {
+            status: 503,
+            message: "Unavailable".to_string(),
+        };
+        let client_error = ApiError::ApiError {
+            status: 400,
+            message: "Bad request".to_string(),
+        };
+        assert!(server_error.is_retryable());
+        assert!(!client_error.is_retryable());
+        assert!(!ApiError::Unauthorized.is_retryable());
+        assert!(!ApiError::NotFound.is_retryable());
+    }
+
     #[test]
     fn test_api_error_display() {
         let err = ApiError::Unauthorized;
@@ -625,7 +867,7 @@ mod tests {
         let err = ApiError::NotFound;
         assert_eq!(format!("{}", err), "Resource not found");
 
-        let err = ApiError::RateLimited;
+        let err = ApiError::RateLimited { retry_after: None };
         assert_eq!(format!("{}", err), "Rate limited - please slow down requests");
 
         let err = ApiError::ApiError {
@@ -0,0 +1,246 @@
+//! Synchronous wrappers around the async clients, behind the `blocking`
+//! feature.
+//!
+//! Each wrapper owns a single-threaded tokio runtime (shared by its clones)
+//! and blocks on it, so scripts and other non-async callers can use core
+//! without setting up a runtime. They must not be called from inside an
+//! async runtime, where blocking would stall it; use the async clients there.
+
+use crate::api_client::{ApiClient, ApiError, RateLimitStatus};
+use crate::config::Config;
+use crate::error::CoreError;
+use crate::sec_client::{ContentType, FundClass, SecClient, SecError};
+use crate::tools::sec_documents;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// Runtime shared by a wrapper and its clones
+#[derive(Clone)]
+struct Blocker(Arc<Runtime>);
+
+impl Blocker {
+    /// Panics if the OS can't provide the runtime's I/O and timer drivers,
+    /// as `reqwest::blocking` does
+    fn new() -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to start a tokio runtime");
+        Self(Arc::new(runtime))
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.0.block_on(future)
+    }
+}
+
+/// Blocking FilingExplorer API client
+#[derive(Clone)]
+pub struct BlockingApiClient {
+    inner: ApiClient,
+    runtime: Blocker,
+}
+
+impl BlockingApiClient {
+    pub fn new(api_token: impl Into<String>) -> Result<Self, ApiError> {
+        Ok(Self::from_async(ApiClient::new(api_token)?))
+    }
+
+    /// Create a client with a custom base URL (for testing)
+    pub fn with_base_url(api_token: impl Into<String>, base_url: impl Into<String>) -> Result<Self, ApiError> {
+        Ok(Self::from_async(ApiClient::with_base_url(api_token, base_url)?))
+    }
+
+    /// Create a client from the API token in `config`
+    pub fn from_config(config: &Config) -> Result<Self, CoreError> {
+        Ok(Self::new(config.require_api_token()?)?)
+    }
+
+    /// Wrap an existing async client
+    pub fn from_async(inner: ApiClient) -> Self {
+        Self {
+            inner,
+            runtime: Blocker::new(),
+        }
+    }
+
+    /// The async client this wraps
+    pub fn inner(&self) -> &ApiClient {
+        &self.inner
+    }
+
+    pub fn get<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        params: Option<HashMap<String, String>>,
+    ) -> Result<T, ApiError> {
+        self.runtime.block_on(self.inner.get(endpoint, params))
+    }
+
+    pub fn get_json(&self, endpoint: &str, params: Option<HashMap<String, String>>) -> Result<Value, ApiError> {
+        self.runtime.block_on(self.inner.get_json(endpoint, params))
+    }
+
+    pub fn post<T: DeserializeOwned>(&self, endpoint: &str, body: Option<&Value>) -> Result<T, ApiError> {
+        self.runtime.block_on(self.inner.post(endpoint, body))
+    }
+
+    pub fn patch<T: DeserializeOwned>(&self, endpoint: &str, body: Option<&Value>) -> Result<T, ApiError> {
+        self.runtime.block_on(self.inner.patch(endpoint, body))
+    }
+
+    pub fn delete(&self, endpoint: &str) -> Result<(), ApiError> {
+        self.runtime.block_on(self.inner.delete(endpoint))
+    }
+
+    pub fn validate_token(&self) -> Result<bool, ApiError> {
+        self.runtime.block_on(self.inner.validate_token())
+    }
+
+    pub fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        self.inner.rate_limit_status()
+    }
+}
+
+/// Blocking SEC EDGAR client. Clones share the rate limiter.
+#[derive(Clone)]
+pub struct BlockingSecClient {
+    inner: SecClient,
+    runtime: Blocker,
+}
+
+impl BlockingSecClient {
+    pub fn new(user_agent_name: &str, user_agent_email: &str) -> Result<Self, SecError> {
+        Ok(Self::from_async(SecClient::new(user_agent_name, user_agent_email)?))
+    }
+
+    /// Create a client from the SEC identity in `config`
+    pub fn from_config(config: &Config) -> Result<Self, SecError> {
+        Ok(Self::from_async(SecClient::from_config(config)?))
+    }
+
+    /// Wrap an existing async client
+    pub fn from_async(inner: SecClient) -> Self {
+        Self {
+            inner,
+            runtime: Blocker::new(),
+        }
+    }
+
+    /// The async client this wraps
+    pub fn inner(&self) -> &SecClient {
+        &self.inner
+    }
+
+    pub fn fetch_document(
+        &self,
+        cik: &str,
+        accession_number: &str,
+        filename: Option<&str>,
+    ) -> Result<(Vec<u8>, ContentType), SecError> {
+        self.runtime.block_on(self.inner.fetch_document(cik, accession_number, filename))
+    }
+
+    /// Fetch a document and extract its text, as the `extract_document_text`
+    /// tool does
+    pub fn fetch_document_text(
+        &self,
+        cik: &str,
+        accession_number: &str,
+        filename: Option<&str>,
+    ) -> Result<String, CoreError> {
+        let (bytes, content_type) = self.fetch_document(cik, accession_number, filename)?;
+        Ok(sec_documents::extract_document_text(&bytes, &content_type)?)
+    }
+
+    pub fn fetch_company_tickers(&self) -> Result<HashMap<String, String>, SecError> {
+        self.runtime.block_on(self.inner.fetch_company_tickers())
+    }
+
+    pub fn fetch_fund_tickers(&self) -> Result<Vec<FundClass>, SecError> {
+        self.runtime.block_on(self.inner.fetch_fund_tickers())
+    }
+
+    pub fn full_text_search(
+        &self,
+        query: &str,
+        cik: &str,
+        forms: &[String],
+        date_range: Option<(&str, &str)>,
+    ) -> Result<Value, SecError> {
+        self.runtime.block_on(self.inner.full_text_search(query, cik, forms, date_range))
+    }
+
+    pub fn check_connectivity(&self) -> Result<u16, SecError> {
+        self.runtime.block_on(self.inner.check_connectivity())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A mock server on its own runtime, since the wrappers can't run on the
+    /// test's
+    fn mock_server(mount: impl FnOnce(&MockServer, &Runtime)) -> (Runtime, MockServer) {
+        let runtime = Builder::new_multi_thread().worker_threads(1).enable_all().build().unwrap();
+        let server = runtime.block_on(MockServer::start());
+        mount(&server, &runtime);
+        (runtime, server)
+    }
+
+    #[test]
+    fn test_blocking_api_client() {
+        let (_runtime, server) = mock_server(|server, runtime| {
+            runtime.block_on(
+                Mock::given(method("GET"))
+                    .and(path("/lists"))
+                    .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": []})))
+                    .mount(server),
+            );
+        });
+        let client = BlockingApiClient::with_base_url("token", server.uri()).unwrap();
+        let lists = client.get_json("lists", None).unwrap();
+        assert_eq!(lists["data"], serde_json::json!([]));
+        assert!(client.clone().validate_token().unwrap());
+        assert!(matches!(client.get_json("missing", None), Err(ApiError::NotFound)));
+    }
+
+    #[test]
+    fn test_blocking_document_text() {
+        let (_runtime, server) = mock_server(|server, runtime| {
+            runtime.block_on(
+                Mock::given(method("GET"))
+                    .and(path("/320193/000032019324000123/aapl.htm"))
+                    .respond_with(
+                        ResponseTemplate::new(200)
+                            .set_body_string("<html><body><p>Risk Factors</p></body></html>")
+                            .insert_header("Content-Type", "text/html"),
+                    )
+                    .mount(server),
+            );
+        });
+        let client =
+            BlockingSecClient::from_async(SecClient::with_base_url("Test Co", "test@example.com", server.uri()).unwrap());
+        let text = client
+            .fetch_document_text("0000320193", "0000320193-24-000123", Some("aapl.htm"))
+            .unwrap();
+        assert!(text.contains("Risk Factors"));
+    }
+
+    #[test]
+    fn test_from_config_requires_settings() {
+        let config = Config::default();
+        assert!(BlockingApiClient::from_config(&config).is_err());
+        assert!(matches!(
+            BlockingSecClient::from_config(&config),
+            Err(SecError::NotConfigured(_))
+        ));
+    }
+}
@@ -0,0 +1,129 @@
+//! Per-call request accounting.
+//!
+//! A caller wraps a unit of work in [`scope`]; the API and SEC clients (and
+//! any response cache) then [`record`] each request against it, so the
+//! caller can report where its data came from. Waits before retrying a
+//! rate-limited call are counted with [`record_retry`]. Recording outside a
+//! scope is a no-op.
+
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Where a piece of data was fetched from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Api,
+    Sec,
+    Cache,
+}
+
+impl Source {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Api => "api",
+            Self::Sec => "sec",
+            Self::Cache => "cache",
+        }
+    }
+}
+
+/// Request counts for one call
+#[derive(Debug, Default)]
+pub struct CallStats {
+    api: AtomicU32,
+    sec: AtomicU32,
+    cache: AtomicU32,
+    retries: AtomicU32,
+    retry_wait_ms: AtomicU64,
+}
+
+impl CallStats {
+    fn counter(&self, source: Source) -> &AtomicU32 {
+        match source {
+            Source::Api => &self.api,
+            Source::Sec => &self.sec,
+            Source::Cache => &self.cache,
+        }
+    }
+
+    pub fn count(&self, source: Source) -> u32 {
+        self.counter(source).load(Ordering::Relaxed)
+    }
+
+    /// Number of times the call was retried after being rate limited
+    pub fn retries(&self) -> u32 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    /// Total time spent waiting before retries
+    pub fn retry_wait(&self) -> Duration {
+        Duration::from_millis(self.retry_wait_ms.load(Ordering::Relaxed))
+    }
+
+    /// Sources used at least once
+    pub fn sources(&self) -> Vec<Source> {
+        [Source::Api, Source::Sec, Source::Cache]
+            .into_iter()
+            .filter(|s| self.count(*s) > 0)
+            .collect()
+    }
+}
+
+tokio::task_local! {
+    static CURRENT: Arc<CallStats>;
+}
+
+/// Run `f` with `stats` collecting the requests it makes
+pub async fn scope<F: Future>(stats: Arc<CallStats>, f: F) -> F::Output {
+    CURRENT.scope(stats, f).await
+}
+
+/// The stats of the enclosing scope, for carrying into spawned tasks
+pub fn current() -> Option<Arc<CallStats>> {
+    CURRENT.try_with(Arc::clone).ok()
+}
+
+/// Count one request against the enclosing scope, if any
+pub fn record(source: Source) {
+    let _ = CURRENT.try_with(|stats| stats.counter(source).fetch_add(1, Ordering::Relaxed));
+}
+
+/// Count a retry after waiting `wait` against the enclosing scope, if any
+pub fn record_retry(wait: Duration) {
+    let _ = CURRENT.try_with(|stats| {
+        stats.retries.fetch_add(1, Ordering::Relaxed);
+        stats.retry_wait_ms.fetch_add(wait.as_millis() as u64, Ordering::Relaxed);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scope_collects_records() {
+        let stats = Arc::new(CallStats::default());
+        scope(stats.clone(), async {
+            record(Source::Api);
+            record(Source::Api);
+            record(Source::Cache);
+            record_retry(Duration::from_millis(1500));
+            assert!(current().is_some());
+        })
+        .await;
+
+        assert_eq!(stats.retries(), 1);
+        assert_eq!(stats.retry_wait(), Duration::from_millis(1500));
+        assert_eq!(stats.count(Source::Api), 2);
+        assert_eq!(stats.count(Source::Sec), 0);
+        assert_eq!(stats.sources(), vec![Source::Api, Source::Cache]);
+    }
+
+    #[test]
+    fn test_record_outside_scope_is_ignored() {
+        record(Source::Sec);
+        assert!(current().is_none());
+    }
+}
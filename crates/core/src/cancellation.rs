@@ -0,0 +1,67 @@
+//! Cancellation of outbound requests.
+//!
+//! The caller wraps a unit of work in [`scope`] with a token; the API and
+//! SEC clients run each request through [`guard`], which abandons the
+//! request (dropping its connection) as soon as the token is cancelled and
+//! before it waits on any rate limiter.
+
+use std::future::Future;
+use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+    static CURRENT: CancellationToken;
+}
+
+/// The work was cancelled before it finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+/// Run `f` with `token` governing the requests it makes
+pub async fn scope<F: Future>(token: CancellationToken, f: F) -> F::Output {
+    CURRENT.scope(token, f).await
+}
+
+/// The token of the enclosing scope, for carrying into spawned tasks
+pub fn current() -> Option<CancellationToken> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// Run `f` unless the enclosing scope is cancelled first. Outside a scope
+/// `f` always runs to completion.
+pub async fn guard<F: Future>(f: F) -> Result<F::Output, Cancelled> {
+    match current() {
+        Some(token) => tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(Cancelled),
+            output = f => Ok(output),
+        },
+        None => Ok(f.await),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_guard_stops_on_cancel() {
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            cancel.cancel();
+        });
+
+        let result = scope(token, guard(tokio::time::sleep(Duration::from_secs(30)))).await;
+        assert_eq!(result, Err(Cancelled));
+    }
+
+    #[tokio::test]
+    async fn test_guard_without_scope_runs() {
+        assert_eq!(guard(async { 7 }).await, Ok(7));
+
+        let token = CancellationToken::new();
+        assert_eq!(scope(token, guard(async { 8 })).await, Ok(8));
+    }
+}
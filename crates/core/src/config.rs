@@ -5,15 +5,34 @@
 //! - Windows: %APPDATA%\FilingExplorer MCP\config.json
 //! - Linux: ~/.config/filing-explorer-mcp/config.json
 
+use crate::tools::markdown::OutputFormat;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use thiserror::Error;
 
 /// Configuration file version for future migrations
 const CONFIG_VERSION: u32 = 1;
 
+/// Name of the config file within the config directory
+pub const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Default size limit for inline tool results
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 100_000;
+
+/// Default time budget for one tool call
+pub const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 300;
+
+/// Environment variables that take precedence over the config file, so an
+/// MCP client config can inject credentials through its `env` block
+pub const ENV_API_TOKEN: &str = "FILINGEXPLORER_API_TOKEN";
+pub const ENV_SEC_USER_AGENT_NAME: &str = "FILINGEXPLORER_SEC_USER_AGENT_NAME";
+pub const ENV_SEC_USER_AGENT_EMAIL: &str = "FILINGEXPLORER_SEC_USER_AGENT_EMAIL";
+pub const ENV_TOOL_TIMEOUT_SECS: &str = "FILINGEXPLORER_TOOL_TIMEOUT_SECS";
+
 /// Application identifiers for directory lookup
 const QUALIFIER: &str = "com";
 const ORGANIZATION: &str = "filingexplorer";
@@ -35,6 +54,12 @@ pub enum ConfigError {
 
     #[error("API token not configured")]
     MissingToken,
+
+    #[error("No account labeled '{0}'")]
+    AccountNotFound(String),
+
+    #[error("Invalid account: {0}")]
+    InvalidAccount(String),
 }
 
 /// Main configuration structure
@@ -44,10 +69,19 @@ pub struct Config {
     #[serde(default = "default_version")]
     pub version: u32,
 
-    /// FilingExplorer API token
+    /// FilingExplorer API token the MCP server uses; kept in sync with the
+    /// active account when accounts are set up
     #[serde(default)]
     pub api_token: Option<String>,
 
+    /// Labeled API tokens (e.g. personal, firm, sandbox)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<Account>,
+
+    /// Label of the account whose token is in `api_token`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_account: Option<String>,
+
     /// User/organization name for SEC EDGAR User-Agent header
     #[serde(default)]
     pub sec_user_agent_name: Option<String>,
@@ -55,6 +89,61 @@ pub struct Config {
     /// Email for SEC EDGAR User-Agent header
     #[serde(default)]
     pub sec_user_agent_email: Option<String>,
+
+    /// Tool results larger than this many bytes are written to a file and
+    /// returned as a preview (defaults to [`DEFAULT_MAX_OUTPUT_BYTES`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<usize>,
+
+    /// Seconds a tool call may run before it fails with a timeout error
+    /// (defaults to [`DEFAULT_TOOL_TIMEOUT_SECS`])
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_timeout_secs: Option<u64>,
+
+    /// Per tool category limits, keyed by category ID (e.g. "sec_documents")
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub category_limits: HashMap<String, CategoryLimit>,
+
+    /// Settings app language, e.g. "en" or "es"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+
+    /// List every tool in tools/list instead of the discovery meta-tools,
+    /// for clients that work better with a flat tool list
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub expose_all_tools: bool,
+
+    /// Have tools that change watchlists report what they would do instead
+    /// of doing it, for running agents read-only
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+
+    /// Format tool results are returned in when a call doesn't pass
+    /// `format` (defaults to JSON)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<OutputFormat>,
+}
+
+/// A labeled FilingExplorer API token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Account {
+    pub label: String,
+    pub api_token: String,
+}
+
+/// Label given to a token configured before accounts existed
+pub const DEFAULT_ACCOUNT_LABEL: &str = "default";
+
+/// Concurrency and rate limits for one tool category
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CategoryLimit {
+    /// Maximum number of calls running at once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+
+    /// Maximum number of calls started per minute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_per_minute: Option<u32>,
 }
 
 fn default_version() -> u32 {
@@ -66,8 +155,17 @@ impl Default for Config {
         Self {
             version: CONFIG_VERSION,
             api_token: None,
+            accounts: Vec::new(),
+            active_account: None,
             sec_user_agent_name: None,
             sec_user_agent_email: None,
+            max_output_bytes: None,
+            tool_timeout_secs: None,
+            category_limits: HashMap::new(),
+            locale: None,
+            expose_all_tools: false,
+            dry_run: false,
+            output_format: None,
         }
     }
 }
@@ -80,20 +178,30 @@ impl Config {
             .ok_or(ConfigError::NoConfigDir)
     }
 
+    /// Get the platform-specific cache directory path
+    pub fn cache_dir() -> Result<PathBuf, ConfigError> {
+        ProjectDirs::from(QUALIFIER, ORGANIZATION, APPLICATION)
+            .map(|dirs| dirs.cache_dir().to_path_buf())
+            .ok_or(ConfigError::NoConfigDir)
+    }
+
     /// Get the full path to the config file
     pub fn config_path() -> Result<PathBuf, ConfigError> {
-        Ok(Self::config_dir()?.join("config.json"))
+        Ok(Self::config_dir()?.join(CONFIG_FILE_NAME))
     }
 
     /// Load configuration from disk
     pub fn load() -> Result<Self, ConfigError> {
-        let path = Self::config_path()?;
+        Self::load_from(&Self::config_path()?)
+    }
 
+    /// Load configuration from a specific file
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
         if !path.exists() {
             return Err(ConfigError::NotFound);
         }
 
-        let contents = fs::read_to_string(&path)?;
+        let contents = fs::read_to_string(path)?;
         let config: Config = serde_json::from_str(&contents)?;
 
         Ok(config)
@@ -101,15 +209,18 @@ impl Config {
 
     /// Save configuration to disk
     pub fn save(&self) -> Result<(), ConfigError> {
-        let path = Self::config_path()?;
+        self.save_to(&Self::config_path()?)
+    }
 
+    /// Save configuration to a specific file
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
         // Ensure config directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
         let contents = serde_json::to_string_pretty(self)?;
-        fs::write(&path, contents)?;
+        fs::write(path, contents)?;
 
         Ok(())
     }
@@ -119,20 +230,43 @@ impl Config {
         Self::load().unwrap_or_default()
     }
 
+    /// Apply credential and tool timeout environment variables, looked up
+    /// with `var`; unset, blank, or unparseable variables are ignored.
+    /// Returns the names of those applied.
+    pub fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Vec<&'static str> {
+        let mut applied = Vec::new();
+        let fields = [
+            (ENV_API_TOKEN, &mut self.api_token),
+            (ENV_SEC_USER_AGENT_NAME, &mut self.sec_user_agent_name),
+            (ENV_SEC_USER_AGENT_EMAIL, &mut self.sec_user_agent_email),
+        ];
+        for (name, field) in fields {
+            if let Some(value) = var(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty()) {
+                *field = Some(value);
+                applied.push(name);
+            }
+        }
+        if let Some(secs) = var(ENV_TOOL_TIMEOUT_SECS).and_then(|v| v.trim().parse().ok()) {
+            self.tool_timeout_secs = Some(secs);
+            applied.push(ENV_TOOL_TIMEOUT_SECS);
+        }
+        applied
+    }
+
     /// Check if the config has required fields for API access
     pub fn is_api_configured(&self) -> bool {
-        self.api_token.as_ref().map_or(false, |t| !t.is_empty())
+        self.api_token.as_ref().is_some_and(|t| !t.is_empty())
     }
 
     /// Check if SEC direct access is configured
     pub fn is_sec_configured(&self) -> bool {
         self.sec_user_agent_name
             .as_ref()
-            .map_or(false, |n| !n.is_empty())
+            .is_some_and(|n| !n.is_empty())
             && self
                 .sec_user_agent_email
                 .as_ref()
-                .map_or(false, |e| !e.is_empty())
+                .is_some_and(|e| !e.is_empty())
     }
 
     /// Get the API token, returning an error if not configured
@@ -143,6 +277,107 @@ impl Config {
             .ok_or(ConfigError::MissingToken)
     }
 
+    /// Size limit for inline tool results
+    pub fn max_output_bytes(&self) -> usize {
+        self.max_output_bytes.unwrap_or(DEFAULT_MAX_OUTPUT_BYTES)
+    }
+
+    /// Time budget for one tool call
+    pub fn tool_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.tool_timeout_secs
+                .filter(|secs| *secs > 0)
+                .unwrap_or(DEFAULT_TOOL_TIMEOUT_SECS),
+        )
+    }
+
+    /// The account whose token the MCP server uses
+    pub fn active_account(&self) -> Option<&Account> {
+        let label = self.active_account.as_deref()?;
+        self.accounts.iter().find(|a| a.label == label)
+    }
+
+    /// Set the API token, updating the active account's token as well
+    pub fn set_api_token(&mut self, api_token: Option<String>) {
+        if let (Some(token), Some(label)) = (&api_token, self.active_account.clone()) {
+            if let Some(account) = self.accounts.iter_mut().find(|a| a.label == label) {
+                account.api_token = token.clone();
+            }
+        }
+        self.api_token = api_token;
+    }
+
+    /// Add an account, or replace the token of an existing one with the same
+    /// label. The first account becomes active; a token configured before
+    /// accounts existed is kept as the "default" account.
+    pub fn add_account(&mut self, label: &str, api_token: &str) -> Result<(), ConfigError> {
+        let label = label.trim();
+        let api_token = api_token.trim();
+        if label.is_empty() {
+            return Err(ConfigError::InvalidAccount("label is empty".to_string()));
+        }
+        if api_token.is_empty() {
+            return Err(ConfigError::InvalidAccount("API token is empty".to_string()));
+        }
+
+        if self.accounts.is_empty() && label != DEFAULT_ACCOUNT_LABEL {
+            if let Some(existing) = self.api_token.clone().filter(|t| !t.is_empty()) {
+                self.accounts.push(Account {
+                    label: DEFAULT_ACCOUNT_LABEL.to_string(),
+                    api_token: existing,
+                });
+                self.active_account = Some(DEFAULT_ACCOUNT_LABEL.to_string());
+            }
+        }
+
+        match self.accounts.iter_mut().find(|a| a.label == label) {
+            Some(account) => account.api_token = api_token.to_string(),
+            None => self.accounts.push(Account {
+                label: label.to_string(),
+                api_token: api_token.to_string(),
+            }),
+        }
+
+        if self.active_account().is_none() || self.active_account.as_deref() == Some(label) {
+            self.switch_account(label)?;
+        }
+        Ok(())
+    }
+
+    /// Remove an account. Removing the active account switches to the first
+    /// remaining one, or clears the token if none are left.
+    pub fn remove_account(&mut self, label: &str) -> Result<(), ConfigError> {
+        let index = self
+            .accounts
+            .iter()
+            .position(|a| a.label == label)
+            .ok_or_else(|| ConfigError::AccountNotFound(label.to_string()))?;
+        self.accounts.remove(index);
+
+        if self.active_account.as_deref() == Some(label) {
+            match self.accounts.first().map(|a| a.label.clone()) {
+                Some(next) => self.switch_account(&next)?,
+                None => {
+                    self.active_account = None;
+                    self.api_token = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Make an account the one the MCP server uses
+    pub fn switch_account(&mut self, label: &str) -> Result<(), ConfigError> {
+        let account = self
+            .accounts
+            .iter()
+            .find(|a| a.label == label)
+            .ok_or_else(|| ConfigError::AccountNotFound(label.to_string()))?;
+        self.api_token = Some(account.api_token.clone());
+        self.active_account = Some(account.label.clone());
+        Ok(())
+    }
+
     /// Get the SEC User-Agent string if configured
     pub fn sec_user_agent(&self) -> Option<String> {
         match (&self.sec_user_agent_name, &self.sec_user_agent_email) {
@@ -186,8 +421,17 @@ mod tests {
         let config = Config {
             version: 1,
             api_token: Some("test_token".to_string()),
+            accounts: Vec::new(),
+            active_account: None,
             sec_user_agent_name: Some("Test".to_string()),
             sec_user_agent_email: Some("test@test.com".to_string()),
+            max_output_bytes: None,
+            tool_timeout_secs: None,
+            category_limits: HashMap::new(),
+            locale: None,
+            expose_all_tools: false,
+            dry_run: false,
+            output_format: None,
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -273,11 +517,12 @@ mod tests {
 
     #[test]
     fn test_sec_user_agent_partial_config() {
-        let mut config = Config::default();
-
         // Only name set
-        config.sec_user_agent_name = Some("Company".to_string());
-        config.sec_user_agent_email = None;
+        let mut config = Config {
+            sec_user_agent_name: Some("Company".to_string()),
+            sec_user_agent_email: None,
+            ..Config::default()
+        };
         assert!(config.sec_user_agent().is_none());
 
         // Only email set
@@ -318,6 +563,65 @@ mod tests {
         assert_eq!(config.api_token, Some("test".to_string()));
     }
 
+    #[test]
+    fn test_max_output_bytes() {
+        let config = Config::default();
+        assert_eq!(config.max_output_bytes(), DEFAULT_MAX_OUTPUT_BYTES);
+        assert!(!serde_json::to_string(&config).unwrap().contains("max_output_bytes"));
+
+        let config: Config = serde_json::from_str(r#"{"max_output_bytes": 2048}"#).unwrap();
+        assert_eq!(config.max_output_bytes(), 2048);
+    }
+
+    #[test]
+    fn test_tool_timeout() {
+        let config = Config::default();
+        assert_eq!(config.tool_timeout(), Duration::from_secs(DEFAULT_TOOL_TIMEOUT_SECS));
+        assert!(!serde_json::to_string(&config).unwrap().contains("tool_timeout_secs"));
+
+        let mut config: Config = serde_json::from_str(r#"{"tool_timeout_secs": 30}"#).unwrap();
+        assert_eq!(config.tool_timeout(), Duration::from_secs(30));
+        let applied = config.apply_env(|name| (name == ENV_TOOL_TIMEOUT_SECS).then(|| " 5 ".to_string()));
+        assert_eq!(applied, vec![ENV_TOOL_TIMEOUT_SECS]);
+        assert_eq!(config.tool_timeout(), Duration::from_secs(5));
+        // Not a number, so only the credential variables apply
+        assert_eq!(config.apply_env(|_| Some("soon".to_string())).len(), 3);
+        assert_eq!(config.tool_timeout(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_expose_all_tools() {
+        assert!(!serde_json::to_string(&Config::default()).unwrap().contains("expose_all_tools"));
+        let config: Config = serde_json::from_str(r#"{"expose_all_tools": true}"#).unwrap();
+        assert!(config.expose_all_tools);
+    }
+
+    #[test]
+    fn test_dry_run() {
+        assert!(!serde_json::to_string(&Config::default()).unwrap().contains("dry_run"));
+        let config: Config = serde_json::from_str(r#"{"dry_run": true}"#).unwrap();
+        assert!(config.dry_run);
+    }
+
+    #[test]
+    fn test_output_format() {
+        assert_eq!(Config::default().output_format, None);
+        let config: Config = serde_json::from_str(r#"{"output_format": "markdown"}"#).unwrap();
+        assert_eq!(config.output_format, Some(OutputFormat::Markdown));
+        assert!(serde_json::from_str::<Config>(r#"{"output_format": "xml"}"#).is_err());
+    }
+
+    #[test]
+    fn test_category_limits() {
+        assert!(!serde_json::to_string(&Config::default()).unwrap().contains("category_limits"));
+
+        let json = r#"{"category_limits": {"sec_documents": {"max_concurrent": 2, "max_per_minute": 60}}}"#;
+        let config: Config = serde_json::from_str(json).unwrap();
+        let limit = &config.category_limits["sec_documents"];
+        assert_eq!(limit.max_concurrent, Some(2));
+        assert_eq!(limit.max_per_minute, Some(60));
+    }
+
     #[test]
     fn test_config_dir_returns_path() {
         // This should work on any platform
@@ -325,7 +629,7 @@ mod tests {
         assert!(result.is_ok());
 
         let path = result.unwrap();
-        assert!(path.to_string_lossy().len() > 0);
+        assert!(!path.to_string_lossy().is_empty());
     }
 
     #[test]
@@ -350,8 +654,17 @@ mod tests {
         let config = Config {
             version: 1,
             api_token: Some("token".to_string()),
+            accounts: Vec::new(),
+            active_account: None,
             sec_user_agent_name: Some("Name".to_string()),
             sec_user_agent_email: Some("email@test.com".to_string()),
+            max_output_bytes: None,
+            tool_timeout_secs: None,
+            category_limits: HashMap::new(),
+            locale: None,
+            expose_all_tools: false,
+            dry_run: false,
+            output_format: None,
         };
 
         let cloned = config.clone();
@@ -369,6 +682,82 @@ mod tests {
         assert!(debug_str.contains("version"));
     }
 
+    #[test]
+    fn test_accounts_keep_legacy_token_and_switch() {
+        let mut config = Config {
+            api_token: Some("legacy".to_string()),
+            ..Config::default()
+        };
+        config.add_account("firm", "firm_token").unwrap();
+
+        // The pre-existing token is kept as the active default account
+        assert_eq!(config.accounts.len(), 2);
+        assert_eq!(config.active_account().unwrap().label, DEFAULT_ACCOUNT_LABEL);
+        assert_eq!(config.api_token.as_deref(), Some("legacy"));
+
+        config.switch_account("firm").unwrap();
+        assert_eq!(config.api_token.as_deref(), Some("firm_token"));
+        assert!(matches!(
+            config.switch_account("sandbox"),
+            Err(ConfigError::AccountNotFound(_))
+        ));
+
+        // Saving a token in the settings form updates the active account
+        config.set_api_token(Some("rotated".to_string()));
+        assert_eq!(config.active_account().unwrap().api_token, "rotated");
+
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.accounts, config.accounts);
+        assert_eq!(parsed.active_account.as_deref(), Some("firm"));
+    }
+
+    #[test]
+    fn test_add_and_remove_accounts() {
+        let mut config = Config::default();
+        assert!(matches!(config.add_account(" ", "token"), Err(ConfigError::InvalidAccount(_))));
+        assert!(matches!(config.add_account("personal", ""), Err(ConfigError::InvalidAccount(_))));
+
+        config.add_account("personal", "p1").unwrap();
+        config.add_account("sandbox", "s1").unwrap();
+        // The first account becomes active; later ones don't
+        assert_eq!(config.active_account.as_deref(), Some("personal"));
+
+        // Re-adding a label replaces its token
+        config.add_account("personal", "p2").unwrap();
+        assert_eq!(config.accounts.len(), 2);
+        assert_eq!(config.api_token.as_deref(), Some("p2"));
+
+        config.remove_account("personal").unwrap();
+        assert_eq!(config.active_account.as_deref(), Some("sandbox"));
+        assert_eq!(config.api_token.as_deref(), Some("s1"));
+
+        config.remove_account("sandbox").unwrap();
+        assert!(config.active_account.is_none());
+        assert!(!config.is_api_configured());
+        assert!(matches!(config.remove_account("sandbox"), Err(ConfigError::AccountNotFound(_))));
+    }
+
+    #[test]
+    fn test_apply_env() {
+        let mut config = Config {
+            api_token: Some("file_token".to_string()),
+            sec_user_agent_name: Some("File Name".to_string()),
+            ..Config::default()
+        };
+        let env = |name: &str| match name {
+            ENV_API_TOKEN => Some(" env_token ".to_string()),
+            ENV_SEC_USER_AGENT_NAME => Some("".to_string()),
+            ENV_SEC_USER_AGENT_EMAIL => Some("env@example.com".to_string()),
+            _ => None,
+        };
+        let applied = config.apply_env(env);
+        assert_eq!(applied, vec![ENV_API_TOKEN, ENV_SEC_USER_AGENT_EMAIL]);
+        assert_eq!(config.api_token.as_deref(), Some("env_token"));
+        assert_eq!(config.sec_user_agent_name.as_deref(), Some("File Name"));
+        assert_eq!(config.sec_user_agent_email.as_deref(), Some("env@example.com"));
+    }
+
     #[test]
     fn test_config_error_display() {
         let err = ConfigError::NoConfigDir;
@@ -401,15 +790,6 @@ mod tests {
             Ok(())
         }
 
-        fn load_config_from_path(path: &std::path::Path) -> Result<Config, ConfigError> {
-            if !path.exists() {
-                return Err(ConfigError::NotFound);
-            }
-            let contents = fs::read_to_string(path)?;
-            let config: Config = serde_json::from_str(&contents)?;
-            Ok(config)
-        }
-
         #[test]
         fn test_save_and_load_config() {
             let temp_dir = TempDir::new().unwrap();
@@ -418,8 +798,17 @@ mod tests {
             let config = Config {
                 version: 1,
                 api_token: Some("test_token_123".to_string()),
+                accounts: Vec::new(),
+                active_account: None,
                 sec_user_agent_name: Some("Test Company".to_string()),
                 sec_user_agent_email: Some("test@example.com".to_string()),
+                max_output_bytes: None,
+                tool_timeout_secs: None,
+                category_limits: HashMap::new(),
+                locale: None,
+                expose_all_tools: false,
+                dry_run: false,
+                output_format: None,
             };
 
             // Save
@@ -427,7 +816,7 @@ mod tests {
             assert!(config_path.exists());
 
             // Load
-            let loaded = load_config_from_path(&config_path).unwrap();
+            let loaded = Config::load_from(&config_path).unwrap();
             assert_eq!(loaded.version, config.version);
             assert_eq!(loaded.api_token, config.api_token);
             assert_eq!(loaded.sec_user_agent_name, config.sec_user_agent_name);
@@ -439,7 +828,7 @@ mod tests {
             let temp_dir = TempDir::new().unwrap();
             let config_path = temp_dir.path().join("nonexistent.json");
 
-            let result = load_config_from_path(&config_path);
+            let result = Config::load_from(&config_path);
             assert!(matches!(result, Err(ConfigError::NotFound)));
         }
 
@@ -451,7 +840,7 @@ mod tests {
             // Write invalid JSON
             fs::write(&config_path, "this is not valid json").unwrap();
 
-            let result = load_config_from_path(&config_path);
+            let result = Config::load_from(&config_path);
             assert!(matches!(result, Err(ConfigError::ParseError(_))));
         }
 
@@ -475,8 +864,17 @@ mod tests {
             let config1 = Config {
                 version: 1,
                 api_token: Some("first_token".to_string()),
+                accounts: Vec::new(),
+                active_account: None,
                 sec_user_agent_name: None,
                 sec_user_agent_email: None,
+                max_output_bytes: None,
+                tool_timeout_secs: None,
+                category_limits: HashMap::new(),
+                locale: None,
+                expose_all_tools: false,
+                dry_run: false,
+                output_format: None,
             };
             save_config_to_path(&config1, &config_path).unwrap();
 
@@ -484,13 +882,22 @@ mod tests {
             let config2 = Config {
                 version: 1,
                 api_token: Some("second_token".to_string()),
+                accounts: Vec::new(),
+                active_account: None,
                 sec_user_agent_name: Some("New Company".to_string()),
                 sec_user_agent_email: Some("new@example.com".to_string()),
+                max_output_bytes: None,
+                tool_timeout_secs: None,
+                category_limits: HashMap::new(),
+                locale: None,
+                expose_all_tools: false,
+                dry_run: false,
+                output_format: None,
             };
             save_config_to_path(&config2, &config_path).unwrap();
 
             // Load and verify it's the second config
-            let loaded = load_config_from_path(&config_path).unwrap();
+            let loaded = Config::load_from(&config_path).unwrap();
             assert_eq!(loaded.api_token, Some("second_token".to_string()));
             assert_eq!(loaded.sec_user_agent_name, Some("New Company".to_string()));
         }
@@ -504,7 +911,7 @@ mod tests {
             let partial_json = r#"{"api_token": "partial_token"}"#;
             fs::write(&config_path, partial_json).unwrap();
 
-            let loaded = load_config_from_path(&config_path).unwrap();
+            let loaded = Config::load_from(&config_path).unwrap();
             assert_eq!(loaded.api_token, Some("partial_token".to_string()));
             assert_eq!(loaded.version, CONFIG_VERSION); // Should use default
             assert!(loaded.sec_user_agent_name.is_none());
@@ -519,8 +926,17 @@ mod tests {
             let config = Config {
                 version: 1,
                 api_token: Some("token".to_string()),
+                accounts: Vec::new(),
+                active_account: None,
                 sec_user_agent_name: None,
                 sec_user_agent_email: None,
+                max_output_bytes: None,
+                tool_timeout_secs: None,
+                category_limits: HashMap::new(),
+                locale: None,
+                expose_all_tools: false,
+                dry_run: false,
+                output_format: None,
             };
             save_config_to_path(&config, &config_path).unwrap();
 
@@ -0,0 +1,287 @@
+//! Unified error type for core operations.
+//!
+//! [`CoreError`] wraps the per-module errors (API, SEC, extraction, config)
+//! and maps each to a stable [`ErrorCode`] that clients can branch on. An
+//! [`ErrorContext`] records which tool, endpoint, and request the failure
+//! belongs to.
+
+use crate::api_client::ApiError;
+use crate::call_stats::Source;
+use crate::config::ConfigError;
+use crate::sec_client::SecError;
+use crate::text_extraction::ExtractionError;
+use serde::Serialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// Stable, machine-readable error code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    MissingParam,
+    InvalidParam,
+    NotConfigured,
+    ConfigInvalid,
+    Unauthorized,
+    NotFound,
+    UpstreamRateLimited,
+    UpstreamError,
+    ExtractionFailed,
+    UnknownTool,
+    NotImplemented,
+    Cancelled,
+    Timeout,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MissingParam => "MISSING_PARAM",
+            Self::InvalidParam => "INVALID_PARAM",
+            Self::NotConfigured => "NOT_CONFIGURED",
+            Self::ConfigInvalid => "CONFIG_INVALID",
+            Self::Unauthorized => "UNAUTHORIZED",
+            Self::NotFound => "NOT_FOUND",
+            Self::UpstreamRateLimited => "UPSTREAM_RATE_LIMITED",
+            Self::UpstreamError => "UPSTREAM_ERROR",
+            Self::ExtractionFailed => "EXTRACTION_FAILED",
+            Self::UnknownTool => "UNKNOWN_TOOL",
+            Self::NotImplemented => "NOT_IMPLEMENTED",
+            Self::Cancelled => "CANCELLED",
+            Self::Timeout => "TIMEOUT",
+            Self::Internal => "INTERNAL",
+        }
+    }
+}
+
+/// Where an error happened
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ErrorContext {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ErrorContext {
+    pub fn endpoint(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: Some(endpoint.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_tool(mut self, tool: impl Into<String>) -> Self {
+        self.tool = Some(tool.into());
+        self
+    }
+
+    pub fn with_request_id(mut self, request_id: impl Into<String>) -> Self {
+        self.request_id = Some(request_id.into());
+        self
+    }
+
+    /// Fill fields missing here from `other`
+    fn merge(self, other: ErrorContext) -> Self {
+        Self {
+            tool: self.tool.or(other.tool),
+            endpoint: self.endpoint.or(other.endpoint),
+            request_id: self.request_id.or(other.request_id),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tool.is_none() && self.endpoint.is_none() && self.request_id.is_none()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum CoreError {
+    #[error(transparent)]
+    Api(#[from] ApiError),
+
+    #[error(transparent)]
+    Sec(#[from] SecError),
+
+    #[error(transparent)]
+    Extraction(#[from] ExtractionError),
+
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    /// A bug rather than a failure of the request, such as a panicked task
+    #[error("{0}")]
+    Internal(String),
+
+    #[error("{source}")]
+    WithContext {
+        source: Box<CoreError>,
+        context: ErrorContext,
+    },
+}
+
+impl CoreError {
+    /// Attach context, keeping any fields already recorded
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            Self::WithContext {
+                source,
+                context: existing,
+            } => Self::WithContext {
+                source,
+                context: existing.merge(context),
+            },
+            other => Self::WithContext {
+                source: Box::new(other),
+                context,
+            },
+        }
+    }
+
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The underlying error, without context
+    pub fn root(&self) -> &CoreError {
+        match self {
+            Self::WithContext { source, .. } => source.root(),
+            other => other,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self.root() {
+            Self::Api(e) => match e {
+                ApiError::Unauthorized => ErrorCode::Unauthorized,
+                ApiError::NotFound => ErrorCode::NotFound,
+                ApiError::RateLimited { .. } => ErrorCode::UpstreamRateLimited,
+                ApiError::Cancelled => ErrorCode::Cancelled,
+                ApiError::ApiError { .. } | ApiError::RequestError(_) | ApiError::ParseError(_) => {
+                    ErrorCode::UpstreamError
+                }
+            },
+            Self::Sec(e) => match e {
+                SecError::NotConfigured(_) => ErrorCode::NotConfigured,
+                SecError::NotFound => ErrorCode::NotFound,
+                SecError::RateLimited => ErrorCode::UpstreamRateLimited,
+                SecError::InvalidInput(_) => ErrorCode::InvalidParam,
+                SecError::Cancelled => ErrorCode::Cancelled,
+                SecError::SecError { .. } | SecError::RequestError(_) => ErrorCode::UpstreamError,
+            },
+            Self::Extraction(_) => ErrorCode::ExtractionFailed,
+            Self::Config(e) => match e {
+                ConfigError::NotFound | ConfigError::MissingToken => ErrorCode::NotConfigured,
+                ConfigError::NoConfigDir
+                | ConfigError::ReadError(_)
+                | ConfigError::ParseError(_)
+                | ConfigError::AccountNotFound(_)
+                | ConfigError::InvalidAccount(_) => ErrorCode::ConfigInvalid,
+            },
+            Self::Internal(_) => ErrorCode::Internal,
+            Self::WithContext { .. } => unreachable!("root() never returns WithContext"),
+        }
+    }
+
+    /// Whether the operation may succeed if retried
+    pub fn is_retryable(&self) -> bool {
+        match self.root() {
+            Self::Api(e) => e.is_retryable(),
+            Self::Sec(SecError::RateLimited) => true,
+            Self::Sec(SecError::SecError { status, .. }) => *status >= 500,
+            Self::Sec(SecError::RequestError(e)) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+
+    /// The service that failed, for errors a request to it came back with
+    pub fn upstream(&self) -> Option<Source> {
+        match self.root() {
+            Self::Api(ApiError::Cancelled)
+            | Self::Sec(SecError::NotConfigured(_) | SecError::InvalidInput(_) | SecError::Cancelled) => None,
+            Self::Api(_) => Some(Source::Api),
+            Self::Sec(_) => Some(Source::Sec),
+            _ => None,
+        }
+    }
+
+    /// Server-suggested wait before retrying, if any
+    pub fn retry_after(&self) -> Option<u64> {
+        match self.root() {
+            Self::Api(ApiError::RateLimited { retry_after }) => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// Compact JSON form for per-item errors inside a larger result
+    pub fn to_json(&self) -> Value {
+        let mut value = json!({
+            "code": self.code(),
+            "message": self.to_string(),
+        });
+        if let Some(context) = self.context().filter(|c| !c.is_empty()) {
+            value["context"] = json!(context);
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes() {
+        assert_eq!(CoreError::from(ApiError::NotFound).code(), ErrorCode::NotFound);
+        assert_eq!(CoreError::from(SecError::RateLimited).code(), ErrorCode::UpstreamRateLimited);
+        assert_eq!(
+            CoreError::from(ExtractionError::UnsupportedType).code(),
+            ErrorCode::ExtractionFailed
+        );
+        assert_eq!(CoreError::from(ConfigError::MissingToken).code(), ErrorCode::NotConfigured);
+        assert_eq!(CoreError::from(SecError::RateLimited).upstream(), Some(Source::Sec));
+        assert_eq!(CoreError::from(ApiError::NotFound).upstream(), Some(Source::Api));
+        assert_eq!(CoreError::from(ApiError::Cancelled).upstream(), None);
+        assert_eq!(CoreError::from(ConfigError::MissingToken).upstream(), None);
+        assert_eq!(CoreError::Internal("task panicked".into()).code(), ErrorCode::Internal);
+        assert_eq!(ErrorCode::UpstreamRateLimited.as_str(), "UPSTREAM_RATE_LIMITED");
+        assert_eq!(
+            serde_json::to_value(ErrorCode::ConfigInvalid).unwrap(),
+            ErrorCode::ConfigInvalid.as_str()
+        );
+    }
+
+    #[test]
+    fn test_context_is_kept_and_merged() {
+        let err = CoreError::from(ApiError::RateLimited { retry_after: Some(10) })
+            .with_context(ErrorContext::endpoint("forms/4"))
+            .with_context(ErrorContext::default().with_tool("get_form4_filings").with_request_id("7"));
+
+        assert_eq!(err.code(), ErrorCode::UpstreamRateLimited);
+        assert!(err.is_retryable());
+        assert_eq!(err.retry_after(), Some(10));
+        assert_eq!(err.to_string(), "Rate limited - please slow down requests");
+
+        let context = err.context().unwrap();
+        assert_eq!(context.endpoint.as_deref(), Some("forms/4"));
+        assert_eq!(context.tool.as_deref(), Some("get_form4_filings"));
+        assert_eq!(context.request_id.as_deref(), Some("7"));
+
+        let value = err.to_json();
+        assert_eq!(value["code"], "UPSTREAM_RATE_LIMITED");
+        assert_eq!(value["context"]["endpoint"], "forms/4");
+    }
+
+    #[test]
+    fn test_to_json_without_context() {
+        let value = CoreError::from(ApiError::NotFound).to_json();
+        assert_eq!(value["code"], "NOT_FOUND");
+        assert!(value.get("context").is_none());
+    }
+}
@@ -3,11 +3,24 @@
 //! Shared business logic for the FilingExplorer MCP server and settings app.
 
 pub mod api_client;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod call_stats;
+pub mod cancellation;
 pub mod config;
+pub mod error;
+pub mod migration;
+pub mod models;
+pub mod params;
+pub mod plugins;
+pub mod result_store;
 pub mod sec_client;
+pub mod text_cache;
 pub mod text_extraction;
 pub mod tools;
+pub mod usage;
 
 pub use api_client::ApiClient;
 pub use config::Config;
+pub use error::{CoreError, ErrorCode, ErrorContext};
 pub use sec_client::SecClient;
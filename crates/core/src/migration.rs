@@ -0,0 +1,288 @@
+//! Export and import of local state for moving to a new machine.
+//!
+//! A [`StateArchive`] is one JSON file holding the config (accounts
+//! included, so it contains API tokens), every other file in the config
+//! directory such as watchlist snapshots, the size of each cache, and which
+//! MCP client configs had the server installed. Caches are not copied since
+//! they rebuild themselves. Importing writes the config directory back and
+//! leaves reinstalling into clients to the caller, which knows where the
+//! server binary lives on the new machine.
+
+use crate::config::{Config, ConfigError, CONFIG_FILE_NAME};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+/// Version of the archive layout; archives from newer versions are refused
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("Failed to access state files: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Not a valid state archive: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+
+    #[error("Archive format {0} is newer than this version supports; update the app first")]
+    UnsupportedVersion(u32),
+
+    #[error("Archive contains an unsafe path: {0}")]
+    UnsafePath(String),
+}
+
+/// File count and size of one cache directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheMetadata {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+/// An MCP client config and whether it had the server installed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientInstall {
+    /// Client config kind, e.g. "desktop" or "code_global"
+    pub config_type: String,
+    pub installed: bool,
+    /// Server command on the exporting machine
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_command: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateArchive {
+    pub format_version: u32,
+    /// RFC 3339 time of the export
+    pub exported_at: String,
+    pub config: Config,
+    /// Other config directory files, keyed by `/`-separated relative path
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+    /// Metadata of each cache subdirectory, keyed by its name
+    #[serde(default)]
+    pub caches: BTreeMap<String, CacheMetadata>,
+    #[serde(default)]
+    pub clients: Vec<ClientInstall>,
+}
+
+/// What an import wrote
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RestoreSummary {
+    pub files_restored: usize,
+    pub accounts: usize,
+}
+
+impl StateArchive {
+    /// Gather `config` and the contents of `config_dir` and `cache_dir`.
+    /// Missing directories are treated as empty.
+    pub fn collect(config: Config, config_dir: &Path, cache_dir: &Path) -> Result<Self, ArchiveError> {
+        let mut files = BTreeMap::new();
+        for path in walk(config_dir)? {
+            let relative = relative_key(config_dir, &path);
+            if relative == CONFIG_FILE_NAME {
+                continue;
+            }
+            // Only text state is archived
+            if let Ok(contents) = fs::read_to_string(&path) {
+                files.insert(relative, contents);
+            }
+        }
+
+        let mut caches = BTreeMap::new();
+        for entry in read_dir_or_empty(cache_dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() {
+                continue;
+            }
+            let mut metadata = CacheMetadata::default();
+            for path in walk(&entry.path())? {
+                metadata.files += 1;
+                metadata.bytes += fs::metadata(&path)?.len();
+            }
+            caches.insert(entry.file_name().to_string_lossy().into_owned(), metadata);
+        }
+
+        Ok(Self {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            exported_at: Utc::now().to_rfc3339(),
+            config,
+            files,
+            caches,
+            clients: Vec::new(),
+        })
+    }
+
+    /// Parse an exported archive
+    pub fn from_json(json: &str) -> Result<Self, ArchiveError> {
+        let archive: Self = serde_json::from_str(json)?;
+        if archive.format_version > ARCHIVE_FORMAT_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(archive.format_version));
+        }
+        Ok(archive)
+    }
+
+    pub fn to_json(&self) -> Result<String, ArchiveError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Write the config and archived files into `config_dir`, replacing
+    /// files of the same name. Every path is checked before anything is
+    /// written.
+    pub fn restore(&self, config_dir: &Path) -> Result<RestoreSummary, ArchiveError> {
+        let targets = self
+            .files
+            .iter()
+            .map(|(relative, contents)| Ok((safe_join(config_dir, relative)?, contents)))
+            .collect::<Result<Vec<_>, ArchiveError>>()?;
+
+        self.config.save_to(&config_dir.join(CONFIG_FILE_NAME))?;
+        for (path, contents) in &targets {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+        }
+        Ok(RestoreSummary {
+            files_restored: targets.len(),
+            accounts: self.config.accounts.len(),
+        })
+    }
+
+    /// Clients to reinstall the server into after an import
+    pub fn installed_clients(&self) -> impl Iterator<Item = &ClientInstall> {
+        self.clients.iter().filter(|client| client.installed)
+    }
+}
+
+fn read_dir_or_empty(dir: &Path) -> io::Result<Vec<io::Result<fs::DirEntry>>> {
+    match fs::read_dir(dir) {
+        Ok(entries) => Ok(entries.collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Every file under `dir`, recursively
+fn walk(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in read_dir_or_empty(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            files.extend(walk(&entry.path())?);
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(files)
+}
+
+fn relative_key(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// `base` joined with an archived relative path, refusing anything that
+/// could land outside `base`
+fn safe_join(base: &Path, relative: &str) -> Result<PathBuf, ArchiveError> {
+    let relative_path = Path::new(relative);
+    let safe = !relative.is_empty()
+        && !relative.contains('\\')
+        && relative_path.components().all(|c| matches!(c, Component::Normal(_)));
+    if !safe {
+        return Err(ArchiveError::UnsafePath(relative.to_string()));
+    }
+    Ok(base.join(relative_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Account;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_round_trip() {
+        let old = TempDir::new().unwrap();
+        let config_dir = old.path().join("config");
+        let cache_dir = old.path().join("cache");
+        fs::create_dir_all(config_dir.join("list-history/42")).unwrap();
+        fs::write(config_dir.join(CONFIG_FILE_NAME), "{}").unwrap();
+        fs::write(config_dir.join("list-history/42/1700000000000.json"), "{\"list_id\":\"42\"}").unwrap();
+        fs::create_dir_all(cache_dir.join("extracted-text/a")).unwrap();
+        fs::write(cache_dir.join("extracted-text/a/doc.v1.txt"), "12345").unwrap();
+
+        let config = Config {
+            accounts: vec![Account {
+                label: "firm".to_string(),
+                api_token: "fe_123".to_string(),
+            }],
+            ..Config::default()
+        };
+        let mut archive = StateArchive::collect(config, &config_dir, &cache_dir).unwrap();
+        assert_eq!(archive.files.keys().collect::<Vec<_>>(), vec!["list-history/42/1700000000000.json"]);
+        assert_eq!(archive.caches["extracted-text"], CacheMetadata { files: 1, bytes: 5 });
+        archive.clients = vec![
+            ClientInstall {
+                config_type: "desktop".to_string(),
+                installed: true,
+                server_command: Some("/Applications/old/mcp-server".to_string()),
+            },
+            ClientInstall {
+                config_type: "code_global".to_string(),
+                installed: false,
+                server_command: None,
+            },
+        ];
+
+        let imported = StateArchive::from_json(&archive.to_json().unwrap()).unwrap();
+        let new = TempDir::new().unwrap();
+        let summary = imported.restore(new.path()).unwrap();
+        assert_eq!(summary, RestoreSummary { files_restored: 1, accounts: 1 });
+        let restored = Config::load_from(&new.path().join(CONFIG_FILE_NAME)).unwrap();
+        assert_eq!(restored.accounts[0].api_token, "fe_123");
+        assert!(new.path().join("list-history/42/1700000000000.json").exists());
+        let installed: Vec<&str> = imported.installed_clients().map(|c| c.config_type.as_str()).collect();
+        assert_eq!(installed, vec!["desktop"]);
+    }
+
+    #[test]
+    fn test_missing_dirs_collect_empty() {
+        let dir = TempDir::new().unwrap();
+        let archive = StateArchive::collect(Config::default(), &dir.path().join("a"), &dir.path().join("b")).unwrap();
+        assert!(archive.files.is_empty());
+        assert!(archive.caches.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_unsafe_paths_and_newer_versions() {
+        let dir = TempDir::new().unwrap();
+        for path in ["../escape.json", "/etc/passwd", "a/../../b", "a\\..\\b", ""] {
+            let mut archive = StateArchive::collect(Config::default(), dir.path(), dir.path()).unwrap();
+            archive.files.insert(path.to_string(), "x".to_string());
+            assert!(matches!(archive.restore(dir.path()), Err(ArchiveError::UnsafePath(_))), "{}", path);
+        }
+        assert!(!dir.path().join(CONFIG_FILE_NAME).exists());
+
+        let json = serde_json::json!({
+            "format_version": ARCHIVE_FORMAT_VERSION + 1,
+            "exported_at": "2030-01-01T00:00:00Z",
+            "config": {}
+        });
+        assert!(matches!(
+            StateArchive::from_json(&json.to_string()),
+            Err(ArchiveError::UnsupportedVersion(_))
+        ));
+    }
+}
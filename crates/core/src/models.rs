@@ -0,0 +1,266 @@
+//! Typed records for API responses.
+//!
+//! Most tools pass upstream JSON through. Code that computes on the data
+//! reads it into these models instead of going through `f64`: ETF holdings
+//! comparisons diff [`Holding`] weights, and the watchlist calendar reads
+//! dates with [`parse_date`]. Dates are [`NaiveDate`] and money and share
+//! counts are [`Decimal`]; the serde adapters below accept the formats the
+//! API sends, such as timestamps for dates and numeric strings for amounts.
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pub use rust_decimal;
+
+/// Parse an API date: `2024-03-31`, `2024-03-31T00:00:00Z`,
+/// `2024-03-31 09:30:00`, `20240331`, ...
+pub fn parse_date(input: &str) -> Option<NaiveDate> {
+    let input = input.trim();
+    crate::params::parse_exact(input).or_else(|| {
+        let day = input.get(..10).filter(|_| input.len() > 10)?;
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()
+    })
+}
+
+/// Parse an API amount: a plain or scientific number, optionally with `$`,
+/// thousands separators, or accounting-style parentheses for negatives
+pub fn parse_decimal(input: &str) -> Option<Decimal> {
+    let trimmed = input.trim();
+    let (negative, body) = match trimmed.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        Some(inner) => (true, inner),
+        None => (false, trimmed),
+    };
+    let cleaned: String = body.chars().filter(|c| !matches!(c, '$' | ',' | ' ')).collect();
+    let value = Decimal::from_str(&cleaned)
+        .or_else(|_| Decimal::from_scientific(&cleaned))
+        .ok()?;
+    Some(if negative { -value } else { value })
+}
+
+/// Serde adapter for a required date, written back as `YYYY-MM-DD`
+pub mod date {
+    use super::parse_date;
+    use chrono::NaiveDate;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(date: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&date.format("%Y-%m-%d"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        parse_date(&s).ok_or_else(|| D::Error::custom(format!("invalid date '{}'", s)))
+    }
+}
+
+/// Serde adapter for an optional date; null and empty strings are `None`
+pub mod option_date {
+    use super::parse_date;
+    use chrono::NaiveDate;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(date: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => super::date::serialize(date, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+        match Option::<String>::deserialize(deserializer)? {
+            Some(s) if !s.trim().is_empty() => parse_date(&s)
+                .map(Some)
+                .ok_or_else(|| D::Error::custom(format!("invalid date '{}'", s))),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// A JSON number or numeric string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawAmount {
+    Number(serde_json::Number),
+    Text(String),
+}
+
+impl RawAmount {
+    /// `Ok(None)` for blank text, `Err` with the input if it isn't a number
+    fn parse(self) -> Result<Option<Decimal>, String> {
+        let text = match self {
+            Self::Number(n) => n.to_string(),
+            Self::Text(s) if s.trim().is_empty() || s.trim().eq_ignore_ascii_case("n/a") => return Ok(None),
+            Self::Text(s) => s,
+        };
+        parse_decimal(&text).map(Some).ok_or(text)
+    }
+}
+
+/// Serde adapter for a required amount, written back as a string so no
+/// precision is lost
+pub mod decimal {
+    use super::RawAmount;
+    use rust_decimal::Decimal;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        match RawAmount::deserialize(deserializer)?.parse() {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => Err(D::Error::custom("missing amount")),
+            Err(text) => Err(D::Error::custom(format!("invalid amount '{}'", text))),
+        }
+    }
+}
+
+/// Serde adapter for an optional amount; null, empty strings, and "N/A" are
+/// `None`
+pub mod option_decimal {
+    use super::RawAmount;
+    use rust_decimal::Decimal;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => super::decimal::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Decimal>, D::Error> {
+        match Option::<RawAmount>::deserialize(deserializer)? {
+            Some(raw) => raw
+                .parse()
+                .map_err(|text| D::Error::custom(format!("invalid amount '{}'", text))),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A filing from a company filings list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Filing {
+    pub accession_number: String,
+    #[serde(default, alias = "form")]
+    pub form_type: Option<String>,
+    #[serde(default, alias = "filed_at", with = "option_date")]
+    pub filing_date: Option<NaiveDate>,
+    #[serde(default, with = "option_date")]
+    pub period_of_report: Option<NaiveDate>,
+    #[serde(default)]
+    pub primary_document: Option<String>,
+}
+
+/// A position from a 13F submission or ETF holdings list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Holding {
+    #[serde(default, alias = "name_of_issuer", alias = "issuer_name", alias = "title")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub cusip: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isin: Option<String>,
+    /// Market value in dollars
+    #[serde(default, alias = "value_usd", alias = "market_value", with = "option_decimal")]
+    pub value: Option<Decimal>,
+    #[serde(default, alias = "ssh_prnamt", alias = "balance", with = "option_decimal")]
+    pub shares: Option<Decimal>,
+    /// Percent of the portfolio
+    #[serde(
+        default,
+        alias = "pct_val",
+        alias = "percent",
+        alias = "percent_value",
+        alias = "percentage",
+        with = "option_decimal"
+    )]
+    pub weight: Option<Decimal>,
+}
+
+impl Holding {
+    /// Identity for matching the position across reports: its CUSIP,
+    /// symbol, or ISIN, else its name
+    pub fn key(&self) -> Option<String> {
+        [&self.cusip, &self.symbol, &self.isin]
+            .into_iter()
+            .flatten()
+            .find(|id| !id.trim().is_empty())
+            .map(|id| id.trim().to_uppercase())
+            .or_else(|| self.name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal("1,234.50"), Some(dec("1234.50")));
+        assert_eq!(parse_decimal(" $12 "), Some(dec("12")));
+        assert_eq!(parse_decimal("(1,000)"), Some(dec("-1000")));
+        assert_eq!(parse_decimal("1.5e3"), Some(dec("1500")));
+        assert_eq!(parse_decimal("abc"), None);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let day = NaiveDate::from_ymd_opt(2024, 3, 31);
+        assert_eq!(parse_date("2024-03-31"), day);
+        assert_eq!(parse_date("2024-03-31T00:00:00Z"), day);
+        assert_eq!(parse_date("2024-03-31 09:30:00"), day);
+        assert_eq!(parse_date("20240331"), day);
+        assert_eq!(parse_date("soon"), None);
+    }
+
+    #[test]
+    fn test_filing_dates() {
+        let filing: Filing = serde_json::from_value(json!({
+            "accession_number": "0000320193-24-000123",
+            "form": "10-K",
+            "filed_at": "2024-11-01T06:01:36Z",
+            "period_of_report": ""
+        }))
+        .unwrap();
+        assert_eq!(filing.form_type.as_deref(), Some("10-K"));
+        assert_eq!(filing.filing_date, NaiveDate::from_ymd_opt(2024, 11, 1));
+        assert_eq!(filing.period_of_report, None);
+
+        let round_trip = serde_json::to_value(&filing).unwrap();
+        assert_eq!(round_trip["filing_date"], "2024-11-01");
+        assert!(serde_json::from_value::<Filing>(json!({"accession_number": "x", "filing_date": "soon"})).is_err());
+    }
+
+    #[test]
+    fn test_holding_amounts_are_exact() {
+        let holding: Holding = serde_json::from_value(json!({
+            "name_of_issuer": "APPLE INC",
+            "value": "0.1",
+            "ssh_prnamt": 915560382,
+            "pct_val": null
+        }))
+        .unwrap();
+        assert_eq!(holding.value, Some(dec("0.1")));
+        assert_eq!(holding.shares, Some(dec("915560382")));
+        assert_eq!(holding.weight, None);
+        // 0.1 + 0.2 is exact in decimal
+        assert_eq!(holding.value.unwrap() + dec("0.2"), dec("0.3"));
+
+        assert_eq!(serde_json::to_value(&holding).unwrap()["value"], "0.1");
+        assert!(serde_json::from_value::<Holding>(json!({"value": "lots"})).is_err());
+        let blank: Holding = serde_json::from_value(json!({"value": "N/A"})).unwrap();
+        assert_eq!(blank.value, None);
+    }
+}
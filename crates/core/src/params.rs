@@ -0,0 +1,475 @@
+//! Shared parameter normalization for tool handlers.
+//!
+//! Date arguments accept exact dates in several formats ("2024-03-31",
+//! "03/31/2024", "March 31, 2024"), whole periods ("2023", "2023-Q4",
+//! "2024-03") and relative phrases ("last quarter", "past 90 days"). Each is
+//! resolved to a date range and then to the `YYYY-MM-DD` form the API
+//! expects, taking the start or end of the range depending on the parameter.
+//!
+//! Company identifiers are normalized here too: CIKs are padded to 10 digits
+//! and tickers are trimmed and uppercased. Accession numbers are accepted
+//! with or without dashes.
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use std::collections::HashMap;
+
+/// Which end of a period a date parameter refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateBound {
+    /// Lower bounds such as `filed_after`
+    Start,
+    /// Upper bounds and point-in-time dates such as `filed_before` or
+    /// `period_of_report` (a quarter resolves to its quarter-end date)
+    End,
+}
+
+/// Inclusive date range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    fn day(date: NaiveDate) -> Self {
+        Self {
+            start: date,
+            end: date,
+        }
+    }
+
+    pub fn bound(&self, bound: DateBound) -> NaiveDate {
+        match bound {
+            DateBound::Start => self.start,
+            DateBound::End => self.end,
+        }
+    }
+}
+
+const EXACT_FORMATS: &[&str] = &[
+    "%Y-%m-%d", "%Y/%m/%d", "%m/%d/%Y", "%Y%m%d", "%B %d, %Y", "%b %d, %Y", "%B %d %Y",
+    "%b %d %Y", "%d %B %Y", "%d %b %Y",
+];
+
+const MONTH_NAMES: &[&str] = &[
+    "january", "february", "march", "april", "may", "june", "july", "august", "september",
+    "october", "november", "december",
+];
+
+/// Calendar quarter (1-4) containing `date`
+pub fn quarter_of(date: NaiveDate) -> (i32, u8) {
+    (date.year(), (date.month0() / 3 + 1) as u8)
+}
+
+/// First and last day of a calendar quarter
+pub fn quarter_range(year: i32, quarter: u8) -> Option<DateRange> {
+    if !(1..=4).contains(&quarter) {
+        return None;
+    }
+    let first_month = u32::from(quarter - 1) * 3 + 1;
+    let start = NaiveDate::from_ymd_opt(year, first_month, 1)?;
+    let end = start.checked_add_months(Months::new(3))?.pred_opt()?;
+    Some(DateRange { start, end })
+}
+
+fn year_range(year: i32) -> Option<DateRange> {
+    Some(DateRange {
+        start: NaiveDate::from_ymd_opt(year, 1, 1)?,
+        end: NaiveDate::from_ymd_opt(year, 12, 31)?,
+    })
+}
+
+fn month_range(year: i32, month: u32) -> Option<DateRange> {
+    let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let end = start.checked_add_months(Months::new(1))?.pred_opt()?;
+    Some(DateRange { start, end })
+}
+
+fn parse_year(s: &str) -> Option<i32> {
+    (s.len() == 4)
+        .then(|| s.parse().ok())
+        .flatten()
+        .filter(|y| (1900..=2100).contains(y))
+}
+
+fn parse_month(s: &str) -> Option<u32> {
+    if s.len() < 3 {
+        return None;
+    }
+    MONTH_NAMES
+        .iter()
+        .position(|m| m.starts_with(s))
+        .map(|i| i as u32 + 1)
+}
+
+/// Phrases relative to `today`: "last quarter", "past 90 days", "3 months ago"
+fn parse_relative(s: &str, today: NaiveDate) -> Option<DateRange> {
+    let (year, quarter) = quarter_of(today);
+    let previous_quarter = if quarter == 1 { (year - 1, 4) } else { (year, quarter - 1) };
+    let to_date = |start| Some(DateRange { start, end: today });
+
+    match s {
+        "today" => return Some(DateRange::day(today)),
+        "yesterday" => return today.pred_opt().map(DateRange::day),
+        "this quarter" | "current quarter" | "qtd" | "quarter to date" => {
+            return to_date(quarter_range(year, quarter)?.start)
+        }
+        "last quarter" | "previous quarter" | "prior quarter" => {
+            return quarter_range(previous_quarter.0, previous_quarter.1)
+        }
+        "this year" | "current year" | "ytd" | "year to date" => return to_date(year_range(year)?.start),
+        "last year" | "previous year" | "prior year" => return year_range(year - 1),
+        "this month" | "current month" | "mtd" | "month to date" => {
+            return to_date(month_range(year, today.month())?.start)
+        }
+        "last month" | "previous month" | "prior month" => {
+            let date = today.checked_sub_months(Months::new(1))?;
+            return month_range(date.year(), date.month());
+        }
+        _ => {}
+    }
+
+    let words: Vec<&str> = s.split_whitespace().collect();
+    let (count, unit, ago) = match words.as_slice() {
+        ["past" | "last" | "previous" | "prior", count, unit] => (*count, *unit, false),
+        [count, unit, "ago"] => (*count, *unit, true),
+        _ => return None,
+    };
+    let count: u32 = count.parse().ok()?;
+    let unit = unit.strip_suffix('s').unwrap_or(unit);
+    let date = match unit {
+        "day" => today.checked_sub_signed(Duration::days(i64::from(count)))?,
+        "week" => today.checked_sub_signed(Duration::weeks(i64::from(count)))?,
+        "month" => today.checked_sub_months(Months::new(count))?,
+        "quarter" => today.checked_sub_months(Months::new(count.checked_mul(3)?))?,
+        "year" => today.checked_sub_months(Months::new(count.checked_mul(12)?))?,
+        _ => return None,
+    };
+    if ago {
+        Some(DateRange::day(date))
+    } else {
+        to_date(date)
+    }
+}
+
+/// Whole periods: "2023", "2023-Q4", "Q4 2023", "2024-03", "March 2024"
+fn parse_period(s: &str) -> Option<DateRange> {
+    if let Some(year) = parse_year(s) {
+        return year_range(year);
+    }
+
+    let tokens: Vec<&str> = s
+        .split(['-', '/', ' ', ','])
+        .filter(|t| !t.is_empty())
+        .collect();
+    let tokens = match tokens.as_slice() {
+        // "2023q4" / "q42023"
+        [t] if t.len() == 6 => {
+            let (a, b) = if t.starts_with('q') { t.split_at(2) } else { t.split_at(4) };
+            vec![a, b]
+        }
+        _ => tokens,
+    };
+    let [a, b] = tokens.as_slice() else {
+        return None;
+    };
+
+    let quarter = |t: &str| {
+        t.strip_prefix('q')
+            .and_then(|q| q.parse::<u8>().ok())
+            .filter(|q| (1..=4).contains(q))
+    };
+    if let (Some(year), Some(q)) = (parse_year(a), quarter(b)) {
+        return quarter_range(year, q);
+    }
+    if let (Some(q), Some(year)) = (quarter(a), parse_year(b)) {
+        return quarter_range(year, q);
+    }
+
+    let month = |t: &str| {
+        t.parse::<u32>()
+            .ok()
+            .filter(|m| (1..=12).contains(m) && t.len() <= 2)
+            .or_else(|| parse_month(t))
+    };
+    if let (Some(year), Some(m)) = (parse_year(a), month(b)) {
+        return month_range(year, m);
+    }
+    if let (Some(m), Some(year)) = (month(a), parse_year(b)) {
+        return month_range(year, m);
+    }
+    None
+}
+
+pub(crate) fn parse_exact(s: &str) -> Option<NaiveDate> {
+    // Timestamps like 2024-03-31T00:00:00Z
+    let s = match s.split_once('T') {
+        Some((date, _)) if date.len() == 10 => date,
+        _ => s,
+    };
+    // The year range check stops "March 2024" parsing as March 20, year 24
+    EXACT_FORMATS
+        .iter()
+        .filter_map(|format| NaiveDate::parse_from_str(s, format).ok())
+        .find(|date| (1900..=2100).contains(&date.year()))
+}
+
+/// Resolve a date expression to the range it covers
+pub fn parse_date_range(input: &str, today: NaiveDate) -> Result<DateRange, String> {
+    let trimmed = input.trim();
+    let lower = trimmed.to_ascii_lowercase();
+    let lower = lower.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    parse_exact(trimmed)
+        .map(DateRange::day)
+        .or_else(|| parse_relative(&lower, today))
+        .or_else(|| parse_period(&lower))
+        .ok_or_else(|| {
+            format!(
+                "Unrecognized date '{}'; use YYYY-MM-DD, a year (2023), a quarter (2023-Q4), \
+                 or a relative date (last quarter, past 90 days)",
+                input
+            )
+        })
+}
+
+/// Normalize a date expression to `YYYY-MM-DD`
+pub fn normalize_date(input: &str, bound: DateBound, today: NaiveDate) -> Result<String, String> {
+    parse_date_range(input, today).map(|range| range.bound(bound).format("%Y-%m-%d").to_string())
+}
+
+/// Resolve a quarter expression ("Q3", "2023-Q4", "last quarter", a date) to
+/// `(year, quarter)`. A bare "Q3" has no year, so the year is None.
+pub fn normalize_quarter(input: &str, today: NaiveDate) -> Result<(Option<i32>, u8), String> {
+    let lower = input.trim().to_ascii_lowercase();
+    if let Some(q) = lower
+        .strip_prefix('q')
+        .and_then(|q| q.parse::<u8>().ok())
+        .filter(|q| (1..=4).contains(q))
+    {
+        return Ok((None, q));
+    }
+    let range = parse_date_range(input, today)?;
+    let (year, quarter) = quarter_of(range.end);
+    Ok((Some(year), quarter))
+}
+
+/// A company identifier as given by the caller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompanyId {
+    /// 10-digit, zero-padded CIK
+    Cik(String),
+    /// Uppercase ticker symbol
+    Ticker(String),
+}
+
+/// Pad a CIK to 10 digits, accepting a "CIK" prefix and surrounding whitespace
+pub fn normalize_cik(input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    let digits = trimmed
+        .strip_prefix("CIK")
+        .or_else(|| trimmed.strip_prefix("cik"))
+        .unwrap_or(trimmed)
+        .trim_start_matches([':', ' ']);
+    if digits.is_empty() || digits.len() > 10 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("Invalid CIK '{}'; expected up to 10 digits", input));
+    }
+    Ok(format!("{:0>10}", digits))
+}
+
+/// Trim and uppercase a ticker, dropping a leading '$'
+pub fn normalize_ticker(input: &str) -> String {
+    input.trim().trim_start_matches('$').to_uppercase()
+}
+
+/// Classify an identifier as a CIK (all digits) or a ticker
+pub fn parse_company_id(input: &str) -> Result<CompanyId, String> {
+    if let Ok(cik) = normalize_cik(input) {
+        return Ok(CompanyId::Cik(cik));
+    }
+    let ticker = normalize_ticker(input);
+    let valid = !ticker.is_empty()
+        && ticker.len() <= 12
+        && ticker
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'));
+    if valid {
+        Ok(CompanyId::Ticker(ticker))
+    } else {
+        Err(format!("Invalid company identifier '{}'; use a CIK or ticker", input))
+    }
+}
+
+/// Look up a ticker in SEC's mapping, which writes class shares with '-'
+/// ("BRK-B") where callers often use '.' ("BRK.B")
+pub fn lookup_ticker<'a>(tickers: &'a HashMap<String, String>, ticker: &str) -> Option<&'a str> {
+    let symbol = ticker.split(':').next().unwrap_or(ticker);
+    tickers
+        .get(symbol)
+        .or_else(|| tickers.get(&symbol.replace('.', "-")))
+        .map(String::as_str)
+}
+
+/// Canonical dashed accession number (`0001193125-23-123456`), accepting
+/// the 18 digits with or without dashes
+pub fn normalize_accession(input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    let digits: String = trimmed.chars().filter(|c| *c != '-').collect();
+    let dashes_ok = !trimmed.contains('-')
+        || (trimmed.len() == 20 && trimmed.find('-') == Some(10) && trimmed.rfind('-') == Some(13));
+    if digits.len() != 18 || !digits.bytes().all(|b| b.is_ascii_digit()) || !dashes_ok {
+        return Err(format!(
+            "Invalid accession number '{}'; expected 18 digits like 0001193125-23-123456",
+            input
+        ));
+    }
+    Ok(format!("{}-{}-{}", &digits[..10], &digits[10..12], &digits[12..]))
+}
+
+/// Accession number without dashes, as used in EDGAR archive paths
+pub fn accession_path(input: &str) -> Result<String, String> {
+    normalize_accession(input).map(|a| a.replace('-', ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        date(2024, 5, 15)
+    }
+
+    fn start(s: &str) -> String {
+        normalize_date(s, DateBound::Start, today()).unwrap()
+    }
+
+    fn end(s: &str) -> String {
+        normalize_date(s, DateBound::End, today()).unwrap()
+    }
+
+    #[test]
+    fn test_exact_formats() {
+        for input in [
+            "2024-03-31",
+            "2024/03/31",
+            "03/31/2024",
+            "20240331",
+            "March 31, 2024",
+            "Mar 31 2024",
+            "31 March 2024",
+            "2024-03-31T12:00:00Z",
+            "  2024-03-31 ",
+        ] {
+            assert_eq!(start(input), "2024-03-31", "{}", input);
+            assert_eq!(end(input), "2024-03-31", "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_periods() {
+        assert_eq!((start("2023"), end("2023")), ("2023-01-01".into(), "2023-12-31".into()));
+        for input in ["2023-Q4", "Q4 2023", "2023q4", "q4-2023"] {
+            assert_eq!(start(input), "2023-10-01", "{}", input);
+            assert_eq!(end(input), "2023-12-31", "{}", input);
+        }
+        assert_eq!(end("2024-02"), "2024-02-29");
+        assert_eq!(start("March 2024"), "2024-03-01");
+        assert_eq!(end("Sep 2023"), "2023-09-30");
+    }
+
+    #[test]
+    fn test_relative() {
+        assert_eq!(end("today"), "2024-05-15");
+        assert_eq!(end("yesterday"), "2024-05-14");
+        assert_eq!((start("last quarter"), end("last quarter")), ("2024-01-01".into(), "2024-03-31".into()));
+        assert_eq!(start("this quarter"), "2024-04-01");
+        assert_eq!(end("this quarter"), "2024-05-15");
+        assert_eq!(end("last year"), "2023-12-31");
+        assert_eq!(start("YTD"), "2024-01-01");
+        assert_eq!(start("last month"), "2024-04-01");
+        assert_eq!(start("past 90 days"), "2024-02-15");
+        assert_eq!(end("past 90 days"), "2024-05-15");
+        assert_eq!(start("last 2 weeks"), "2024-05-01");
+        assert_eq!(start("past  6 months"), "2023-11-15");
+        assert_eq!(end("1 year ago"), "2023-05-15");
+    }
+
+    #[test]
+    fn test_last_quarter_wraps_year() {
+        let range = parse_date_range("last quarter", date(2024, 2, 1)).unwrap();
+        assert_eq!(range.start, date(2023, 10, 1));
+        assert_eq!(range.end, date(2023, 12, 31));
+    }
+
+    #[test]
+    fn test_unrecognized() {
+        for input in ["", "soon", "2024-13-01", "Q5 2023", "past many days"] {
+            let err = parse_date_range(input, today()).unwrap_err();
+            assert!(err.contains("YYYY-MM-DD"), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_count() {
+        for unit in ["days", "weeks", "months", "quarters", "years"] {
+            for input in [format!("past 999999999 {}", unit), format!("4294967295 {} ago", unit)] {
+                let err = parse_date_range(&input, today()).unwrap_err();
+                assert!(err.contains("YYYY-MM-DD"), "{}", input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize_quarter() {
+        assert_eq!(normalize_quarter("Q3", today()), Ok((None, 3)));
+        assert_eq!(normalize_quarter("2023-Q4", today()), Ok((Some(2023), 4)));
+        assert_eq!(normalize_quarter("last quarter", today()), Ok((Some(2024), 1)));
+        assert_eq!(normalize_quarter("2023-08-15", today()), Ok((Some(2023), 3)));
+        assert!(normalize_quarter("Q7", today()).is_err());
+    }
+
+    #[test]
+    fn test_normalize_cik() {
+        assert_eq!(normalize_cik("320193"), Ok("0000320193".to_string()));
+        assert_eq!(normalize_cik(" CIK0000320193 "), Ok("0000320193".to_string()));
+        assert_eq!(normalize_cik("cik: 320193"), Ok("0000320193".to_string()));
+        assert!(normalize_cik("AAPL").is_err());
+        assert!(normalize_cik("12345678901").is_err());
+        assert!(normalize_cik("").is_err());
+    }
+
+    #[test]
+    fn test_parse_company_id() {
+        assert_eq!(parse_company_id("320193"), Ok(CompanyId::Cik("0000320193".into())));
+        assert_eq!(parse_company_id(" aapl "), Ok(CompanyId::Ticker("AAPL".into())));
+        assert_eq!(parse_company_id("$brk.b"), Ok(CompanyId::Ticker("BRK.B".into())));
+        assert!(parse_company_id("not a ticker").is_err());
+    }
+
+    #[test]
+    fn test_lookup_ticker() {
+        let tickers = HashMap::from([
+            ("AAPL".to_string(), "0000320193".to_string()),
+            ("BRK-B".to_string(), "0001067983".to_string()),
+        ]);
+        assert_eq!(lookup_ticker(&tickers, "AAPL"), Some("0000320193"));
+        assert_eq!(lookup_ticker(&tickers, "AAPL:NASDAQ"), Some("0000320193"));
+        assert_eq!(lookup_ticker(&tickers, "BRK.B"), Some("0001067983"));
+        assert_eq!(lookup_ticker(&tickers, "MSFT"), None);
+    }
+
+    #[test]
+    fn test_normalize_accession() {
+        for input in ["0001193125-23-123456", "000119312523123456", " 0001193125-23-123456\n"] {
+            assert_eq!(normalize_accession(input).unwrap(), "0001193125-23-123456");
+            assert_eq!(accession_path(input).unwrap(), "000119312523123456");
+        }
+        for input in ["", "12345", "0001193125-23-12345X", "00011931-2523-123456", "0001193125-23-1234567"] {
+            assert!(normalize_accession(input).unwrap_err().contains("18 digits"), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! User-defined tools.
+//!
+//! A `plugins.json` file beside the config file can add tools the server
+//! doesn't ship with. Each has a name, description, category, and input
+//! schema like a built-in tool, and one of:
+//!
+//! - `endpoint`: a FilingExplorer API path such as `companies/{cik}/peers`.
+//!   Placeholders are filled from the call's arguments and the other
+//!   arguments in the input schema are sent as query parameters.
+//! - `command`: a program and its arguments, run with the call's arguments
+//!   as JSON on stdin. What it prints to stdout is the result.
+//!
+//! Plugins are installed into the tool registry once at startup, so search,
+//! listing, and argument validation treat them like any other tool.
+
+use crate::tools::registry::{self, Category, Tool};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+
+/// File in the config dir that defines plugins
+const PLUGINS_FILE_NAME: &str = "plugins.json";
+
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("Failed to read plugins file: {0}")]
+    ReadError(#[from] io::Error),
+
+    #[error("Failed to parse plugins file: {0}")]
+    ParseError(#[from] serde_json::Error),
+
+    #[error("Plugin '{0}' has the same name as another tool")]
+    DuplicateName(String),
+
+    #[error("Plugins are already installed")]
+    AlreadyInstalled,
+
+    #[error("Failed to run {command}: {source}")]
+    SpawnFailed { command: String, source: io::Error },
+
+    #[error("{command} failed ({status}): {stderr}")]
+    CommandFailed {
+        command: String,
+        status: String,
+        stderr: String,
+    },
+}
+
+/// How a plugin produces its result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginTarget {
+    /// FilingExplorer API path, with `{argument}` placeholders
+    Endpoint(String),
+    /// Program and its arguments
+    Command(Vec<String>),
+}
+
+/// A tool defined in `plugins.json`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "PluginSpec")]
+pub struct PluginTool {
+    pub name: String,
+    pub description: String,
+    pub category: Category,
+    pub keywords: Vec<String>,
+    pub examples: Vec<String>,
+    pub tags: Vec<String>,
+    pub input_schema: Value,
+    pub target: PluginTarget,
+}
+
+/// A plugin as written in the file, before it's checked
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginSpec {
+    name: String,
+    description: String,
+    category: Category,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    examples: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    input_schema: Option<Value>,
+    #[serde(default)]
+    endpoint: Option<String>,
+    #[serde(default)]
+    command: Option<Vec<String>>,
+}
+
+impl TryFrom<PluginSpec> for PluginTool {
+    type Error = String;
+
+    fn try_from(spec: PluginSpec) -> Result<Self, String> {
+        let name = spec.name;
+        let invalid = |reason: &str| format!("plugin '{}' {}", name, reason);
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            return Err(invalid("must be named with lowercase letters, digits, and underscores"));
+        }
+        if let Some(tag) = spec.tags.iter().find(|tag| !registry::TAGS.iter().any(|(known, _)| known == tag)) {
+            return Err(invalid(&format!("has unknown tag \"{}\"", tag)));
+        }
+        let input_schema = spec
+            .input_schema
+            .unwrap_or_else(|| json!({ "type": "object", "properties": {} }));
+        if input_schema.get("type").and_then(Value::as_str) != Some("object") {
+            return Err(invalid("needs an input_schema with \"type\": \"object\""));
+        }
+        let target = match (spec.endpoint, spec.command) {
+            (Some(endpoint), None) => {
+                let properties = input_schema.get("properties").and_then(Value::as_object);
+                if let Some(missing) = placeholders(&endpoint)
+                    .find(|p| !properties.is_some_and(|properties| properties.contains_key(*p)))
+                {
+                    return Err(invalid(&format!(
+                        "uses {{{}}} in its endpoint, which isn't in its input_schema",
+                        missing
+                    )));
+                }
+                PluginTarget::Endpoint(endpoint)
+            }
+            (None, Some(command)) if !command.is_empty() => PluginTarget::Command(command),
+            (None, Some(_)) => return Err(invalid("has an empty command")),
+            _ => return Err(invalid("needs either an endpoint or a command")),
+        };
+        Ok(Self {
+            name,
+            description: spec.description,
+            category: spec.category,
+            keywords: spec.keywords,
+            examples: spec.examples,
+            tags: spec.tags,
+            input_schema,
+            target,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct PluginFile {
+    tools: Vec<PluginTool>,
+}
+
+impl PluginTool {
+    /// The API path for the plugin's endpoint `template` with its
+    /// placeholders filled in, and the other arguments in the input schema
+    /// as query parameters. Fails with the name of a placeholder argument
+    /// that wasn't given.
+    pub fn endpoint_request(&self, template: &str, args: &Value) -> Result<(String, HashMap<String, String>), String> {
+        let argument = |name: &str| args.get(name).filter(|v| !v.is_null()).map(query_value);
+
+        let mut path = template.to_string();
+        let used: Vec<&str> = placeholders(template).collect();
+        for name in &used {
+            let value = argument(name).ok_or_else(|| name.to_string())?;
+            path = path.replace(&format!("{{{}}}", name), &encode_segment(&value));
+        }
+
+        let params = self
+            .input_schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|properties| properties.keys())
+            .filter(|name| !used.contains(&name.as_str()))
+            .filter_map(|name| Some((name.clone(), argument(name)?)))
+            .collect();
+        Ok((path, params))
+    }
+
+    /// Run the plugin's command `argv` with `args` as JSON on stdin,
+    /// returning what it printed
+    pub async fn run_command(argv: &[String], args: &Value) -> Result<String, PluginError> {
+        let command = argv[0].clone();
+        let mut child = tokio::process::Command::new(&command)
+            .args(&argv[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|source| PluginError::SpawnFailed {
+                command: command.clone(),
+                source,
+            })?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A command that doesn't read its input closes the pipe early
+            let _ = stdin.write_all(args.to_string().as_bytes()).await;
+        }
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|source| PluginError::SpawnFailed {
+                command: command.clone(),
+                source,
+            })?;
+        if !output.status.success() {
+            return Err(PluginError::CommandFailed {
+                command,
+                status: output.status.to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+    }
+
+    /// Registry entry for the plugin. Plugins are installed once and live
+    /// as long as the process, so their text is leaked to match the
+    /// built-in tools' static lifetime.
+    fn tool(&self) -> Tool {
+        fn leak(s: &str) -> &'static str {
+            Box::leak(s.to_string().into_boxed_str())
+        }
+        fn leak_all(list: &[String]) -> &'static [&'static str] {
+            Box::leak(list.iter().map(|s| leak(s)).collect::<Vec<_>>().into_boxed_slice())
+        }
+        Tool {
+            name: leak(&self.name),
+            category: self.category,
+            description: leak(&self.description),
+            keywords: leak_all(&self.keywords),
+            examples: leak_all(&self.examples),
+            tags: leak_all(&self.tags),
+            input_schema_json: leak(&self.input_schema.to_string()),
+            output_schema_json: None,
+        }
+    }
+}
+
+/// `{name}` placeholders in an endpoint template
+fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+}
+
+/// An argument as a query or path value; lists are comma-separated
+fn query_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(query_value).collect::<Vec<_>>().join(","),
+        other => other.to_string(),
+    }
+}
+
+/// Percent-encode a value for use as one path segment
+fn encode_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// The plugins file beside the config file at `config_path`
+pub fn path_beside(config_path: &Path) -> PathBuf {
+    config_path.parent().unwrap_or(Path::new(".")).join(PLUGINS_FILE_NAME)
+}
+
+/// Plugins defined in the file at `path`; none if there is no file
+pub fn load(path: &Path) -> Result<Vec<PluginTool>, PluginError> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let file: PluginFile = serde_json::from_str(&contents)?;
+    for (i, plugin) in file.tools.iter().enumerate() {
+        if file.tools[..i].iter().any(|other| other.name == plugin.name) {
+            return Err(PluginError::DuplicateName(plugin.name.clone()));
+        }
+    }
+    Ok(file.tools)
+}
+
+static INSTALLED: OnceLock<Vec<PluginTool>> = OnceLock::new();
+
+/// Add plugins to the tool registry. Done once, at startup.
+pub fn install(plugins: Vec<PluginTool>) -> Result<(), PluginError> {
+    if let Some(plugin) = plugins.iter().find(|p| registry::tool_exists(&p.name)) {
+        return Err(PluginError::DuplicateName(plugin.name.clone()));
+    }
+    if INSTALLED.get().is_some() || !registry::register_tools(plugins.iter().map(PluginTool::tool).collect()) {
+        return Err(PluginError::AlreadyInstalled);
+    }
+    let _ = INSTALLED.set(plugins);
+    Ok(())
+}
+
+/// An installed plugin by name
+pub fn get(name: &str) -> Option<&'static PluginTool> {
+    INSTALLED.get()?.iter().find(|p| p.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: Value) -> Result<PluginTool, serde_json::Error> {
+        serde_json::from_value(json)
+    }
+
+    fn peers() -> PluginTool {
+        parse(json!({
+            "name": "get_company_peers",
+            "description": "Companies in the same industry",
+            "category": "company_data",
+            "input_schema": {
+                "type": "object",
+                "properties": {
+                    "cik": { "type": "string" },
+                    "limit": { "type": "integer" },
+                    "forms": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["cik"]
+            },
+            "endpoint": "companies/{cik}/peers"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_plugin() {
+        let plugin = peers();
+        assert_eq!(plugin.category, Category::CompanyData);
+        assert_eq!(plugin.target, PluginTarget::Endpoint("companies/{cik}/peers".to_string()));
+
+        let plugin = parse(json!({
+            "name": "score_filing",
+            "description": "Score a filing with a local model",
+            "category": "sec_documents",
+            "command": ["python3", "score.py"]
+        }))
+        .unwrap();
+        assert_eq!(plugin.target, PluginTarget::Command(vec!["python3".into(), "score.py".into()]));
+        assert_eq!(plugin.input_schema["type"], "object");
+    }
+
+    #[test]
+    fn test_parse_invalid_plugins() {
+        let base = json!({ "name": "my_tool", "description": "d", "category": "company_data" });
+        let with = |extra: Value| {
+            let mut spec = base.clone();
+            spec.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+            parse(spec).unwrap_err().to_string()
+        };
+        assert!(with(json!({})).contains("either an endpoint or a command"));
+        assert!(with(json!({ "endpoint": "a", "command": ["b"] })).contains("either an endpoint or a command"));
+        assert!(with(json!({ "command": [] })).contains("empty command"));
+        assert!(with(json!({ "endpoint": "companies/{cik}" })).contains("{cik}"));
+        assert!(with(json!({ "name": "My Tool", "endpoint": "a" })).contains("lowercase"));
+        assert!(with(json!({ "endpoint": "a", "input_schema": { "type": "string" } })).contains("type"));
+        assert!(with(json!({ "category": "weather", "endpoint": "a" })).contains("unknown variant"));
+        assert!(with(json!({ "tags": ["fast"], "endpoint": "a" })).contains("unknown tag \"fast\""));
+    }
+
+    #[test]
+    fn test_endpoint_request() {
+        let plugin = peers();
+        let template = "companies/{cik}/peers";
+        let (path, params) = plugin
+            .endpoint_request(template, &json!({ "cik": "0000 320193", "limit": 5, "forms": ["10-K", "10-Q"], "other": 1 }))
+            .unwrap();
+        assert_eq!(path, "companies/0000%20320193/peers");
+        assert_eq!(params.len(), 2);
+        assert_eq!(params["limit"], "5");
+        assert_eq!(params["forms"], "10-K,10-Q");
+
+        assert_eq!(plugin.endpoint_request(template, &json!({ "limit": 5 })).unwrap_err(), "cik");
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = path_beside(&dir.path().join("config.json"));
+        assert!(load(&path).unwrap().is_empty());
+
+        let plugin = json!({ "name": "a_tool", "description": "d", "category": "lobbying", "endpoint": "a" });
+        std::fs::write(&path, json!({ "tools": [plugin] }).to_string()).unwrap();
+        assert_eq!(load(&path).unwrap()[0].name, "a_tool");
+
+        std::fs::write(&path, json!({ "tools": [plugin, plugin] }).to_string()).unwrap();
+        assert!(matches!(load(&path), Err(PluginError::DuplicateName(name)) if name == "a_tool"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_command() {
+        let argv = |command: &[&str]| command.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let output = PluginTool::run_command(&argv(&["cat"]), &json!({ "cik": "320193" })).await.unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&output).unwrap(), json!({ "cik": "320193" }));
+
+        let error = PluginTool::run_command(&argv(&["sh", "-c", "echo broken >&2; exit 3"]), &json!({})).await;
+        assert!(matches!(error, Err(PluginError::CommandFailed { stderr, .. }) if stderr == "broken"));
+        let error = PluginTool::run_command(&argv(&["/nonexistent/plugin"]), &json!({})).await;
+        assert!(matches!(error, Err(PluginError::SpawnFailed { .. })));
+    }
+}
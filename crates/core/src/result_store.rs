@@ -0,0 +1,166 @@
+//! On-disk copies of tool results for offline use.
+//!
+//! The MCP server saves the last successful result of each read-only call
+//! under the cache directory. When the FilingExplorer API or SEC EDGAR can't
+//! be reached, the saved copy is returned instead of an error, marked with
+//! when it was saved, so a flaky connection or a demo without network still
+//! gets answers. Entries are keyed by tool and normalized arguments; the
+//! least recently used are evicted beyond [`MAX_STORED_RESULTS`].
+
+use crate::config::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Subdirectory of the cache dir that holds saved results
+const RESULT_STORE_DIR: &str = "results";
+
+/// Results kept before the least recently used are evicted
+pub const MAX_STORED_RESULTS: usize = 500;
+
+/// A saved result and when it was saved
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredResult {
+    /// The call the result answers, to tell apart keys that hash alike
+    key: String,
+    /// RFC 3339 time the result was saved
+    pub cached_at: String,
+    pub result: String,
+}
+
+pub struct ResultStore {
+    dir: PathBuf,
+}
+
+impl ResultStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The store in the platform cache directory
+    pub fn open_default() -> Result<Self, ConfigError> {
+        Ok(Self::new(Config::cache_dir()?.join(RESULT_STORE_DIR)))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// File for a key, named by a hash that is stable across builds
+    fn path(&self, key: &str) -> PathBuf {
+        let hash = key
+            .bytes()
+            .fold(0xcbf29ce484222325_u64, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3));
+        self.dir.join(format!("{:016x}.json", hash))
+    }
+
+    /// The saved result for `key`, or None if there isn't one
+    pub fn get(&self, key: &str) -> Option<StoredResult> {
+        let path = self.path(key);
+        let stored: StoredResult = serde_json::from_slice(&fs::read(&path).ok()?).ok()?;
+        if stored.key != key {
+            return None;
+        }
+        // Touch so eviction sees the entry as recently used
+        if let Ok(file) = fs::File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(stored)
+    }
+
+    /// Save a result, evicting the least recently used beyond
+    /// [`MAX_STORED_RESULTS`]
+    pub fn put(&self, key: &str, result: &str) -> io::Result<()> {
+        let stored = StoredResult {
+            key: key.to_string(),
+            cached_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            result: result.to_string(),
+        };
+        fs::create_dir_all(&self.dir)?;
+        // Through a temporary file so a concurrent reader never sees half a result
+        let path = self.path(key);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(&stored)?)?;
+        fs::rename(&tmp, &path)?;
+        self.evict(MAX_STORED_RESULTS)
+    }
+
+    fn entries(&self) -> Vec<(SystemTime, PathBuf)> {
+        fs::read_dir(&self.dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let modified = entry.metadata().ok()?.modified().ok()?;
+                Some((modified, entry.path()))
+            })
+            .filter(|(_, path)| path.extension().is_some_and(|ext| ext == "json"))
+            .collect()
+    }
+
+    fn evict(&self, keep: usize) -> io::Result<()> {
+        let mut entries = self.entries();
+        if entries.len() <= keep {
+            return Ok(());
+        }
+        entries.sort();
+        for (_, path) in &entries[..entries.len() - keep] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Remove every saved result, returning how many there were
+    pub fn clear(&self) -> io::Result<usize> {
+        let count = self.entries().len();
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_get_and_put() {
+        let dir = TempDir::new().unwrap();
+        let store = ResultStore::new(dir.path().join("results"));
+        assert_eq!(store.get("get_list:{}"), None);
+
+        store.put("get_list:{}", "first").unwrap();
+        store.put("get_list:{}", "second").unwrap();
+        store.put("get_lists:{}", "lists").unwrap();
+        let stored = store.get("get_list:{}").unwrap();
+        assert_eq!(stored.result, "second");
+        assert!(chrono::DateTime::parse_from_rfc3339(&stored.cached_at).is_ok());
+        assert_eq!(store.get("get_lists:{}").unwrap().result, "lists");
+
+        // A file holding another key's result is a miss
+        fs::copy(store.path("get_lists:{}"), store.path("get_list:{}")).unwrap();
+        assert_eq!(store.get("get_list:{}"), None);
+
+        assert_eq!(store.clear().unwrap(), 2);
+        assert_eq!(store.clear().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let dir = TempDir::new().unwrap();
+        let store = ResultStore::new(dir.path());
+        for (i, key) in ["a", "b", "c"].iter().enumerate() {
+            store.put(key, "x").unwrap();
+            let file = fs::File::options().append(true).open(store.path(key)).unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(i as u64 + 1)).unwrap();
+        }
+        store.evict(2).unwrap();
+        assert_eq!(store.get("a"), None);
+        assert!(store.get("b").is_some());
+        assert!(store.get("c").is_some());
+    }
+}
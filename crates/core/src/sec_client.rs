@@ -3,16 +3,35 @@
 //! Handles direct requests to SEC EDGAR with proper User-Agent headers
 //! and rate limiting (max 10 requests per second per SEC fair access policy).
 
+use crate::call_stats::{self, Source};
+use crate::cancellation;
+use crate::config::Config;
 use governor::{Quota, RateLimiter};
-use reqwest::{Client, StatusCode};
-use std::num::NonZeroU32;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tracing::{Instrument, Span};
 
 /// SEC EDGAR base URL
 const SEC_BASE_URL: &str = "https://www.sec.gov/Archives/edgar/data";
 
+/// SEC ticker to CIK mapping for all listed companies
+const SEC_TICKERS_URL: &str = "https://www.sec.gov/files/company_tickers.json";
+
+/// SEC ticker to series/class mapping for mutual funds and ETFs
+const SEC_FUND_TICKERS_URL: &str = "https://www.sec.gov/files/company_tickers_mf.json";
+
+/// EDGAR full-text search (filings from 2001 onwards)
+const SEC_FULL_TEXT_SEARCH_URL: &str = "https://efts.sec.gov/LATEST/search-index";
+
+/// Per-company filing history, one `CIK##########.json` file per filer
+const SEC_SUBMISSIONS_URL: &str = "https://data.sec.gov/submissions";
+
 /// Default request timeout in seconds
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
@@ -24,8 +43,8 @@ pub enum SecError {
     #[error("HTTP request failed: {0}")]
     RequestError(#[from] reqwest::Error),
 
-    #[error("SEC access not configured. Please set your email in settings.")]
-    NotConfigured,
+    #[error("SEC access not configured: {0}. Please set your name and email in settings.")]
+    NotConfigured(SecConfigIssue),
 
     #[error("Document not found at SEC")]
     NotFound,
@@ -35,6 +54,66 @@ pub enum SecError {
 
     #[error("Rate limit exceeded")]
     RateLimited,
+
+    #[error("{0}")]
+    InvalidInput(String),
+
+    #[error("Request cancelled")]
+    Cancelled,
+}
+
+/// What keeps the SEC User-Agent settings from being usable
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SecConfigIssue {
+    #[error("name and email are not set")]
+    MissingNameAndEmail,
+
+    #[error("name is not set")]
+    MissingName,
+
+    #[error("email is not set")]
+    MissingEmail,
+
+    #[error("name '{0}' must be plain ASCII text")]
+    InvalidName(String),
+
+    #[error("'{0}' is not a valid contact email")]
+    InvalidEmail(String),
+}
+
+/// Whether `email` looks like a deliverable address: one `@`, a non-empty
+/// local part, and a dotted domain
+fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && email.chars().all(|c| c.is_ascii_graphic())
+}
+
+/// Build the User-Agent SEC expects, "Company Name admin@company.com",
+/// from the configured name and email
+pub fn sec_user_agent(name: Option<&str>, email: Option<&str>) -> Result<String, SecConfigIssue> {
+    let name = name.map(str::trim).filter(|n| !n.is_empty());
+    let email = email.map(str::trim).filter(|e| !e.is_empty());
+    let (name, email) = match (name, email) {
+        (Some(name), Some(email)) => (name, email),
+        (None, None) => return Err(SecConfigIssue::MissingNameAndEmail),
+        (None, Some(_)) => return Err(SecConfigIssue::MissingName),
+        (Some(_), None) => return Err(SecConfigIssue::MissingEmail),
+    };
+    // Header values are limited to visible ASCII and spaces
+    if !name.chars().all(|c| c == ' ' || c.is_ascii_graphic()) {
+        return Err(SecConfigIssue::InvalidName(name.to_string()));
+    }
+    if !is_valid_email(email) {
+        return Err(SecConfigIssue::InvalidEmail(email.to_string()));
+    }
+    Ok(format!("{} {}", name, email))
 }
 
 /// Content type detected from response
@@ -47,11 +126,70 @@ pub enum ContentType {
     Unknown,
 }
 
-/// SEC EDGAR client with rate limiting
+/// A fund share class from SEC's fund ticker mapping
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FundClass {
+    /// Registrant (trust) CIK, 10 digits
+    pub cik: String,
+    /// N-PORT series identifier (e.g. "S000004310")
+    pub series_id: String,
+    /// Share class identifier (e.g. "C000012062")
+    pub class_id: String,
+    pub symbol: Option<String>,
+}
+
+/// Result of a burst of test requests against SEC EDGAR
+#[derive(Debug, Clone, Serialize)]
+pub struct RateProbe {
+    pub requests: usize,
+    pub elapsed_ms: u64,
+    /// Requests per second over the whole burst
+    pub average_rate: f64,
+    /// Most requests sent within any one-second window
+    pub peak_requests_per_second: usize,
+    pub limit_per_second: u32,
+    pub user_agent: String,
+    /// Response count per HTTP status
+    pub statuses: BTreeMap<u16, usize>,
+    /// Requests that failed without a response
+    pub failures: usize,
+}
+
+impl RateProbe {
+    /// Whether the burst stayed within SEC's rate limit and EDGAR accepted
+    /// the User-Agent (403 means rejected, 429 means throttled)
+    pub fn is_compliant(&self) -> bool {
+        self.peak_requests_per_second <= self.limit_per_second as usize
+            && self.failures == 0
+            && !self.statuses.contains_key(&403)
+            && !self.statuses.contains_key(&429)
+    }
+}
+
+/// Most timestamps falling within any one-second window
+fn peak_per_second(mut sent: Vec<Instant>) -> usize {
+    sent.sort();
+    let mut start = 0;
+    let mut peak = 0;
+    for end in 0..sent.len() {
+        while sent[end].duration_since(sent[start]) >= Duration::from_secs(1) {
+            start += 1;
+        }
+        peak = peak.max(end - start + 1);
+    }
+    peak
+}
+
+/// SEC EDGAR client with rate limiting. Clones share the rate limiter.
+#[derive(Clone)]
 pub struct SecClient {
     client: Client,
     user_agent: String,
     base_url: String,
+    tickers_url: String,
+    fund_tickers_url: String,
+    full_text_search_url: String,
+    submissions_url: String,
     rate_limiter: Arc<RateLimiter<governor::state::NotKeyed, governor::state::InMemoryState, governor::clock::DefaultClock>>,
 }
 
@@ -61,8 +199,21 @@ impl SecClient {
     /// Per SEC fair access policy, the User-Agent should identify
     /// your organization and include a contact email.
     pub fn new(user_agent_name: &str, user_agent_email: &str) -> Result<Self, SecError> {
-        let user_agent = format!("{} {}", user_agent_name, user_agent_email);
+        Self::with_user_agent(format!("{} {}", user_agent_name, user_agent_email))
+    }
+
+    /// Create a client from the SEC identity in `config`, failing with
+    /// [`SecError::NotConfigured`] if it is missing or malformed
+    pub fn from_config(config: &Config) -> Result<Self, SecError> {
+        let user_agent = sec_user_agent(
+            config.sec_user_agent_name.as_deref(),
+            config.sec_user_agent_email.as_deref(),
+        )
+        .map_err(SecError::NotConfigured)?;
+        Self::with_user_agent(user_agent)
+    }
 
+    fn with_user_agent(user_agent: String) -> Result<Self, SecError> {
         let client = Client::builder()
             .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .gzip(true)
@@ -70,18 +221,29 @@ impl SecClient {
             .build()
             .map_err(SecError::RequestError)?;
 
-        // Rate limiter: 10 requests per second
-        let quota = Quota::per_second(NonZeroU32::new(SEC_RATE_LIMIT_PER_SECOND).unwrap());
+        // Rate limiter: one request every 100ms. A per-second quota would
+        // allow a burst of 10 on top of the steady rate, up to 19 requests
+        // in one second.
+        let quota = Quota::with_period(Duration::from_secs(1) / SEC_RATE_LIMIT_PER_SECOND).unwrap();
         let rate_limiter = Arc::new(RateLimiter::direct(quota));
 
         Ok(Self {
             client,
             user_agent,
             base_url: SEC_BASE_URL.to_string(),
+            tickers_url: SEC_TICKERS_URL.to_string(),
+            fund_tickers_url: SEC_FUND_TICKERS_URL.to_string(),
+            full_text_search_url: SEC_FULL_TEXT_SEARCH_URL.to_string(),
+            submissions_url: SEC_SUBMISSIONS_URL.to_string(),
             rate_limiter,
         })
     }
 
+    /// The User-Agent sent with every request
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
     /// Create a client with a custom base URL (for testing)
     #[allow(dead_code)]
     pub fn with_base_url(
@@ -106,12 +268,12 @@ impl SecClient {
         accession_number: &str,
         filename: Option<&str>,
     ) -> Result<(Vec<u8>, ContentType), SecError> {
-        // Wait for rate limiter
-        self.rate_limiter.until_ready().await;
+        let accession_number = crate::params::normalize_accession(accession_number)
+            .map_err(SecError::InvalidInput)?;
 
         // Build URL
         // CIK without leading zeros, accession number without dashes
-        let cik_stripped = cik.trim_start_matches('0');
+        let cik_stripped = cik.trim().trim_start_matches('0');
         let accession_no_dashes = accession_number.replace('-', "");
 
         let url = match filename {
@@ -126,11 +288,7 @@ impl SecClient {
         };
 
         let response = self
-            .client
-            .get(&url)
-            .header("User-Agent", &self.user_agent)
-            .header("Accept-Encoding", "gzip, deflate")
-            .send()
+            .send(self.client.get(&url).header("Accept-Encoding", "gzip, deflate"))
             .await?;
 
         let status = response.status();
@@ -157,11 +315,201 @@ impl SecClient {
         // Detect content type from headers
         let content_type = self.detect_content_type(&response, filename);
 
-        let bytes = response.bytes().await?.to_vec();
+        let bytes = cancellation::guard(response.bytes())
+            .await
+            .map_err(|_| SecError::Cancelled)??
+            .to_vec();
 
         Ok((bytes, content_type))
     }
 
+    /// Fetch SEC's ticker to CIK mapping, keyed by uppercase ticker with
+    /// 10-digit CIKs
+    pub async fn fetch_company_tickers(&self) -> Result<HashMap<String, String>, SecError> {
+        #[derive(Deserialize)]
+        struct TickerEntry {
+            cik_str: u64,
+            ticker: String,
+        }
+
+        let entries: HashMap<String, TickerEntry> =
+            self.fetch_json(&self.tickers_url, "company tickers").await?;
+        Ok(entries
+            .into_values()
+            .map(|e| (e.ticker.to_uppercase(), format!("{:010}", e.cik_str)))
+            .collect())
+    }
+
+    /// Fetch SEC's mutual fund and ETF ticker mapping, one entry per share
+    /// class
+    pub async fn fetch_fund_tickers(&self) -> Result<Vec<FundClass>, SecError> {
+        /// cik, seriesId, classId, symbol
+        type Row = (u64, Option<String>, Option<String>, Option<String>);
+
+        #[derive(Deserialize)]
+        struct FundTickers {
+            data: Vec<Row>,
+        }
+
+        let tickers: FundTickers = self.fetch_json(&self.fund_tickers_url, "fund tickers").await?;
+        Ok(tickers
+            .data
+            .into_iter()
+            .filter_map(|(cik, series_id, class_id, symbol)| {
+                Some(FundClass {
+                    cik: format!("{:010}", cik),
+                    series_id: series_id?,
+                    class_id: class_id?,
+                    symbol: symbol.filter(|s| !s.is_empty()).map(|s| s.to_uppercase()),
+                })
+            })
+            .collect())
+    }
+
+    /// Run an EDGAR full-text search
+    ///
+    /// # Arguments
+    /// * `query` - Search terms; quoted phrases, OR and -exclusions are supported
+    /// * `cik` - Company CIK to restrict the search to
+    /// * `forms` - Form types to restrict the search to; empty for all
+    /// * `date_range` - Optional inclusive filing date range (YYYY-MM-DD)
+    pub async fn full_text_search(
+        &self,
+        query: &str,
+        cik: &str,
+        forms: &[String],
+        date_range: Option<(&str, &str)>,
+    ) -> Result<serde_json::Value, SecError> {
+        let mut params = vec![
+            ("q", query.to_string()),
+            ("ciks", format!("{:0>10}", cik.trim())),
+        ];
+        if !forms.is_empty() {
+            params.push(("forms", forms.join(",")));
+        }
+        if let Some((start, end)) = date_range {
+            params.push(("dateRange", "custom".to_string()));
+            params.push(("startdt", start.to_string()));
+            params.push(("enddt", end.to_string()));
+        }
+        let url = reqwest::Url::parse_with_params(&self.full_text_search_url, &params)
+            .map_err(|e| SecError::InvalidInput(e.to_string()))?;
+
+        self.fetch_json(url.as_str(), "full-text search results").await
+    }
+
+    /// Fetch a filer's EDGAR submissions: company details and its recent
+    /// filings as parallel arrays under `filings.recent`
+    pub async fn fetch_submissions(&self, cik: &str) -> Result<serde_json::Value, SecError> {
+        let url = format!("{}/CIK{:0>10}.json", self.submissions_url, cik.trim());
+        match self.fetch_json(&url, "company submissions").await {
+            Err(SecError::SecError { status: 404, .. }) => Err(SecError::NotFound),
+            result => result,
+        }
+    }
+
+    /// GET a JSON file from SEC, describing it as `what` in errors
+    async fn fetch_json<T: DeserializeOwned>(&self, url: &str, what: &str) -> Result<T, SecError> {
+        let response = self.send(self.client.get(url)).await?;
+
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(SecError::RateLimited);
+        }
+        if !status.is_success() {
+            return Err(SecError::SecError {
+                status: status.as_u16(),
+                message: format!("Failed to fetch {}", what),
+            });
+        }
+
+        cancellation::guard(response.json())
+            .await
+            .map_err(|_| SecError::Cancelled)?
+            .map_err(SecError::from)
+    }
+
+    /// Check that SEC EDGAR is reachable, returning the HTTP status received.
+    ///
+    /// Any HTTP response counts as reachable; a 403 usually means EDGAR
+    /// rejected the User-Agent.
+    pub async fn check_connectivity(&self) -> Result<u16, SecError> {
+        let response = self.send(self.client.head(format!("{}/", self.base_url))).await?;
+
+        Ok(response.status().as_u16())
+    }
+
+    /// Send `requests` HEAD requests to EDGAR at once through the rate
+    /// limiter, recording when each was sent and what came back, to check
+    /// the client complies with SEC's fair access policy
+    pub async fn probe_rate(&self, requests: usize) -> Result<RateProbe, SecError> {
+        let url = format!("{}/", self.base_url);
+        let started = Instant::now();
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..requests {
+            let client = self.clone();
+            let request = self.client.head(&url);
+            tasks.spawn(cancellation::scope(
+                cancellation::current().unwrap_or_default(),
+                async move { client.send_timed(request).await },
+            ));
+        }
+
+        let mut sent = Vec::with_capacity(requests);
+        let mut statuses = BTreeMap::new();
+        let mut failures = 0;
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok(Ok((at, response))) => {
+                    sent.push(at);
+                    *statuses.entry(response.status().as_u16()).or_insert(0) += 1;
+                }
+                Ok(Err(SecError::Cancelled)) => return Err(SecError::Cancelled),
+                Ok(Err(_)) | Err(_) => failures += 1,
+            }
+        }
+
+        let elapsed = started.elapsed();
+        Ok(RateProbe {
+            requests,
+            elapsed_ms: elapsed.as_millis() as u64,
+            average_rate: (requests as f64 / elapsed.as_secs_f64().max(0.001) * 100.0).round() / 100.0,
+            peak_requests_per_second: peak_per_second(sent),
+            limit_per_second: SEC_RATE_LIMIT_PER_SECOND,
+            user_agent: self.user_agent.clone(),
+            statuses,
+            failures,
+        })
+    }
+
+    /// Wait for the rate limiter and send `request` with our User-Agent,
+    /// abandoning both if the caller's cancellation scope is cancelled
+    async fn send(&self, request: RequestBuilder) -> Result<Response, SecError> {
+        self.send_timed(request).await.map(|(_, response)| response)
+    }
+
+    /// `send`, also returning when the request left the rate limiter
+    async fn send_timed(&self, request: RequestBuilder) -> Result<(Instant, Response), SecError> {
+        let request = request.header("User-Agent", &self.user_agent).build()?;
+        let span = tracing::debug_span!(
+            "sec_request",
+            method = %request.method(),
+            path = request.url().path(),
+            status = tracing::field::Empty,
+        );
+        cancellation::guard(async {
+            self.rate_limiter.until_ready().await;
+            let sent = Instant::now();
+            call_stats::record(Source::Sec);
+            let response = self.client.execute(request).await?;
+            Span::current().record("status", response.status().as_u16());
+            Ok((sent, response))
+        })
+        .instrument(span)
+        .await
+        .unwrap_or(Err(SecError::Cancelled))
+    }
+
     /// Detect content type from response headers and filename
     fn detect_content_type(&self, response: &reqwest::Response, filename: Option<&str>) -> ContentType {
         // Check Content-Type header
@@ -244,6 +592,41 @@ mod tests {
         assert_eq!(client.base_url, SEC_BASE_URL);
     }
 
+    #[test]
+    fn test_sec_user_agent_validation() {
+        assert_eq!(
+            sec_user_agent(Some(" Acme Corp "), Some("ops@acme.com")),
+            Ok("Acme Corp ops@acme.com".to_string())
+        );
+        assert_eq!(sec_user_agent(None, Some(" ")), Err(SecConfigIssue::MissingNameAndEmail));
+        assert_eq!(sec_user_agent(None, Some("ops@acme.com")), Err(SecConfigIssue::MissingName));
+        assert_eq!(sec_user_agent(Some("Acme"), Some("")), Err(SecConfigIssue::MissingEmail));
+        assert_eq!(
+            sec_user_agent(Some("Acmé"), Some("ops@acme.com")),
+            Err(SecConfigIssue::InvalidName("Acmé".to_string()))
+        );
+        for email in ["ops", "ops@acme", "@acme.com", "ops@.com", "ops@acme.com.", "o ps@acme.com", "a@b@acme.com"] {
+            assert_eq!(
+                sec_user_agent(Some("Acme"), Some(email)),
+                Err(SecConfigIssue::InvalidEmail(email.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_sec_client_from_config() {
+        let mut config = Config {
+            sec_user_agent_name: Some("Acme Corp".to_string()),
+            ..Config::default()
+        };
+        assert!(matches!(
+            SecClient::from_config(&config),
+            Err(SecError::NotConfigured(SecConfigIssue::MissingEmail))
+        ));
+        config.sec_user_agent_email = Some("ops@acme.com".to_string());
+        assert_eq!(SecClient::from_config(&config).unwrap().user_agent, "Acme Corp ops@acme.com");
+    }
+
     #[test]
     fn test_sec_client_with_custom_base_url() {
         let client =
@@ -256,10 +639,10 @@ mod tests {
 
     #[test]
     fn test_sec_error_display() {
-        let err = SecError::NotConfigured;
+        let err = SecError::NotConfigured(SecConfigIssue::MissingEmail);
         assert_eq!(
             format!("{}", err),
-            "SEC access not configured. Please set your email in settings."
+            "SEC access not configured: email is not set. Please set your name and email in settings."
         );
 
         let err = SecError::NotFound;
@@ -306,6 +689,152 @@ mod tests {
         assert_eq!(content_type, ContentType::Text);
     }
 
+    #[tokio::test]
+    async fn test_full_text_search() {
+        use wiremock::matchers::query_param;
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/LATEST/search-index"))
+            .and(query_param("q", "\"supply chain\""))
+            .and(query_param("ciks", "0000320193"))
+            .and(query_param("forms", "10-K,10-Q"))
+            .and(query_param("startdt", "2023-01-01"))
+            .and(query_param("enddt", "2023-12-31"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"hits": {"total": {"value": 1}, "hits": []}}"#,
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let mut client = SecClient::new("Test Company", "test@example.com").unwrap();
+        client.full_text_search_url = format!("{}/LATEST/search-index", mock_server.uri());
+
+        let forms = vec!["10-K".to_string(), "10-Q".to_string()];
+        let result = client
+            .full_text_search("\"supply chain\"", "320193", &forms, Some(("2023-01-01", "2023-12-31")))
+            .await
+            .unwrap();
+        assert_eq!(result["hits"]["total"]["value"], 1);
+    }
+
+    #[test]
+    fn test_peak_per_second() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+        assert_eq!(peak_per_second(vec![]), 0);
+        assert_eq!(peak_per_second(vec![at(0), at(500), at(999), at(1000), at(1100)]), 4);
+        assert_eq!(peak_per_second((0..12).map(|i| at(i * 100)).collect()), 10);
+    }
+
+    #[tokio::test]
+    async fn test_probe_rate_stays_within_limit() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("HEAD"))
+            .and(header("User-Agent", "Test Company test@example.com"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let client =
+            SecClient::with_base_url("Test Company", "test@example.com", mock_server.uri())
+                .unwrap();
+        let probe = client.probe_rate(15).await.unwrap();
+
+        assert_eq!(probe.statuses.get(&200), Some(&15));
+        assert!(probe.peak_requests_per_second <= 10, "{:?}", probe);
+        assert!(probe.elapsed_ms >= 1300);
+        assert!(probe.is_compliant());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_company_tickers() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/files/company_tickers.json"))
+            .and(header("User-Agent", "Test Company test@example.com"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"0": {"cik_str": 320193, "ticker": "AAPL", "title": "Apple Inc."},
+                    "1": {"cik_str": 1067983, "ticker": "brk-b", "title": "Berkshire Hathaway"}}"#,
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let mut client = SecClient::new("Test Company", "test@example.com").unwrap();
+        client.tickers_url = format!("{}/files/company_tickers.json", mock_server.uri());
+
+        let tickers = client.fetch_company_tickers().await.unwrap();
+        assert_eq!(tickers.len(), 2);
+        assert_eq!(tickers["AAPL"], "0000320193");
+        assert_eq!(tickers["BRK-B"], "0001067983");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_fund_tickers() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/files/company_tickers_mf.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"fields": ["cik", "seriesId", "classId", "symbol"],
+                    "data": [[36405, "S000002277", "C000005930", "voo"],
+                             [36405, "S000002277", "C000005931", null],
+                             [884394, null, null, "SPY"]]}"#,
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let mut client = SecClient::new("Test Company", "test@example.com").unwrap();
+        client.fund_tickers_url = format!("{}/files/company_tickers_mf.json", mock_server.uri());
+
+        let funds = client.fetch_fund_tickers().await.unwrap();
+        assert_eq!(funds.len(), 2);
+        assert_eq!(funds[0].cik, "0000036405");
+        assert_eq!(funds[0].symbol.as_deref(), Some("VOO"));
+        assert_eq!(funds[1].symbol, None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_submissions() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/submissions/CIK0000320193.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{"cik": "320193", "name": "Apple Inc.", "filings": {"recent": {"form": ["10-K"]}}}"#,
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let mut client = SecClient::new("Test Company", "test@example.com").unwrap();
+        client.submissions_url = format!("{}/submissions", mock_server.uri());
+
+        let submissions = client.fetch_submissions("320193").await.unwrap();
+        assert_eq!(submissions["name"], "Apple Inc.");
+        assert!(matches!(client.fetch_submissions("1").await, Err(SecError::NotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_check_connectivity() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("HEAD"))
+            .and(path("/"))
+            .and(header("User-Agent", "Test Company test@example.com"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let client =
+            SecClient::with_base_url("Test Company", "test@example.com", mock_server.uri())
+                .unwrap();
+
+        assert_eq!(client.check_connectivity().await.unwrap(), 200);
+    }
+
     #[tokio::test]
     async fn test_fetch_document_default_filename() {
         let mock_server = MockServer::start().await;
@@ -338,7 +867,7 @@ mod tests {
 
         // CIK with many leading zeros should be stripped
         Mock::given(method("GET"))
-            .and(path("/123/000012300012345/doc.htm"))
+            .and(path("/123/000012300012000345/doc.htm"))
             .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
             .mount(&mock_server)
             .await;
@@ -349,10 +878,36 @@ mod tests {
 
         // CIK 0000000123 should become 123
         let result = client
-            .fetch_document("0000000123", "0000123000-12-345", Some("doc.htm"))
+            .fetch_document("0000000123", "0000123000-12-000345", Some("doc.htm"))
+            .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_document_normalizes_accession() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/320193/000119312523123456/0001193125-23-123456.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("Filing content"))
+            .mount(&mock_server)
             .await;
 
+        let client =
+            SecClient::with_base_url("Test Company", "test@example.com", mock_server.uri())
+                .unwrap();
+
+        let result = client
+            .fetch_document("0000320193", " 000119312523123456 ", None)
+            .await;
         assert!(result.is_ok());
+
+        let err = client
+            .fetch_document("0000320193", "not-an-accession", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SecError::InvalidInput(_)));
     }
 
     #[tokio::test]
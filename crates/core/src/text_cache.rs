@@ -0,0 +1,224 @@
+//! On-disk cache of text extracted from SEC documents.
+//!
+//! Filings never change once accepted, so extracted text is kept under the
+//! cache directory keyed by accession number, filename, and extraction
+//! profile. Repeat reads of the same document skip both the EDGAR fetch and
+//! the HTML/PDF parse. Bumping [`EXTRACTION_PROFILE`] when extraction output
+//! changes makes older entries misses.
+
+use crate::config::{Config, ConfigError};
+use crate::params::normalize_accession;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Version of the extraction pipeline whose output is cached
+pub const EXTRACTION_PROFILE: &str = "v1";
+
+/// Subdirectory of the cache dir that holds extracted text
+const TEXT_CACHE_DIR: &str = "extracted-text";
+
+/// Documents kept before the least recently used are evicted
+pub const MAX_CACHED_DOCUMENTS: usize = 500;
+
+/// Stand-in filename for a filing's full submission text
+const SUBMISSION_NAME: &str = "_submission";
+
+/// Entry count and size of the cache
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TextCacheStats {
+    pub documents: usize,
+    pub filings: usize,
+    pub bytes: u64,
+}
+
+pub struct TextCache {
+    dir: PathBuf,
+}
+
+impl TextCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The cache in the platform cache directory
+    pub fn open_default() -> Result<Self, ConfigError> {
+        Ok(Self::new(Config::cache_dir()?.join(TEXT_CACHE_DIR)))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Keep path components to characters that are safe on every platform,
+    /// and never "." or ".."
+    fn safe(name: &str) -> String {
+        let safe: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+            .collect();
+        if safe.starts_with('.') {
+            format!("_{}", safe)
+        } else {
+            safe
+        }
+    }
+
+    /// Directory of a filing's documents; accession numbers with and
+    /// without dashes share one
+    fn filing_dir(&self, accession: &str) -> PathBuf {
+        let accession = normalize_accession(accession).unwrap_or_else(|_| accession.to_string());
+        self.dir.join(Self::safe(&accession))
+    }
+
+    fn path(&self, accession: &str, filename: Option<&str>) -> PathBuf {
+        let name = Self::safe(filename.unwrap_or(SUBMISSION_NAME));
+        self.filing_dir(accession)
+            .join(format!("{}.{}.txt", name, EXTRACTION_PROFILE))
+    }
+
+    /// Cached text of a document, or None on a miss
+    pub fn get(&self, accession: &str, filename: Option<&str>) -> Option<String> {
+        let path = self.path(accession, filename);
+        let text = fs::read_to_string(&path).ok()?;
+        // Touch so eviction sees the entry as recently used
+        if let Ok(file) = fs::File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(text)
+    }
+
+    /// Store a document's text, evicting the least recently used documents
+    /// beyond [`MAX_CACHED_DOCUMENTS`]
+    pub fn put(&self, accession: &str, filename: Option<&str>, text: &str) -> io::Result<()> {
+        let path = self.path(accession, filename);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, text)?;
+        self.evict(MAX_CACHED_DOCUMENTS)
+    }
+
+    /// Every cached document with its last use and size
+    fn entries(&self) -> Vec<(SystemTime, u64, PathBuf)> {
+        fs::read_dir(&self.dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .flat_map(|filing| fs::read_dir(filing.path()).into_iter().flatten())
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let meta = entry.metadata().ok()?;
+                Some((meta.modified().ok()?, meta.len(), entry.path()))
+            })
+            .collect()
+    }
+
+    fn evict(&self, keep: usize) -> io::Result<()> {
+        let mut entries = self.entries();
+        if entries.len() <= keep {
+            return Ok(());
+        }
+        entries.sort();
+        for (_, _, path) in &entries[..entries.len() - keep] {
+            fs::remove_file(path)?;
+            if let Some(parent) = path.parent() {
+                // Only succeeds once the filing's directory is empty
+                let _ = fs::remove_dir(parent);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> TextCacheStats {
+        let entries = self.entries();
+        let mut filings: Vec<&Path> = entries.iter().filter_map(|(_, _, path)| path.parent()).collect();
+        filings.dedup();
+        TextCacheStats {
+            documents: entries.len(),
+            filings: filings.len(),
+            bytes: entries.iter().map(|(_, len, _)| len).sum(),
+        }
+    }
+
+    /// Remove the cached documents of one filing, or everything when
+    /// `accession` is None. Returns how many documents were removed.
+    pub fn clear(&self, accession: Option<&str>) -> io::Result<usize> {
+        let before = self.stats().documents;
+        let target = match accession {
+            Some(accession) => self.filing_dir(accession),
+            None => self.dir.clone(),
+        };
+        match fs::remove_dir_all(&target) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        Ok(before - self.stats().documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_get_and_put() {
+        let dir = TempDir::new().unwrap();
+        let cache = TextCache::new(dir.path());
+        assert_eq!(cache.get("0000320193-24-000123", Some("aapl.htm")), None);
+
+        cache.put("0000320193-24-000123", Some("aapl.htm"), "Item 1A").unwrap();
+        cache.put("0000320193-24-000123", None, "full").unwrap();
+        assert_eq!(cache.get("0000320193-24-000123", Some("aapl.htm")).as_deref(), Some("Item 1A"));
+        assert_eq!(cache.get("0000320193-24-000123", None).as_deref(), Some("full"));
+        assert_eq!(cache.get("0000320193-24-000123", Some("ex99.htm")), None);
+        assert_eq!(cache.get("000032019324000123", None).as_deref(), Some("full"));
+
+        let stats = cache.stats();
+        assert_eq!((stats.documents, stats.filings, stats.bytes), (2, 1, 11));
+    }
+
+    #[test]
+    fn test_clear() {
+        let dir = TempDir::new().unwrap();
+        let cache = TextCache::new(dir.path().join("text"));
+        assert_eq!(cache.clear(None).unwrap(), 0);
+
+        cache.put("a", Some("1.htm"), "x").unwrap();
+        cache.put("a", Some("2.htm"), "x").unwrap();
+        cache.put("b", Some("1.htm"), "x").unwrap();
+        assert_eq!(cache.clear(Some("a")).unwrap(), 2);
+        assert_eq!(cache.get("b", Some("1.htm")).as_deref(), Some("x"));
+        assert_eq!(cache.clear(None).unwrap(), 1);
+        assert_eq!(cache.stats(), TextCacheStats::default());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let dir = TempDir::new().unwrap();
+        let cache = TextCache::new(dir.path());
+        for (i, accession) in ["a", "b", "c"].iter().enumerate() {
+            cache.put(accession, None, "x").unwrap();
+            let path = cache.path(accession, None);
+            let file = fs::File::options().append(true).open(path).unwrap();
+            file.set_modified(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(i as u64 + 1)).unwrap();
+        }
+        cache.evict(2).unwrap();
+        assert_eq!(cache.get("a", None), None);
+        assert!(cache.get("b", None).is_some());
+        assert!(!dir.path().join("a").exists());
+    }
+
+    #[test]
+    fn test_unsafe_names_stay_inside_cache() {
+        let dir = TempDir::new().unwrap();
+        let cache = TextCache::new(dir.path().join("text"));
+        cache.put("..", Some("../../evil"), "x").unwrap();
+        assert!(cache.path("..", Some("../../evil")).starts_with(dir.path().join("text")));
+        assert_eq!(cache.stats().documents, 1);
+    }
+}
@@ -57,22 +57,20 @@ pub fn extract_text_from_html(html: &str) -> Result<String, ExtractionError> {
             // Handle table structure
             match tag_name {
                 "table" => in_table = true,
-                "tr" => {
-                    if !table_row.is_empty() {
-                        text_parts.push(format!("| {} |", table_row.join(" | ")));
-                        table_row.clear();
-                    }
+                "tr" if !table_row.is_empty() => {
+                    text_parts.push(format!("| {} |", table_row.join(" | ")));
+                    table_row.clear();
                 }
                 "td" | "th" => {
                     // Text will be collected in text node handler
                 }
-                "p" | "div" | "br" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "li" => {
-                    // Add newline before block elements
-                    if !text_parts.is_empty() {
-                        let last = text_parts.last().map(|s: &String| s.as_str()).unwrap_or("");
-                        if !last.ends_with('\n') {
-                            text_parts.push("\n".to_string());
-                        }
+                // Add newline before block elements
+                "p" | "div" | "br" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "li"
+                    if !text_parts.is_empty() =>
+                {
+                    let last = text_parts.last().map(|s: &String| s.as_str()).unwrap_or("");
+                    if !last.ends_with('\n') {
+                        text_parts.push("\n".to_string());
                     }
                 }
                 _ => {}
@@ -187,22 +185,38 @@ pub fn is_html_or_xml(bytes: &[u8]) -> bool {
     }
 }
 
-/// Truncate text to a maximum character count, trying to break at sentence boundaries
+/// Truncate text to a maximum character count, preferring to break at a
+/// paragraph, then a sentence, then a word boundary.
+///
+/// Counts characters rather than bytes, so multi-byte text (©, ™, accented
+/// names) is never split mid-character.
 pub fn truncate_for_llm(text: &str, max_chars: usize) -> String {
-    if text.len() <= max_chars {
+    let Some((limit, _)) = text.char_indices().nth(max_chars) else {
         return text.to_string();
-    }
-
-    // Try to break at a sentence boundary
-    let search_window = &text[..max_chars];
-    let break_point = search_window
-        .rfind(". ")
-        .or_else(|| search_window.rfind(".\n"))
-        .map(|i| i + 1)
-        .unwrap_or(max_chars);
-
-    let truncated = &text[..break_point];
-    format!("{}\n\n[Content truncated at {} characters]", truncated, break_point)
+    };
+
+    let window = &text[..limit];
+    // Only take a natural break if it keeps at least half the window
+    let min_break = limit / 2;
+    let after_min = |i: &usize| *i >= min_break && *i > 0;
+
+    let paragraph = window.rfind("\n\n").filter(after_min);
+    let sentence = || {
+        [". ", ".\n", "? ", "?\n", "! ", "!\n"]
+            .iter()
+            .filter_map(|p| window.rfind(p).map(|i| i + 1))
+            .max()
+            .filter(after_min)
+    };
+    let word = || window.rfind(char::is_whitespace).filter(after_min);
+
+    let break_point = paragraph.or_else(sentence).or_else(word).unwrap_or(limit);
+    let truncated = window[..break_point].trim_end();
+    format!(
+        "{}\n\n[Content truncated at {} characters]",
+        truncated,
+        truncated.chars().count()
+    )
 }
 
 #[cfg(test)]
@@ -519,6 +533,42 @@ mod tests {
         assert!(result.contains("[Content truncated"));
     }
 
+    #[test]
+    fn test_truncate_multibyte() {
+        let text = "Acme™ Holdings © 2024 — Société Générale. ".repeat(10);
+        let result = truncate_for_llm(&text, 17);
+        assert!(result.starts_with("Acme™ Holdings ©"));
+        assert!(result.contains("[Content truncated"));
+    }
+
+    #[test]
+    fn test_truncate_prefers_paragraph_break() {
+        let text = "First paragraph. Still first.\n\nSecond paragraph. More text here.";
+        let result = truncate_for_llm(text, 45);
+        assert!(result.starts_with("First paragraph. Still first.\n\n[Content truncated"));
+    }
+
+    #[test]
+    fn test_truncate_word_boundary() {
+        let text = "words without any sentence punctuation at all";
+        let result = truncate_for_llm(text, 20);
+        assert!(result.starts_with("words without any\n\n"));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_truncate_never_panics(text in "\\PC{0,200}", max in 0usize..250) {
+            let result = truncate_for_llm(&text, max);
+            if text.chars().count() <= max {
+                proptest::prop_assert_eq!(result, text);
+            } else {
+                let (kept, _) = result.split_once("\n\n[Content truncated").unwrap();
+                proptest::prop_assert!(text.starts_with(kept));
+                proptest::prop_assert!(kept.chars().count() <= max);
+            }
+        }
+    }
+
     #[test]
     fn test_extraction_error_display() {
         let err = ExtractionError::HtmlParseError("test error".to_string());
@@ -0,0 +1,152 @@
+//! Company data helpers.
+//!
+//! Post-processing for company endpoints that the MCP server fans out
+//! across several companies (e.g. batched filings lookups).
+
+use super::fields::{records, str_field};
+use crate::error::CoreError;
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+
+/// Maximum number of companies accepted in a single batched filings call
+pub const MAX_BATCH_COMPANIES: usize = 25;
+
+/// Extract the list of filings from a filings endpoint response.
+///
+/// Accepts the JSON:API style `{"data": [...]}`, a `{"filings": [...]}`
+/// wrapper, or a bare array.
+pub fn filings_from_response(response: &Value) -> Vec<Value> {
+    records(response, &["data", "filings", "results"]).to_vec()
+}
+
+/// Read the filing date of a filing record, looking in `attributes` as well
+pub fn filing_date(filing: &Value) -> Option<&str> {
+    str_field(filing, &["filing_date", "filed_at"])
+}
+
+/// Merge per-company filings responses into a single list sorted by filing
+/// date (newest first).
+///
+/// Each filing is tagged with the `cik` it was requested for. Failed lookups
+/// are reported under `errors` instead of failing the whole batch.
+pub fn merge_company_filings(results: Vec<(String, Result<Value, CoreError>)>) -> Value {
+    let mut ciks = Vec::new();
+    let mut filings = Vec::new();
+    let mut errors = serde_json::Map::new();
+
+    for (cik, result) in results {
+        ciks.push(cik.clone());
+        match result {
+            Ok(response) => {
+                for mut filing in filings_from_response(&response) {
+                    if let Some(obj) = filing.as_object_mut() {
+                        obj.entry("cik").or_insert_with(|| json!(cik));
+                    }
+                    filings.push(filing);
+                }
+            }
+            Err(e) => {
+                errors.insert(cik, e.to_json());
+            }
+        }
+    }
+
+    // Newest first; filings without a date sink to the bottom
+    filings.sort_by(|a, b| match (filing_date(a), filing_date(b)) {
+        (Some(da), Some(db)) => db.cmp(da),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+
+    let mut result = json!({
+        "ciks": ciks,
+        "count": filings.len(),
+        "data": filings
+    });
+    if !errors.is_empty() {
+        result["errors"] = Value::Object(errors);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_client::ApiError;
+
+    #[test]
+    fn test_filings_from_response_shapes() {
+        assert_eq!(filings_from_response(&json!({"data": [1, 2]})).len(), 2);
+        assert_eq!(filings_from_response(&json!({"filings": [1]})).len(), 1);
+        assert_eq!(filings_from_response(&json!([1, 2, 3])).len(), 3);
+        assert!(filings_from_response(&json!({"count": 0})).is_empty());
+    }
+
+    #[test]
+    fn test_filing_date_from_attributes() {
+        let filing = json!({"attributes": {"filing_date": "2024-01-02"}});
+        assert_eq!(filing_date(&filing), Some("2024-01-02"));
+
+        let filing = json!({"filing_date": "2024-03-04"});
+        assert_eq!(filing_date(&filing), Some("2024-03-04"));
+
+        assert_eq!(filing_date(&json!({})), None);
+    }
+
+    #[test]
+    fn test_merge_sorts_newest_first_and_tags_cik() {
+        let merged = merge_company_filings(vec![
+            (
+                "0000320193".to_string(),
+                Ok(json!({"data": [
+                    {"filing_date": "2024-01-10", "form_type": "8-K"},
+                    {"filing_date": "2023-11-02", "form_type": "10-K"}
+                ]})),
+            ),
+            (
+                "0000789019".to_string(),
+                Ok(json!({"data": [
+                    {"filing_date": "2024-01-15", "form_type": "10-Q"}
+                ]})),
+            ),
+        ]);
+
+        assert_eq!(merged["count"], 3);
+        let data = merged["data"].as_array().unwrap();
+        assert_eq!(data[0]["filing_date"], "2024-01-15");
+        assert_eq!(data[0]["cik"], "0000789019");
+        assert_eq!(data[1]["filing_date"], "2024-01-10");
+        assert_eq!(data[2]["filing_date"], "2023-11-02");
+        assert!(merged.get("errors").is_none());
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_cik_and_reports_errors() {
+        let merged = merge_company_filings(vec![
+            (
+                "AAPL".to_string(),
+                Ok(json!([{"cik": "0000320193", "filing_date": "2024-01-01"}])),
+            ),
+            ("BAD".to_string(), Err(ApiError::NotFound.into())),
+        ]);
+
+        assert_eq!(merged["count"], 1);
+        assert_eq!(merged["data"][0]["cik"], "0000320193");
+        assert_eq!(merged["errors"]["BAD"]["code"], "NOT_FOUND");
+        assert_eq!(merged["errors"]["BAD"]["message"], "Resource not found");
+        assert_eq!(merged["ciks"], json!(["AAPL", "BAD"]));
+    }
+
+    #[test]
+    fn test_merge_undated_filings_sort_last() {
+        let merged = merge_company_filings(vec![(
+            "1".to_string(),
+            Ok(json!({"data": [{"id": "undated"}, {"filing_date": "2020-01-01"}]})),
+        )]);
+
+        let data = merged["data"].as_array().unwrap();
+        assert_eq!(data[0]["filing_date"], "2020-01-01");
+        assert_eq!(data[1]["id"], "undated");
+    }
+}
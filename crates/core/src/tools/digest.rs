@@ -0,0 +1,185 @@
+//! Digests of documents too long to return whole.
+//!
+//! The text is split into chunks at paragraph breaks, the client's model
+//! summarizes each chunk through MCP sampling (`sampling/createMessage`),
+//! and the summaries are joined in document order. Each part also gets a
+//! pointer to where it lies in the text and the section headings it holds,
+//! so the caller can go back and read any part in full.
+
+use super::sec_documents::{section_headings, DocumentKind};
+use serde_json::{json, Value};
+
+/// Characters of document text per summarized chunk
+pub const DIGEST_CHUNK_CHARS: usize = 40_000;
+
+/// Chunks are made larger rather than sending more sampling requests than
+/// this for one document
+pub const MAX_DIGEST_CHUNKS: usize = 12;
+
+/// Token budget for each chunk's summary
+pub const CHUNK_SUMMARY_MAX_TOKENS: u64 = 1_000;
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You summarize excerpts of SEC filings for an analyst. \
+Keep every material figure, date, name, and risk, and say nothing that is not in the excerpt.";
+
+/// Split `text` into chunks of at most `chunk_chars` characters, breaking at
+/// a paragraph, line, or word boundary where one falls in the second half of
+/// the chunk
+pub fn chunk_text(text: &str, chunk_chars: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let Some((limit, _)) = rest.char_indices().nth(chunk_chars.max(1)) else {
+            chunks.push(rest);
+            break;
+        };
+        let window = &rest[..limit];
+        let late = |i: &usize| *i > 0 && *i >= limit / 2;
+        let end = window
+            .rfind("\n\n")
+            .filter(late)
+            .or_else(|| window.rfind('\n').filter(late))
+            .or_else(|| window.rfind(char::is_whitespace).filter(late))
+            .unwrap_or(limit);
+        chunks.push(rest[..end].trim_end());
+        rest = rest[end..].trim_start();
+    }
+    chunks
+}
+
+/// Chunks to summarize for a digest of `text`
+pub fn digest_chunks(text: &str) -> Vec<&str> {
+    let chunk_chars = DIGEST_CHUNK_CHARS.max(text.chars().count().div_ceil(MAX_DIGEST_CHUNKS));
+    chunk_text(text, chunk_chars)
+}
+
+/// Where each chunk lies in `text`, as character offsets from `base` (the
+/// offset `text` itself starts at), with the section headings it contains.
+/// `chunks` must be slices of `text`, as [`digest_chunks`] returns.
+pub fn part_pointers(text: &str, chunks: &[&str], base: usize) -> Vec<Value> {
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let byte_start = chunk.as_ptr() as usize - text.as_ptr() as usize;
+            let start = base + text[..byte_start].chars().count();
+            json!({
+                "part": i + 1,
+                "start_char": start,
+                "end_char": start + chunk.chars().count(),
+                "sections": section_headings(chunk, DocumentKind::Periodic),
+            })
+        })
+        .collect()
+}
+
+/// `sampling/createMessage` params asking for a summary of one chunk of the
+/// document described by `label`
+pub fn sampling_request(label: &str, chunk: &str, index: usize, total: usize) -> Value {
+    let prompt = format!(
+        "This is part {} of {} of {}. Summarize it in a few paragraphs or bullet points.\n\n---\n\n{}",
+        index + 1,
+        total,
+        label,
+        chunk
+    );
+    json!({
+        "messages": [{
+            "role": "user",
+            "content": { "type": "text", "text": prompt }
+        }],
+        "systemPrompt": SUMMARY_SYSTEM_PROMPT,
+        "includeContext": "none",
+        "maxTokens": CHUNK_SUMMARY_MAX_TOKENS,
+        "modelPreferences": { "speedPriority": 0.7, "intelligencePriority": 0.5 }
+    })
+}
+
+/// Text of a `sampling/createMessage` result, whose content is one block or
+/// a list of blocks
+pub fn sampled_text(result: &Value) -> Option<String> {
+    let content = &result["content"];
+    let blocks = match content.as_array() {
+        Some(blocks) => blocks.iter().collect(),
+        None => vec![content],
+    };
+    let text: Vec<&str> = blocks
+        .into_iter()
+        .filter(|block| block["type"] == "text")
+        .filter_map(|block| block["text"].as_str())
+        .collect();
+    (!text.is_empty()).then(|| text.join("\n"))
+}
+
+/// Join chunk summaries into one digest
+pub fn format_digest(summaries: &[String]) -> String {
+    let total = summaries.len();
+    summaries
+        .iter()
+        .enumerate()
+        .map(|(i, summary)| format!("## Part {} of {}\n\n{}", i + 1, total, summary.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_breaks_at_paragraphs() {
+        let text = "First paragraph here.\n\nSecond paragraph is longer.\n\nThird.";
+        let chunks = chunk_text(text, 30);
+        assert_eq!(chunks, vec!["First paragraph here.", "Second paragraph is longer.", "Third."]);
+        assert_eq!(chunk_text(text, 1_000), vec![text]);
+        assert!(chunk_text("  ", 10).is_empty());
+    }
+
+    #[test]
+    fn test_chunk_text_without_breaks() {
+        let chunks = chunk_text("abcdefghij", 4);
+        assert_eq!(chunks, vec!["abcd", "efgh", "ij"]);
+        let chunks = chunk_text("ééééé", 2);
+        assert_eq!(chunks.concat(), "ééééé");
+    }
+
+    #[test]
+    fn test_digest_chunks_bounded() {
+        let text = "word ".repeat(DIGEST_CHUNK_CHARS * MAX_DIGEST_CHUNKS / 2);
+        let chunks = digest_chunks(&text);
+        assert!(chunks.len() <= MAX_DIGEST_CHUNKS + 1);
+        assert!(chunks.len() > 1);
+    }
+
+    #[test]
+    fn test_sampling_round_trip() {
+        let request = sampling_request("Apple 10-K", "Revenue rose.", 0, 3);
+        let prompt = request["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(prompt.starts_with("This is part 1 of 3 of Apple 10-K."));
+        assert!(prompt.ends_with("Revenue rose."));
+
+        let single = json!({"role": "assistant", "content": {"type": "text", "text": "Up 5%."}});
+        assert_eq!(sampled_text(&single).as_deref(), Some("Up 5%."));
+        let blocks = json!({"content": [{"type": "text", "text": "a"}, {"type": "image"}, {"type": "text", "text": "b"}]});
+        assert_eq!(sampled_text(&blocks).as_deref(), Some("a\nb"));
+        assert_eq!(sampled_text(&json!({"content": {"type": "image"}})), None);
+
+        let digest = format_digest(&["One.".to_string(), "Two.\n".to_string()]);
+        assert_eq!(digest, "## Part 1 of 2\n\nOne.\n\n## Part 2 of 2\n\nTwo.");
+    }
+
+    #[test]
+    fn test_part_pointers() {
+        let text = "Item 1. Business\nWé make things.\n\nItem 1A. Risk Factors\nRisky.";
+        let chunks = chunk_text(text, 36);
+        assert_eq!(chunks.len(), 2);
+        let parts = part_pointers(text, &chunks, 100);
+        assert_eq!(parts[0]["start_char"], 100);
+        assert_eq!(parts[0]["sections"], json!(["Item 1. Business"]));
+        let start = parts[1]["start_char"].as_u64().unwrap() as usize - 100;
+        let end = parts[1]["end_char"].as_u64().unwrap() as usize - 100;
+        let part: String = text.chars().skip(start).take(end - start).collect();
+        assert_eq!(part, chunks[1]);
+        assert_eq!(parts[1]["sections"], json!(["Item 1A. Risk Factors"]));
+    }
+}
@@ -0,0 +1,285 @@
+//! ETF holdings helpers.
+//!
+//! Normalizes N-PORT holdings responses into a common shape, compares
+//! holdings between two reporting quarters, and resolves fund tickers to
+//! their N-PORT series and share classes.
+
+use super::fields::{records, str_field};
+use crate::models::{rust_decimal::Decimal, Holding};
+use crate::sec_client::FundClass;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Normalize a holdings endpoint response into a list of holdings.
+///
+/// Fields of JSON:API records are read from `attributes` as well. Records
+/// without any usable identity (see [`Holding::key`]) are skipped.
+pub fn holdings_from_response(response: &Value) -> Vec<Holding> {
+    records(response, &["holdings", "data"])
+        .iter()
+        .filter_map(|record| {
+            let holding: Holding = serde_json::from_value(flatten(record)).ok()?;
+            holding.key().is_some().then_some(holding)
+        })
+        .collect()
+}
+
+/// A record's `attributes` merged into it, its own non-null fields winning
+fn flatten(record: &Value) -> Value {
+    let Some(attributes) = record.get("attributes").and_then(Value::as_object) else {
+        return record.clone();
+    };
+    let mut merged: Map<String, Value> = attributes.clone();
+    for (key, value) in record.as_object().into_iter().flatten() {
+        if key != "attributes" && !value.is_null() {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+    Value::Object(merged)
+}
+
+/// A holding as listed in a comparison
+fn entry(key: &str, holding: &Holding) -> Value {
+    json!({
+        "key": key,
+        "name": holding.name,
+        "weight": holding.weight,
+        "value": holding.value
+    })
+}
+
+/// Compare two quarters of holdings, returning added and removed
+/// constituents plus weight changes for holdings present in both. Weights
+/// are decimals, so unchanged positions diff to exactly zero.
+///
+/// Changes are sorted by absolute weight change, largest first.
+pub fn compare_holdings(current: &[Holding], prior: &[Holding]) -> Value {
+    let keyed = |holdings: &[Holding]| -> Vec<(String, Holding)> {
+        holdings.iter().filter_map(|h| Some((h.key()?, h.clone()))).collect()
+    };
+    let current = keyed(current);
+    let prior = keyed(prior);
+    let prior_by_key: HashMap<&str, &Holding> = prior.iter().map(|(k, h)| (k.as_str(), h)).collect();
+    let current_keys: HashMap<&str, &Holding> = current.iter().map(|(k, h)| (k.as_str(), h)).collect();
+
+    let mut added = Vec::new();
+    let mut changed: Vec<(Option<Decimal>, Value)> = Vec::new();
+
+    for (key, holding) in &current {
+        match prior_by_key.get(key.as_str()) {
+            None => added.push(entry(key, holding)),
+            Some(before) => {
+                let weight_change = match (holding.weight, before.weight) {
+                    (Some(now), Some(then)) => Some((now - then).round_dp(4).normalize()),
+                    _ => None,
+                };
+                changed.push((
+                    weight_change,
+                    json!({
+                        "key": key,
+                        "name": holding.name,
+                        "weight": holding.weight,
+                        "prior_weight": before.weight,
+                        "weight_change": weight_change
+                    }),
+                ));
+            }
+        }
+    }
+
+    let removed: Vec<Value> = prior
+        .iter()
+        .filter(|(key, _)| !current_keys.contains_key(key.as_str()))
+        .map(|(key, holding)| entry(key, holding))
+        .collect();
+
+    changed.sort_by_key(|(change, _)| std::cmp::Reverse(change.map(|c| c.abs()).unwrap_or_default()));
+    let changed: Vec<Value> = changed.into_iter().map(|(_, change)| change).collect();
+
+    let mut summary = BTreeMap::new();
+    summary.insert("added", added.len());
+    summary.insert("removed", removed.len());
+    summary.insert("unchanged_membership", changed.len());
+
+    json!({
+        "summary": summary,
+        "added": added,
+        "removed": removed,
+        "weight_changes": changed
+    })
+}
+
+/// Resolve a fund ticker, series ID, or class ID to its share classes.
+///
+/// Returns the matching series, each with all of its share classes so the
+/// caller can see sibling tickers. Exchange suffixes (`SPY:ARCX`) are ignored.
+pub fn resolve_fund(classes: &[FundClass], identifier: &str) -> Value {
+    let id = identifier.trim().to_uppercase();
+    let id = id.split(':').next().unwrap_or_default();
+
+    let series_ids: BTreeSet<&str> = classes
+        .iter()
+        .filter(|c| c.symbol.as_deref() == Some(id) || c.series_id == id || c.class_id == id)
+        .map(|c| c.series_id.as_str())
+        .collect();
+
+    let series: Vec<Value> = series_ids
+        .iter()
+        .map(|series_id| {
+            let members: Vec<&FundClass> = classes.iter().filter(|c| c.series_id == *series_id).collect();
+            let share_classes: Vec<Value> = members
+                .iter()
+                .map(|c| json!({ "class_id": c.class_id, "symbol": c.symbol }))
+                .collect();
+            json!({
+                "series_id": series_id,
+                "cik": members[0].cik,
+                "share_classes": share_classes
+            })
+        })
+        .collect();
+
+    json!({
+        "identifier": identifier,
+        "matched": !series.is_empty(),
+        "series": series
+    })
+}
+
+/// N-PORT reporting periods from a filings list response, newest first and
+/// one entry per period (the latest filing wins over amendments)
+pub fn nport_periods(response: &Value) -> Vec<Value> {
+    let mut periods: BTreeMap<&str, Value> = BTreeMap::new();
+    for filing in records(response, &["data", "filings", "results"]) {
+        let form = str_field(filing, &["form_type", "form"]).unwrap_or("NPORT-P");
+        if !form.to_uppercase().starts_with("NPORT") {
+            continue;
+        }
+        let Some(period) = str_field(filing, &["period_of_report", "report_date", "period"]) else {
+            continue;
+        };
+        let filed = super::company::filing_date(filing);
+        let newer = periods
+            .get(period)
+            .is_none_or(|existing| existing["filing_date"].as_str() < filed);
+        if newer {
+            periods.insert(
+                period,
+                json!({
+                    "period_of_report": period,
+                    "form_type": form,
+                    "filing_date": filed,
+                    "accession_number": str_field(filing, &["accession_number", "accession_no"])
+                }),
+            );
+        }
+    }
+    periods.into_values().rev().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(key: &str, weight: &str) -> Holding {
+        serde_json::from_value(json!({ "symbol": key, "name": format!("{} Inc", key), "weight": weight })).unwrap()
+    }
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_holdings_from_response_variants() {
+        let response = json!({"holdings": [
+            {"cusip": "037833100", "name": "Apple Inc", "percent_value": "7.1", "value": 1000},
+            {"attributes": {"symbol": "msft", "weight": 6.5}},
+            {"name": "Cash Collateral"},
+            {"value": 10}
+        ]});
+
+        let holdings = holdings_from_response(&response);
+        assert_eq!(holdings.len(), 3);
+        assert_eq!(holdings[0].key().as_deref(), Some("037833100"));
+        assert_eq!(holdings[0].weight, Some(dec("7.1")));
+        assert_eq!(holdings[0].value, Some(dec("1000")));
+        assert_eq!(holdings[1].key().as_deref(), Some("MSFT"));
+        assert_eq!(holdings[1].weight, Some(dec("6.5")));
+        assert_eq!(holdings[2].key().as_deref(), Some("Cash Collateral"));
+    }
+
+    #[test]
+    fn test_compare_holdings() {
+        let current = vec![holding("AAPL", "7.0"), holding("MSFT", "6.0"), holding("NVDA", "5.0")];
+        let prior = vec![holding("AAPL", "6.5"), holding("MSFT", "6.0"), holding("XOM", "1.0")];
+
+        let diff = compare_holdings(&current, &prior);
+        assert_eq!(diff["summary"]["added"], 1);
+        assert_eq!(diff["summary"]["removed"], 1);
+        assert_eq!(diff["added"][0]["key"], "NVDA");
+        assert_eq!(diff["removed"][0]["key"], "XOM");
+
+        let changes = diff["weight_changes"].as_array().unwrap();
+        assert_eq!(changes[0]["key"], "AAPL");
+        assert_eq!(changes[0]["weight_change"], "0.5");
+        assert_eq!(changes[1]["weight_change"], "0");
+    }
+
+    #[test]
+    fn test_compare_holdings_is_exact() {
+        // 0.3 - 0.1 is 0.19999999999999998 in f64
+        let diff = compare_holdings(&[holding("AAPL", "0.3")], &[holding("AAPL", "0.1")]);
+        assert_eq!(diff["weight_changes"][0]["weight_change"], "0.2");
+    }
+
+    #[test]
+    fn test_compare_holdings_missing_weights() {
+        let mut current = holding("AAPL", "0");
+        current.weight = None;
+        let diff = compare_holdings(&[current], &[holding("AAPL", "1.0")]);
+        assert!(diff["weight_changes"][0]["weight_change"].is_null());
+    }
+
+    fn fund(cik: &str, series: &str, class: &str, symbol: Option<&str>) -> FundClass {
+        FundClass {
+            cik: cik.to_string(),
+            series_id: series.to_string(),
+            class_id: class.to_string(),
+            symbol: symbol.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_resolve_fund() {
+        let classes = vec![
+            fund("0000036405", "S000002277", "C000005930", Some("VOO")),
+            fund("0000036405", "S000002277", "C000005931", Some("VFIAX")),
+            fund("0000036405", "S000002848", "C000007800", Some("VTI")),
+        ];
+
+        let result = resolve_fund(&classes, "voo:arcx");
+        assert_eq!(result["matched"], true);
+        assert_eq!(result["series"].as_array().unwrap().len(), 1);
+        assert_eq!(result["series"][0]["series_id"], "S000002277");
+        assert_eq!(result["series"][0]["share_classes"][1]["symbol"], "VFIAX");
+
+        assert_eq!(resolve_fund(&classes, "C000007800")["series"][0]["series_id"], "S000002848");
+        assert_eq!(resolve_fund(&classes, "S000002277")["series"][0]["cik"], "0000036405");
+        assert_eq!(resolve_fund(&classes, "ZZZZ")["matched"], false);
+    }
+
+    #[test]
+    fn test_nport_periods() {
+        let response = json!({"data": [
+            {"form_type": "NPORT-P", "period_of_report": "2024-03-31", "filing_date": "2024-05-28", "accession_number": "a"},
+            {"form_type": "NPORT-P/A", "period_of_report": "2024-03-31", "filing_date": "2024-06-10", "accession_number": "b"},
+            {"form_type": "NPORT-P", "period_of_report": "2024-06-30", "filing_date": "2024-08-27"},
+            {"form_type": "N-CSR", "period_of_report": "2024-06-30"}
+        ]});
+
+        let periods = nport_periods(&response);
+        assert_eq!(periods.len(), 2);
+        assert_eq!(periods[0]["period_of_report"], "2024-06-30");
+        assert_eq!(periods[1]["accession_number"], "b");
+    }
+}
@@ -0,0 +1,233 @@
+//! Filing exhibit listing.
+//!
+//! Parses an EDGAR filing index page (`<accession>-index.htm`) and labels
+//! each exhibit from its Regulation S-K Item 601 number, so "EX-10.1" reads
+//! as a material contract and "EX-99.1" as an additional exhibit such as a
+//! press release.
+
+use scraper::{Html, Selector};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Item 601 exhibit numbers and what they contain
+const EXHIBIT_CATEGORIES: &[(u32, &str)] = &[
+    (1, "Underwriting Agreement"),
+    (2, "Plan of Acquisition, Reorganization or Liquidation"),
+    (3, "Articles of Incorporation or Bylaws"),
+    (4, "Instruments Defining the Rights of Security Holders"),
+    (5, "Opinion Regarding Legality"),
+    (7, "Correspondence Regarding Non-Reliance on Audit Report"),
+    (8, "Opinion Regarding Tax Matters"),
+    (9, "Voting Trust Agreement"),
+    (10, "Material Contract"),
+    (11, "Computation of Per Share Earnings"),
+    (13, "Annual Report to Security Holders"),
+    (14, "Code of Ethics"),
+    (15, "Letter Regarding Unaudited Interim Financial Information"),
+    (16, "Letter Regarding Change in Certifying Accountant"),
+    (17, "Correspondence on Departure of Director"),
+    (18, "Letter Regarding Change in Accounting Principles"),
+    (19, "Insider Trading Policies"),
+    (20, "Other Documents to Security Holders"),
+    (21, "Subsidiaries"),
+    (22, "Subsidiary Guarantors and Issuers"),
+    (23, "Consent of Experts or Counsel"),
+    (24, "Power of Attorney"),
+    (25, "Statement of Eligibility of Trustee"),
+    (26, "Invitation for Competitive Bids"),
+    (31, "Rule 13a-14(a) Certification"),
+    (32, "Section 1350 Certification"),
+    (33, "Report on Assessment of Compliance with Servicing Criteria"),
+    (34, "Attestation Report on Assessment of Compliance"),
+    (35, "Servicer Compliance Statement"),
+    (95, "Mine Safety Disclosure"),
+    (96, "Technical Report Summary"),
+    (97, "Clawback Policy"),
+    (99, "Additional Exhibit (e.g. Press Release)"),
+    (101, "XBRL Interactive Data"),
+    (104, "Cover Page Interactive Data"),
+    (107, "Filing Fee Table"),
+];
+
+/// Item 601 number of XBRL instance, schema and linkbase exhibits
+const XBRL_EXHIBIT: u32 = 101;
+
+/// A document listed in a filing index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IndexDocument {
+    pub seq: Option<u32>,
+    pub description: Option<String>,
+    /// Filename within the filing
+    pub document: String,
+    /// Document type, e.g. "10-K", "EX-10.1", "GRAPHIC"
+    pub doc_type: String,
+    pub size: Option<u64>,
+}
+
+/// Documents listed in the "Document Format Files" and "Data Files" tables
+/// of a filing index page
+pub fn parse_filing_index(html: &str) -> Vec<IndexDocument> {
+    let document = Html::parse_document(html);
+    let rows = Selector::parse("table.tableFile tr").unwrap();
+    let cells = Selector::parse("td").unwrap();
+    let link = Selector::parse("a").unwrap();
+
+    document
+        .select(&rows)
+        .filter_map(|row| {
+            let cells: Vec<_> = row.select(&cells).collect();
+            if cells.len() < 4 {
+                return None;
+            }
+            let text = |i: usize| {
+                cells
+                    .get(i)
+                    .map(|c| c.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" "))
+                    .filter(|t| !t.is_empty())
+            };
+            // Inline XBRL links go through the viewer ("/ix?doc=/Archives/...")
+            let name = cells[2]
+                .select(&link)
+                .next()
+                .and_then(|a| a.value().attr("href"))
+                .and_then(|href| href.rsplit('/').next())
+                .map(str::to_string)
+                .or_else(|| text(2).and_then(|t| t.split_whitespace().next().map(str::to_string)))?;
+            Some(IndexDocument {
+                seq: text(0).and_then(|t| t.parse().ok()),
+                description: text(1),
+                document: name,
+                doc_type: text(3).unwrap_or_default(),
+                size: text(4).and_then(|t| t.parse().ok()),
+            })
+        })
+        .collect()
+}
+
+/// Item 601 number of an exhibit type ("EX-10.1" -> 10, "EX-101.INS" -> 101)
+pub fn exhibit_number(doc_type: &str) -> Option<u32> {
+    let rest = doc_type.trim().to_uppercase();
+    let rest = rest.strip_prefix("EX-")?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// What an exhibit type contains, if it follows Item 601 numbering
+pub fn exhibit_category(doc_type: &str) -> Option<&'static str> {
+    let number = exhibit_number(doc_type)?;
+    EXHIBIT_CATEGORIES
+        .iter()
+        .find(|(n, _)| *n == number)
+        .map(|(_, label)| *label)
+}
+
+/// Labeled exhibit list for a filing index. XBRL exhibits are counted but
+/// only listed when `include_xbrl` is set.
+pub fn label_exhibits(documents: &[IndexDocument], include_xbrl: bool) -> Value {
+    let primary = documents
+        .iter()
+        .find(|d| d.seq == Some(1))
+        .or_else(|| documents.iter().find(|d| exhibit_number(&d.doc_type).is_none()));
+
+    let mut xbrl_documents = 0;
+    let exhibits: Vec<Value> = documents
+        .iter()
+        .filter(|d| {
+            let Some(number) = exhibit_number(&d.doc_type) else {
+                return false;
+            };
+            if number == XBRL_EXHIBIT {
+                xbrl_documents += 1;
+                return include_xbrl;
+            }
+            true
+        })
+        .map(|d| {
+            json!({
+                "exhibit": d.doc_type,
+                "category": exhibit_category(&d.doc_type),
+                "description": d.description,
+                "document": d.document,
+                "size": d.size,
+            })
+        })
+        .collect();
+
+    json!({
+        "primary_document": primary.map(|d| json!({
+            "document": d.document,
+            "type": d.doc_type,
+            "description": d.description,
+        })),
+        "exhibit_count": exhibits.len(),
+        "exhibits": exhibits,
+        "xbrl_documents": xbrl_documents,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INDEX: &str = r#"<html><body>
+        <table class="tableFile" summary="Document Format Files">
+          <tr><th>Seq</th><th>Description</th><th>Document</th><th>Type</th><th>Size</th></tr>
+          <tr><td>1</td><td>8-K</td>
+              <td><a href="/ix?doc=/Archives/edgar/data/320193/000032019324000069/aapl-20240502.htm">aapl-20240502.htm</a> &nbsp;&nbsp;iXBRL</td>
+              <td>8-K</td><td>30123</td></tr>
+          <tr><td>2</td><td>EX-99.1</td>
+              <td><a href="/Archives/edgar/data/320193/000032019324000069/a8-kex991q2202403302024.htm">a8-kex991q2202403302024.htm</a></td>
+              <td>EX-99.1</td><td>48210</td></tr>
+          <tr><td>3</td><td>EX-10.1 Credit Agreement</td>
+              <td><a href="/Archives/edgar/data/320193/000032019324000069/ex101.htm">ex101.htm</a></td>
+              <td>EX-10.1</td><td>912345</td></tr>
+          <tr><td>&nbsp;</td><td>Complete submission text file</td>
+              <td><a href="/Archives/edgar/data/320193/000032019324000069/0000320193-24-000069.txt">0000320193-24-000069.txt</a></td>
+              <td>&nbsp;</td><td>2045678</td></tr>
+        </table>
+        <table class="tableFile" summary="Data Files">
+          <tr><th>Seq</th><th>Description</th><th>Document</th><th>Type</th><th>Size</th></tr>
+          <tr><td>4</td><td>XBRL TAXONOMY EXTENSION SCHEMA DOCUMENT</td>
+              <td><a href="/Archives/edgar/data/320193/000032019324000069/aapl-20240502.xsd">aapl-20240502.xsd</a></td>
+              <td>EX-101.SCH</td><td>3021</td></tr>
+        </table>
+    </body></html>"#;
+
+    #[test]
+    fn test_parse_filing_index() {
+        let documents = parse_filing_index(INDEX);
+        assert_eq!(documents.len(), 5);
+        assert_eq!(documents[0].document, "aapl-20240502.htm");
+        assert_eq!(documents[0].seq, Some(1));
+        assert_eq!(documents[0].size, Some(30123));
+        assert_eq!(documents[2].description.as_deref(), Some("EX-10.1 Credit Agreement"));
+        assert_eq!(documents[3].seq, None);
+        assert_eq!(documents[3].doc_type, "");
+        assert_eq!(documents[4].doc_type, "EX-101.SCH");
+    }
+
+    #[test]
+    fn test_exhibit_category() {
+        assert_eq!(exhibit_number("EX-10.1"), Some(10));
+        assert_eq!(exhibit_number("ex-3(i)"), Some(3));
+        assert_eq!(exhibit_number("EX-101.INS"), Some(101));
+        assert_eq!(exhibit_number("GRAPHIC"), None);
+        assert_eq!(exhibit_category("EX-10.1"), Some("Material Contract"));
+        assert_eq!(exhibit_category("EX-31.2"), Some("Rule 13a-14(a) Certification"));
+        assert_eq!(exhibit_category("EX-6"), None);
+    }
+
+    #[test]
+    fn test_label_exhibits() {
+        let documents = parse_filing_index(INDEX);
+        let labeled = label_exhibits(&documents, false);
+        assert_eq!(labeled["primary_document"]["document"], "aapl-20240502.htm");
+        assert_eq!(labeled["exhibit_count"], 2);
+        assert_eq!(labeled["exhibits"][0]["exhibit"], "EX-99.1");
+        assert_eq!(labeled["exhibits"][1]["category"], "Material Contract");
+        assert_eq!(labeled["xbrl_documents"], 1);
+
+        let with_xbrl = label_exhibits(&documents, true);
+        assert_eq!(with_xbrl["exhibit_count"], 3);
+    }
+}
@@ -0,0 +1,74 @@
+//! Field access helpers shared by the tool post-processors.
+//!
+//! Upstream responses mix flat records and JSON:API style records with an
+//! `attributes` object, and encode numbers either as numbers or strings.
+
+use serde_json::Value;
+
+/// Look up the first present, non-null field, checking `attributes` as well
+pub(crate) fn field<'a>(record: &'a Value, names: &[&str]) -> Option<&'a Value> {
+    names.iter().find_map(|name| {
+        record
+            .get(*name)
+            .filter(|v| !v.is_null())
+            .or_else(|| record.get("attributes").and_then(|a| a.get(*name)))
+            .filter(|v| !v.is_null())
+    })
+}
+
+/// Look up the first present string field
+pub(crate) fn str_field<'a>(record: &'a Value, names: &[&str]) -> Option<&'a str> {
+    field(record, names).and_then(|v| v.as_str())
+}
+
+/// Read a number that may be encoded as a JSON number or a numeric string
+pub(crate) fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().replace(',', "").parse().ok(),
+        _ => None,
+    }
+}
+
+/// Extract the record list from a list endpoint response.
+///
+/// Accepts a bare array or an object wrapping the list under one of `keys`.
+pub(crate) fn records<'a>(response: &'a Value, keys: &[&str]) -> &'a [Value] {
+    if let Some(arr) = response.as_array() {
+        return arr;
+    }
+    keys.iter()
+        .find_map(|key| response.get(*key).and_then(|v| v.as_array()))
+        .map(|v| v.as_slice())
+        .unwrap_or(&[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_field_checks_attributes() {
+        let record = json!({"a": null, "attributes": {"a": 1, "b": 2}});
+        assert_eq!(field(&record, &["a"]), Some(&json!(1)));
+        assert_eq!(field(&record, &["missing", "b"]), Some(&json!(2)));
+        assert_eq!(field(&record, &["missing"]), None);
+    }
+
+    #[test]
+    fn test_number_parsing() {
+        assert_eq!(number(&json!(1.5)), Some(1.5));
+        assert_eq!(number(&json!("1,234.5")), Some(1234.5));
+        assert_eq!(number(&json!("n/a")), None);
+        assert_eq!(number(&json!(null)), None);
+    }
+
+    #[test]
+    fn test_records_shapes() {
+        assert_eq!(records(&json!([1, 2]), &["data"]).len(), 2);
+        assert_eq!(records(&json!({"data": [1]}), &["data"]).len(), 1);
+        assert_eq!(records(&json!({"holdings": [1]}), &["data", "holdings"]).len(), 1);
+        assert!(records(&json!({"count": 0}), &["data"]).is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+//! Keyword search across a company's filings.
+//!
+//! EDGAR full-text search finds the matching documents but returns no
+//! excerpts, so each matched document is fetched and the snippets around the
+//! query terms are cut here, with the matches wrapped in `**`.
+
+use super::fields::str_field;
+use serde::Serialize;
+use serde_json::Value;
+use std::ops::Range;
+
+/// Default number of matching documents to fetch snippets from
+pub const DEFAULT_SEARCH_FILINGS: usize = 10;
+
+/// Most matching documents fetched per call; each is a separate SEC request
+pub const MAX_SEARCH_FILINGS: usize = 20;
+
+/// Default number of snippets returned per document
+pub const DEFAULT_SNIPPETS_PER_FILING: usize = 3;
+
+/// Characters of context kept on each side of a match
+const SNIPPET_CONTEXT_CHARS: usize = 150;
+
+/// A document matched by EDGAR full-text search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchHit {
+    pub cik: String,
+    pub accession_number: String,
+    /// Filename of the matched document within the filing
+    pub document: String,
+    pub form_type: Option<String>,
+    pub filing_date: Option<String>,
+    /// Exhibit or document type, e.g. "10-K" or "EX-99.1"
+    pub document_type: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Total match count reported by a full-text search response
+pub fn total_hits(response: &Value) -> u64 {
+    response["hits"]["total"]["value"].as_u64().unwrap_or(0)
+}
+
+/// Matched documents in a full-text search response, in relevance order.
+///
+/// Hit ids have the form "<accession number>:<filename>".
+pub fn search_hits(response: &Value) -> Vec<SearchHit> {
+    response["hits"]["hits"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|hit| {
+            let (accession_number, document) = hit["_id"].as_str()?.split_once(':')?;
+            let source = &hit["_source"];
+            let owned = |names: &[&str]| str_field(source, names).map(str::to_string);
+            Some(SearchHit {
+                cik: source["ciks"].get(0)?.as_str()?.to_string(),
+                accession_number: accession_number.to_string(),
+                document: document.to_string(),
+                form_type: owned(&["form", "root_form"]),
+                filing_date: owned(&["file_date"]),
+                document_type: owned(&["file_type"]),
+                description: owned(&["file_description"]),
+            })
+        })
+        .collect()
+}
+
+/// Lowercased terms to highlight from a full-text search query: quoted
+/// phrases and bare words, skipping boolean operators and excluded terms
+pub fn search_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            let phrase = part.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !phrase.is_empty() {
+                terms.push(phrase.to_lowercase());
+            }
+            continue;
+        }
+        for word in part.split_whitespace() {
+            if matches!(word, "OR" | "AND" | "NOT") || word.starts_with('-') {
+                continue;
+            }
+            let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+            if !word.is_empty() {
+                terms.push(word.to_lowercase());
+            }
+        }
+    }
+    terms
+}
+
+/// Byte ranges of whole-word, case-insensitive matches of `terms`, sorted
+/// and non-overlapping
+pub fn keyword_matches(text: &str, terms: &[String]) -> Vec<Range<usize>> {
+    // ASCII lowercasing keeps byte offsets aligned with the original text
+    let haystack = text.to_ascii_lowercase();
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric());
+    let mut matches: Vec<Range<usize>> = Vec::new();
+    for term in terms.iter().filter(|t| !t.is_empty()) {
+        let term = term.to_ascii_lowercase();
+        for (start, _) in haystack.match_indices(&term) {
+            let end = start + term.len();
+            if !is_word(haystack[..start].chars().next_back()) && !is_word(haystack[end..].chars().next()) {
+                matches.push(start..end);
+            }
+        }
+    }
+    matches.sort_by_key(|m| (m.start, std::cmp::Reverse(m.end)));
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(matches.len());
+    for m in matches {
+        match merged.last() {
+            Some(last) if m.start < last.end => {}
+            _ => merged.push(m),
+        }
+    }
+    merged
+}
+
+/// Up to `max` excerpts around `matches`, each match wrapped in `**`.
+/// Matches close enough to share context are combined into one excerpt.
+pub fn snippets(text: &str, matches: &[Range<usize>], max: usize) -> Vec<String> {
+    let mut excerpts = Vec::new();
+    let mut i = 0;
+    while i < matches.len() && excerpts.len() < max {
+        let start = floor_char_boundary(text, matches[i].start.saturating_sub(SNIPPET_CONTEXT_CHARS));
+        let mut end = ceil_char_boundary(text, matches[i].end + SNIPPET_CONTEXT_CHARS);
+        let mut excerpt = String::new();
+        let mut cursor = start;
+        while i < matches.len() && matches[i].start < end {
+            let m = &matches[i];
+            end = end.max(ceil_char_boundary(text, m.end + SNIPPET_CONTEXT_CHARS));
+            excerpt.push_str(&text[cursor..m.start]);
+            excerpt.push_str("**");
+            excerpt.push_str(&text[m.clone()]);
+            excerpt.push_str("**");
+            cursor = m.end;
+            i += 1;
+        }
+        excerpt.push_str(&text[cursor..end]);
+
+        let mut excerpt = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+        if start > 0 {
+            excerpt.insert_str(0, "...");
+        }
+        if end < text.len() {
+            excerpt.push_str("...");
+        }
+        excerpts.push(excerpt);
+    }
+    excerpts
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn ceil_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_search_hits() {
+        let response = json!({
+            "hits": {
+                "total": {"value": 42, "relation": "eq"},
+                "hits": [
+                    {
+                        "_id": "0000320193-23-000106:aapl-20230930.htm",
+                        "_source": {
+                            "ciks": ["0000320193"],
+                            "form": "10-K",
+                            "file_date": "2023-11-03",
+                            "file_type": "10-K",
+                            "file_description": "10-K"
+                        }
+                    },
+                    {"_id": "malformed", "_source": {"ciks": ["0000320193"]}}
+                ]
+            }
+        });
+        assert_eq!(total_hits(&response), 42);
+        let hits = search_hits(&response);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].accession_number, "0000320193-23-000106");
+        assert_eq!(hits[0].document, "aapl-20230930.htm");
+        assert_eq!(hits[0].form_type.as_deref(), Some("10-K"));
+        assert_eq!(hits[0].filing_date.as_deref(), Some("2023-11-03"));
+    }
+
+    #[test]
+    fn test_search_terms() {
+        assert_eq!(
+            search_terms(r#""supply chain" OR tariffs -china"#),
+            vec!["supply chain", "tariffs"]
+        );
+        assert_eq!(search_terms("AI, cybersecurity"), vec!["ai", "cybersecurity"]);
+        assert!(search_terms("\"\"").is_empty());
+    }
+
+    #[test]
+    fn test_keyword_matches_whole_words() {
+        let text = "AI said the Tariffs on AI chips";
+        let matches = keyword_matches(text, &search_terms("ai tariffs"));
+        let found: Vec<&str> = matches.iter().map(|m| &text[m.clone()]).collect();
+        assert_eq!(found, vec!["AI", "Tariffs", "AI"]);
+    }
+
+    #[test]
+    fn test_snippets_highlight_and_merge() {
+        let text = format!("{} Tariffs and more tariffs. {}", "x ".repeat(200), "y ".repeat(200));
+        let matches = keyword_matches(&text, &["tariffs".to_string()]);
+        assert_eq!(matches.len(), 2);
+        let excerpts = snippets(&text, &matches, 3);
+        assert_eq!(excerpts.len(), 1);
+        assert!(excerpts[0].starts_with("..."));
+        assert!(excerpts[0].ends_with("..."));
+        assert!(excerpts[0].contains("**Tariffs** and more **tariffs**."));
+    }
+
+    #[test]
+    fn test_snippets_respect_max_and_char_boundaries() {
+        let text = format!("é{} risk {} risk", "é".repeat(300), "é".repeat(300));
+        let matches = keyword_matches(&text, &["risk".to_string()]);
+        let excerpts = snippets(&text, &matches, 1);
+        assert_eq!(excerpts.len(), 1);
+        assert!(excerpts[0].contains("**risk**"));
+    }
+}
@@ -0,0 +1,364 @@
+//! Financial statement helpers.
+//!
+//! Maps common metric names (revenue, net income, EPS, ...) to the line
+//! items financial statements report them under, and aligns one metric
+//! across several companies into comparable quarterly or annual series with
+//! CAGR and relative indexing.
+
+use super::fields::{field, number, records, str_field};
+use crate::error::CoreError;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Maximum number of companies compared in one request
+pub const MAX_COMPARE_COMPANIES: usize = 10;
+
+/// Normalized metric names and the statement line items they are read from,
+/// most specific first
+pub const KEY_METRICS: &[(&str, &[&str])] = &[
+    ("revenue", &["revenues", "revenue", "total_revenue", "net_sales", "sales"]),
+    ("gross_profit", &["gross_profit"]),
+    ("operating_income", &["operating_income_loss", "operating_income"]),
+    ("net_income", &["net_income_loss_attributable_to_parent", "net_income_loss", "net_income"]),
+    ("eps_basic", &["basic_earnings_per_share", "eps_basic"]),
+    ("eps_diluted", &["diluted_earnings_per_share", "eps_diluted"]),
+    ("total_assets", &["assets", "total_assets"]),
+    ("total_liabilities", &["liabilities", "total_liabilities"]),
+    ("equity", &["equity_attributable_to_parent", "equity", "stockholders_equity"]),
+    ("operating_cash_flow", &["net_cash_flow_from_operating_activities", "operating_cash_flow"]),
+    ("research_and_development", &["research_and_development", "research_and_development_expense"]),
+];
+
+/// Line items for a normalized metric name
+pub fn metric_keys(metric: &str) -> Option<&'static [&'static str]> {
+    let metric = metric.trim().to_lowercase().replace([' ', '-'], "_");
+    KEY_METRICS
+        .iter()
+        .find(|(name, _)| *name == metric)
+        .map(|(_, keys)| *keys)
+}
+
+/// Normalized metric names, for schemas and error messages
+pub fn metric_names() -> Vec<&'static str> {
+    KEY_METRICS.iter().map(|(name, _)| *name).collect()
+}
+
+/// Reporting frequency of a comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timeframe {
+    Quarterly,
+    Annual,
+}
+
+impl Timeframe {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Quarterly => "quarterly",
+            Self::Annual => "annual",
+        }
+    }
+
+    fn periods_per_year(&self) -> f64 {
+        match self {
+            Self::Quarterly => 4.0,
+            Self::Annual => 1.0,
+        }
+    }
+}
+
+impl std::str::FromStr for Timeframe {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "quarterly" | "quarter" | "q" => Ok(Self::Quarterly),
+            "annual" | "annually" | "yearly" | "fy" => Ok(Self::Annual),
+            _ => Err(format!("Unknown timeframe '{}': use quarterly or annual", s)),
+        }
+    }
+}
+
+/// One company's financials response
+#[derive(Debug)]
+pub struct CompanyFinancials {
+    pub company: String,
+    pub response: Result<Value, CoreError>,
+}
+
+/// Read a line item anywhere in a statement, either as a bare number or as
+/// an object with a `value`
+fn line_item(statement: &Value, keys: &[&str]) -> Option<f64> {
+    fn find(value: &Value, key: &str, depth: usize) -> Option<f64> {
+        let obj = value.as_object()?;
+        if let Some(item) = obj.get(key) {
+            let n = match item {
+                Value::Object(_) => item.get("value").and_then(number),
+                other => number(other),
+            };
+            if n.is_some() {
+                return n;
+            }
+        }
+        if depth == 0 {
+            return None;
+        }
+        obj.values().find_map(|child| find(child, key, depth - 1))
+    }
+    keys.iter().find_map(|key| find(statement, key, 3))
+}
+
+/// Period end date of a statement (YYYY-MM-DD)
+fn period_end(statement: &Value) -> Option<&str> {
+    str_field(statement, &["period_of_report_date", "end_date", "period_end"])
+}
+
+/// Label for the period a statement covers ("2023" or "2023-Q2"), or None
+/// if it doesn't belong to `timeframe`
+fn period_label(statement: &Value, timeframe: Timeframe) -> Option<String> {
+    let fiscal_period = str_field(statement, &["fiscal_period"]).map(|p| p.trim().to_uppercase());
+    let end = period_end(statement);
+    let fiscal_year = field(statement, &["fiscal_year"])
+        .and_then(number)
+        .map(|y| y as i32)
+        .or_else(|| end.and_then(|d| d.get(..4)).and_then(|y| y.parse().ok()))?;
+
+    match (timeframe, fiscal_period.as_deref()) {
+        (Timeframe::Annual, Some("FY")) => Some(fiscal_year.to_string()),
+        (Timeframe::Quarterly, Some(p @ ("Q1" | "Q2" | "Q3" | "Q4"))) => {
+            Some(format!("{}-{}", fiscal_year, p))
+        }
+        (_, Some(_)) => None,
+        // Without a fiscal period, go by the statement's own timeframe and
+        // the calendar quarter of its end date
+        (_, None) => {
+            let declared = str_field(statement, &["timeframe"]).and_then(|t| t.parse::<Timeframe>().ok());
+            if declared.is_some_and(|t| t != timeframe) {
+                return None;
+            }
+            match timeframe {
+                Timeframe::Annual => Some(fiscal_year.to_string()),
+                Timeframe::Quarterly => {
+                    let month: u32 = end?.get(5..7)?.parse().ok()?;
+                    Some(format!("{}-Q{}", fiscal_year, month.div_ceil(3)))
+                }
+            }
+        }
+    }
+}
+
+/// Sequence number of a period label, so gaps between labels count
+fn period_ordinal(label: &str) -> i32 {
+    let year: i32 = label.get(..4).and_then(|y| y.parse().ok()).unwrap_or(0);
+    match label.get(6..7).and_then(|q| q.parse::<i32>().ok()) {
+        Some(quarter) => year * 4 + quarter - 1,
+        None => year,
+    }
+}
+
+/// Compound annual growth rate in percent over a (possibly fractional)
+/// number of years
+fn cagr(start: f64, end: f64, years: f64) -> Option<f64> {
+    if start <= 0.0 || end < 0.0 || years <= 0.0 {
+        return None;
+    }
+    Some(round2(((end / start).powf(1.0 / years) - 1.0) * 100.0))
+}
+
+/// Align `metric` across companies into index-aligned series.
+///
+/// Periods ending before `since` (YYYY-MM-DD) are dropped. Each company gets
+/// its raw series, a series indexed to 100 at the first period every
+/// company reported, and a CAGR between its first and last reported values.
+/// Companies that failed to load are reported under `errors`.
+pub fn compare_timeseries(
+    metric: &str,
+    keys: &[&str],
+    timeframe: Timeframe,
+    since: Option<&str>,
+    companies: Vec<CompanyFinancials>,
+) -> Value {
+    let mut per_company: Vec<(String, BTreeMap<String, f64>)> = Vec::new();
+    let mut errors = serde_json::Map::new();
+
+    for company in companies {
+        let response = match company.response {
+            Ok(response) => response,
+            Err(e) => {
+                errors.insert(company.company, e.to_json());
+                continue;
+            }
+        };
+        let mut values = BTreeMap::new();
+        for statement in records(&response, &["data", "results", "financials"]) {
+            if since.is_some_and(|since| period_end(statement).is_some_and(|end| end < since)) {
+                continue;
+            }
+            if let (Some(label), Some(value)) = (period_label(statement, timeframe), line_item(statement, keys)) {
+                // Keep the first (latest filed) value for restated periods
+                values.entry(label).or_insert(value);
+            }
+        }
+        per_company.push((company.company, values));
+    }
+
+    let labels: Vec<String> = per_company
+        .iter()
+        .flat_map(|(_, values)| values.keys().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let index_base = labels
+        .iter()
+        .find(|label| per_company.iter().all(|(_, values)| values.contains_key(*label)));
+
+    let mut series = serde_json::Map::new();
+    let mut indexed = serde_json::Map::new();
+    let mut growth = serde_json::Map::new();
+    for (company, values) in &per_company {
+        let raw: Vec<Option<f64>> = labels.iter().map(|label| values.get(label).copied()).collect();
+
+        let base = index_base.and_then(|label| values.get(label)).filter(|b| **b != 0.0);
+        let relative: Vec<Option<f64>> = raw
+            .iter()
+            .map(|v| v.zip(base).map(|(v, base)| round2(v / base * 100.0)))
+            .collect();
+
+        let first = raw.iter().position(Option::is_some);
+        let last = raw.iter().rposition(Option::is_some);
+        let company_growth = match (first, last) {
+            (Some(first), Some(last)) if last > first => {
+                let years = (period_ordinal(&labels[last]) - period_ordinal(&labels[first])) as f64
+                    / timeframe.periods_per_year();
+                json!({
+                    "start_period": labels[first],
+                    "end_period": labels[last],
+                    "percent": cagr(raw[first].unwrap(), raw[last].unwrap(), years)
+                })
+            }
+            _ => Value::Null,
+        };
+
+        series.insert(company.clone(), json!(raw));
+        indexed.insert(company.clone(), json!(relative));
+        growth.insert(company.clone(), company_growth);
+    }
+
+    let mut result = json!({
+        "metric": metric,
+        "line_items": keys,
+        "timeframe": timeframe.as_str(),
+        "labels": labels,
+        "series": series,
+        "index_base": index_base,
+        "indexed": indexed,
+        "cagr": growth
+    });
+    if !errors.is_empty() {
+        result["errors"] = Value::Object(errors);
+    }
+    result
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_client::ApiError;
+
+    fn statement(fiscal_year: i32, fiscal_period: &str, end: &str, revenue: f64) -> Value {
+        json!({
+            "fiscal_year": fiscal_year,
+            "fiscal_period": fiscal_period,
+            "period_of_report_date": end,
+            "financials": { "income_statement": { "revenues": { "value": revenue, "unit": "USD" } } }
+        })
+    }
+
+    #[test]
+    fn test_metric_keys() {
+        assert_eq!(metric_keys("Net Income").unwrap()[0], "net_income_loss_attributable_to_parent");
+        assert!(metric_keys("eps-diluted").is_some());
+        assert!(metric_keys("vibes").is_none());
+        assert!(metric_names().contains(&"revenue"));
+    }
+
+    #[test]
+    fn test_line_item_shapes() {
+        assert_eq!(line_item(&json!({"revenues": 5}), &["revenues"]), Some(5.0));
+        assert_eq!(line_item(&json!({"attributes": {"net_sales": "7"}}), &["revenues", "net_sales"]), Some(7.0));
+        assert_eq!(line_item(&json!({"revenues": null}), &["revenues"]), None);
+    }
+
+    #[test]
+    fn test_period_labels() {
+        let q = statement(2024, "Q2", "2024-03-30", 1.0);
+        assert_eq!(period_label(&q, Timeframe::Quarterly).as_deref(), Some("2024-Q2"));
+        assert_eq!(period_label(&q, Timeframe::Annual), None);
+
+        let fy = statement(2023, "FY", "2023-09-30", 1.0);
+        assert_eq!(period_label(&fy, Timeframe::Annual).as_deref(), Some("2023"));
+        assert_eq!(period_label(&fy, Timeframe::Quarterly), None);
+
+        let bare = json!({"period_of_report_date": "2022-08-31", "timeframe": "quarterly"});
+        assert_eq!(period_label(&bare, Timeframe::Quarterly).as_deref(), Some("2022-Q3"));
+        assert_eq!(period_label(&bare, Timeframe::Annual), None);
+    }
+
+    #[test]
+    fn test_compare_annual() {
+        let aapl = json!({"results": [
+            statement(2023, "FY", "2023-09-30", 121.0),
+            statement(2022, "FY", "2022-09-24", 110.0),
+            statement(2021, "FY", "2021-09-25", 100.0),
+            statement(2020, "FY", "2020-09-26", 90.0),
+        ]});
+        let msft = json!({"results": [
+            statement(2023, "FY", "2023-06-30", 60.0),
+            statement(2022, "FY", "2022-06-30", 50.0),
+        ]});
+        let result = compare_timeseries(
+            "revenue",
+            metric_keys("revenue").unwrap(),
+            Timeframe::Annual,
+            Some("2021-01-01"),
+            vec![
+                CompanyFinancials { company: "AAPL".into(), response: Ok(aapl) },
+                CompanyFinancials { company: "MSFT".into(), response: Ok(msft) },
+                CompanyFinancials { company: "NOPE".into(), response: Err(ApiError::NotFound.into()) },
+            ],
+        );
+
+        assert_eq!(result["labels"], json!(["2021", "2022", "2023"]));
+        assert_eq!(result["series"]["AAPL"], json!([100.0, 110.0, 121.0]));
+        assert_eq!(result["series"]["MSFT"], json!([null, 50.0, 60.0]));
+        assert_eq!(result["index_base"], "2022");
+        assert_eq!(result["indexed"]["MSFT"], json!([null, 100.0, 120.0]));
+        assert_eq!(result["cagr"]["AAPL"]["percent"], 10.0);
+        assert_eq!(result["cagr"]["MSFT"]["start_period"], "2022");
+        assert_eq!(result["errors"]["NOPE"]["code"], "NOT_FOUND");
+    }
+
+    #[test]
+    fn test_compare_quarterly_cagr_uses_fractional_years() {
+        let data = json!({"data": [
+            statement(2024, "Q1", "2023-12-30", 110.0),
+            statement(2023, "Q3", "2023-07-01", 100.0),
+            statement(2023, "FY", "2023-09-30", 400.0),
+        ]});
+        let result = compare_timeseries(
+            "revenue",
+            &["revenues"],
+            Timeframe::Quarterly,
+            None,
+            vec![CompanyFinancials { company: "X".into(), response: Ok(data) }],
+        );
+        assert_eq!(result["labels"], json!(["2023-Q3", "2024-Q1"]));
+        // Two quarters of 10% growth is 21% a year
+        assert_eq!(result["cagr"]["X"]["percent"], 21.0);
+        assert!(result.get("errors").is_none());
+    }
+}
@@ -0,0 +1,104 @@
+//! Typo-tolerant matching for tool search.
+//!
+//! `search_tools` matches queries against tool names, descriptions, and
+//! keywords by substring, so a misspelled word ("finanicals", "lobying")
+//! finds nothing. Words that appear nowhere in the registry are corrected
+//! to the closest known word within a few edits, and the search is retried
+//! with the corrected query at a penalty per edit.
+
+/// Words shorter than this are never corrected; too many short words are a
+/// single edit apart
+const MIN_CORRECTABLE_LEN: usize = 4;
+
+/// Edits allowed when correcting a word of `len` characters
+pub fn max_edits(len: usize) -> usize {
+    match len {
+        0..MIN_CORRECTABLE_LEN => 0,
+        MIN_CORRECTABLE_LEN..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Edit distance between two words, counting insertions, deletions,
+/// substitutions, and swaps of adjacent characters as one edit each
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // Three rows of the optimal string alignment matrix
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// The closest word in `vocabulary` to `word` within [`max_edits`], with
+/// its distance. Ties go to the earlier word.
+pub fn closest<'a>(word: &str, vocabulary: impl IntoIterator<Item = &'a str>) -> Option<(&'a str, usize)> {
+    let limit = max_edits(word.chars().count());
+    if limit == 0 {
+        return None;
+    }
+    let mut best: Option<(&str, usize)> = None;
+    for candidate in vocabulary {
+        // Lengths further apart than the limit can't be close enough
+        if candidate.chars().count().abs_diff(word.chars().count()) > limit {
+            continue;
+        }
+        let distance = edit_distance(word, candidate);
+        if distance <= limit && best.is_none_or(|(_, d)| distance < d) {
+            best = Some((candidate, distance));
+        }
+    }
+    best
+}
+
+/// Split text into lowercase words
+pub fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("lobying", "lobbying"), 1);
+        assert_eq!(edit_distance("finanicals", "financials"), 1);
+        assert_eq!(edit_distance("insdier", "insider"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("same", "same"), 0);
+    }
+
+    #[test]
+    fn test_closest() {
+        let vocabulary = ["filings", "financials", "lobbying", "holdings"];
+        assert_eq!(closest("finanicals", vocabulary), Some(("financials", 1)));
+        assert_eq!(closest("lobying", vocabulary), Some(("lobbying", 1)));
+        assert_eq!(closest("holdngs", vocabulary), Some(("holdings", 1)));
+        // Too far, and too short to correct
+        assert_eq!(closest("weather", vocabulary), None);
+        assert_eq!(closest("fil", vocabulary), None);
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(words("get_form4_filing, Form 4!").collect::<Vec<_>>(), ["get", "form4", "filing", "form", "4"]);
+    }
+}
@@ -0,0 +1,823 @@
+//! Institutional filings helpers.
+//!
+//! Groups Form 13-F holdings into issuer, asset class, or sector totals so
+//! large portfolios fit into a single response, diffs a filer's positions
+//! between quarters, decodes Form 4 transaction codes into readable labels,
+//! and scores insider sentiment from open market Form 4 trades.
+
+use super::fields::{field, number, records, str_field};
+use crate::error::CoreError;
+use crate::params;
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Grouping dimension for 13-F holdings aggregation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateBy {
+    Issuer,
+    AssetClass,
+    Sector,
+}
+
+impl AggregateBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Issuer => "issuer",
+            Self::AssetClass => "asset_class",
+            Self::Sector => "sector",
+        }
+    }
+}
+
+impl std::str::FromStr for AggregateBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "issuer" => Ok(Self::Issuer),
+            "asset_class" => Ok(Self::AssetClass),
+            "sector" => Ok(Self::Sector),
+            _ => Err(format!(
+                "Unknown aggregate_by '{}'. Valid values: issuer, asset_class, sector",
+                s
+            )),
+        }
+    }
+}
+
+/// Classify a 13-F line item from its title of class and put/call flag
+pub fn asset_class(title_of_class: Option<&str>, put_call: Option<&str>) -> &'static str {
+    if let Some(pc) = put_call.map(|s| s.trim().to_uppercase()) {
+        if pc == "PUT" {
+            return "Put Options";
+        }
+        if pc == "CALL" {
+            return "Call Options";
+        }
+    }
+
+    let title = title_of_class.unwrap_or("").to_uppercase();
+    if title.is_empty() {
+        "Unknown"
+    } else if title.contains("NOTE") || title.contains("BOND") || title.contains("DEBT") {
+        "Debt"
+    } else if title.contains("PFD") || title.contains("PREF") {
+        "Preferred Stock"
+    } else if title.contains("WT") || title.contains("WARRANT") {
+        "Warrants"
+    } else if title.contains("ADR") || title.contains("ADS") || title.contains("SPONSORED") {
+        "ADRs"
+    } else if title.contains("ETF") || title.contains("FUND") || title.contains("TR UNIT") {
+        "Funds"
+    } else {
+        "Common Stock"
+    }
+}
+
+/// Aggregate 13-F holdings into grouped totals with percentage of portfolio value.
+///
+/// Groups are sorted by value, largest first. Holdings without a resolvable
+/// group (e.g. no sector in the data) are collected under "Unknown".
+pub fn aggregate_holdings(response: &Value, by: AggregateBy) -> Value {
+    struct Group {
+        value: f64,
+        shares: f64,
+        positions: usize,
+    }
+
+    let holdings = records(response, &["holdings", "data", "positions"]);
+    let mut groups: HashMap<String, Group> = HashMap::new();
+    let mut total_value = 0.0;
+
+    for holding in holdings {
+        let key = match by {
+            AggregateBy::Issuer => str_field(holding, &["name_of_issuer", "issuer", "name"])
+                .map(|s| s.trim().to_string()),
+            AggregateBy::AssetClass => Some(
+                asset_class(
+                    str_field(holding, &["title_of_class", "class_title"]),
+                    str_field(holding, &["put_call"]),
+                )
+                .to_string(),
+            ),
+            AggregateBy::Sector => {
+                str_field(holding, &["sector", "gics_sector"]).map(|s| s.trim().to_string())
+            }
+        }
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "Unknown".to_string());
+
+        let value = field(holding, &["value", "market_value"])
+            .and_then(number)
+            .unwrap_or(0.0);
+        let shares = field(holding, &["shares", "ssh_prnamt", "shares_or_principal"])
+            .and_then(number)
+            .unwrap_or(0.0);
+
+        total_value += value;
+        let group = groups.entry(key).or_insert(Group {
+            value: 0.0,
+            shares: 0.0,
+            positions: 0,
+        });
+        group.value += value;
+        group.shares += shares;
+        group.positions += 1;
+    }
+
+    let mut rows: Vec<(String, Group)> = groups.into_iter().collect();
+    rows.sort_by(|a, b| {
+        b.1.value
+            .partial_cmp(&a.1.value)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+
+    let groups: Vec<Value> = rows
+        .into_iter()
+        .map(|(name, g)| {
+            let percent = if total_value > 0.0 {
+                (g.value / total_value * 10_000.0).round() / 100.0
+            } else {
+                0.0
+            };
+            json!({
+                "group": name,
+                "value": g.value,
+                "percent": percent,
+                "positions": g.positions,
+                "shares": g.shares
+            })
+        })
+        .collect();
+
+    json!({
+        "aggregate_by": by.as_str(),
+        "total_value": total_value,
+        "position_count": holdings.len(),
+        "group_count": groups.len(),
+        "groups": groups
+    })
+}
+
+/// Maximum number of filers tracked in one 13-F activity request
+pub const MAX_ACTIVITY_FILERS: usize = 10;
+
+/// Positions listed per category (new, closed, increased, decreased)
+const ACTIVITY_TOP_N: usize = 10;
+
+/// A 13-F position, summed over line items for the same security
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Position {
+    issuer: String,
+    title_of_class: Option<String>,
+    value: f64,
+    shares: f64,
+}
+
+/// Positions keyed by CUSIP (or issuer name) plus put/call, so options
+/// don't net against the underlying shares
+fn positions_from_response(response: &Value) -> HashMap<String, Position> {
+    let mut positions: HashMap<String, Position> = HashMap::new();
+    for holding in records(response, &["holdings", "data", "positions"]) {
+        let issuer = str_field(holding, &["name_of_issuer", "issuer", "name"]).map(str::trim);
+        let Some(id) = str_field(holding, &["cusip"]).map(str::to_uppercase).or(issuer.map(str::to_uppercase))
+        else {
+            continue;
+        };
+        let key = match str_field(holding, &["put_call"]).map(|pc| pc.trim().to_uppercase()) {
+            Some(pc) if !pc.is_empty() => format!("{}:{}", id, pc),
+            _ => id,
+        };
+        let position = positions.entry(key).or_insert_with(|| Position {
+            issuer: issuer.unwrap_or("Unknown").to_string(),
+            title_of_class: str_field(holding, &["title_of_class", "class_title"]).map(str::to_string),
+            ..Position::default()
+        });
+        position.value += field(holding, &["value", "market_value"]).and_then(number).unwrap_or(0.0);
+        position.shares += field(holding, &["shares", "ssh_prnamt", "shares_or_principal"])
+            .and_then(number)
+            .unwrap_or(0.0);
+    }
+    positions
+}
+
+/// Quarter end before the quarter containing `period` (YYYY-MM-DD)
+pub fn prior_quarter_end(period: &str) -> Option<String> {
+    let date = NaiveDate::parse_from_str(period.get(..10)?, "%Y-%m-%d").ok()?;
+    let (year, quarter) = params::quarter_of(date);
+    let (year, quarter) = if quarter == 1 { (year - 1, 4) } else { (year, quarter - 1) };
+    params::quarter_range(year, quarter).map(|range| range.end.format("%Y-%m-%d").to_string())
+}
+
+/// Report period of a 13-F holdings response, if it says
+pub fn report_period(response: &Value) -> Option<&str> {
+    str_field(response, &["period_of_report", "report_period", "period"]).or_else(|| {
+        records(response, &["holdings", "data", "positions"])
+            .first()
+            .and_then(|h| str_field(h, &["period_of_report", "report_period"]))
+    })
+}
+
+/// Diff a filer's positions between two quarters: brand-new positions, full
+/// exits, and the largest share increases and decreases in held positions.
+pub fn diff_positions(current: &Value, prior: &Value) -> Value {
+    let current = positions_from_response(current);
+    let prior = positions_from_response(prior);
+
+    let row = |key: &str, p: &Position| {
+        json!({
+            "key": key,
+            "issuer": p.issuer,
+            "title_of_class": p.title_of_class,
+            "value": p.value,
+            "shares": p.shares
+        })
+    };
+    let by_value = |a: &(&String, &Position), b: &(&String, &Position)| {
+        b.1.value
+            .partial_cmp(&a.1.value)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(b.0))
+    };
+
+    let mut new_positions: Vec<_> = current.iter().filter(|(k, _)| !prior.contains_key(*k)).collect();
+    new_positions.sort_by(by_value);
+    let mut closed_positions: Vec<_> = prior.iter().filter(|(k, _)| !current.contains_key(*k)).collect();
+    closed_positions.sort_by(by_value);
+
+    let mut changes: Vec<(&String, &Position, &Position, f64)> = current
+        .iter()
+        .filter_map(|(key, now)| {
+            let then = prior.get(key)?;
+            let change = now.shares - then.shares;
+            (change != 0.0).then_some((key, now, then, change))
+        })
+        .collect();
+    // Largest moves by value of the shares traded, priced at this quarter's value
+    let traded_value = |(_, now, _, change): &(&String, &Position, &Position, f64)| {
+        if now.shares > 0.0 {
+            (change / now.shares * now.value).abs()
+        } else {
+            change.abs()
+        }
+    };
+    changes.sort_by(|a, b| {
+        traded_value(b)
+            .partial_cmp(&traded_value(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(b.0))
+    });
+    let change_row = |(key, now, then, change): &(&String, &Position, &Position, f64)| {
+        let percent = (then.shares > 0.0).then(|| round2(change / then.shares * 100.0));
+        json!({
+            "key": key,
+            "issuer": now.issuer,
+            "shares": now.shares,
+            "prior_shares": then.shares,
+            "share_change": change,
+            "percent_change": percent,
+            "value": now.value,
+            "prior_value": then.value
+        })
+    };
+    let increases: Vec<Value> = changes.iter().filter(|c| c.3 > 0.0).take(ACTIVITY_TOP_N).map(change_row).collect();
+    let decreases: Vec<Value> = changes.iter().filter(|c| c.3 < 0.0).take(ACTIVITY_TOP_N).map(change_row).collect();
+
+    json!({
+        "summary": {
+            "positions": current.len(),
+            "prior_positions": prior.len(),
+            "new": new_positions.len(),
+            "closed": closed_positions.len(),
+            "increased": changes.iter().filter(|c| c.3 > 0.0).count(),
+            "decreased": changes.iter().filter(|c| c.3 < 0.0).count()
+        },
+        "new_positions": new_positions.iter().take(ACTIVITY_TOP_N).map(|(k, p)| row(k, p)).collect::<Vec<_>>(),
+        "closed_positions": closed_positions.iter().take(ACTIVITY_TOP_N).map(|(k, p)| row(k, p)).collect::<Vec<_>>(),
+        "largest_increases": increases,
+        "largest_decreases": decreases
+    })
+}
+
+/// One filer's latest and prior quarter holdings
+#[derive(Debug)]
+pub struct FilerQuarters {
+    pub filer_cik: String,
+    pub result: Result<(String, Value, Value), CoreError>,
+}
+
+/// Combine per-filer quarter diffs; failed filers are reported under `errors`
+pub fn filer_activity(filers: Vec<FilerQuarters>) -> Value {
+    let mut activity = Vec::new();
+    let mut errors = serde_json::Map::new();
+    for filer in filers {
+        match filer.result {
+            Ok((period, current, prior)) => {
+                let mut diff = diff_positions(&current, &prior);
+                diff["filer_cik"] = json!(filer.filer_cik);
+                diff["period_of_report"] = json!(period);
+                diff["prior_period"] = json!(prior_quarter_end(&period));
+                activity.push(diff);
+            }
+            Err(e) => {
+                errors.insert(filer.filer_cik, e.to_json());
+            }
+        }
+    }
+    let mut result = json!({ "filers": activity });
+    if !errors.is_empty() {
+        result["errors"] = Value::Object(errors);
+    }
+    result
+}
+
+/// Human-readable label for a Form 4 transaction code (SEC Form 4, General Instruction 8)
+pub fn transaction_code_label(code: &str) -> Option<&'static str> {
+    let label = match code.trim().to_uppercase().as_str() {
+        "P" => "Open market or private purchase",
+        "S" => "Open market or private sale",
+        "A" => "Grant or award",
+        "D" => "Disposition to the issuer",
+        "F" => "Tax or exercise payment with securities",
+        "I" => "Discretionary transaction",
+        "M" => "Exercise or conversion of exempt derivative",
+        "C" => "Conversion of derivative security",
+        "E" => "Expiration of short derivative position",
+        "H" => "Expiration of long derivative position",
+        "O" => "Exercise of out-of-the-money derivative",
+        "X" => "Exercise of in-the-money derivative",
+        "G" => "Bona fide gift",
+        "L" => "Small acquisition",
+        "W" => "Acquisition or disposition by will or laws of descent",
+        "Z" => "Deposit into or withdrawal from voting trust",
+        "J" => "Other acquisition or disposition",
+        "K" => "Equity swap or similar instrument",
+        "U" => "Disposition due to tender of shares in change of control",
+        "V" => "Transaction voluntarily reported earlier than required",
+        _ => return None,
+    };
+    Some(label)
+}
+
+/// Annotate every transaction in a Form 4 response with a
+/// `transaction_code_label`, returning per-code counts.
+fn label_transactions(value: &mut Value, counts: &mut HashMap<String, usize>) {
+    match value {
+        Value::Object(obj) => {
+            let label = obj
+                .get("transaction_code")
+                .and_then(|v| v.as_str())
+                .map(|code| (code.to_uppercase(), transaction_code_label(code)));
+            if let Some((code, label)) = label {
+                obj.insert("transaction_code_label".to_string(), json!(label));
+                *counts.entry(code).or_insert(0) += 1;
+            }
+            for (_, child) in obj.iter_mut() {
+                label_transactions(child, counts);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                label_transactions(item, counts);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Decode Form 4 transaction codes throughout a response and attach a
+/// `transaction_summary` with counts per code.
+pub fn decode_form4_transactions(mut response: Value) -> Value {
+    let mut counts = HashMap::new();
+    label_transactions(&mut response, &mut counts);
+
+    let mut summary: Vec<(String, usize)> = counts.into_iter().collect();
+    summary.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let summary: Vec<Value> = summary
+        .into_iter()
+        .map(|(code, count)| {
+            json!({
+                "code": code,
+                "label": transaction_code_label(&code),
+                "count": count
+            })
+        })
+        .collect();
+
+    match response {
+        Value::Object(mut obj) => {
+            obj.insert("transaction_summary".to_string(), json!(summary));
+            Value::Object(obj)
+        }
+        other => json!({
+            "data": other,
+            "transaction_summary": summary
+        }),
+    }
+}
+
+/// Look-back window for insider sentiment when no start date is given
+pub const DEFAULT_SENTIMENT_DAYS: i64 = 90;
+
+/// Sentiment scores above this are bullish, below its negative bearish
+const SENTIMENT_THRESHOLD: f64 = 0.2;
+
+/// Insiders and transactions listed in a sentiment summary
+const SENTIMENT_TOP_N: usize = 10;
+
+/// One open market insider trade
+struct Trade {
+    owner: String,
+    title: Option<String>,
+    date: Option<String>,
+    code: &'static str,
+    shares: f64,
+    price: Option<f64>,
+}
+
+impl Trade {
+    fn value(&self) -> f64 {
+        self.shares * self.price.unwrap_or(0.0)
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "date": self.date,
+            "owner": self.owner,
+            "title": self.title,
+            "transaction_code": self.code,
+            "shares": self.shares,
+            "price": self.price,
+            "value": round2(self.value())
+        })
+    }
+}
+
+/// Role of a reporting owner, from officer title and director/10% flags
+fn owner_title(record: &Value) -> Option<String> {
+    if let Some(title) = str_field(record, &["officer_title", "reporting_owner_title", "title"]) {
+        return Some(title.to_string());
+    }
+    let flag = |names: &[&str]| field(record, names).is_some_and(|v| v.as_bool() == Some(true) || v == "1");
+    if flag(&["is_director", "director"]) {
+        Some("Director".to_string())
+    } else if flag(&["is_ten_percent_owner", "ten_percent_owner"]) {
+        Some("10% Owner".to_string())
+    } else {
+        None
+    }
+}
+
+/// Open market purchases (P) and sales (S) in a Form 4 response. Grants,
+/// exercises, and tax withholding say little about conviction and are left
+/// out.
+fn open_market_trades(response: &Value) -> (Vec<Trade>, usize) {
+    let mut trades = Vec::new();
+    let mut other = 0;
+    for filing in records(response, &["data", "filings", "results"]) {
+        let owner = str_field(filing, &["reporting_owner_name", "reporting_owner", "owner_name"])
+            .unwrap_or("Unknown")
+            .to_string();
+        let title = owner_title(filing);
+        let filed = str_field(filing, &["filing_date", "filed_at"]);
+
+        // Transactions are nested per filing, or the filing is itself one
+        let nested = records(filing, &["transactions", "non_derivative_transactions"]);
+        let transactions: Vec<&Value> = if nested.is_empty() {
+            vec![filing]
+        } else {
+            nested.iter().collect()
+        };
+
+        for transaction in transactions {
+            let code = match str_field(transaction, &["transaction_code"]).map(|c| c.trim().to_uppercase()) {
+                Some(c) if c == "P" => "P",
+                Some(c) if c == "S" => "S",
+                Some(_) => {
+                    other += 1;
+                    continue;
+                }
+                None => continue,
+            };
+            let Some(shares) = field(transaction, &["shares", "transaction_shares", "amount"]).and_then(number) else {
+                continue;
+            };
+            trades.push(Trade {
+                owner: owner.clone(),
+                title: title.clone(),
+                date: str_field(transaction, &["transaction_date"]).or(filed).map(str::to_string),
+                code,
+                shares: shares.abs(),
+                price: field(transaction, &["price_per_share", "transaction_price_per_share", "price"])
+                    .and_then(number),
+            });
+        }
+    }
+    (trades, other)
+}
+
+/// Balance of buying over selling in [-1, 1]: by dollar value when prices
+/// are reported, otherwise by shares
+fn sentiment_score(bought: (f64, f64), sold: (f64, f64)) -> Option<f64> {
+    let (bought_value, bought_shares) = bought;
+    let (sold_value, sold_shares) = sold;
+    if bought_value + sold_value > 0.0 {
+        Some(round2((bought_value - sold_value) / (bought_value + sold_value)))
+    } else if bought_shares + sold_shares > 0.0 {
+        Some(round2((bought_shares - sold_shares) / (bought_shares + sold_shares)))
+    } else {
+        None
+    }
+}
+
+/// Summarize open market insider trading in a Form 4 response into buy/sell
+/// counts, net shares, a sentiment score with a label, the most active
+/// insiders, and the largest supporting transactions.
+pub fn insider_sentiment(response: &Value) -> Value {
+    let (trades, other_transactions) = open_market_trades(response);
+
+    let total = |code: &str| {
+        trades.iter().filter(|t| t.code == code).fold((0.0, 0.0, 0), |(value, shares, count), t| {
+            (value + t.value(), shares + t.shares, count + 1)
+        })
+    };
+    let (bought_value, bought_shares, buys) = total("P");
+    let (sold_value, sold_shares, sells) = total("S");
+
+    let score = sentiment_score((bought_value, bought_shares), (sold_value, sold_shares));
+    let label = match score {
+        Some(s) if s > SENTIMENT_THRESHOLD => "bullish",
+        Some(s) if s < -SENTIMENT_THRESHOLD => "bearish",
+        Some(_) => "neutral",
+        None => "no open market activity",
+    };
+
+    // Per insider: title, buys, sells, net shares, gross value traded
+    let mut insiders: HashMap<&str, (Option<&str>, usize, usize, f64, f64)> = HashMap::new();
+    for trade in &trades {
+        let entry = insiders
+            .entry(trade.owner.as_str())
+            .or_insert((trade.title.as_deref(), 0, 0, 0.0, 0.0));
+        if trade.code == "P" {
+            entry.1 += 1;
+            entry.3 += trade.shares;
+        } else {
+            entry.2 += 1;
+            entry.3 -= trade.shares;
+        }
+        entry.4 += trade.value();
+    }
+    let mut insiders: Vec<_> = insiders.into_iter().collect();
+    insiders.sort_by(|a, b| {
+        (b.1 .4, b.1 .3.abs())
+            .partial_cmp(&(a.1 .4, a.1 .3.abs()))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(b.0))
+    });
+    let notable: Vec<Value> = insiders
+        .into_iter()
+        .take(SENTIMENT_TOP_N)
+        .map(|(owner, (title, buys, sells, net_shares, value))| {
+            json!({
+                "owner": owner,
+                "title": title,
+                "buys": buys,
+                "sells": sells,
+                "net_shares": net_shares,
+                "value_traded": round2(value)
+            })
+        })
+        .collect();
+
+    let mut largest: Vec<&Trade> = trades.iter().collect();
+    largest.sort_by(|a, b| {
+        (b.value(), b.shares)
+            .partial_cmp(&(a.value(), a.shares))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let transactions: Vec<Value> = largest.into_iter().take(SENTIMENT_TOP_N).map(Trade::to_json).collect();
+
+    json!({
+        "score": score,
+        "sentiment": label,
+        "buys": buys,
+        "sells": sells,
+        "shares_bought": bought_shares,
+        "shares_sold": sold_shares,
+        "net_shares": bought_shares - sold_shares,
+        "value_bought": round2(bought_value),
+        "value_sold": round2(sold_value),
+        "other_transactions": other_transactions,
+        "notable_insiders": notable,
+        "transactions": transactions
+    })
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Value {
+        json!({"holdings": [
+            {"name_of_issuer": "APPLE INC", "title_of_class": "COM", "value": 600, "shares": 10},
+            {"name_of_issuer": "APPLE INC", "title_of_class": "COM", "put_call": "Put", "value": 100, "shares": 5},
+            {"name_of_issuer": "BANK OF AMERICA", "title_of_class": "COM", "value": "300", "sector": "Financials"}
+        ]})
+    }
+
+    #[test]
+    fn test_aggregate_by_from_str() {
+        assert_eq!("issuer".parse::<AggregateBy>().unwrap(), AggregateBy::Issuer);
+        assert_eq!("asset_class".parse::<AggregateBy>().unwrap(), AggregateBy::AssetClass);
+        assert_eq!("sector".parse::<AggregateBy>().unwrap(), AggregateBy::Sector);
+        assert!("industry".parse::<AggregateBy>().is_err());
+    }
+
+    #[test]
+    fn test_asset_class() {
+        assert_eq!(asset_class(Some("COM"), None), "Common Stock");
+        assert_eq!(asset_class(Some("COM"), Some("CALL")), "Call Options");
+        assert_eq!(asset_class(Some("NOTE 2.5% 2030"), None), "Debt");
+        assert_eq!(asset_class(Some("SPONSORED ADR"), None), "ADRs");
+        assert_eq!(asset_class(Some("PFD SER A"), None), "Preferred Stock");
+        assert_eq!(asset_class(None, None), "Unknown");
+    }
+
+    #[test]
+    fn test_aggregate_by_issuer() {
+        let result = aggregate_holdings(&sample(), AggregateBy::Issuer);
+        assert_eq!(result["total_value"], 1000.0);
+        assert_eq!(result["group_count"], 2);
+        assert_eq!(result["groups"][0]["group"], "APPLE INC");
+        assert_eq!(result["groups"][0]["value"], 700.0);
+        assert_eq!(result["groups"][0]["percent"], 70.0);
+        assert_eq!(result["groups"][0]["positions"], 2);
+        assert_eq!(result["groups"][0]["shares"], 15.0);
+    }
+
+    #[test]
+    fn test_aggregate_by_asset_class() {
+        let result = aggregate_holdings(&sample(), AggregateBy::AssetClass);
+        assert_eq!(result["groups"][0]["group"], "Common Stock");
+        assert_eq!(result["groups"][0]["value"], 900.0);
+        assert_eq!(result["groups"][1]["group"], "Put Options");
+    }
+
+    #[test]
+    fn test_aggregate_by_sector_unknown_fallback() {
+        let result = aggregate_holdings(&sample(), AggregateBy::Sector);
+        assert_eq!(result["groups"][0]["group"], "Unknown");
+        assert_eq!(result["groups"][1]["group"], "Financials");
+    }
+
+    #[test]
+    fn test_transaction_code_label() {
+        assert_eq!(transaction_code_label("P"), Some("Open market or private purchase"));
+        assert_eq!(transaction_code_label("s"), Some("Open market or private sale"));
+        assert_eq!(transaction_code_label("Q"), None);
+    }
+
+    #[test]
+    fn test_decode_form4_transactions() {
+        let response = json!({"data": [
+            {"accession_number": "1", "transactions": [
+                {"transaction_code": "S", "shares": 100},
+                {"transaction_code": "S", "shares": 50}
+            ]},
+            {"accession_number": "2", "derivative_transactions": [
+                {"transaction_code": "M"}
+            ]}
+        ]});
+
+        let decoded = decode_form4_transactions(response);
+        assert_eq!(
+            decoded["data"][0]["transactions"][0]["transaction_code_label"],
+            "Open market or private sale"
+        );
+        assert_eq!(
+            decoded["data"][1]["derivative_transactions"][0]["transaction_code_label"],
+            "Exercise or conversion of exempt derivative"
+        );
+        assert_eq!(decoded["transaction_summary"][0]["code"], "S");
+        assert_eq!(decoded["transaction_summary"][0]["count"], 2);
+        assert_eq!(decoded["transaction_summary"][1]["code"], "M");
+    }
+
+    #[test]
+    fn test_decode_form4_wraps_arrays() {
+        let decoded = decode_form4_transactions(json!([{"transaction_code": "P"}]));
+        assert_eq!(decoded["data"][0]["transaction_code_label"], "Open market or private purchase");
+        assert_eq!(decoded["transaction_summary"][0]["count"], 1);
+    }
+
+    #[test]
+    fn test_aggregate_empty() {
+        let result = aggregate_holdings(&json!({"holdings": []}), AggregateBy::Issuer);
+        assert_eq!(result["total_value"], 0.0);
+        assert_eq!(result["group_count"], 0);
+    }
+
+    #[test]
+    fn test_insider_sentiment() {
+        let response = json!({"data": [
+            {"reporting_owner_name": "Cook Timothy", "officer_title": "CEO", "filing_date": "2024-04-03", "transactions": [
+                {"transaction_code": "S", "shares": 1000, "price_per_share": "170.00"},
+                {"transaction_code": "F", "shares": 400},
+                {"transaction_code": "M", "shares": 2000}
+            ]},
+            {"reporting_owner_name": "Levinson Arthur", "is_director": true, "transactions": [
+                {"transaction_code": "P", "shares": 500, "price_per_share": 160, "transaction_date": "2024-04-10"}
+            ]},
+            {"reporting_owner_name": "Levinson Arthur", "transaction_code": "P", "shares": 100, "price_per_share": 165}
+        ]});
+
+        let summary = insider_sentiment(&response);
+        assert_eq!(summary["buys"], 2);
+        assert_eq!(summary["sells"], 1);
+        assert_eq!(summary["net_shares"], -400.0);
+        assert_eq!(summary["value_bought"], 96500.0);
+        assert_eq!(summary["value_sold"], 170000.0);
+        assert_eq!(summary["score"], -0.28);
+        assert_eq!(summary["sentiment"], "bearish");
+        assert_eq!(summary["other_transactions"], 2);
+
+        assert_eq!(summary["notable_insiders"][0]["owner"], "Cook Timothy");
+        assert_eq!(summary["notable_insiders"][0]["title"], "CEO");
+        assert_eq!(summary["notable_insiders"][1]["title"], "Director");
+        assert_eq!(summary["notable_insiders"][1]["net_shares"], 600.0);
+
+        assert_eq!(summary["transactions"][0]["value"], 170000.0);
+        assert_eq!(summary["transactions"][0]["date"], "2024-04-03");
+        assert_eq!(summary["transactions"][1]["date"], "2024-04-10");
+    }
+
+    #[test]
+    fn test_insider_sentiment_without_trades() {
+        let summary = insider_sentiment(&json!({"data": [{"transaction_code": "A", "shares": 10}]}));
+        assert_eq!(summary["score"], Value::Null);
+        assert_eq!(summary["sentiment"], "no open market activity");
+        assert_eq!(sentiment_score((0.0, 10.0), (0.0, 30.0)), Some(-0.5));
+    }
+
+    #[test]
+    fn test_prior_quarter_end() {
+        assert_eq!(prior_quarter_end("2024-03-31").as_deref(), Some("2023-12-31"));
+        assert_eq!(prior_quarter_end("2024-09-30").as_deref(), Some("2024-06-30"));
+        assert_eq!(prior_quarter_end("not a date"), None);
+    }
+
+    #[test]
+    fn test_diff_positions() {
+        let prior = json!({"holdings": [
+            {"cusip": "037833100", "name_of_issuer": "APPLE INC", "value": 1000, "shares": 100},
+            {"cusip": "037833100", "name_of_issuer": "APPLE INC", "put_call": "Put", "value": 50, "shares": 10},
+            {"cusip": "060505104", "name_of_issuer": "BANK OF AMERICA", "value": 300, "shares": 30},
+            {"cusip": "166764100", "name_of_issuer": "CHEVRON", "value": 200, "shares": 20}
+        ]});
+        let current = json!({"holdings": [
+            {"cusip": "037833100", "name_of_issuer": "APPLE INC", "value": 600, "shares": 50},
+            {"cusip": "037833100", "name_of_issuer": "APPLE INC", "put_call": "Put", "value": 50, "shares": 10},
+            {"cusip": "060505104", "name_of_issuer": "BANK OF AMERICA", "value": 200, "shares": 10},
+            {"cusip": "060505104", "name_of_issuer": "BANK OF AMERICA", "value": 200, "shares": 30},
+            {"cusip": "67066G104", "name_of_issuer": "NVIDIA", "value": 900, "shares": 5}
+        ]});
+
+        let diff = diff_positions(&current, &prior);
+        assert_eq!(diff["summary"]["new"], 1);
+        assert_eq!(diff["summary"]["closed"], 1);
+        assert_eq!(diff["summary"]["increased"], 1);
+        assert_eq!(diff["summary"]["decreased"], 1);
+        assert_eq!(diff["new_positions"][0]["issuer"], "NVIDIA");
+        assert_eq!(diff["closed_positions"][0]["issuer"], "CHEVRON");
+        assert_eq!(diff["largest_increases"][0]["key"], "060505104");
+        assert_eq!(diff["largest_increases"][0]["shares"], 40.0);
+        assert_eq!(diff["largest_increases"][0]["percent_change"], 33.33);
+        assert_eq!(diff["largest_decreases"][0]["share_change"], -50.0);
+    }
+
+    #[test]
+    fn test_filer_activity() {
+        let holdings = json!({"period_of_report": "2024-06-30", "holdings": []});
+        assert_eq!(report_period(&holdings), Some("2024-06-30"));
+
+        let result = filer_activity(vec![
+            FilerQuarters {
+                filer_cik: "0001067983".into(),
+                result: Ok(("2024-06-30".into(), holdings.clone(), holdings)),
+            },
+            FilerQuarters {
+                filer_cik: "0000000001".into(),
+                result: Err(crate::api_client::ApiError::NotFound.into()),
+            },
+        ]);
+        assert_eq!(result["filers"][0]["prior_period"], "2024-03-31");
+        assert_eq!(result["filers"][0]["summary"]["new"], 0);
+        assert_eq!(result["errors"]["0000000001"]["code"], "NOT_FOUND");
+    }
+}
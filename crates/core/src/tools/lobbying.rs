@@ -0,0 +1,463 @@
+//! Lobbying helpers.
+//!
+//! Turns per-quarter client performance pages into chart-ready trend series
+//! (spend, rank, quarter-over-quarter change, CAGR) for a client or industry,
+//! and client detail into a client/registrant/issue graph.
+
+use super::fields::{field, number, records, str_field};
+use crate::error::CoreError;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+
+/// Maximum number of years covered by a single trends request
+pub const MAX_TREND_YEARS: i32 = 10;
+
+/// What a lobbying trend is computed for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrendTarget {
+    /// A single client, matched by numeric ID or case-insensitive name
+    Client(String),
+    /// All clients whose industry contains the given text
+    Industry(String),
+}
+
+impl TrendTarget {
+    fn to_json(&self) -> Value {
+        match self {
+            Self::Client(v) => json!({ "type": "client", "value": v }),
+            Self::Industry(v) => json!({ "type": "industry", "value": v }),
+        }
+    }
+}
+
+/// One reporting period's client performance response
+#[derive(Debug)]
+pub struct PeriodData {
+    pub year: i32,
+    pub quarter: u8,
+    pub response: Result<Value, CoreError>,
+}
+
+impl PeriodData {
+    fn label(&self) -> String {
+        format!("{}-Q{}", self.year, self.quarter)
+    }
+}
+
+fn record_spend(record: &Value) -> Option<f64> {
+    field(record, &["total_spend", "spend", "amount", "total_amount", "expenses", "income"])
+        .and_then(number)
+}
+
+fn matches_client(record: &Value, client: &str) -> bool {
+    let id_matches = field(record, &["client_id", "id"]).is_some_and(|v| match v {
+        Value::Number(n) => n.to_string() == client,
+        Value::String(s) => s == client,
+        _ => false,
+    });
+    id_matches
+        || str_field(record, &["client_name", "name"])
+            .is_some_and(|name| name.eq_ignore_ascii_case(client.trim()))
+}
+
+/// Spend and rank for the target within one period's records
+fn period_values(target: &TrendTarget, response: &Value) -> (Option<f64>, Option<u64>) {
+    let rows = records(response, &["data", "clients", "results"]);
+    match target {
+        TrendTarget::Client(client) => rows
+            .iter()
+            .enumerate()
+            .find(|(_, r)| matches_client(r, client))
+            .map(|(i, r)| {
+                let rank = field(r, &["rank", "spend_rank"])
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(i as u64 + 1);
+                (record_spend(r), Some(rank))
+            })
+            .unwrap_or((None, None)),
+        TrendTarget::Industry(industry) => {
+            let needle = industry.to_lowercase();
+            let spend: Vec<f64> = rows
+                .iter()
+                .filter(|r| {
+                    str_field(r, &["industry", "client_industry", "sector"])
+                        .is_some_and(|s| s.to_lowercase().contains(&needle))
+                })
+                .filter_map(record_spend)
+                .collect();
+            if spend.is_empty() {
+                (None, None)
+            } else {
+                (Some(spend.iter().sum()), None)
+            }
+        }
+    }
+}
+
+/// Compound annual growth rate in percent between two annual totals
+pub fn cagr(start: f64, end: f64, years: i32) -> Option<f64> {
+    if start <= 0.0 || end < 0.0 || years <= 0 {
+        return None;
+    }
+    Some(round2(((end / start).powf(1.0 / years as f64) - 1.0) * 100.0))
+}
+
+/// Build chart-ready trend series from per-quarter performance data.
+///
+/// `labels` and every entry in `series` are index-aligned so they can be
+/// plotted directly. Periods that failed to load are reported under `errors`
+/// and plotted as gaps.
+pub fn lobbying_trends(target: &TrendTarget, mut periods: Vec<PeriodData>) -> Value {
+    periods.sort_by_key(|p| (p.year, p.quarter));
+
+    let mut labels = Vec::new();
+    let mut spend_series = Vec::new();
+    let mut rank_series = Vec::new();
+    let mut qoq = Vec::new();
+    let mut ranking_shifts = Vec::new();
+    let mut annual: BTreeMap<i32, f64> = BTreeMap::new();
+    let mut errors = serde_json::Map::new();
+
+    let mut previous_spend: Option<f64> = None;
+    let mut previous_rank: Option<u64> = None;
+
+    for period in &periods {
+        let label = period.label();
+        let (spend, rank) = match &period.response {
+            Ok(response) => period_values(target, response),
+            Err(e) => {
+                errors.insert(label.clone(), e.to_json());
+                (None, None)
+            }
+        };
+
+        if let Some(s) = spend {
+            *annual.entry(period.year).or_insert(0.0) += s;
+        }
+
+        let change = spend.zip(previous_spend).map(|(now, then)| now - then);
+        let percent_change = spend
+            .zip(previous_spend)
+            .filter(|(_, then)| *then > 0.0)
+            .map(|(now, then)| round2((now - then) / then * 100.0));
+        qoq.push(json!({
+            "period": label,
+            "spend": spend,
+            "change": change,
+            "percent_change": percent_change
+        }));
+
+        if let (Some(now), Some(then)) = (rank, previous_rank) {
+            if now != then {
+                ranking_shifts.push(json!({
+                    "period": label,
+                    "rank": now,
+                    "previous_rank": then,
+                    // Positive means the target moved up the ranking
+                    "shift": then as i64 - now as i64
+                }));
+            }
+        }
+
+        labels.push(label);
+        spend_series.push(json!(spend));
+        rank_series.push(json!(rank));
+        if spend.is_some() {
+            previous_spend = spend;
+        }
+        if rank.is_some() {
+            previous_rank = rank;
+        }
+    }
+
+    let annual_series: Vec<Value> = annual
+        .iter()
+        .map(|(year, spend)| json!({ "year": year, "spend": spend }))
+        .collect();
+
+    let growth = match (annual.iter().find(|(_, s)| **s > 0.0), annual.iter().next_back()) {
+        (Some((&start_year, &start)), Some((&end_year, &end))) if end_year > start_year => json!({
+            "start_year": start_year,
+            "end_year": end_year,
+            "percent": cagr(start, end, end_year - start_year)
+        }),
+        _ => Value::Null,
+    };
+
+    let mut series = serde_json::Map::new();
+    series.insert("spend".to_string(), json!(spend_series));
+    if matches!(target, TrendTarget::Client(_)) {
+        series.insert("rank".to_string(), json!(rank_series));
+    }
+
+    let mut result = json!({
+        "target": target.to_json(),
+        "labels": labels,
+        "series": series,
+        "annual": annual_series,
+        "cagr": growth,
+        "quarter_over_quarter": qoq,
+        "ranking_shifts": ranking_shifts
+    });
+    if !errors.is_empty() {
+        result["errors"] = Value::Object(errors);
+    }
+    result
+}
+
+/// Graph nodes and weighted edges, deduplicated by ID
+#[derive(Default)]
+struct GraphBuilder {
+    nodes: Vec<Value>,
+    node_ids: HashSet<String>,
+    edges: BTreeMap<(String, String, &'static str), (usize, f64)>,
+}
+
+impl GraphBuilder {
+    fn node(&mut self, kind: &str, key: &str, label: &str, extra: Value) -> String {
+        let id = format!("{}:{}", kind, key);
+        if self.node_ids.insert(id.clone()) {
+            let mut node = json!({ "id": id, "type": kind, "label": label });
+            if let (Some(obj), Value::Object(extra)) = (node.as_object_mut(), extra) {
+                obj.extend(extra);
+            }
+            self.nodes.push(node);
+        }
+        id
+    }
+
+    fn edge(&mut self, source: &str, target: &str, relation: &'static str, amount: Option<f64>) {
+        let entry = self
+            .edges
+            .entry((source.to_string(), target.to_string(), relation))
+            .or_insert((0, 0.0));
+        entry.0 += 1;
+        entry.1 += amount.unwrap_or(0.0);
+    }
+}
+
+/// Node key and label for a registrant record
+fn registrant_key(record: &Value) -> Option<(String, String)> {
+    let name = str_field(record, &["registrant_name", "name"]).map(|s| s.trim().to_string());
+    let id = field(record, &["registrant_id", "id"]).map(|v| match v {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    });
+    match (id, name) {
+        (Some(id), Some(name)) => Some((id, name)),
+        (Some(id), None) => Some((id.clone(), id)),
+        (None, Some(name)) => Some((name.to_lowercase(), name)),
+        (None, None) => None,
+    }
+}
+
+/// Issue codes and descriptions mentioned by an activity record
+fn activity_issues(activity: &Value) -> Vec<(String, String)> {
+    let single = str_field(activity, &["general_issue_code", "issue_code", "issue"]).map(|code| {
+        let label = str_field(activity, &["general_issue_code_display", "issue_description"])
+            .unwrap_or(code);
+        (code.to_uppercase(), label.to_string())
+    });
+    let listed = records(activity, &["issues", "issue_codes"]).iter().filter_map(|issue| {
+        match issue {
+            Value::String(code) => Some((code.to_uppercase(), code.clone())),
+            _ => str_field(issue, &["code", "general_issue_code", "issue_code"]).map(|code| {
+                let label = str_field(issue, &["description", "display", "name"]).unwrap_or(code);
+                (code.to_uppercase(), label.to_string())
+            }),
+        }
+    });
+    single.into_iter().chain(listed).collect()
+}
+
+/// Convert a lobbying client detail response into a graph of
+/// client ↔ registrant ↔ issue relationships.
+///
+/// Edges carry the number of supporting records (`count`) and summed
+/// reported amounts (`amount`) so they can be used as weights.
+pub fn client_graph(detail: &Value) -> Value {
+    let root = detail.get("data").filter(|d| d.is_object()).unwrap_or(detail);
+    let client = root.get("client").filter(|c| c.is_object()).unwrap_or(root);
+
+    let mut graph = GraphBuilder::default();
+    let client_key = field(client, &["client_id", "id"])
+        .map(|v| match v {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+    let client_name = str_field(client, &["client_name", "name"]).unwrap_or("Unknown client");
+    let client_id = graph.node("client", &client_key, client_name, json!({}));
+
+    for registrant in records(root, &["registrants"]) {
+        if let Some((key, name)) = registrant_key(registrant) {
+            let id = graph.node("registrant", &key, &name, json!({}));
+            let amount = field(registrant, &["total_amount", "amount", "income"]).and_then(number);
+            graph.edge(&client_id, &id, "hired", amount);
+        }
+    }
+
+    for activity in records(root, &["activities", "lobbying_activities", "filings"]) {
+        let amount = field(activity, &["amount", "income", "expenses"]).and_then(number);
+        let registrant_id = activity
+            .get("registrant")
+            .filter(|r| r.is_object())
+            .and_then(registrant_key)
+            .or_else(|| registrant_key(&json!({
+                "registrant_id": field(activity, &["registrant_id"]),
+                "registrant_name": field(activity, &["registrant_name"])
+            })))
+            .map(|(key, name)| graph.node("registrant", &key, &name, json!({})));
+
+        if let Some(registrant_id) = &registrant_id {
+            graph.edge(&client_id, registrant_id, "hired", amount);
+        }
+        for (code, label) in activity_issues(activity) {
+            let issue_id = graph.node("issue", &code, &label, json!({ "code": code }));
+            graph.edge(&client_id, &issue_id, "lobbied_on", amount);
+            if let Some(registrant_id) = &registrant_id {
+                graph.edge(registrant_id, &issue_id, "lobbied_on", amount);
+            }
+        }
+    }
+
+    let edges: Vec<Value> = graph
+        .edges
+        .into_iter()
+        .map(|((source, target, relation), (count, amount))| {
+            json!({
+                "source": source,
+                "target": target,
+                "relation": relation,
+                "count": count,
+                "amount": amount
+            })
+        })
+        .collect();
+
+    json!({
+        "node_count": graph.nodes.len(),
+        "edge_count": edges.len(),
+        "nodes": graph.nodes,
+        "edges": edges
+    })
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_client::ApiError;
+
+    fn period(year: i32, quarter: u8, response: Value) -> PeriodData {
+        PeriodData {
+            year,
+            quarter,
+            response: Ok(response),
+        }
+    }
+
+    #[test]
+    fn test_cagr() {
+        assert_eq!(cagr(100.0, 121.0, 2), Some(10.0));
+        assert_eq!(cagr(0.0, 100.0, 2), None);
+        assert_eq!(cagr(100.0, 100.0, 0), None);
+    }
+
+    #[test]
+    fn test_client_trends() {
+        let periods = vec![
+            period(2023, 2, json!({"data": [
+                {"client_name": "Other", "total_spend": 500},
+                {"client_id": 7, "client_name": "Acme Corp", "total_spend": "150"}
+            ]})),
+            period(2023, 1, json!({"data": [
+                {"client_id": 7, "client_name": "Acme Corp", "total_spend": 100, "rank": 3}
+            ]})),
+            period(2024, 1, json!({"data": [
+                {"client_id": 7, "client_name": "Acme Corp", "total_spend": 250, "rank": 1}
+            ]})),
+        ];
+
+        let trends = lobbying_trends(&TrendTarget::Client("acme corp".to_string()), periods);
+        assert_eq!(trends["labels"], json!(["2023-Q1", "2023-Q2", "2024-Q1"]));
+        assert_eq!(trends["series"]["spend"], json!([100.0, 150.0, 250.0]));
+        assert_eq!(trends["series"]["rank"], json!([3, 2, 1]));
+        assert_eq!(trends["quarter_over_quarter"][1]["percent_change"], 50.0);
+        assert_eq!(trends["ranking_shifts"][0]["shift"], 1);
+        assert_eq!(trends["annual"][0]["spend"], 250.0);
+        assert_eq!(trends["cagr"]["percent"], 0.0);
+    }
+
+    #[test]
+    fn test_client_match_by_id_and_errors() {
+        let periods = vec![
+            period(2023, 1, json!([{"id": "7", "total_spend": 10}])),
+            PeriodData {
+                year: 2023,
+                quarter: 2,
+                response: Err(ApiError::RateLimited { retry_after: None }.into()),
+            },
+        ];
+        let trends = lobbying_trends(&TrendTarget::Client("7".to_string()), periods);
+        assert_eq!(trends["series"]["spend"], json!([10.0, null]));
+        assert_eq!(trends["errors"]["2023-Q2"]["code"], "UPSTREAM_RATE_LIMITED");
+        assert!(trends["cagr"].is_null());
+    }
+
+    #[test]
+    fn test_industry_trends() {
+        let periods = vec![
+            period(2022, 4, json!({"data": [
+                {"industry": "Pharmaceuticals", "total_spend": 100},
+                {"industry": "Pharmaceuticals/Health Products", "total_spend": 100},
+                {"industry": "Oil & Gas", "total_spend": 900}
+            ]})),
+            period(2024, 4, json!({"data": [
+                {"industry": "Pharmaceuticals", "total_spend": 288}
+            ]})),
+        ];
+        let trends = lobbying_trends(&TrendTarget::Industry("pharma".to_string()), periods);
+        assert_eq!(trends["series"]["spend"], json!([200.0, 288.0]));
+        assert!(trends["series"].get("rank").is_none());
+        assert_eq!(trends["cagr"]["percent"], 20.0);
+    }
+
+    #[test]
+    fn test_client_graph() {
+        let detail = json!({
+            "client": {"id": 7, "name": "Acme Corp"},
+            "registrants": [{"id": 1, "name": "K Street LLC", "total_amount": 50000}],
+            "activities": [
+                {"registrant_id": 1, "registrant_name": "K Street LLC", "amount": 20000,
+                 "issues": [{"code": "TAX", "description": "Taxation"}, "TRD"]},
+                {"registrant": {"id": 2, "name": "Inhouse"}, "general_issue_code": "TAX"}
+            ]
+        });
+
+        let graph = client_graph(&detail);
+        assert_eq!(graph["node_count"], 5);
+        let nodes = graph["nodes"].as_array().unwrap();
+        assert_eq!(nodes[0]["id"], "client:7");
+        assert_eq!(nodes[0]["label"], "Acme Corp");
+        assert!(nodes.iter().any(|n| n["id"] == "issue:TAX" && n["label"] == "Taxation"));
+
+        let edges = graph["edges"].as_array().unwrap();
+        let edge = |s: &str, t: &str| edges.iter().find(|e| e["source"] == s && e["target"] == t).unwrap();
+        assert_eq!(edge("client:7", "registrant:1")["count"], 2);
+        assert_eq!(edge("client:7", "registrant:1")["amount"], 70000.0);
+        assert_eq!(edge("client:7", "issue:TAX")["count"], 2);
+        assert_eq!(edge("registrant:2", "issue:TAX")["relation"], "lobbied_on");
+    }
+
+    #[test]
+    fn test_client_graph_empty_detail() {
+        let graph = client_graph(&json!({"data": {"name": "Solo"}}));
+        assert_eq!(graph["node_count"], 1);
+        assert_eq!(graph["nodes"][0]["label"], "Solo");
+        assert_eq!(graph["edge_count"], 0);
+    }
+}
@@ -0,0 +1,251 @@
+//! Markdown rendering of tool results.
+//!
+//! Pretty-printed JSON spends much of a model's context on braces, quotes,
+//! and repeated keys. With `format: "markdown"` (or `output_format` in the
+//! config) results are rendered instead as Markdown: scalar fields as a short
+//! list, and arrays of records (holdings, filings, financial periods) as
+//! tables with one column per field.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
+
+/// How tool results are returned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Pretty-printed JSON, as the tools produce it
+    #[default]
+    Json,
+    /// Markdown lists and tables
+    Markdown,
+}
+
+impl OutputFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Markdown => "markdown",
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "markdown" | "md" => Ok(Self::Markdown),
+            other => Err(format!("Unknown format '{}': expected json or markdown", other)),
+        }
+    }
+}
+
+/// Deepest heading used for nested sections
+const MAX_HEADING_LEVEL: usize = 4;
+
+/// Render a tool result in `format`. Results that aren't JSON, such as
+/// document text, are returned unchanged.
+pub fn render(text: String, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Json => text,
+        OutputFormat::Markdown => match serde_json::from_str::<Value>(&text) {
+            Ok(value @ (Value::Object(_) | Value::Array(_))) => to_markdown(&value),
+            _ => text,
+        },
+    }
+}
+
+/// Render a JSON value as Markdown
+pub fn to_markdown(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value, 2);
+    out.trim_end().to_string()
+}
+
+fn write_value(out: &mut String, value: &Value, level: usize) {
+    match value {
+        Value::Object(object) => write_object(out, object, level),
+        Value::Array(items) if is_table(items) => write_table(out, items),
+        Value::Array(items) => {
+            for item in items {
+                match item {
+                    Value::Object(_) | Value::Array(_) => write_value(out, item, level),
+                    _ => out.push_str(&format!("- {}\n", cell(item))),
+                }
+            }
+            out.push('\n');
+        }
+        _ => {
+            out.push_str(&cell(value));
+            out.push_str("\n\n");
+        }
+    }
+}
+
+/// Scalar fields as a list, then each nested object or array as a section
+fn write_object(out: &mut String, object: &Map<String, Value>, level: usize) {
+    let mut wrote_fields = false;
+    for (key, value) in object {
+        if is_inline(value) {
+            out.push_str(&format!("- **{}:** {}\n", key, cell(value)));
+            wrote_fields = true;
+        }
+    }
+    if wrote_fields {
+        out.push('\n');
+    }
+
+    for (key, value) in object {
+        if is_inline(value) {
+            continue;
+        }
+        match value {
+            Value::Array(items) if items.is_empty() => {
+                out.push_str(&format!("{} {}\n\nNone\n\n", "#".repeat(level), key));
+            }
+            _ => {
+                out.push_str(&format!("{} {}\n\n", "#".repeat(level), key));
+                write_value(out, value, (level + 1).min(MAX_HEADING_LEVEL));
+            }
+        }
+    }
+}
+
+/// Arrays of objects become tables with a column per field. Fields of
+/// nested objects get their own `parent.child` column.
+fn write_table(out: &mut String, rows: &[Value]) {
+    let rows: Vec<Map<String, Value>> = rows.iter().filter_map(|row| row.as_object()).map(flatten).collect();
+    let mut columns: Vec<&str> = Vec::new();
+    for row in &rows {
+        for key in row.keys() {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+
+    out.push_str(&format!("| {} |\n", columns.iter().map(|c| escape(c)).collect::<Vec<_>>().join(" | ")));
+    out.push_str(&format!("|{}\n", "---|".repeat(columns.len())));
+    for row in &rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|c| row.get(*c).map(cell).unwrap_or_default())
+            .collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    out.push('\n');
+}
+
+/// One level of nested objects folded into `parent.child` keys
+fn flatten(row: &Map<String, Value>) -> Map<String, Value> {
+    let mut flat = Map::new();
+    for (key, value) in row {
+        match value {
+            Value::Object(nested) if !nested.is_empty() => {
+                for (child, value) in nested {
+                    flat.insert(format!("{}.{}", key, child), value.clone());
+                }
+            }
+            _ => {
+                flat.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    flat
+}
+
+fn is_table(items: &[Value]) -> bool {
+    !items.is_empty() && items.iter().all(Value::is_object)
+}
+
+/// Scalars and lists of scalars fit on one line
+fn is_inline(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => object.is_empty(),
+        Value::Array(items) => !items.is_empty() && items.iter().all(|v| !v.is_object() && !v.is_array()),
+        _ => true,
+    }
+}
+
+/// A value as table cell or list text
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => escape(s),
+        Value::Array(items) if items.iter().all(|v| !v.is_object() && !v.is_array()) => {
+            items.iter().map(cell).collect::<Vec<_>>().join(", ")
+        }
+        Value::Object(object) if object.is_empty() => String::new(),
+        other => escape(&other.to_string()),
+    }
+}
+
+/// Keep a value on one line and out of the table's column separators
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("markdown".parse::<OutputFormat>(), Ok(OutputFormat::Markdown));
+        assert_eq!(" JSON ".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+        assert!("xml".parse::<OutputFormat>().is_err());
+        assert_eq!(serde_json::to_value(OutputFormat::Markdown).unwrap(), "markdown");
+    }
+
+    #[test]
+    fn test_records_as_table() {
+        let result = json!({
+            "cik": "0001067983",
+            "total": 2,
+            "holdings": [
+                {"name": "APPLE INC", "value": 1000, "shares": {"amount": 10, "type": "SH"}},
+                {"name": "A|B CORP", "value": null, "put_call": "Put"}
+            ]
+        });
+        assert_eq!(
+            to_markdown(&result),
+            "- **cik:** 0001067983\n\
+             - **total:** 2\n\
+             \n\
+             ## holdings\n\
+             \n\
+             | name | shares.amount | shares.type | value | put_call |\n\
+             |---|---|---|---|---|\n\
+             | APPLE INC | 10 | SH | 1000 |  |\n\
+             | A\\|B CORP |  |  |  | Put |"
+        );
+    }
+
+    #[test]
+    fn test_nested_sections() {
+        let result = json!({
+            "company": {"name": "Apple", "tickers": ["AAPL", "AAPL.MX"]},
+            "filings": [],
+        });
+        let markdown = to_markdown(&result);
+        assert!(markdown.starts_with("## company\n\n- **name:** Apple\n- **tickers:** AAPL, AAPL.MX\n"));
+        assert!(markdown.ends_with("## filings\n\nNone"));
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(render("Plain text".to_string(), OutputFormat::Markdown), "Plain text");
+        assert_eq!(render(r#"{"a": 1}"#.to_string(), OutputFormat::Json), r#"{"a": 1}"#);
+        assert_eq!(render(r#"[{"a": 1}]"#.to_string(), OutputFormat::Markdown), "| a |\n|---|\n| 1 |");
+    }
+}
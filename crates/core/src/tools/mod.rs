@@ -4,18 +4,32 @@
 //! - Tool registry with metadata and search functionality
 //! - Individual tool implementations organized by category
 
+pub mod company;
+pub mod digest;
+pub mod etf;
+pub mod exhibits;
+mod fields;
+pub mod filing_search;
+pub mod financials;
+pub mod fuzzy;
+pub mod institutional;
+pub mod lobbying;
+pub mod markdown;
+pub mod paged_stream;
+pub mod pagination;
+pub mod prompts;
+pub mod ranking;
+pub mod red_flags;
 pub mod registry;
+pub mod resources;
+pub mod sec_documents;
+pub mod watchlists;
 
 // Tool implementation modules (to be added)
-// pub mod company;
-// pub mod sec_documents;
-// pub mod institutional;
-// pub mod etf;
 // pub mod form_adv;
-// pub mod lobbying;
-// pub mod watchlists;
 
 pub use registry::{
     get_categories, get_tool_metadata, list_tools_by_category, search_tools,
     Category, DetailLevel, SearchResult, Tool, ToolCategory,
 };
+pub use paged_stream::PagedStream;
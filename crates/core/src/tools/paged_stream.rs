@@ -0,0 +1,224 @@
+//! Async streams over paginated list endpoints.
+//!
+//! [`PagedStream`] requests pages on demand and yields the records in them
+//! one by one, following [`PageRequest::next`] until the endpoint runs out,
+//! so callers can aggregate across pages without handling cursors.
+
+use super::fields::records;
+use super::pagination::{PageRequest, PageStyle, RECORD_KEYS};
+use crate::api_client::{ApiClient, ApiError};
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Records requested per page when the caller doesn't choose
+pub const DEFAULT_STREAM_PAGE_SIZE: u64 = 100;
+
+/// Stream of records from a paginated endpoint.
+///
+/// Pages are fetched lazily as the stream is polled. A failed request or a
+/// record that doesn't deserialize into `T` is yielded as an error and ends
+/// the stream.
+pub struct PagedStream<T> {
+    inner: BoxStream<'static, Result<T, ApiError>>,
+}
+
+struct State {
+    client: ApiClient,
+    endpoint: String,
+    params: HashMap<String, String>,
+    page: Option<PageRequest>,
+    records: VecDeque<Value>,
+    pages_left: usize,
+}
+
+impl State {
+    /// Next record, fetching pages until one has records or there are no more
+    async fn next_record(&mut self) -> Result<Option<Value>, ApiError> {
+        loop {
+            if let Some(record) = self.records.pop_front() {
+                return Ok(Some(record));
+            }
+            let Some(page) = self.page.take().filter(|_| self.pages_left > 0) else {
+                return Ok(None);
+            };
+            self.pages_left -= 1;
+
+            let mut params = self.params.clone();
+            page.apply(&mut params);
+            let response: Value = self.client.get(&self.endpoint, Some(params)).await?;
+            self.page = page.next(&response, RECORD_KEYS);
+            self.records = records(&response, RECORD_KEYS).iter().cloned().collect();
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Send + 'static> PagedStream<T> {
+    /// Stream every record of `endpoint`, starting from `first` and sending
+    /// `params` with each page request
+    pub fn new(
+        client: ApiClient,
+        endpoint: impl Into<String>,
+        params: HashMap<String, String>,
+        first: PageRequest,
+    ) -> Self {
+        Self::with_max_pages(client, endpoint, params, first, usize::MAX)
+    }
+
+    /// Like [`PagedStream::new`], stopping after `max_pages` requests
+    pub fn with_max_pages(
+        client: ApiClient,
+        endpoint: impl Into<String>,
+        params: HashMap<String, String>,
+        first: PageRequest,
+        max_pages: usize,
+    ) -> Self {
+        let state = State {
+            client,
+            endpoint: endpoint.into(),
+            params,
+            page: Some(first),
+            records: VecDeque::new(),
+            pages_left: max_pages,
+        };
+        let inner = stream::try_unfold(state, |mut state| async move {
+            match state.next_record().await? {
+                Some(record) => Ok(Some((serde_json::from_value(record)?, state))),
+                None => Ok(None),
+            }
+        });
+        Self { inner: inner.boxed() }
+    }
+
+    /// A company's filings, newest first, optionally filtered with the same
+    /// query parameters `get_company_filings` accepts
+    pub fn company_filings(client: ApiClient, cik: &str, params: HashMap<String, String>) -> Self {
+        Self::new(client, format!("companies/{}/filings", cik), params, first_page(PageStyle::JSON_API))
+    }
+
+    /// An ETF's holdings for its latest (or a `quarter` param's) report
+    pub fn etf_holdings(client: ApiClient, identifier: &str, params: HashMap<String, String>) -> Self {
+        Self::new(client, format!("etfs/{}/holdings", identifier), params, first_page(PageStyle::LIMIT_OFFSET))
+    }
+
+    /// Form ADV firms matching `params` (e.g. `search`, `state`)
+    pub fn form_adv_firms(client: ApiClient, params: HashMap<String, String>) -> Self {
+        Self::new(client, "forms/adv/firms", params, first_page(PageStyle::JSON_API))
+    }
+
+    /// Collect up to `limit` records, stopping at the first error
+    pub async fn take_items(self, limit: usize) -> Result<Vec<T>, ApiError> {
+        let mut items = Vec::new();
+        let mut stream = self.take(limit);
+        while let Some(item) = stream.next().await {
+            items.push(item?);
+        }
+        Ok(items)
+    }
+}
+
+fn first_page(style: PageStyle) -> PageRequest {
+    PageRequest::from_args("", style, DEFAULT_STREAM_PAGE_SIZE, None, None, None)
+        .expect("no cursor to decode")
+}
+
+impl<T> Stream for PagedStream<T> {
+    type Item = Result<T, ApiError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Filing {
+        accession_number: String,
+    }
+
+    fn filings(ids: &[&str]) -> Value {
+        let data: Vec<Value> = ids.iter().map(|id| json!({"accession_number": id})).collect();
+        json!({ "data": data })
+    }
+
+    async fn mock_pages(server: &MockServer) {
+        for (offset, body) in [("0", filings(&["a", "b"])), ("2", filings(&["c", "d"])), ("4", filings(&["e"]))] {
+            Mock::given(method("GET"))
+                .and(path("/companies/320193/filings"))
+                .and(query_param("page[offset]", offset))
+                .and(query_param("form_type", "8-K"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(body))
+                .expect(1)
+                .mount(server)
+                .await;
+        }
+    }
+
+    fn first(size: u64) -> PageRequest {
+        PageRequest {
+            style: PageStyle::JSON_API,
+            position: 0,
+            size,
+        }
+    }
+
+    fn params() -> HashMap<String, String> {
+        HashMap::from([("form_type".to_string(), "8-K".to_string())])
+    }
+
+    #[tokio::test]
+    async fn test_streams_across_pages() {
+        let server = MockServer::start().await;
+        mock_pages(&server).await;
+        let client = ApiClient::with_base_url("token", server.uri()).unwrap();
+
+        let stream = PagedStream::<Filing>::new(client, "companies/320193/filings", params(), first(2));
+        let ids: Vec<String> = stream
+            .map(|filing| filing.unwrap().accession_number)
+            .collect()
+            .await;
+        assert_eq!(ids, vec!["a", "b", "c", "d", "e"]);
+    }
+
+    #[tokio::test]
+    async fn test_take_stops_fetching() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("page[offset]", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(filings(&["a", "b"])))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let client = ApiClient::with_base_url("token", server.uri()).unwrap();
+
+        let stream = PagedStream::<Filing>::new(client.clone(), "companies/320193/filings", params(), first(2));
+        assert_eq!(stream.take_items(2).await.unwrap().len(), 2);
+
+        let capped = PagedStream::<Value>::with_max_pages(client, "companies/320193/filings", params(), first(2), 1);
+        assert_eq!(capped.take_items(10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_errors_end_the_stream() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        let client = ApiClient::with_base_url("token", server.uri()).unwrap();
+
+        let mut stream = PagedStream::<Filing>::form_adv_firms(client, HashMap::new());
+        assert!(matches!(stream.next().await, Some(Err(ApiError::NotFound))));
+        assert!(stream.next().await.is_none());
+    }
+}
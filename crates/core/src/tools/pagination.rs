@@ -0,0 +1,373 @@
+//! Uniform pagination for list tools.
+//!
+//! Upstream endpoints page in different ways (`limit`/`offset`,
+//! `page[size]`/`page[offset]`, `page`/`per_page`). Tools expose a single
+//! opaque `cursor` argument and return `next_cursor`, and this module maps
+//! between the two.
+
+use super::fields::{field, number, records};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Keys under which list endpoints return their records
+pub const RECORD_KEYS: &[&str] = &["data", "results", "filings", "holdings", "firms", "clients"];
+
+/// How an endpoint pages its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageStyle {
+    /// Position is a zero-based record offset
+    Offset {
+        offset: &'static str,
+        size: &'static str,
+    },
+    /// Position is a one-based page number
+    PageNumber {
+        page: &'static str,
+        size: &'static str,
+    },
+}
+
+impl PageStyle {
+    /// Plain `offset`/`limit` query parameters
+    pub const LIMIT_OFFSET: Self = Self::Offset {
+        offset: "offset",
+        size: "limit",
+    };
+
+    /// JSON:API `page[offset]`/`page[size]` query parameters
+    pub const JSON_API: Self = Self::Offset {
+        offset: "page[offset]",
+        size: "page[size]",
+    };
+
+    fn first_position(&self) -> u64 {
+        match self {
+            Self::Offset { .. } => 0,
+            Self::PageNumber { .. } => 1,
+        }
+    }
+}
+
+/// Cursor payload; encoded as hex so clients treat it as opaque
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CursorData {
+    #[serde(rename = "t")]
+    tool: String,
+    #[serde(rename = "p")]
+    position: u64,
+    #[serde(rename = "s")]
+    size: u64,
+}
+
+/// A single page request for a list tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRequest {
+    pub style: PageStyle,
+    pub position: u64,
+    pub size: u64,
+}
+
+impl PageRequest {
+    /// Build the page request for a tool call.
+    ///
+    /// A `cursor` argument takes precedence; otherwise `position` and `size`
+    /// come from the tool's own paging arguments, falling back to the first
+    /// page of `default_size` records.
+    pub fn from_args(
+        tool: &str,
+        style: PageStyle,
+        default_size: u64,
+        cursor: Option<&str>,
+        position: Option<u64>,
+        size: Option<u64>,
+    ) -> Result<Self, String> {
+        if let Some(cursor) = cursor {
+            let data = decode_cursor(cursor)?;
+            if data.tool != tool {
+                return Err(format!(
+                    "Cursor was issued by {}, not {}",
+                    data.tool, tool
+                ));
+            }
+            return Ok(Self {
+                style,
+                position: data.position,
+                size: data.size,
+            });
+        }
+
+        Ok(Self {
+            style,
+            position: position.unwrap_or_else(|| style.first_position()),
+            size: size.filter(|s| *s > 0).unwrap_or(default_size),
+        })
+    }
+
+    /// Set this page's query parameters
+    pub fn apply(&self, params: &mut HashMap<String, String>) {
+        let (position_key, size_key) = match self.style {
+            PageStyle::Offset { offset, size } => (offset, size),
+            PageStyle::PageNumber { page, size } => (page, size),
+        };
+        params.insert(position_key.to_string(), self.position.to_string());
+        params.insert(size_key.to_string(), self.size.to_string());
+    }
+
+    /// The following page, if the response suggests there is one.
+    ///
+    /// Uses `links.next` or a total count when the API provides them, and
+    /// otherwise assumes more results exist when this page came back full.
+    pub fn next(&self, response: &Value, record_keys: &[&str]) -> Option<Self> {
+        let returned = records(response, record_keys).len() as u64;
+        if returned == 0 {
+            return None;
+        }
+
+        let next = Self {
+            position: match self.style {
+                PageStyle::Offset { .. } => self.position + returned,
+                PageStyle::PageNumber { .. } => self.position + 1,
+            },
+            ..self.clone()
+        };
+
+        if let Some(link) = response.pointer("/links/next") {
+            return (!link.is_null()).then_some(next);
+        }
+
+        let meta = response.get("meta").unwrap_or(response);
+        if let Some(total) = field(meta, &["total", "total_count", "total_results"]).and_then(number) {
+            let seen = match self.style {
+                PageStyle::Offset { .. } => self.position + returned,
+                PageStyle::PageNumber { .. } => (self.position - 1) * self.size + returned,
+            };
+            return ((seen as f64) < total).then_some(next);
+        }
+        if let Some(pages) = field(meta, &["total_pages", "last_page"]).and_then(number) {
+            if let PageStyle::PageNumber { .. } = self.style {
+                return ((self.position as f64) < pages).then_some(next);
+            }
+        }
+
+        (returned >= self.size).then_some(next)
+    }
+
+    /// This page of a list held in memory, and the following page if more
+    /// items remain
+    pub fn slice<T>(&self, items: Vec<T>) -> (Vec<T>, Option<Self>) {
+        let (start, next_position) = match self.style {
+            PageStyle::Offset { .. } => (self.position, self.position + self.size),
+            PageStyle::PageNumber { .. } => (self.position.saturating_sub(1) * self.size, self.position + 1),
+        };
+        let start = (start as usize).min(items.len());
+        let end = start.saturating_add(self.size as usize).min(items.len());
+        let next = (end < items.len()).then(|| Self {
+            position: next_position,
+            ..self.clone()
+        });
+        let page = items.into_iter().skip(start).take(end - start).collect();
+        (page, next)
+    }
+
+    /// Opaque cursor for this page
+    pub fn cursor(&self, tool: &str) -> String {
+        encode_token(&CursorData {
+            tool: tool.to_string(),
+            position: self.position,
+            size: self.size,
+        })
+    }
+}
+
+/// Encode `data` as an opaque token: hex-encoded JSON, so clients pass it
+/// back unchanged rather than editing it
+pub fn encode_token(data: &impl Serialize) -> String {
+    serde_json::to_vec(data)
+        .unwrap()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Decode a token made by [`encode_token`]
+pub fn decode_token<T: DeserializeOwned>(token: &str) -> Option<T> {
+    let token = token.trim();
+    if !token.len().is_multiple_of(2) || !token.is_ascii() {
+        return None;
+    }
+    let bytes = (0..token.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&token[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn decode_cursor(cursor: &str) -> Result<CursorData, String> {
+    decode_token(cursor).ok_or_else(|| "Invalid cursor; pass next_cursor from a previous result unchanged".to_string())
+}
+
+/// Attach `next_cursor` to a list response, wrapping bare arrays in `{data}`
+pub fn attach_next_cursor(tool: &str, response: Value, next: Option<PageRequest>) -> Value {
+    let cursor = next.map(|page| Value::String(page.cursor(tool))).unwrap_or(Value::Null);
+    match response {
+        Value::Object(mut obj) => {
+            obj.insert("next_cursor".to_string(), cursor);
+            Value::Object(obj)
+        }
+        other => serde_json::json!({ "data": other, "next_cursor": cursor }),
+    }
+}
+
+/// Cut a response built in memory down to one page of its `key` array and
+/// attach `next_cursor`
+pub fn paginate(tool: &str, mut response: Value, key: &str, page: &PageRequest) -> Value {
+    let mut next = None;
+    if let Some(records) = response.get_mut(key).and_then(Value::as_array_mut) {
+        let (page_records, following) = page.slice(std::mem::take(records));
+        *records = page_records;
+        next = following;
+    }
+    attach_next_cursor(tool, response, next)
+}
+
+/// Like [`paginate`] for a list response whose records sit under the first
+/// of `record_keys` holding an array, or are the response itself
+pub fn paginate_records(tool: &str, response: Value, record_keys: &[&str], page: &PageRequest) -> Value {
+    let response = match response {
+        Value::Array(items) => serde_json::json!({ "data": items }),
+        other => other,
+    };
+    let key = record_keys
+        .iter()
+        .find(|key| response.get(**key).is_some_and(Value::is_array))
+        .copied()
+        .unwrap_or("data");
+    paginate(tool, response, key, page)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const KEYS: &[&str] = &["data"];
+
+    fn page(style: PageStyle, position: u64, size: u64) -> PageRequest {
+        PageRequest {
+            style,
+            position,
+            size,
+        }
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let first = page(PageStyle::LIMIT_OFFSET, 50, 25);
+        let cursor = first.cursor("get_form4_filings");
+        let decoded = PageRequest::from_args(
+            "get_form4_filings",
+            PageStyle::LIMIT_OFFSET,
+            10,
+            Some(&cursor),
+            Some(0),
+            Some(5),
+        )
+        .unwrap();
+        assert_eq!(decoded, first);
+
+        assert!(PageRequest::from_args("get_etf_holdings", PageStyle::LIMIT_OFFSET, 10, Some(&cursor), None, None)
+            .unwrap_err()
+            .contains("get_form4_filings"));
+        assert!(PageRequest::from_args("x", PageStyle::LIMIT_OFFSET, 10, Some("zz"), None, None).is_err());
+    }
+
+    #[test]
+    fn test_from_args_defaults() {
+        let style = PageStyle::PageNumber {
+            page: "page",
+            size: "per_page",
+        };
+        let request = PageRequest::from_args("t", style, 20, None, None, None).unwrap();
+        assert_eq!(request, page(style, 1, 20));
+
+        let mut params = HashMap::new();
+        request.apply(&mut params);
+        assert_eq!(params["page"], "1");
+        assert_eq!(params["per_page"], "20");
+    }
+
+    #[test]
+    fn test_next_from_full_page() {
+        let request = page(PageStyle::JSON_API, 0, 2);
+        let next = request.next(&json!({"data": [1, 2]}), KEYS).unwrap();
+        assert_eq!(next.position, 2);
+        assert!(request.next(&json!({"data": [1]}), KEYS).is_none());
+        assert!(request.next(&json!({"data": []}), KEYS).is_none());
+    }
+
+    #[test]
+    fn test_next_prefers_links_and_totals() {
+        let request = page(PageStyle::LIMIT_OFFSET, 0, 2);
+        let response = json!({"data": [1, 2], "links": {"next": null}});
+        assert!(request.next(&response, KEYS).is_none());
+
+        let response = json!({"data": [1], "links": {"next": "/v1/x?offset=1"}});
+        assert_eq!(request.next(&response, KEYS).unwrap().position, 1);
+
+        let response = json!({"data": [1, 2], "meta": {"total": 2}});
+        assert!(request.next(&response, KEYS).is_none());
+
+        let style = PageStyle::PageNumber {
+            page: "page",
+            size: "per_page",
+        };
+        let response = json!({"data": [1], "meta": {"total_pages": 3}});
+        assert_eq!(page(style, 2, 2).next(&response, KEYS).unwrap().position, 3);
+    }
+
+    #[test]
+    fn test_slice_and_paginate() {
+        let (items, next) = page(PageStyle::LIMIT_OFFSET, 2, 2).slice(vec![1, 2, 3, 4, 5]);
+        assert_eq!(items, vec![3, 4]);
+        assert_eq!(next.unwrap().position, 4);
+        let (items, next) = page(PageStyle::LIMIT_OFFSET, 4, 2).slice(vec![1, 2, 3, 4, 5]);
+        assert_eq!((items, next), (vec![5], None));
+        let (items, _) = page(PageStyle::LIMIT_OFFSET, 9, 2).slice(vec![1, 2]);
+        assert!(items.is_empty());
+
+        let response = json!({"match_count": 3, "matches": [1, 2, 3]});
+        let first = paginate("search_tools", response.clone(), "matches", &page(PageStyle::LIMIT_OFFSET, 0, 2));
+        assert_eq!(first["matches"], json!([1, 2]));
+        assert_eq!(first["match_count"], 3);
+        let cursor = first["next_cursor"].as_str().unwrap();
+        let next = PageRequest::from_args("search_tools", PageStyle::LIMIT_OFFSET, 2, Some(cursor), None, None).unwrap();
+        let second = paginate("search_tools", response, "matches", &next);
+        assert_eq!(second["matches"], json!([3]));
+        assert!(second["next_cursor"].is_null());
+    }
+
+    #[test]
+    fn test_paginate_records() {
+        let first = page(PageStyle::LIMIT_OFFSET, 0, 2);
+        let bare = paginate_records("get_lists", json!([1, 2, 3]), RECORD_KEYS, &first);
+        assert_eq!(bare["data"], json!([1, 2]));
+        assert!(bare["next_cursor"].is_string());
+
+        let keyed = paginate_records("get_lists", json!({"results": [1, 2], "meta": {}}), RECORD_KEYS, &first);
+        assert_eq!(keyed["results"], json!([1, 2]));
+        assert!(keyed["next_cursor"].is_null());
+    }
+
+    #[test]
+    fn test_attach_next_cursor() {
+        let with_more = attach_next_cursor("t", json!({"data": []}), Some(page(PageStyle::LIMIT_OFFSET, 10, 10)));
+        assert!(with_more["next_cursor"].is_string());
+
+        let wrapped = attach_next_cursor("t", json!([1, 2]), None);
+        assert_eq!(wrapped["data"], json!([1, 2]));
+        assert!(wrapped["next_cursor"].is_null());
+    }
+}
@@ -0,0 +1,187 @@
+//! Example prompts built from the tool registry.
+//!
+//! Each category's example queries and each tool's examples become
+//! ready-to-paste prompts that name the tool expected to answer them,
+//! optionally rendered as a Markdown starter playbook. The settings app shows these, and the MCP server
+//! exposes the playbook as a prompt.
+
+use super::registry::{self, Category, Tool};
+use serde::Serialize;
+
+/// Name of the playbook prompt exposed over MCP
+pub const PLAYBOOK_PROMPT_NAME: &str = "starter_playbook";
+
+/// A ready-to-paste prompt for one example query
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExamplePrompt {
+    pub category: &'static str,
+    pub category_name: &'static str,
+    pub query: &'static str,
+    /// The tool expected to answer the query, if one stands out
+    pub tool: Option<&'static str>,
+    pub prompt: String,
+}
+
+/// Lowercased words of three or more letters
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '-')
+        .filter(|w| w.len() >= 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// How well a tool's name, keywords, and description cover a query
+fn relevance(tool: &Tool, query_words: &[String]) -> usize {
+    let name = tool.name.replace('_', " ");
+    let keywords = tool.keywords.join(" ").to_lowercase();
+    let description = tool.description.to_lowercase();
+    query_words
+        .iter()
+        .map(|word| {
+            let stem = word.trim_end_matches('s');
+            3 * usize::from(name.contains(stem))
+                + 2 * usize::from(keywords.contains(stem))
+                + usize::from(description.contains(stem))
+        })
+        .sum()
+}
+
+/// The category tool listing the query as an example, or else the one that
+/// best matches it, ties going to the first by name
+fn best_tool(category: Category, query: &str) -> Option<&'static str> {
+    if let Some(tool) = registry::category_tools(category).find(|t| t.examples.contains(&query)) {
+        return Some(tool.name);
+    }
+    let query_words = words(query);
+    registry::category_tools(category)
+        .map(|tool| (relevance(tool, &query_words), tool.name))
+        .filter(|(score, _)| *score > 0)
+        .fold(None, |best: Option<(usize, &str)>, candidate| match best {
+            Some(b) if b.0 >= candidate.0 => Some(b),
+            _ => Some(candidate),
+        })
+        .map(|(_, name)| name)
+}
+
+/// Turn an example query into a prompt that points at its tool
+fn format_prompt(query: &str, tool: Option<&str>) -> String {
+    let query = query.trim();
+    let sentence = if query.ends_with(['?', '.', '!']) {
+        query.to_string()
+    } else {
+        format!("{}.", query)
+    };
+    match tool {
+        Some(tool) => format!("{} Use the FilingExplorer {} tool.", sentence, tool),
+        None => format!("{} Use the FilingExplorer tools.", sentence),
+    }
+}
+
+/// Example prompts for one category, or for every category: the category's
+/// example queries, then any other tool examples
+pub fn example_prompts(category: Option<Category>) -> Vec<ExamplePrompt> {
+    let categories = match category {
+        Some(c) => vec![c],
+        None => Category::all().to_vec(),
+    };
+    let mut prompts = Vec::new();
+    for info in categories.into_iter().filter_map(registry::category_info) {
+        let mut push = |query: &'static str, tool: Option<&'static str>| {
+            prompts.push(ExamplePrompt {
+                category: info.id.as_str(),
+                category_name: info.name,
+                query,
+                tool,
+                prompt: format_prompt(query, tool),
+            })
+        };
+        for query in info.example_queries {
+            push(query, best_tool(info.id, query));
+        }
+        for tool in registry::category_tools(info.id) {
+            for example in tool.examples.iter().filter(|e| !info.example_queries.contains(e)) {
+                push(example, Some(tool.name));
+            }
+        }
+    }
+    prompts
+}
+
+/// Render prompts as a Markdown playbook, one section per category
+pub fn format_playbook(prompts: &[ExamplePrompt]) -> String {
+    let mut playbook = String::from("# FilingExplorer starter playbook\n\nPaste any of these prompts to get started.\n");
+    let mut current = None;
+    let mut number = 0;
+    for prompt in prompts {
+        if current != Some(prompt.category) {
+            current = Some(prompt.category);
+            number = 0;
+            playbook.push_str(&format!("\n## {}\n\n", prompt.category_name));
+        }
+        number += 1;
+        playbook.push_str(&format!("{}. {}\n", number, prompt.prompt));
+    }
+    playbook
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_prompts_stay_in_category() {
+        let prompts = example_prompts(None);
+        let expected: usize = Category::all()
+            .iter()
+            .filter_map(|c| registry::category_info(*c))
+            .map(|info| info.example_queries.len())
+            .sum();
+        assert!(prompts.len() >= expected);
+        for prompt in &prompts {
+            if let Some(tool) = prompt.tool {
+                assert_eq!(registry::get_tool_category(tool).map(|c| c.as_str()), Some(prompt.category));
+            }
+        }
+    }
+
+    #[test]
+    fn test_example_prompts_pick_matching_tool() {
+        let prompts = example_prompts(Some(Category::EtfData));
+        let holdings = prompts.iter().find(|p| p.query == "Show SPY's top holdings").unwrap();
+        assert_eq!(holdings.tool, Some("get_etf_holdings"));
+        assert_eq!(
+            holdings.prompt,
+            "Show SPY's top holdings. Use the FilingExplorer get_etf_holdings tool."
+        );
+    }
+
+    #[test]
+    fn test_example_prompts_use_tool_examples() {
+        let prompts = example_prompts(Some(Category::Watchlists));
+        let lists = prompts.iter().find(|p| p.query == "Show my watchlists").unwrap();
+        assert_eq!(lists.tool, Some("get_lists"));
+        // Tool examples that aren't category queries are added once
+        let renames: Vec<_> = prompts.iter().filter(|p| p.tool == Some("update_list")).collect();
+        assert_eq!(renames.len(), 1);
+        assert_eq!(prompts.iter().filter(|p| p.query == "Create a new watchlist").count(), 1);
+    }
+
+    #[test]
+    fn test_format_prompt_keeps_questions() {
+        assert_eq!(
+            format_prompt("Who owns this adviser? ", Some("get_adv_owners")),
+            "Who owns this adviser? Use the FilingExplorer get_adv_owners tool."
+        );
+        assert_eq!(format_prompt("List filings", None), "List filings. Use the FilingExplorer tools.");
+    }
+
+    #[test]
+    fn test_format_playbook_groups_by_category() {
+        let mut prompts = example_prompts(Some(Category::EtfData));
+        prompts.extend(example_prompts(Some(Category::Lobbying)));
+        let playbook = format_playbook(&prompts);
+        assert!(playbook.starts_with("# FilingExplorer starter playbook"));
+        assert!(playbook.contains("\n## ETF Data\n\n1. "));
+        assert!(playbook.contains("\n## Lobbying Data\n\n1. "));
+    }
+}
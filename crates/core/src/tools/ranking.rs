@@ -0,0 +1,175 @@
+//! Multi-term relevance ranking for tool search.
+//!
+//! Queries are split into terms and each term is scored against each field
+//! of a tool (name, description, keywords, category) with BM25, a TF-IDF
+//! variant: terms that appear in few tools count for more than words like
+//! "get" or "filings", repeated hits saturate, and a hit in a short field
+//! counts for more than one in a long description. Field scores are
+//! weighted and summed over the query's terms.
+
+use super::fuzzy;
+
+/// How quickly repeated hits of a term in one field saturate
+const K1: f64 = 1.2;
+
+/// How much a field's length relative to the average discounts its hits
+const B: f64 = 0.75;
+
+/// Shortest term matched as a word prefix ("financial" finds "financials");
+/// shorter terms must match a whole word
+const MIN_PREFIX_LEN: usize = 3;
+
+/// Words that say nothing about which tool is wanted
+const STOPWORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "at", "by", "do", "does", "for", "from", "how", "i", "in", "is", "it", "me",
+    "my", "of", "on", "or", "show", "the", "this", "to", "what", "which", "with",
+];
+
+/// Whether a word in a tool's text matches a query term
+pub fn matches(word: &str, term: &str) -> bool {
+    word == term || (term.len() >= MIN_PREFIX_LEN && word.starts_with(term))
+}
+
+/// The distinct search terms in a query
+pub fn terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in fuzzy::words(query) {
+        if !STOPWORDS.contains(&word.as_str()) && !terms.contains(&word) {
+            terms.push(word);
+        }
+    }
+    terms
+}
+
+/// Words of a field, plus hyphenated forms like "13-F" and "10-K" joined
+/// up, so a query can spell them either way
+fn index_words(text: &str) -> Vec<String> {
+    let joined = text
+        .split_whitespace()
+        .filter(|token| token.contains('-'))
+        .map(|token| token.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase())
+        .filter(|word| !word.is_empty());
+    fuzzy::words(text).chain(joined).collect()
+}
+
+/// Documents split into weighted fields of words
+pub struct Index {
+    weights: Vec<f64>,
+    /// Words of each field of each document
+    documents: Vec<Vec<Vec<String>>>,
+    /// Average word count of each field
+    average_lengths: Vec<f64>,
+}
+
+impl Index {
+    /// Index documents given as the text of each field, in the order of
+    /// `weights`
+    pub fn new(weights: &[f64], documents: impl IntoIterator<Item = Vec<String>>) -> Self {
+        let documents: Vec<Vec<Vec<String>>> = documents
+            .into_iter()
+            .map(|fields| fields.iter().map(|text| index_words(text)).collect())
+            .collect();
+        let average_lengths = (0..weights.len())
+            .map(|field| {
+                let total: usize = documents.iter().map(|d| d[field].len()).sum();
+                (total as f64 / documents.len().max(1) as f64).max(1.0)
+            })
+            .collect();
+        Self {
+            weights: weights.to_vec(),
+            documents,
+            average_lengths,
+        }
+    }
+
+    /// Score every document against a query, in index order. Documents that
+    /// match none of its terms score 0.
+    pub fn scores(&self, query: &str) -> Vec<f64> {
+        let mut scores = vec![0.0; self.documents.len()];
+        for term in terms(query) {
+            let frequencies: Vec<Vec<usize>> = self
+                .documents
+                .iter()
+                .map(|fields| {
+                    fields
+                        .iter()
+                        .map(|words| words.iter().filter(|w| matches(w, &term)).count())
+                        .collect()
+                })
+                .collect();
+            let containing = frequencies.iter().filter(|f| f.iter().any(|&n| n > 0)).count();
+            if containing == 0 {
+                continue;
+            }
+            let n = self.documents.len() as f64;
+            let idf = ((n - containing as f64 + 0.5) / (containing as f64 + 0.5) + 1.0).ln();
+
+            for (document, fields) in frequencies.iter().enumerate() {
+                for (field, &tf) in fields.iter().enumerate() {
+                    if tf == 0 {
+                        continue;
+                    }
+                    let tf = tf as f64;
+                    let length = self.documents[document][field].len() as f64 / self.average_lengths[field];
+                    let saturated = tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * length));
+                    scores[document] += self.weights[field] * idf * saturated;
+                }
+            }
+        }
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> Index {
+        Index::new(
+            &[2.0, 1.0],
+            [
+                vec!["cash_flow".to_string(), "Quarterly cash flow and income statements".to_string()],
+                vec!["filings".to_string(), "List filings by form, such as quarterly reports".to_string()],
+                vec!["holdings".to_string(), "Holdings reported in quarterly filings".to_string()],
+            ],
+        )
+    }
+
+    #[test]
+    fn test_terms() {
+        assert_eq!(terms("Show me the quarterly cash flow"), ["quarterly", "cash", "flow"]);
+        assert_eq!(terms("cash, CASH"), ["cash"]);
+        assert!(terms("what is the").is_empty());
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("financials", "financial"));
+        assert!(matches("4", "4"));
+        assert!(!matches("form4", "4"));
+        assert!(!matches("variance", "ria"));
+    }
+
+    #[test]
+    fn test_index_words() {
+        assert_eq!(index_words("Form 13-F filers"), ["form", "13", "f", "filers", "13f"]);
+    }
+
+    #[test]
+    fn test_rare_terms_outweigh_common_ones() {
+        let scores = index().scores("quarterly cash flow statement");
+        assert!(scores[0] > scores[1] && scores[0] > scores[2], "{:?}", scores);
+        // "quarterly" is in every document, so it barely separates them
+        let scores = index().scores("quarterly");
+        assert!(scores.iter().all(|&s| s > 0.0 && s < 1.0), "{:?}", scores);
+        assert_eq!(index().scores("weather"), [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_short_fields_count_for_more() {
+        // "filings" is the whole name of one and a description word of another
+        let scores = index().scores("filings");
+        assert!(scores[1] > scores[2], "{:?}", scores);
+        assert_eq!(scores[0], 0.0);
+    }
+}
@@ -0,0 +1,333 @@
+//! Composite red-flag report.
+//!
+//! Each check turns one upstream response (insider trades, 8-K search hits,
+//! an ADV firm record, lobbying trends) into a status and the findings
+//! behind it; the report gathers them so a caller sees every risk signal
+//! for a company or adviser in one result.
+
+use super::filing_search::SearchHit;
+use crate::error::CoreError;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+/// Default look-back window for the company checks
+pub const DEFAULT_RED_FLAG_DAYS: i64 = 365;
+
+/// Quarters of lobbying history fetched for the spike check
+pub const LOBBYING_SPIKE_QUARTERS: usize = 8;
+
+/// Latest quarterly lobbying spend at least this multiple of the trailing
+/// average counts as a spike
+const LOBBYING_SPIKE_RATIO: f64 = 2.0;
+
+/// Quarters averaged as the lobbying baseline
+const LOBBYING_BASELINE_QUARTERS: usize = 4;
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Flagged,
+    Clear,
+    /// The data needed for the check was missing from the response
+    Inconclusive,
+    /// The check didn't apply to the given inputs
+    Skipped,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Flagged => "flagged",
+            Self::Clear => "clear",
+            Self::Inconclusive => "inconclusive",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+/// A check's status, a one-line summary, and supporting findings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub status: CheckStatus,
+    pub summary: String,
+    pub details: Value,
+}
+
+impl Check {
+    fn new(status: CheckStatus, summary: impl Into<String>, details: Value) -> Self {
+        Self {
+            status,
+            summary: summary.into(),
+            details,
+        }
+    }
+
+    pub fn skipped(reason: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Skipped, reason, Value::Null)
+    }
+}
+
+/// Flags net open market selling by insiders, from an `insider_sentiment`
+/// summary
+pub fn insider_selling_check(sentiment: &Value) -> Check {
+    let sells = sentiment["sells"].as_u64().unwrap_or(0);
+    let details = json!({
+        "score": sentiment["score"],
+        "buys": sentiment["buys"],
+        "sells": sells,
+        "value_sold": sentiment["value_sold"],
+        "value_bought": sentiment["value_bought"],
+        "notable_insiders": sentiment["notable_insiders"],
+    });
+    match sentiment["sentiment"].as_str() {
+        Some("bearish") => Check::new(
+            CheckStatus::Flagged,
+            format!("Insiders were net sellers: {} open market sales", sells),
+            details,
+        ),
+        Some(label) => Check::new(CheckStatus::Clear, format!("Insider trading is {}", label), details),
+        None => Check::new(CheckStatus::Inconclusive, "No insider sentiment available", details),
+    }
+}
+
+/// Flags 8-Ks reporting Item 4.01 (change in certifying accountant), from
+/// full-text search hits for that item
+pub fn auditor_change_check(hits: &[SearchHit]) -> Check {
+    let mut seen = HashSet::new();
+    let filings: Vec<Value> = hits
+        .iter()
+        .filter(|hit| seen.insert(hit.accession_number.as_str()))
+        .map(|hit| {
+            json!({
+                "filing_date": hit.filing_date,
+                "form_type": hit.form_type,
+                "accession_number": hit.accession_number,
+                "document": hit.document,
+            })
+        })
+        .collect();
+    if filings.is_empty() {
+        Check::new(CheckStatus::Clear, "No auditor changes reported on 8-K", json!({ "filings": [] }))
+    } else {
+        Check::new(
+            CheckStatus::Flagged,
+            format!("{} 8-K filing(s) reporting a change in certifying accountant", filings.len()),
+            json!({ "filings": filings }),
+        )
+    }
+}
+
+/// Whether a disclosure field's value reports something
+fn is_reported(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n > 0.0),
+        Value::String(s) => matches!(s.trim().to_uppercase().as_str(), "Y" | "YES" | "TRUE"),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+/// Disclosure fields anywhere in an ADV record, as (path, value), and how
+/// many were seen in total
+fn disclosure_fields(value: &Value, path: &str, reported: &mut Vec<(String, Value)>) -> usize {
+    let Value::Object(map) = value else {
+        return 0;
+    };
+    let mut seen = 0;
+    for (key, child) in map {
+        let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+        let lower = key.to_lowercase();
+        if lower.contains("disciplinary") || lower.contains("disclosure") {
+            seen += 1;
+            if is_reported(child) {
+                reported.push((child_path, child.clone()));
+                continue;
+            }
+        }
+        seen += disclosure_fields(child, &child_path, reported);
+    }
+    seen
+}
+
+/// Flags disciplinary or other disclosures reported on a Form ADV firm
+/// record
+pub fn adv_disclosure_check(firm: &Value) -> Check {
+    let mut reported = Vec::new();
+    let seen = disclosure_fields(firm, "", &mut reported);
+    let disclosures: Vec<Value> = reported
+        .into_iter()
+        .map(|(field, value)| json!({ "field": field, "value": value }))
+        .collect();
+    if seen == 0 {
+        Check::new(CheckStatus::Inconclusive, "Firm record has no disclosure fields", Value::Null)
+    } else if disclosures.is_empty() {
+        Check::new(CheckStatus::Clear, "No disciplinary disclosures reported on Form ADV", json!({ "disclosures": [] }))
+    } else {
+        Check::new(
+            CheckStatus::Flagged,
+            format!("{} disclosure item(s) reported on Form ADV", disclosures.len()),
+            json!({ "disclosures": disclosures }),
+        )
+    }
+}
+
+/// Flags a latest quarterly lobbying spend well above the trailing
+/// average, from a `lobbying_trends` result
+pub fn lobbying_spike_check(trends: &Value) -> Check {
+    let quarters: Vec<(&str, f64)> = trends["quarter_over_quarter"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|q| Some((q["period"].as_str()?, q["spend"].as_f64()?)))
+        .collect();
+    let Some(((period, latest), history)) = quarters.split_last() else {
+        return Check::new(CheckStatus::Inconclusive, "No lobbying spend reported", Value::Null);
+    };
+    let baseline: Vec<f64> = history
+        .iter()
+        .rev()
+        .take(LOBBYING_BASELINE_QUARTERS)
+        .map(|(_, spend)| *spend)
+        .collect();
+    if baseline.is_empty() {
+        return Check::new(
+            CheckStatus::Inconclusive,
+            "Only one quarter of lobbying spend reported",
+            json!({ "period": period, "spend": latest }),
+        );
+    }
+    let average = baseline.iter().sum::<f64>() / baseline.len() as f64;
+    let ratio = (average > 0.0).then(|| round2(latest / average));
+    let details = json!({
+        "period": period,
+        "spend": latest,
+        "trailing_average": round2(average),
+        "ratio": ratio,
+    });
+    // Any spend after a quiet baseline is a spike as well
+    let spiked = match ratio {
+        Some(ratio) => ratio >= LOBBYING_SPIKE_RATIO,
+        None => *latest > 0.0,
+    };
+    if spiked {
+        Check::new(
+            CheckStatus::Flagged,
+            format!("Lobbying spend in {} is well above the trailing average", period),
+            details,
+        )
+    } else {
+        Check::new(CheckStatus::Clear, "No unusual lobbying spend", details)
+    }
+}
+
+/// Gather named check results into one report; failed checks are listed
+/// with their error instead of a status
+pub fn red_flag_report(checks: Vec<(&'static str, Result<Check, CoreError>)>) -> Value {
+    let mut flagged = Vec::new();
+    let mut results = serde_json::Map::new();
+    for (name, result) in checks {
+        let entry = match result {
+            Ok(check) => {
+                if check.status == CheckStatus::Flagged {
+                    flagged.push(name);
+                }
+                json!({
+                    "status": check.status.as_str(),
+                    "summary": check.summary,
+                    "details": check.details,
+                })
+            }
+            Err(e) => json!({ "status": "error", "error": e.to_json() }),
+        };
+        results.insert(name.to_string(), entry);
+    }
+    json!({
+        "flag_count": flagged.len(),
+        "flagged": flagged,
+        "checks": results,
+    })
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_client::ApiError;
+
+    #[test]
+    fn test_insider_selling_check() {
+        let bearish = json!({"sentiment": "bearish", "sells": 4, "buys": 0, "score": -1.0});
+        assert_eq!(insider_selling_check(&bearish).status, CheckStatus::Flagged);
+        let neutral = json!({"sentiment": "neutral", "sells": 1, "buys": 1});
+        assert_eq!(insider_selling_check(&neutral).status, CheckStatus::Clear);
+    }
+
+    #[test]
+    fn test_auditor_change_check_dedupes_filings() {
+        let hit = |document: &str| SearchHit {
+            cik: "0000000001".to_string(),
+            accession_number: "0000000001-24-000001".to_string(),
+            document: document.to_string(),
+            form_type: Some("8-K".to_string()),
+            filing_date: Some("2024-03-01".to_string()),
+            document_type: None,
+            description: None,
+        };
+        let check = auditor_change_check(&[hit("form8-k.htm"), hit("ex16.htm")]);
+        assert_eq!(check.status, CheckStatus::Flagged);
+        assert_eq!(check.details["filings"].as_array().unwrap().len(), 1);
+        assert_eq!(auditor_change_check(&[]).status, CheckStatus::Clear);
+    }
+
+    #[test]
+    fn test_adv_disclosure_check() {
+        let firm = json!({
+            "name": "Example Advisers",
+            "attributes": {"has_disciplinary_disclosures": "Y", "disclosures": {"criminal_disclosure_count": 0}}
+        });
+        let check = adv_disclosure_check(&firm);
+        assert_eq!(check.status, CheckStatus::Flagged);
+        assert_eq!(check.details["disclosures"][0]["field"], "attributes.has_disciplinary_disclosures");
+
+        let clean = json!({"has_disciplinary_disclosures": false});
+        assert_eq!(adv_disclosure_check(&clean).status, CheckStatus::Clear);
+        assert_eq!(adv_disclosure_check(&json!({"name": "x"})).status, CheckStatus::Inconclusive);
+    }
+
+    #[test]
+    fn test_lobbying_spike_check() {
+        let trends = |spend: &[Option<f64>]| {
+            let qoq: Vec<Value> = spend
+                .iter()
+                .enumerate()
+                .map(|(i, s)| json!({"period": format!("2024-Q{}", i + 1), "spend": s}))
+                .collect();
+            json!({ "quarter_over_quarter": qoq })
+        };
+        let spike = lobbying_spike_check(&trends(&[Some(100.0), Some(120.0), Some(300.0), None]));
+        assert_eq!(spike.status, CheckStatus::Flagged);
+        assert_eq!(spike.details["period"], "2024-Q3");
+        assert_eq!(spike.details["ratio"], 2.73);
+
+        let steady = lobbying_spike_check(&trends(&[Some(100.0), Some(110.0)]));
+        assert_eq!(steady.status, CheckStatus::Clear);
+        assert_eq!(lobbying_spike_check(&trends(&[])).status, CheckStatus::Inconclusive);
+    }
+
+    #[test]
+    fn test_red_flag_report() {
+        let report = red_flag_report(vec![
+            ("insider_selling", Ok(insider_selling_check(&json!({"sentiment": "bearish", "sells": 2})))),
+            ("auditor_changes", Err(CoreError::from(ApiError::NotFound))),
+            ("adv_disclosures", Ok(Check::skipped("No crd given"))),
+        ]);
+        assert_eq!(report["flag_count"], 1);
+        assert_eq!(report["flagged"], json!(["insider_selling"]));
+        assert_eq!(report["checks"]["auditor_changes"]["status"], "error");
+        assert_eq!(report["checks"]["adv_disclosures"]["status"], "skipped");
+    }
+}
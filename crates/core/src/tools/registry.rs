@@ -1,15 +1,22 @@
 //! Tool Registry for Progressive Discovery
 //!
-//! This module provides metadata and search functionality for 39 MCP tools
-//! organized into 12 categories. It implements the progressive discovery pattern
+//! This module provides metadata and search functionality for the MCP tools,
+//! organized into categories. It implements the progressive discovery pattern
 //! to reduce initial token load from ~25K to ~2K tokens.
+//!
+//! Categories, tools, their schemas, and search synonyms are defined in
+//! `registry.json` beside this file. `build.rs` checks it and generates the
+//! `Category` enum and the static tables included below, with each
+//! category's tool count computed.
 
+use super::{fuzzy, ranking};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::sync::OnceLock;
 
 /// Detail level for category/tool listings
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum DetailLevel {
     /// Just category names and counts
@@ -17,17 +24,14 @@ pub enum DetailLevel {
     /// Include tool names
     WithToolNames,
     /// Include full descriptions
+    #[default]
     WithDescriptions,
     /// Names only (for search results)
     NamesOnly,
     /// Full JSON schema (for search results)
     FullSchema,
-}
-
-impl Default for DetailLevel {
-    fn default() -> Self {
-        Self::WithDescriptions
-    }
+    /// Categories nested under their groups, with names and counts only
+    Tree,
 }
 
 impl std::str::FromStr for DetailLevel {
@@ -40,97 +44,37 @@ impl std::str::FromStr for DetailLevel {
             "with_descriptions" => Ok(Self::WithDescriptions),
             "names_only" => Ok(Self::NamesOnly),
             "full_schema" => Ok(Self::FullSchema),
+            "tree" => Ok(Self::Tree),
             _ => Err(format!("Unknown detail level: {}", s)),
         }
     }
 }
 
-/// Tool category identifier
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum Category {
-    CompanyData,
-    SecDocuments,
-    InstitutionalFilings,
-    EtfData,
-    FormAdvFirms,
-    FormAdvOwnership,
-    FormAdvFunds,
-    FormAdvDisclosures,
-    FormAdvOther,
-    Lobbying,
-    Watchlists,
-    WatchlistItems,
-}
-
-impl Category {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Self::CompanyData => "company_data",
-            Self::SecDocuments => "sec_documents",
-            Self::InstitutionalFilings => "institutional_filings",
-            Self::EtfData => "etf_data",
-            Self::FormAdvFirms => "form_adv_firms",
-            Self::FormAdvOwnership => "form_adv_ownership",
-            Self::FormAdvFunds => "form_adv_funds",
-            Self::FormAdvDisclosures => "form_adv_disclosures",
-            Self::FormAdvOther => "form_adv_other",
-            Self::Lobbying => "lobbying",
-            Self::Watchlists => "watchlists",
-            Self::WatchlistItems => "watchlist_items",
-        }
-    }
-
-    pub fn all() -> &'static [Category] {
-        &[
-            Self::CompanyData,
-            Self::SecDocuments,
-            Self::InstitutionalFilings,
-            Self::EtfData,
-            Self::FormAdvFirms,
-            Self::FormAdvOwnership,
-            Self::FormAdvFunds,
-            Self::FormAdvDisclosures,
-            Self::FormAdvOther,
-            Self::Lobbying,
-            Self::Watchlists,
-            Self::WatchlistItems,
-        ]
-    }
-}
-
-impl std::str::FromStr for Category {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "company_data" => Ok(Self::CompanyData),
-            "sec_documents" => Ok(Self::SecDocuments),
-            "institutional_filings" => Ok(Self::InstitutionalFilings),
-            "etf_data" => Ok(Self::EtfData),
-            "form_adv_firms" => Ok(Self::FormAdvFirms),
-            "form_adv_ownership" => Ok(Self::FormAdvOwnership),
-            "form_adv_funds" => Ok(Self::FormAdvFunds),
-            "form_adv_disclosures" => Ok(Self::FormAdvDisclosures),
-            "form_adv_other" => Ok(Self::FormAdvOther),
-            "lobbying" => Ok(Self::Lobbying),
-            "watchlists" => Ok(Self::Watchlists),
-            "watchlist_items" => Ok(Self::WatchlistItems),
-            _ => Err(format!("Unknown category: {}", s)),
-        }
-    }
-}
-
 /// Category metadata
 #[derive(Debug, Clone)]
 pub struct ToolCategory {
     pub id: Category,
     pub name: &'static str,
     pub description: &'static str,
+    /// Built-in tools in the category, not counting plugins
     pub tool_count: usize,
+    /// Names and descriptions of tools announced for the category that
+    /// can't be called yet
+    pub planned_tools: &'static [(&'static str, &'static str)],
+    /// Group the category belongs to, such as "form_adv"
+    pub parent: Option<&'static str>,
     pub example_queries: &'static [&'static str],
 }
 
+/// A group of related categories, such as the Form ADV ones, which can be
+/// used wherever a category filters tools
+#[derive(Debug, Clone)]
+pub struct CategoryGroup {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
 /// Tool metadata
 #[derive(Debug, Clone)]
 pub struct Tool {
@@ -138,7 +82,29 @@ pub struct Tool {
     pub category: Category,
     pub description: &'static str,
     pub keywords: &'static [&'static str],
-    pub input_schema: Value,
+    /// Example requests the tool answers, shown as starter prompts
+    pub examples: &'static [&'static str],
+    /// Facets such as "read-only" or "requires-token", from [`TAGS`]
+    pub tags: &'static [&'static str],
+    /// JSON schema for the tool's arguments, kept as source text so the
+    /// registry is built at compile time
+    pub input_schema_json: &'static str,
+    /// JSON schema for the tool's structured result, for tools whose result
+    /// is always a JSON object
+    pub output_schema_json: Option<&'static str>,
+}
+
+impl Tool {
+    /// The tool's input schema
+    pub fn input_schema(&self) -> Value {
+        serde_json::from_str(self.input_schema_json).expect("registry schemas are valid JSON")
+    }
+
+    /// The tool's output schema, if its results are structured
+    pub fn output_schema(&self) -> Option<Value> {
+        self.output_schema_json
+            .map(|json| serde_json::from_str(json).expect("registry schemas are valid JSON"))
+    }
 }
 
 /// Search result with relevance score
@@ -152,708 +118,79 @@ pub struct SearchResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub keywords: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub input_schema: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
 }
 
 // ============================================================================
-// CATEGORY DEFINITIONS
+// REGISTRY DATA
 // ============================================================================
 
-lazy_static::lazy_static! {
-    static ref CATEGORIES: HashMap<Category, ToolCategory> = {
-        let mut m = HashMap::new();
-
-        m.insert(Category::CompanyData, ToolCategory {
-            id: Category::CompanyData,
-            name: "Company Data",
-            description: "Financial statements (10-K/10-Q), fiscal calendars, and SEC filings for public companies",
-            tool_count: 3,
-            example_queries: &[
-                "Get Apple's financial statements",
-                "Show Tesla's fiscal calendar",
-                "List Microsoft's SEC filings",
-            ],
-        });
-
-        m.insert(Category::SecDocuments, ToolCategory {
-            id: Category::SecDocuments,
-            name: "SEC Documents",
-            description: "Proxy/stream SEC filing documents, retrieve document metadata, fetch documents directly from SEC EDGAR, and extract text from documents",
-            tool_count: 4,
-            example_queries: &[
-                "Get document from SEC filing",
-                "Check document size before downloading",
-                "Fetch 10-K directly from SEC EDGAR",
-                "Extract text from a PDF filing",
-            ],
-        });
+// CATEGORIES, CATEGORY_GROUPS, TOOLS, TAGS, and SYNONYMS, generated by build.rs from registry.json
+include!(concat!(env!("OUT_DIR"), "/registry_data.rs"));
 
-        m.insert(Category::InstitutionalFilings, ToolCategory {
-            id: Category::InstitutionalFilings,
-            name: "Institutional Filings",
-            description: "Form 13-F institutional holdings and Form 4 insider trading data",
-            tool_count: 3,
-            example_queries: &[
-                "Show Berkshire Hathaway's holdings",
-                "Find hedge funds by name",
-                "Get insider trading Form 4",
-            ],
-        });
-
-        m.insert(Category::EtfData, ToolCategory {
-            id: Category::EtfData,
-            name: "ETF Data",
-            description: "ETF holdings from N-PORT filings with valuations and asset categories",
-            tool_count: 1,
-            example_queries: &[
-                "Show SPY's top holdings",
-                "Get QQQ portfolio",
-            ],
-        });
-
-        m.insert(Category::FormAdvFirms, ToolCategory {
-            id: Category::FormAdvFirms,
-            name: "Form ADV - Firms",
-            description: "Search and retrieve investment adviser firms by CRD number, registration status, AUM",
-            tool_count: 2,
-            example_queries: &[
-                "Find SEC-registered advisers in California",
-                "Get Vanguard's Form ADV details",
-            ],
-        });
-
-        m.insert(Category::FormAdvOwnership, ToolCategory {
-            id: Category::FormAdvOwnership,
-            name: "Form ADV - Ownership",
-            description: "Direct owners (Schedule A), indirect owners (Schedule B), ownership chains, and cross-firm owner search",
-            tool_count: 4,
-            example_queries: &[
-                "Who owns this investment adviser?",
-                "Show ownership chain for firm",
-                "Find firms owned by a person",
-            ],
-        });
-
-        m.insert(Category::FormAdvFunds, ToolCategory {
-            id: Category::FormAdvFunds,
-            name: "Form ADV - Private Funds",
-            description: "Private funds (Schedule D.7.B) managed by firms - hedge funds, PE, VC, real estate funds",
-            tool_count: 2,
-            example_queries: &[
-                "What hedge funds does Bridgewater manage?",
-                "Search for private equity funds over $1B",
-            ],
-        });
-
-        m.insert(Category::FormAdvDisclosures, ToolCategory {
-            id: Category::FormAdvDisclosures,
-            name: "Form ADV - Disclosures & Brochures",
-            description: "DRP regulatory disclosures, sanctions, fines, and Part 2A/2B brochures",
-            tool_count: 2,
-            example_queries: &[
-                "Does this adviser have any regulatory issues?",
-                "Get firm brochure",
-            ],
-        });
-
-        m.insert(Category::FormAdvOther, ToolCategory {
-            id: Category::FormAdvOther,
-            name: "Form ADV - Other Data",
-            description: "Filings, addresses, notice filings, related persons, other names, SMA data, AUM history",
-            tool_count: 8,
-            example_queries: &[
-                "Show firm's filing history",
-                "Get AUM growth over time",
-                "What states is this adviser registered in?",
-            ],
-        });
-
-        m.insert(Category::Lobbying, ToolCategory {
-            id: Category::Lobbying,
-            name: "Lobbying Data",
-            description: "Lobbying client spending patterns, growth metrics, statistical analysis, and detailed client information",
-            tool_count: 3,
-            example_queries: &[
-                "Which companies increased lobbying most?",
-                "Search for lobbying clients",
-                "Get detailed lobbying history",
-            ],
-        });
-
-        m.insert(Category::Watchlists, ToolCategory {
-            id: Category::Watchlists,
-            name: "Watchlists",
-            description: "Create, list, retrieve, update, and delete user watchlists",
-            tool_count: 5,
-            example_queries: &[
-                "Show my watchlists",
-                "Create a new watchlist",
-                "Delete a watchlist",
-            ],
-        });
-
-        m.insert(Category::WatchlistItems, ToolCategory {
-            id: Category::WatchlistItems,
-            name: "Watchlist Items",
-            description: "Add, toggle, update, and delete items (securities or institutional investors) in watchlists",
-            tool_count: 4,
-            example_queries: &[
-                "Add AAPL to my watchlist",
-                "Remove item from watchlist",
-                "Toggle stock in list",
-            ],
-        });
-
-        m
-    };
-
-    static ref TOOLS: HashMap<&'static str, Tool> = {
-        let mut m = HashMap::new();
-
-        // =====================================================================
-        // COMPANY DATA (3 tools)
-        // =====================================================================
-
-        m.insert("get_company_financials", Tool {
-            name: "get_company_financials",
-            category: Category::CompanyData,
-            description: "Retrieve financial statements for a company by CIK or ticker symbol. Returns balance sheet, income statement, cash flow statement, and comprehensive income data from 10-K and 10-Q filings.",
-            keywords: &["financials", "10-K", "10-Q", "balance sheet", "income statement", "cash flow", "quarterly", "annual", "ticker", "CIK", "statements", "revenue", "earnings"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "company_id": {
-                        "type": "string",
-                        "description": "Company CIK or ticker symbol (e.g., '0000927003' or 'AAPL')"
-                    },
-                    "period_of_report_date": {
-                        "type": "string",
-                        "description": "Filter by period end date (YYYY-MM-DD)"
-                    },
-                    "timeframe": {
-                        "type": "string",
-                        "enum": ["quarterly", "annual"],
-                        "description": "Filter by reporting timeframe"
-                    },
-                    "filing_date": {
-                        "type": "string",
-                        "description": "Filter by filing date (YYYY-MM-DD)"
-                    },
-                    "limit": {
-                        "type": "integer",
-                        "minimum": 1,
-                        "maximum": 50,
-                        "default": 10
-                    },
-                    "page": {
-                        "type": "integer",
-                        "minimum": 1,
-                        "default": 1
-                    },
-                    "sort": {
-                        "type": "string",
-                        "enum": ["filing_date", "period_of_report_date"],
-                        "default": "period_of_report_date"
-                    },
-                    "order": {
-                        "type": "string",
-                        "enum": ["asc", "desc"],
-                        "default": "desc"
-                    }
-                },
-                "required": ["company_id"]
-            }),
-        });
-
-        m.insert("get_company_calendar", Tool {
-            name: "get_company_calendar",
-            category: Category::CompanyData,
-            description: "Retrieve the fiscal calendar for a company showing fiscal year end dates and reporting schedules.",
-            keywords: &["calendar", "fiscal year", "fiscal quarter", "reporting schedule", "year end"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "company_cik": {
-                        "type": "string",
-                        "description": "Company CIK (e.g., '0000320193')"
-                    }
-                },
-                "required": ["company_cik"]
-            }),
-        });
-
-        m.insert("get_company_filings", Tool {
-            name: "get_company_filings",
-            category: Category::CompanyData,
-            description: "Retrieve SEC filings for a company by CIK with filtering and pagination.",
-            keywords: &["filings", "SEC", "10-K", "10-Q", "8-K", "forms", "documents"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "cik": {
-                        "type": "string",
-                        "description": "10-digit CIK with leading zeros"
-                    },
-                    "form_type": {
-                        "type": "string",
-                        "description": "Filter by form type (e.g., '10-K', '10-Q', '8-K')"
-                    },
-                    "form_types": {
-                        "type": "array",
-                        "items": { "type": "string" },
-                        "description": "Filter by multiple form types"
-                    },
-                    "filed_after": {
-                        "type": "string",
-                        "description": "Filings on or after date (YYYY-MM-DD)"
-                    },
-                    "filed_before": {
-                        "type": "string",
-                        "description": "Filings on or before date (YYYY-MM-DD)"
-                    },
-                    "sort": {
-                        "type": "string",
-                        "default": "-filing_date"
-                    },
-                    "page_size": {
-                        "type": "integer",
-                        "maximum": 100,
-                        "default": 25
-                    },
-                    "page_offset": {
-                        "type": "integer",
-                        "default": 0
-                    }
-                },
-                "required": ["cik"]
-            }),
-        });
-
-        // =====================================================================
-        // SEC DOCUMENTS (4 tools)
-        // =====================================================================
-
-        m.insert("get_sec_document", Tool {
-            name: "get_sec_document",
-            category: Category::SecDocuments,
-            description: "Proxy/stream an SEC document through the API.",
-            keywords: &["document", "filing", "stream", "download", "SEC"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "accession_number": {
-                        "type": "string",
-                        "description": "SEC accession number"
-                    },
-                    "cik": {
-                        "type": "string",
-                        "description": "10-digit CIK"
-                    },
-                    "filename": {
-                        "type": "string",
-                        "description": "Specific document filename"
-                    },
-                    "download": {
-                        "type": "boolean",
-                        "description": "Set Content-Disposition to attachment"
-                    }
-                },
-                "required": ["accession_number", "cik"]
-            }),
-        });
-
-        m.insert("get_sec_document_metadata", Tool {
-            name: "get_sec_document_metadata",
-            category: Category::SecDocuments,
-            description: "Get metadata about an SEC document without streaming the content.",
-            keywords: &["metadata", "document", "size", "type"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "accession_number": { "type": "string" },
-                    "cik": { "type": "string" },
-                    "filename": { "type": "string" }
-                },
-                "required": ["accession_number", "cik"]
-            }),
-        });
-
-        m.insert("fetch_sec_document_direct", Tool {
-            name: "fetch_sec_document_direct",
-            category: Category::SecDocuments,
-            description: "Fetch a document directly from SEC EDGAR. Requires email configuration for User-Agent header.",
-            keywords: &["SEC", "EDGAR", "direct", "fetch", "document"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "cik": { "type": "string", "description": "10-digit CIK" },
-                    "accession_number": { "type": "string" },
-                    "filename": { "type": "string" }
-                },
-                "required": ["cik", "accession_number"]
-            }),
-        });
-
-        m.insert("extract_document_text", Tool {
-            name: "extract_document_text",
-            category: Category::SecDocuments,
-            description: "Extract text from a document (PDF, HTML, XML) for LLM processing.",
-            keywords: &["extract", "text", "PDF", "HTML", "parse"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "cik": { "type": "string" },
-                    "accession_number": { "type": "string" },
-                    "filename": { "type": "string" },
-                    "max_chars": {
-                        "type": "integer",
-                        "default": 100000,
-                        "description": "Maximum characters to return"
-                    }
-                },
-                "required": ["cik", "accession_number"]
-            }),
-        });
-
-        // =====================================================================
-        // INSTITUTIONAL FILINGS (3 tools)
-        // =====================================================================
-
-        m.insert("get_form13f_submissions", Tool {
-            name: "get_form13f_submissions",
-            category: Category::InstitutionalFilings,
-            description: "List and search Form 13-F institutional filers.",
-            keywords: &["13-F", "institutional", "holdings", "filers", "search"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "search": { "type": "string" },
-                    "limit": { "type": "integer", "maximum": 500, "default": 50 },
-                    "offset": { "type": "integer", "default": 0 }
-                }
-            }),
-        });
-
-        m.insert("get_form13f_submission", Tool {
-            name: "get_form13f_submission",
-            category: Category::InstitutionalFilings,
-            description: "Retrieve Form 13-F holdings data for a specific institutional investor.",
-            keywords: &["13-F", "holdings", "portfolio", "institutional", "investments"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "filer_cik": { "type": "string", "description": "Filer's CIK" },
-                    "period_of_report": { "type": "string", "description": "Quarter end date" },
-                    "limit": { "type": "integer", "maximum": 500, "default": 50 },
-                    "offset": { "type": "integer", "default": 0 }
-                },
-                "required": ["filer_cik"]
-            }),
-        });
-
-        m.insert("get_form4_filing", Tool {
-            name: "get_form4_filing",
-            category: Category::InstitutionalFilings,
-            description: "Retrieve SEC Form 4 insider trading filings by accession number.",
-            keywords: &["Form 4", "insider", "trading", "transactions", "executive"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "accession_number": { "type": "string" }
-                },
-                "required": ["accession_number"]
-            }),
-        });
-
-        // =====================================================================
-        // ETF DATA (1 tool)
-        // =====================================================================
-
-        m.insert("get_etf_holdings", Tool {
-            name: "get_etf_holdings",
-            category: Category::EtfData,
-            description: "Retrieve holdings for a specific ETF from N-PORT filings.",
-            keywords: &["ETF", "holdings", "N-PORT", "portfolio", "fund"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "identifier": {
-                        "type": "string",
-                        "description": "ETF symbol, symbol:exchange, or CUSIP"
-                    },
-                    "quarter": { "type": "string" },
-                    "limit": { "type": "integer", "maximum": 100, "default": 10 },
-                    "offset": { "type": "integer", "default": 0 },
-                    "sort_direction": {
-                        "type": "string",
-                        "enum": ["asc", "desc"],
-                        "default": "desc"
-                    }
-                },
-                "required": ["identifier"]
-            }),
-        });
-
-        // =====================================================================
-        // Continue with remaining tools...
-        // (Form ADV, Lobbying, Watchlists - abbreviated for initial implementation)
-        // =====================================================================
-
-        // Form ADV - Firms
-        m.insert("get_form_adv_firms", Tool {
-            name: "get_form_adv_firms",
-            category: Category::FormAdvFirms,
-            description: "List and search Form ADV investment adviser firms.",
-            keywords: &["ADV", "adviser", "RIA", "search", "firms"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "search": { "type": "string" },
-                    "registration_status": { "type": "string", "enum": ["SEC", "ERA"] },
-                    "state": { "type": "string" },
-                    "min_aum": { "type": "integer" },
-                    "max_aum": { "type": "integer" },
-                    "page_size": { "type": "integer", "default": 25 },
-                    "page_offset": { "type": "integer", "default": 0 }
-                }
-            }),
-        });
-
-        m.insert("get_form_adv_firm", Tool {
-            name: "get_form_adv_firm",
-            category: Category::FormAdvFirms,
-            description: "Get detailed information about a specific investment adviser firm by CRD number.",
-            keywords: &["ADV", "firm", "CRD", "details", "adviser"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "crd": { "type": "string", "description": "CRD number" },
-                    "include": { "type": "string", "description": "Comma-separated resources to include" }
-                },
-                "required": ["crd"]
-            }),
-        });
-
-        // Lobbying
-        m.insert("get_lobbying_client_performance", Tool {
-            name: "get_lobbying_client_performance",
-            category: Category::Lobbying,
-            description: "Retrieve lobbying client spending patterns with growth metrics.",
-            keywords: &["lobbying", "spending", "growth", "performance"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "year": { "type": "integer" },
-                    "quarter": { "type": "string", "enum": ["Q1", "Q2", "Q3", "Q4"] },
-                    "page": { "type": "integer", "default": 1 },
-                    "per_page": { "type": "integer", "default": 20 },
-                    "sort_by": { "type": "string" },
-                    "with_cik": { "type": "boolean" },
-                    "with_stock_symbol": { "type": "boolean" },
-                    "min_spend": { "type": "number" }
-                }
-            }),
-        });
-
-        m.insert("get_lobbying_clients_search", Tool {
-            name: "get_lobbying_clients_search",
-            category: Category::Lobbying,
-            description: "Search for lobbying clients by name.",
-            keywords: &["lobbying", "client", "search"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "query": { "type": "string", "description": "Search term" },
-                    "limit": { "type": "integer", "default": 10 }
-                },
-                "required": ["query"]
-            }),
-        });
-
-        m.insert("get_lobbying_client_detail", Tool {
-            name: "get_lobbying_client_detail",
-            category: Category::Lobbying,
-            description: "Retrieve comprehensive information about a specific lobbying client.",
-            keywords: &["lobbying", "client", "detail", "history"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "client_id": { "type": "integer" },
-                    "years": { "type": "integer", "default": 10 },
-                    "include_registrants": { "type": "boolean", "default": true },
-                    "include_activities": { "type": "boolean", "default": true }
-                },
-                "required": ["client_id"]
-            }),
-        });
-
-        // Watchlists
-        m.insert("get_lists", Tool {
-            name: "get_lists",
-            category: Category::Watchlists,
-            description: "Retrieve all watchlists for the authenticated user.",
-            keywords: &["watchlist", "lists", "portfolio"],
-            input_schema: json!({ "type": "object", "properties": {} }),
-        });
-
-        m.insert("create_list", Tool {
-            name: "create_list",
-            category: Category::Watchlists,
-            description: "Create a new watchlist.",
-            keywords: &["watchlist", "create", "new"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "name": { "type": "string" },
-                    "notes": { "type": "string" }
-                },
-                "required": ["name"]
-            }),
-        });
-
-        m.insert("get_list", Tool {
-            name: "get_list",
-            category: Category::Watchlists,
-            description: "Retrieve a specific watchlist with its items.",
-            keywords: &["watchlist", "get", "items"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "id_or_name": { "type": "string" },
-                    "limit": { "type": "integer", "default": 50 },
-                    "offset": { "type": "integer", "default": 0 }
-                },
-                "required": ["id_or_name"]
-            }),
-        });
-
-        m.insert("update_list", Tool {
-            name: "update_list",
-            category: Category::Watchlists,
-            description: "Update a watchlist's name or notes.",
-            keywords: &["watchlist", "update", "rename"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "id_or_name": { "type": "string" },
-                    "name": { "type": "string" },
-                    "notes": { "type": "string" }
-                },
-                "required": ["id_or_name"]
-            }),
-        });
-
-        m.insert("delete_list", Tool {
-            name: "delete_list",
-            category: Category::Watchlists,
-            description: "Permanently delete a watchlist.",
-            keywords: &["watchlist", "delete", "remove"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "id_or_name": { "type": "string" }
-                },
-                "required": ["id_or_name"]
-            }),
-        });
-
-        // Watchlist Items
-        m.insert("add_list_item", Tool {
-            name: "add_list_item",
-            category: Category::WatchlistItems,
-            description: "Add a security or institutional investor to a watchlist.",
-            keywords: &["watchlist", "add", "item", "security"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "list_id": { "type": "string" },
-                    "symbol": { "type": "string" },
-                    "exchange": { "type": "string" },
-                    "cik": { "type": "string" },
-                    "notes": { "type": "string" }
-                },
-                "required": ["list_id"]
-            }),
-        });
-
-        m.insert("toggle_list_item", Tool {
-            name: "toggle_list_item",
-            category: Category::WatchlistItems,
-            description: "Toggle an item's presence in a watchlist.",
-            keywords: &["watchlist", "toggle", "item"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "list_id": { "type": "string" },
-                    "symbol": { "type": "string" },
-                    "exchange": { "type": "string" },
-                    "cik": { "type": "string" }
-                },
-                "required": ["list_id"]
-            }),
-        });
-
-        m.insert("update_list_item", Tool {
-            name: "update_list_item",
-            category: Category::WatchlistItems,
-            description: "Update notes for a specific item in a watchlist.",
-            keywords: &["watchlist", "update", "item", "notes"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "list_id": { "type": "string" },
-                    "item_id": { "type": "string" },
-                    "notes": { "type": "string" }
-                },
-                "required": ["list_id", "item_id"]
-            }),
-        });
-
-        m.insert("delete_list_item", Tool {
-            name: "delete_list_item",
-            category: Category::WatchlistItems,
-            description: "Remove an item from a watchlist.",
-            keywords: &["watchlist", "delete", "item", "remove"],
-            input_schema: json!({
-                "type": "object",
-                "properties": {
-                    "list_id": { "type": "string" },
-                    "item_id": { "type": "string" }
-                },
-                "required": ["list_id", "item_id"]
-            }),
-        });
-
-        // TODO: Add remaining Form ADV tools (ownership, funds, disclosures, other)
-        // These follow the same pattern and can be added incrementally
+// ============================================================================
+// SEARCH
+// ============================================================================
 
-        m
-    };
+/// Weight of a match through a synonym, relative to a direct match
+const SYNONYM_WEIGHT: f64 = 0.5;
+
+/// Registry terms for the synonym phrases in a lowercase query, without
+/// duplicates
+pub fn expand_query(query_lower: &str) -> Vec<&'static str> {
+    let query = format!(" {} ", fuzzy::words(query_lower).collect::<Vec<_>>().join(" "));
+    let found: Vec<&(&str, &[&str])> = SYNONYMS
+        .iter()
+        .filter(|(phrase, _)| query.contains(&format!(" {} ", phrase)))
+        .collect();
+    let mut terms: Vec<&'static str> = Vec::new();
+    for (phrase, expansions) in &found {
+        // The longest phrase wins: "hedge fund holdings" means 13F, not "hedge fund"
+        if found.iter().any(|(other, _)| other.len() > phrase.len() && other.contains(phrase)) {
+            continue;
+        }
+        for term in *expansions {
+            if !terms.contains(term) {
+                terms.push(term);
+            }
+        }
+    }
+    terms
 }
 
 // ============================================================================
 // PUBLIC API FUNCTIONS
 // ============================================================================
 
-/// Get all categories with the specified detail level
+/// Get all categories with the specified detail level. Each says how many
+/// tools it has available and how many are only planned, and a category
+/// with no tools yet is marked as not implemented. At `Tree`, categories in
+/// a group are listed as its children, in place of the first of them.
 pub fn get_categories(detail_level: DetailLevel) -> Value {
-    let mut categories_list = Vec::new();
+    let mut categories_list: Vec<Value> = Vec::new();
+    let mut groups: HashMap<&'static str, GroupNode> = HashMap::new();
 
     for cat in Category::all() {
-        if let Some(cat_info) = CATEGORIES.get(cat) {
+        if let Some(cat_info) = category_info(*cat) {
+            let tool_count = category_tools(*cat).count();
             let mut cat_dict = json!({
                 "id": cat.as_str(),
                 "name": cat_info.name,
-                "tool_count": cat_info.tool_count
+                "tool_count": tool_count,
+                "planned_count": cat_info.planned_tools.len(),
+                "implemented": tool_count > 0
             });
 
             if matches!(detail_level, DetailLevel::WithToolNames | DetailLevel::WithDescriptions) {
-                let tools: Vec<&str> = TOOLS
-                    .iter()
-                    .filter(|(_, t)| t.category == *cat)
-                    .map(|(name, _)| *name)
-                    .collect();
+                let tools: Vec<&str> = category_tools(*cat).map(|t| t.name).collect();
                 cat_dict["tools"] = json!(tools);
+                if !cat_info.planned_tools.is_empty() {
+                    cat_dict["planned_tools"] = planned_tools(cat_info, detail_level);
+                }
             }
 
             if detail_level == DetailLevel::WithDescriptions {
@@ -861,21 +198,79 @@ pub fn get_categories(detail_level: DetailLevel) -> Value {
                 cat_dict["example_queries"] = json!(cat_info.example_queries);
             }
 
+            match cat_info.parent.and_then(group_info) {
+                Some(group) if detail_level == DetailLevel::Tree => {
+                    let node = groups.entry(group.id).or_insert_with(|| {
+                        // Held for the group's node, filled in below
+                        categories_list.push(Value::Null);
+                        GroupNode {
+                            group,
+                            position: categories_list.len() - 1,
+                            tool_count: 0,
+                            planned_count: 0,
+                            children: Vec::new(),
+                        }
+                    });
+                    node.tool_count += tool_count;
+                    node.planned_count += cat_info.planned_tools.len();
+                    node.children.push(cat_dict);
+                    continue;
+                }
+                Some(group) => cat_dict["parent"] = json!(group.id),
+                None => {}
+            }
             categories_list.push(cat_dict);
         }
     }
 
+    for node in groups.into_values() {
+        categories_list[node.position] = json!({
+            "id": node.group.id,
+            "name": node.group.name,
+            "description": node.group.description,
+            "tool_count": node.tool_count,
+            "planned_count": node.planned_count,
+            "implemented": node.tool_count > 0,
+            "children": node.children
+        });
+    }
+
     json!({
         "total_categories": CATEGORIES.len(),
-        "total_tools": TOOLS.len(),
+        "total_tools": tools().count(),
+        "total_planned_tools": CATEGORIES.iter().map(|c| c.planned_tools.len()).sum::<usize>(),
         "categories": categories_list
     })
 }
 
-/// Search tools by keyword with relevance scoring
+/// A category group's node in a `Tree` listing, built up from its categories
+struct GroupNode {
+    group: &'static CategoryGroup,
+    /// Where the node goes in the listing: in place of its first category
+    position: usize,
+    tool_count: usize,
+    planned_count: usize,
+    children: Vec<Value>,
+}
+
+/// A category's planned tools: names, or names and descriptions at
+/// `WithDescriptions`
+fn planned_tools(category: &ToolCategory, detail_level: DetailLevel) -> Value {
+    let planned = category.planned_tools.iter();
+    match detail_level {
+        DetailLevel::WithDescriptions => json!(planned
+            .map(|(name, description)| json!({ "name": name, "description": description }))
+            .collect::<Vec<_>>()),
+        _ => json!(planned.map(|(name, _)| *name).collect::<Vec<_>>()),
+    }
+}
+
+/// Search tools by keyword with relevance scoring, optionally only those in
+/// a category or with a tag
 pub fn search_tools(
     query: &str,
     category: Option<&str>,
+    tag: Option<&str>,
     detail_level: DetailLevel,
 ) -> Value {
     if query.len() < 2 {
@@ -888,93 +283,178 @@ pub fn search_tools(
         });
     }
 
+    if let Some(error) = tag.and_then(unknown_tag_error) {
+        return json!({
+            "query": query,
+            "tag_filter": tag,
+            "match_count": 0,
+            "error": error,
+            "matches": []
+        });
+    }
+
     // Validate category if provided
     if let Some(cat_str) = category {
-        if cat_str.parse::<Category>().is_err() {
-            let valid_cats: Vec<&str> = Category::all().iter().map(|c| c.as_str()).collect();
+        if categories_in(cat_str).is_empty() {
             return json!({
                 "query": query,
                 "category_filter": category,
                 "match_count": 0,
-                "error": format!("Unknown category '{}'. Valid categories: {}", cat_str, valid_cats.join(", ")),
+                "error": unknown_category_error(cat_str),
                 "matches": []
             });
         }
     }
 
     let query_lower = query.to_lowercase();
-    let mut matches: Vec<SearchResult> = Vec::new();
-
-    for (tool_name, tool) in TOOLS.iter() {
-        // Filter by category if specified
-        if let Some(cat_str) = category {
-            if tool.category.as_str() != cat_str {
-                continue;
+    // Misspelled words are corrected to the closest word in the registry
+    let (corrected, edits) = correct_query(&query_lower);
+    let expansions = expand_query(&corrected);
+    let index = search_index();
+    let mut scores = index.scores(&query_lower);
+    if edits > 0 {
+        // Matches through a corrected word rank below exact matches
+        let penalty = (1.0 - FUZZY_PENALTY_PER_EDIT * edits as f64).max(FUZZY_PENALTY_PER_EDIT);
+        for (score, fuzzy) in scores.iter_mut().zip(index.scores(&corrected)) {
+            if *score == 0.0 {
+                *score = fuzzy * penalty;
             }
         }
-
-        // Calculate relevance score
-        let mut score = 0.0;
-
-        // Name match (highest weight)
-        if tool_name.to_lowercase().contains(&query_lower) {
-            score += 10.0;
-        }
-
-        // Description match
-        if tool.description.to_lowercase().contains(&query_lower) {
-            score += 5.0;
+    }
+    for term in &expansions {
+        for (score, expanded) in scores.iter_mut().zip(index.scores(term)) {
+            *score += expanded * SYNONYM_WEIGHT;
         }
+    }
+    let mut matches: Vec<SearchResult> = Vec::new();
 
-        // Keyword matches
-        for keyword in tool.keywords {
-            if keyword.to_lowercase().contains(&query_lower) {
-                score += 3.0;
+    for (tool, score) in tools().zip(scores) {
+        // Filter by category, or category group, if specified
+        if let Some(cat_str) = category {
+            if !categories_in(cat_str).contains(&tool.category) {
+                continue;
             }
         }
-
-        // Category name match
-        if tool.category.as_str().to_lowercase().contains(&query_lower) {
-            score += 2.0;
+        if tag.is_some_and(|tag| !tool.tags.contains(&tag)) {
+            continue;
         }
 
         if score > 0.0 {
             let mut result = SearchResult {
                 name: tool.name.to_string(),
                 category: tool.category.as_str().to_string(),
-                relevance_score: score,
+                relevance_score: (score * 1000.0).round() / 1000.0,
                 description: None,
                 keywords: None,
+                tags: None,
                 input_schema: None,
+                output_schema: None,
             };
 
             if matches!(detail_level, DetailLevel::WithDescriptions | DetailLevel::FullSchema) {
                 result.description = Some(tool.description.to_string());
                 result.keywords = Some(tool.keywords.iter().map(|s| s.to_string()).collect());
+                result.tags = Some(tool.tags.iter().map(|s| s.to_string()).collect());
             }
 
             if detail_level == DetailLevel::FullSchema {
-                result.input_schema = Some(tool.input_schema.clone());
+                result.input_schema = Some(tool.input_schema());
+                result.output_schema = tool.output_schema();
             }
 
             matches.push(result);
         }
     }
 
-    // Sort by relevance score descending
-    matches.sort_by(|a, b| b.relevance_score.partial_cmp(&a.relevance_score).unwrap());
+    // Sort by relevance score descending, then by name so ties are stable
+    matches.sort_by(|a, b| {
+        b.relevance_score
+            .total_cmp(&a.relevance_score)
+            .then_with(|| a.name.cmp(&b.name))
+    });
 
-    json!({
+    let mut result = json!({
         "query": query,
         "category_filter": category,
         "match_count": matches.len(),
         "matches": matches
+    });
+    if tag.is_some() {
+        result["tag_filter"] = json!(tag);
+    }
+    if edits > 0 {
+        result["did_you_mean"] = json!(corrected);
+    }
+    if !expansions.is_empty() {
+        result["expanded_terms"] = json!(expansions);
+    }
+    result
+}
+
+/// Weights of a tool's name, description, keywords, and category in
+/// search, in the order they are indexed
+const FIELD_WEIGHTS: [f64; 4] = [10.0, 5.0, 3.0, 2.0];
+
+/// Every tool's searchable text, indexed in [`tools`] order
+fn search_index() -> &'static ranking::Index {
+    static INDEX: OnceLock<ranking::Index> = OnceLock::new();
+    INDEX.get_or_init(|| {
+        let documents = tools().map(|tool| {
+            vec![
+                tool.name.to_string(),
+                tool.description.to_string(),
+                tool.keywords.join(" "),
+                tool.category.as_str().to_string(),
+            ]
+        });
+        ranking::Index::new(&FIELD_WEIGHTS, documents)
     })
 }
 
+/// Share of the score lost per corrected edit
+const FUZZY_PENALTY_PER_EDIT: f64 = 0.25;
+
+/// Every distinct word in tool names, descriptions, keywords, categories,
+/// and synonym phrases
+fn vocabulary() -> &'static [String] {
+    static VOCABULARY: OnceLock<Vec<String>> = OnceLock::new();
+    VOCABULARY.get_or_init(|| {
+        let mut words: Vec<String> = tools()
+            .flat_map(|tool| {
+                let text = [tool.name, tool.description, tool.category.as_str()]
+                    .into_iter()
+                    .chain(tool.keywords.iter().copied());
+                text.flat_map(fuzzy::words).collect::<Vec<_>>()
+            })
+            .chain(SYNONYMS.iter().flat_map(|(phrase, _)| fuzzy::words(phrase)))
+            .collect();
+        words.sort();
+        words.dedup();
+        words
+    })
+}
+
+/// The query with each word that appears nowhere in the registry replaced
+/// by its closest known word, and the number of edits that took
+fn correct_query(query_lower: &str) -> (String, usize) {
+    let vocabulary = vocabulary();
+    let mut corrected = query_lower.to_string();
+    let mut edits = 0;
+    for word in fuzzy::words(query_lower) {
+        if vocabulary.iter().any(|known| ranking::matches(known, &word)) {
+            continue;
+        }
+        if let Some((known, distance)) = fuzzy::closest(&word, vocabulary.iter().map(String::as_str)) {
+            corrected = corrected.replace(&word, known);
+            edits += distance;
+        }
+    }
+    (corrected, edits)
+}
+
 /// Get metadata for a specific tool by name
 pub fn get_tool_metadata(name: &str, detail_level: DetailLevel) -> Value {
-    match TOOLS.get(name) {
+    match find_tool(name) {
         None => json!({
             "error": format!("Unknown tool '{}'. Use search_tools to find available tools.", name),
             "tool_name": name
@@ -988,10 +468,15 @@ pub fn get_tool_metadata(name: &str, detail_level: DetailLevel) -> Value {
             if matches!(detail_level, DetailLevel::WithDescriptions | DetailLevel::FullSchema) {
                 result["description"] = json!(tool.description);
                 result["keywords"] = json!(tool.keywords);
+                result["tags"] = json!(tool.tags);
+                result["examples"] = json!(tool.examples);
             }
 
             if detail_level == DetailLevel::FullSchema {
-                result["inputSchema"] = tool.input_schema.clone();
+                result["inputSchema"] = tool.input_schema();
+                if let Some(schema) = tool.output_schema() {
+                    result["outputSchema"] = schema;
+                }
             }
 
             result
@@ -999,58 +484,185 @@ pub fn get_tool_metadata(name: &str, detail_level: DetailLevel) -> Value {
     }
 }
 
-/// List all tools in a specific category
-pub fn list_tools_by_category(category: &str, detail_level: DetailLevel) -> Value {
-    let cat = match category.parse::<Category>() {
-        Ok(c) => c,
-        Err(_) => {
-            let valid_cats: Vec<&str> = Category::all().iter().map(|c| c.as_str()).collect();
+/// List all tools in a category, or in every category of a group,
+/// optionally only those with a tag
+pub fn list_tools_by_category(category: &str, tag: Option<&str>, detail_level: DetailLevel) -> Value {
+    let categories = categories_in(category);
+    let (name, description) = match (category.parse::<Category>().ok().and_then(category_info), group_info(category)) {
+        (Some(info), _) => (info.name, info.description),
+        (None, Some(group)) => (group.name, group.description),
+        (None, None) => {
             return json!({
-                "error": format!("Unknown category '{}'. Valid categories: {}", category, valid_cats.join(", ")),
+                "error": unknown_category_error(category),
                 "category": category
             });
         }
     };
 
-    let cat_info = CATEGORIES.get(&cat).unwrap();
-    let mut tools_list = Vec::new();
+    if let Some(error) = tag.and_then(unknown_tag_error) {
+        return json!({ "error": error, "category": category });
+    }
 
-    for (_, tool) in TOOLS.iter() {
-        if tool.category != cat {
-            continue;
-        }
+    let mut tools_list = Vec::new();
 
+    let listed = categories.iter().flat_map(|cat| category_tools(*cat));
+    for tool in listed.filter(|t| tag.is_none_or(|tag| t.tags.contains(&tag))) {
         let mut tool_dict = json!({ "name": tool.name });
 
         if matches!(detail_level, DetailLevel::WithDescriptions | DetailLevel::FullSchema) {
             tool_dict["description"] = json!(tool.description);
             tool_dict["keywords"] = json!(tool.keywords);
+            tool_dict["tags"] = json!(tool.tags);
         }
 
         if detail_level == DetailLevel::FullSchema {
-            tool_dict["inputSchema"] = tool.input_schema.clone();
+            tool_dict["inputSchema"] = tool.input_schema();
+            if let Some(schema) = tool.output_schema() {
+                tool_dict["outputSchema"] = schema;
+            }
         }
 
         tools_list.push(tool_dict);
     }
 
-    json!({
+    let mut result = json!({
         "category": category,
-        "category_name": cat_info.name,
-        "category_description": cat_info.description,
+        "category_name": name,
+        "category_description": description,
         "tool_count": tools_list.len(),
         "tools": tools_list
-    })
+    });
+    let planned: Vec<Value> = categories
+        .iter()
+        .filter_map(|cat| category_info(*cat))
+        .flat_map(|info| info.planned_tools.iter())
+        .map(|(name, description)| json!({ "name": name, "description": description }))
+        .collect();
+    if !planned.is_empty() {
+        result["planned_tools"] = json!(planned);
+    }
+    if tag.is_some() {
+        result["tag_filter"] = json!(tag);
+    }
+    result
+}
+
+/// The categories a category or group id stands for; none if it is neither
+fn categories_in(id: &str) -> Vec<Category> {
+    match id.parse::<Category>() {
+        Ok(category) => vec![category],
+        Err(_) => CATEGORIES
+            .iter()
+            .filter(|c| c.parent == Some(id))
+            .map(|c| c.id)
+            .collect(),
+    }
+}
+
+/// Error message for an id that is neither a category nor a group
+fn unknown_category_error(id: &str) -> String {
+    let valid: Vec<&str> = Category::all()
+        .iter()
+        .map(|c| c.as_str())
+        .chain(CATEGORY_GROUPS.iter().map(|g| g.id))
+        .collect();
+    format!("Unknown category '{}'. Valid categories: {}", id, valid.join(", "))
+}
+
+/// Error message for a tag no tool can carry
+fn unknown_tag_error(tag: &str) -> Option<String> {
+    if TAGS.iter().any(|(known, _)| *known == tag) {
+        return None;
+    }
+    let valid: Vec<&str> = TAGS.iter().map(|(known, _)| *known).collect();
+    Some(format!("Unknown tag '{}'. Valid tags: {}", tag, valid.join(", ")))
 }
 
 /// Check if a tool exists
 pub fn tool_exists(name: &str) -> bool {
-    TOOLS.contains_key(name)
+    find_tool(name).is_some()
+}
+
+/// Whether a tool carries a tag from [`TAGS`], such as "read-only"
+pub fn has_tag(name: &str, tag: &str) -> bool {
+    find_tool(name).is_some_and(|t| t.tags.contains(&tag))
+}
+
+/// Get the category a tool belongs to
+pub fn get_tool_category(name: &str) -> Option<Category> {
+    find_tool(name).map(|t| t.category)
 }
 
 /// Get a tool's input schema
 pub fn get_tool_schema(name: &str) -> Option<Value> {
-    TOOLS.get(name).map(|t| t.input_schema.clone())
+    find_tool(name).map(Tool::input_schema)
+}
+
+/// Get a tool's output schema, if its results are structured
+pub fn get_tool_output_schema(name: &str) -> Option<Value> {
+    find_tool(name).and_then(Tool::output_schema)
+}
+
+/// MCP tool definitions for every tool, sorted by name, for clients that
+/// call them directly instead of through discovery
+pub fn tool_definitions() -> Vec<Value> {
+    tools()
+        .map(|tool| {
+            let mut definition = json!({
+                "name": tool.name,
+                "description": tool.description,
+                "inputSchema": tool.input_schema(),
+            });
+            if let Some(schema) = tool.output_schema() {
+                definition["outputSchema"] = schema;
+            }
+            definition
+        })
+        .collect()
+}
+
+/// Add tools defined at runtime, such as plugins, alongside the built-in
+/// ones. The search index is built on first use, so this must be called
+/// before any search. Returns false if tools were already registered.
+pub fn register_tools(tools: Vec<Tool>) -> bool {
+    RUNTIME_TOOLS.set(tools).is_ok()
+}
+
+/// Tools added with [`register_tools`]
+static RUNTIME_TOOLS: OnceLock<Vec<Tool>> = OnceLock::new();
+
+/// A built-in or registered tool by name
+fn find_tool(name: &str) -> Option<&'static Tool> {
+    TOOLS
+        .get(name)
+        .or_else(|| RUNTIME_TOOLS.get()?.iter().find(|t| t.name == name))
+}
+
+/// Metadata for a category group
+pub fn group_info(id: &str) -> Option<&'static CategoryGroup> {
+    CATEGORY_GROUPS.iter().find(|g| g.id == id)
+}
+
+/// All category groups
+pub fn category_groups() -> &'static [CategoryGroup] {
+    &CATEGORY_GROUPS
+}
+
+/// Metadata for a category
+pub(crate) fn category_info(category: Category) -> Option<&'static ToolCategory> {
+    CATEGORIES.iter().find(|c| c.id == category)
+}
+
+/// All tools, by name
+fn tools() -> impl Iterator<Item = &'static Tool> {
+    let mut tools: Vec<&'static Tool> = TOOLS.values().chain(RUNTIME_TOOLS.get().into_iter().flatten()).collect();
+    tools.sort_unstable_by_key(|t| t.name);
+    tools.into_iter()
+}
+
+/// All tools in a category, by name
+pub(crate) fn category_tools(category: Category) -> impl Iterator<Item = &'static Tool> {
+    tools().filter(move |t| t.category == category)
 }
 
 #[cfg(test)]
@@ -1068,6 +680,22 @@ mod tests {
         assert!(result["total_tools"].as_u64().unwrap() > 0);
     }
 
+    #[test]
+    fn test_get_categories_available_and_planned() {
+        let result = get_categories(DetailLevel::WithDescriptions);
+        let planned: usize = CATEGORIES.iter().map(|c| c.planned_tools.len()).sum();
+        assert_eq!(result["total_planned_tools"], planned);
+        for (cat, info) in result["categories"].as_array().unwrap().iter().zip(CATEGORIES.iter()) {
+            assert_eq!(cat["planned_count"], info.planned_tools.len());
+            assert_eq!(cat["implemented"], cat["tool_count"].as_u64().unwrap() > 0);
+            assert_eq!(cat.get("planned_tools").is_some(), !info.planned_tools.is_empty());
+        }
+        // Planned tools aren't callable
+        for (name, _) in CATEGORIES.iter().flat_map(|c| c.planned_tools.iter()) {
+            assert!(!tool_exists(name), "{}", name);
+        }
+    }
+
     #[test]
     fn test_get_categories_summary() {
         let result = get_categories(DetailLevel::Summary);
@@ -1093,6 +721,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_categories_tree() {
+        let result = get_categories(DetailLevel::Tree);
+        let nodes = result["categories"].as_array().unwrap();
+        let form_adv = nodes.iter().find(|n| n["id"] == "form_adv").unwrap();
+        let children = form_adv["children"].as_array().unwrap();
+        assert_eq!(children.len(), 5);
+        assert!(children.iter().all(|c| c["id"].as_str().unwrap().starts_with("form_adv_")));
+        let total: u64 = children.iter().map(|c| c["tool_count"].as_u64().unwrap()).sum();
+        assert_eq!(form_adv["tool_count"], total);
+        assert_eq!(form_adv["implemented"], true);
+        assert_eq!(form_adv["description"], group_info("form_adv").unwrap().description);
+        // Grouped categories aren't repeated at the top level
+        assert!(!nodes.iter().any(|n| n["id"] == "form_adv_firms"));
+        assert_eq!(nodes.len(), CATEGORIES.len() - children.len() + 1);
+        assert!(nodes.iter().all(|n| n.get("tools").is_none()));
+
+        // Flat listings name each category's group instead
+        let flat = get_categories(DetailLevel::Summary);
+        let firms = flat["categories"].as_array().unwrap().iter().find(|c| c["id"] == "form_adv_firms").unwrap();
+        assert_eq!(firms["parent"], "form_adv");
+        assert_eq!("tree".parse::<DetailLevel>(), Ok(DetailLevel::Tree));
+    }
+
+    #[test]
+    fn test_filter_by_category_group() {
+        let listed = list_tools_by_category("form_adv", None, DetailLevel::NamesOnly);
+        assert_eq!(listed["category_name"], "Form ADV");
+        let names: Vec<&str> = listed["tools"].as_array().unwrap().iter().filter_map(|t| t["name"].as_str()).collect();
+        assert!(names.contains(&"get_form_adv_firm"));
+        assert!(names.contains(&"get_form_adv_funds"));
+        assert!(!names.contains(&"get_etf_holdings"));
+
+        let found = search_tools("owners", Some("form_adv"), None, DetailLevel::NamesOnly);
+        let matches = found["matches"].as_array().unwrap();
+        assert!(!matches.is_empty());
+        assert!(matches.iter().all(|m| m["category"].as_str().unwrap().starts_with("form_adv_")));
+
+        let unknown = search_tools("owners", Some("adv"), None, DetailLevel::NamesOnly);
+        assert!(unknown["error"].as_str().unwrap().ends_with(", form_adv"));
+    }
+
     #[test]
     fn test_get_categories_with_descriptions() {
         let result = get_categories(DetailLevel::WithDescriptions);
@@ -1193,13 +863,13 @@ mod tests {
 
     #[test]
     fn test_search_tools() {
-        let result = search_tools("financials", None, DetailLevel::WithDescriptions);
+        let result = search_tools("financials", None, None, DetailLevel::WithDescriptions);
         assert!(result["match_count"].as_u64().unwrap() > 0);
     }
 
     #[test]
     fn test_search_tools_short_query() {
-        let result = search_tools("a", None, DetailLevel::NamesOnly);
+        let result = search_tools("a", None, None, DetailLevel::NamesOnly);
         assert!(result["error"].is_string());
         assert!(result["error"]
             .as_str()
@@ -1209,13 +879,13 @@ mod tests {
 
     #[test]
     fn test_search_tools_empty_query() {
-        let result = search_tools("", None, DetailLevel::NamesOnly);
+        let result = search_tools("", None, None, DetailLevel::NamesOnly);
         assert!(result["error"].is_string());
     }
 
     #[test]
     fn test_search_tools_with_category_filter() {
-        let result = search_tools("holdings", Some("etf_data"), DetailLevel::NamesOnly);
+        let result = search_tools("holdings", Some("etf_data"), None, DetailLevel::NamesOnly);
         assert!(result["match_count"].as_u64().unwrap() > 0);
 
         // All results should be from etf_data category
@@ -1227,7 +897,7 @@ mod tests {
 
     #[test]
     fn test_search_tools_invalid_category() {
-        let result = search_tools("test", Some("invalid_cat"), DetailLevel::NamesOnly);
+        let result = search_tools("test", Some("invalid_cat"), None, DetailLevel::NamesOnly);
         assert!(result["error"].is_string());
         assert!(result["error"]
             .as_str()
@@ -1237,14 +907,92 @@ mod tests {
 
     #[test]
     fn test_search_tools_no_matches() {
-        let result = search_tools("zzzznonexistent", None, DetailLevel::NamesOnly);
+        let result = search_tools("zzzznonexistent", None, None, DetailLevel::NamesOnly);
         assert_eq!(result["match_count"], 0);
         assert!(result["matches"].as_array().unwrap().is_empty());
+        assert!(result.get("did_you_mean").is_none());
+    }
+
+    #[test]
+    fn test_synonyms_reach_tools() {
+        for (phrase, expansions) in SYNONYMS {
+            assert_eq!(*phrase, phrase.to_lowercase(), "synonym phrases are matched lowercase");
+            for term in *expansions {
+                assert!(
+                    search_index().scores(term).iter().any(|&s| s > 0.0),
+                    "'{}' for '{}' matches no tool",
+                    term,
+                    phrase
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_tools_ranks_multi_word_queries() {
+        let cases = [
+            ("quarterly cash flow statement", "get_company_financials"),
+            ("Show me insider selling at Apple", "get_insider_sentiment"),
+            ("13f holdings of berkshire", "get_13f_activity"),
+            ("etf holdings", "get_etf_holdings"),
+            ("read a section of a 10-K", "read_filing_section"),
+            ("full text search filings", "search_company_filings_text"),
+            ("lobbying spend growth", "get_lobbying_client_performance"),
+            ("adviser regulatory disclosures", "get_form_adv_firm_disclosures"),
+            ("aum history", "get_form_adv_firm_aum_history"),
+            ("upcoming earnings", "get_upcoming_reports"),
+        ];
+        for (query, expected) in cases {
+            let result = search_tools(query, None, None, DetailLevel::NamesOnly);
+            assert_eq!(result["matches"][0]["name"], expected, "{}: {}", query, result["matches"]);
+        }
+    }
+
+    #[test]
+    fn test_search_tools_synonyms() {
+        let names = |query: &str| -> Vec<String> {
+            search_tools(query, None, None, DetailLevel::NamesOnly)["matches"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|m| m["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+        assert!(names("insider trades").contains(&"get_form4_filing".to_string()));
+        assert!(names("hedge fund holdings").contains(&"get_form13f_submissions".to_string()));
+        assert!(names("RIA").iter().take(5).all(|name| name.starts_with("get_form_adv")), "{:?}", names("RIA"));
+
+        let result = search_tools("Insider trades this week", None, None, DetailLevel::NamesOnly);
+        assert_eq!(result["expanded_terms"], json!(["form 4", "insider"]));
+        assert!(result.get("did_you_mean").is_none());
+        assert_eq!(expand_query("variance"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_search_tools_typos() {
+        let result = search_tools("lobying", None, None, DetailLevel::NamesOnly);
+        assert_eq!(result["did_you_mean"], "lobbying");
+        let names: Vec<&str> = result["matches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"get_lobbying_trends"), "{:?}", names);
+
+        let result = search_tools("finanicals", None, None, DetailLevel::NamesOnly);
+        assert_eq!(result["did_you_mean"], "financials");
+        assert_eq!(result["matches"][0]["name"], "get_company_financials");
+
+        // A corrected match scores below the same exact match
+        let exact = search_tools("financials", None, None, DetailLevel::NamesOnly);
+        assert!(result["matches"][0]["relevance_score"].as_f64() < exact["matches"][0]["relevance_score"].as_f64());
+        assert!(exact.get("did_you_mean").is_none());
     }
 
     #[test]
     fn test_search_tools_relevance_sorting() {
-        let result = search_tools("watchlist", None, DetailLevel::NamesOnly);
+        let result = search_tools("watchlist", None, None, DetailLevel::NamesOnly);
         let matches = result["matches"].as_array().unwrap();
 
         // Should have multiple matches
@@ -1259,9 +1007,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_listings_are_deterministic() {
+        let names = |v: &Value, key: &str| -> Vec<String> {
+            v[key]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|t| t["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        let listed = list_tools_by_category("watchlist_items", None, DetailLevel::NamesOnly);
+        let listed = names(&listed, "tools");
+        let mut sorted = listed.clone();
+        sorted.sort();
+        assert_eq!(listed, sorted);
+
+        // Equal scores fall back to name order
+        let result = search_tools("watchlist", None, None, DetailLevel::NamesOnly);
+        let matches = result["matches"].as_array().unwrap();
+        for pair in matches.windows(2) {
+            if pair[0]["relevance_score"] == pair[1]["relevance_score"] {
+                assert!(pair[0]["name"].as_str() < pair[1]["name"].as_str());
+            }
+        }
+        assert_eq!(
+            names(&result, "matches"),
+            names(&search_tools("watchlist", None, None, DetailLevel::NamesOnly), "matches")
+        );
+
+        let categories = get_categories(DetailLevel::WithToolNames);
+        let tools: Vec<&str> = categories["categories"][0]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t.as_str().unwrap())
+            .collect();
+        assert!(tools.windows(2).all(|w| w[0] < w[1]));
+    }
+
     #[test]
     fn test_search_tools_names_only_detail() {
-        let result = search_tools("financials", None, DetailLevel::NamesOnly);
+        let result = search_tools("financials", None, None, DetailLevel::NamesOnly);
         let matches = result["matches"].as_array().unwrap();
 
         for m in matches {
@@ -1275,7 +1063,7 @@ mod tests {
 
     #[test]
     fn test_search_tools_full_schema_detail() {
-        let result = search_tools("financials", None, DetailLevel::FullSchema);
+        let result = search_tools("financials", None, None, DetailLevel::FullSchema);
         let matches = result["matches"].as_array().unwrap();
 
         // At least one match should have full schema
@@ -1288,14 +1076,14 @@ mod tests {
     #[test]
     fn test_search_by_keyword() {
         // Search by a keyword rather than tool name
-        let result = search_tools("10-K", None, DetailLevel::NamesOnly);
+        let result = search_tools("10-K", None, None, DetailLevel::NamesOnly);
         assert!(result["match_count"].as_u64().unwrap() > 0);
     }
 
     #[test]
     fn test_search_case_insensitive() {
-        let result_lower = search_tools("etf", None, DetailLevel::NamesOnly);
-        let result_upper = search_tools("ETF", None, DetailLevel::NamesOnly);
+        let result_lower = search_tools("etf", None, None, DetailLevel::NamesOnly);
+        let result_upper = search_tools("ETF", None, None, DetailLevel::NamesOnly);
 
         assert_eq!(
             result_lower["match_count"].as_u64().unwrap(),
@@ -1343,7 +1131,7 @@ mod tests {
 
     #[test]
     fn test_list_tools_by_category() {
-        let result = list_tools_by_category("company_data", DetailLevel::NamesOnly);
+        let result = list_tools_by_category("company_data", None, DetailLevel::NamesOnly);
         assert!(result["tool_count"].as_u64().unwrap() > 0);
         assert_eq!(result["category"], "company_data");
         assert!(result.get("tools").is_some());
@@ -1351,7 +1139,7 @@ mod tests {
 
     #[test]
     fn test_list_tools_by_category_invalid() {
-        let result = list_tools_by_category("invalid_category", DetailLevel::NamesOnly);
+        let result = list_tools_by_category("invalid_category", None, DetailLevel::NamesOnly);
         assert!(result["error"].is_string());
         assert!(result["error"]
             .as_str()
@@ -1359,9 +1147,42 @@ mod tests {
             .contains("Unknown category"));
     }
 
+    #[test]
+    fn test_filter_by_tag() {
+        let listed = list_tools_by_category("watchlists", Some("mutating"), DetailLevel::WithDescriptions);
+        let tools = listed["tools"].as_array().unwrap();
+        assert!(!tools.is_empty());
+        for tool in tools {
+            assert!(tool["tags"].as_array().unwrap().contains(&json!("mutating")), "{}", tool);
+        }
+        assert!(!tools.iter().any(|t| t["name"] == "get_lists"));
+
+        let found = search_tools("watchlist", None, Some("read-only"), DetailLevel::NamesOnly);
+        assert_eq!(found["tag_filter"], "read-only");
+        let names: Vec<&str> = found["matches"].as_array().unwrap().iter().filter_map(|m| m["name"].as_str()).collect();
+        assert!(names.contains(&"get_lists"));
+        assert!(!names.contains(&"delete_list"));
+
+        let unknown = search_tools("watchlist", None, Some("fast"), DetailLevel::NamesOnly);
+        assert!(unknown["error"].as_str().unwrap().contains("Valid tags"));
+        assert!(list_tools_by_category("watchlists", Some("fast"), DetailLevel::NamesOnly)["error"].is_string());
+
+        assert!(has_tag("extract_document_text", "read-only"));
+        assert!(!has_tag("delete_list", "read-only"));
+        assert!(!has_tag("search", "read-only"));
+    }
+
+    #[test]
+    fn test_every_tool_is_read_only_or_mutating() {
+        for tool in TOOLS.values() {
+            let read_only = tool.tags.contains(&"read-only");
+            assert_ne!(read_only, tool.tags.contains(&"mutating"), "{}", tool.name);
+        }
+    }
+
     #[test]
     fn test_list_tools_by_category_with_full_schema() {
-        let result = list_tools_by_category("etf_data", DetailLevel::FullSchema);
+        let result = list_tools_by_category("etf_data", None, DetailLevel::FullSchema);
         let tools = result["tools"].as_array().unwrap();
 
         // ETF data category has tools
@@ -1384,6 +1205,13 @@ mod tests {
         assert!(!tool_exists("nonexistent_tool"));
     }
 
+    #[test]
+    fn test_get_tool_category() {
+        assert_eq!(get_tool_category("get_sec_document"), Some(Category::SecDocuments));
+        assert_eq!(get_tool_category("get_lists"), Some(Category::Watchlists));
+        assert_eq!(get_tool_category("nonexistent_tool"), None);
+    }
+
     #[test]
     fn test_get_tool_schema() {
         let schema = get_tool_schema("get_company_financials");
@@ -1406,14 +1234,20 @@ mod tests {
 
     #[test]
     fn test_all_categories_have_metadata() {
-        // Verify every category has an entry in CATEGORIES
-        for cat in Category::all() {
-            assert!(
-                CATEGORIES.get(cat).is_some(),
-                "Category {:?} missing from CATEGORIES",
-                cat
-            );
+        // Verify every category has an entry in CATEGORIES, in order
+        for (cat, info) in Category::all().iter().zip(CATEGORIES.iter()) {
+            assert_eq!(*cat, info.id, "Category {:?} out of order in CATEGORIES", cat);
+        }
+        assert_eq!(CATEGORIES.len(), Category::all().len());
+    }
+
+    #[test]
+    fn test_category_tool_counts_are_computed() {
+        for info in CATEGORIES.iter() {
+            assert_eq!(info.tool_count, category_tools(info.id).count(), "{:?}", info.id);
         }
+        let total: usize = CATEGORIES.iter().map(|c| c.tool_count).sum();
+        assert_eq!(total, TOOLS.len());
     }
 
     #[test]
@@ -1421,8 +1255,8 @@ mod tests {
         // For categories that have tools in TOOLS, verify consistency
         let mut categories_with_tools: std::collections::HashSet<Category> =
             std::collections::HashSet::new();
-        for (_, tool) in TOOLS.iter() {
-            categories_with_tools.insert(tool.category.clone());
+        for tool in TOOLS.values() {
+            categories_with_tools.insert(tool.category);
         }
 
         // At minimum, we should have some categories implemented
@@ -1433,7 +1267,7 @@ mod tests {
 
         // Verify implemented categories have proper metadata
         for cat in &categories_with_tools {
-            let cat_info = CATEGORIES.get(cat).unwrap();
+            let cat_info = category_info(*cat).unwrap();
             assert!(
                 !cat_info.name.is_empty(),
                 "Category {:?} has empty name",
@@ -1449,8 +1283,9 @@ mod tests {
 
     #[test]
     fn test_tool_schemas_have_required_fields() {
-        for (name, tool) in TOOLS.iter() {
-            let schema = &tool.input_schema;
+        for (name, tool) in TOOLS.entries() {
+            let schema: Value = serde_json::from_str(tool.input_schema_json)
+                .unwrap_or_else(|e| panic!("Tool {} has an invalid schema: {}", name, e));
 
             // All tools should have type: object
             assert_eq!(
@@ -1468,9 +1303,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_output_schemas_are_objects() {
+        for (name, tool) in TOOLS.entries() {
+            let Some(json) = tool.output_schema_json else { continue };
+            let schema: Value = serde_json::from_str(json)
+                .unwrap_or_else(|e| panic!("Tool {} has an invalid output schema: {}", name, e));
+            // structuredContent must be an object
+            assert_eq!(schema["type"], "object", "Tool {} output should have type: object", name);
+        }
+
+        assert!(get_tool_output_schema("get_red_flags").unwrap()["properties"]["checks"].is_object());
+        // Results prefixed with a prose summary aren't structured
+        assert_eq!(get_tool_output_schema("get_company_financials"), None);
+        let metadata = get_tool_metadata("delete_list", DetailLevel::FullSchema);
+        assert_eq!(metadata["outputSchema"]["required"], json!(["success", "message"]));
+    }
+
+    #[test]
+    fn test_tool_definitions() {
+        let definitions = tool_definitions();
+        assert_eq!(definitions.len(), TOOLS.len());
+        let red_flags = definitions.iter().find(|d| d["name"] == "get_red_flags").unwrap();
+        assert_eq!(red_flags["inputSchema"], get_tool_schema("get_red_flags").unwrap());
+        assert!(red_flags["outputSchema"].is_object());
+        let financials = definitions.iter().find(|d| d["name"] == "get_company_financials").unwrap();
+        assert!(financials.get("outputSchema").is_none());
+    }
+
     #[test]
     fn test_search_result_structure() {
-        let result = search_tools("company", None, DetailLevel::FullSchema);
+        let result = search_tools("company", None, None, DetailLevel::FullSchema);
 
         // Check the result has the expected structure
         assert!(result.get("query").is_some());
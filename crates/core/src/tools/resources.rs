@@ -0,0 +1,324 @@
+//! MCP resource templates for SEC filings.
+//!
+//! Clients can build URIs from the advertised templates and read them
+//! directly instead of going through a tool call: `edgar-index://` lists a
+//! company's recent filings, `filing://` lists the documents in a filing,
+//! and `document://` (or `sec://`, matching EDGAR's archive paths) returns
+//! one document's text. Filing lists from `get_company_filings` carry these
+//! URIs so a client can go straight from a filing to its documents.
+
+use super::exhibits::{exhibit_category, IndexDocument};
+use super::fields::str_field;
+use crate::params::{normalize_accession, normalize_cik};
+use serde_json::{json, Value};
+
+pub const FILING_URI_TEMPLATE: &str = "filing://{cik}/{accession}";
+pub const DOCUMENT_URI_TEMPLATE: &str = "document://{cik}/{accession}/{filename}";
+pub const SEC_URI_TEMPLATE: &str = "sec://{cik}/{accession}/{filename}";
+pub const INDEX_URI_TEMPLATE: &str = "edgar-index://{cik}";
+
+/// Most recent filings listed in an `edgar-index://` resource
+pub const MAX_INDEX_FILINGS: usize = 100;
+
+/// A parsed resource URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUri {
+    Index { cik: String },
+    Filing { cik: String, accession: String },
+    Document { cik: String, accession: String, filename: String },
+}
+
+impl ResourceUri {
+    /// Parse an `edgar-index://`, `filing://`, `document://`, or `sec://`
+    /// URI, normalizing the CIK to 10 digits and the accession number to its
+    /// dashed form
+    pub fn parse(uri: &str) -> Result<Self, String> {
+        let (scheme, rest) = uri
+            .split_once("://")
+            .ok_or_else(|| format!("Invalid resource URI '{}'", uri))?;
+        let parts: Vec<&str> = rest.trim_end_matches('/').split('/').collect();
+        if scheme == "edgar-index" {
+            return match parts.as_slice() {
+                [cik] if !cik.is_empty() => Ok(Self::Index { cik: normalize_cik(cik)? }),
+                _ => Err(format!("Expected {}, got '{}'", INDEX_URI_TEMPLATE, uri)),
+            };
+        }
+        let (cik, accession) = match parts.as_slice() {
+            [cik, accession, ..] => (normalize_cik(cik)?, normalize_accession(accession)?),
+            _ => return Err(format!("Resource URI '{}' is missing a CIK or accession number", uri)),
+        };
+        match (scheme, parts.len()) {
+            ("filing", 2) => Ok(Self::Filing { cik, accession }),
+            ("document" | "sec", 3) if !parts[2].is_empty() => Ok(Self::Document {
+                cik,
+                accession,
+                filename: parts[2].to_string(),
+            }),
+            ("filing", _) => Err(format!("Expected {}, got '{}'", FILING_URI_TEMPLATE, uri)),
+            ("document", _) => Err(format!("Expected {}, got '{}'", DOCUMENT_URI_TEMPLATE, uri)),
+            ("sec", _) => Err(format!("Expected {}, got '{}'", SEC_URI_TEMPLATE, uri)),
+            _ => Err(format!("Unsupported resource scheme '{}'", scheme)),
+        }
+    }
+}
+
+pub fn filing_uri(cik: &str, accession: &str) -> String {
+    format!("filing://{}/{}", cik, accession)
+}
+
+pub fn document_uri(cik: &str, accession: &str, filename: &str) -> String {
+    format!("document://{}/{}/{}", cik, accession, filename)
+}
+
+pub fn sec_uri(cik: &str, accession: &str, filename: &str) -> String {
+    format!("sec://{}/{}/{}", cik, accession, filename)
+}
+
+pub fn index_uri(cik: &str) -> String {
+    format!("edgar-index://{}", cik)
+}
+
+/// The `resources/templates/list` entries
+pub fn resource_templates() -> Value {
+    json!([
+        {
+            "uriTemplate": INDEX_URI_TEMPLATE,
+            "name": "EDGAR filing index",
+            "description": "A company's most recent SEC filings, each with a filing:// URI and a sec:// URI for its primary document. cik is the company's CIK.",
+            "mimeType": "application/json"
+        },
+        {
+            "uriTemplate": FILING_URI_TEMPLATE,
+            "name": "SEC filing",
+            "description": "Documents in an SEC filing, each with a document:// URI to read it. cik is the filer's CIK; accession is the accession number, e.g. 0000320193-24-000123.",
+            "mimeType": "application/json"
+        },
+        {
+            "uriTemplate": DOCUMENT_URI_TEMPLATE,
+            "name": "SEC filing document",
+            "description": "Text extracted from one document in an SEC filing (HTML, PDF, XML, or plain text). filename is the document's name in the filing index.",
+            "mimeType": "text/plain"
+        },
+        {
+            "uriTemplate": SEC_URI_TEMPLATE,
+            "name": "SEC EDGAR document",
+            "description": "Same as document://, with the path of the document in EDGAR's archive. Build it from the cik, accession_number, and primary_document of a filing returned by get_company_filings.",
+            "mimeType": "text/plain"
+        }
+    ])
+}
+
+/// Contents of a `filing://` resource: the filing index with a readable
+/// URI for each document
+pub fn filing_contents(cik: &str, accession: &str, documents: &[IndexDocument]) -> Value {
+    let documents: Vec<Value> = documents
+        .iter()
+        .map(|d| {
+            json!({
+                "uri": document_uri(cik, accession, &d.document),
+                "document": d.document,
+                "type": d.doc_type,
+                "category": exhibit_category(&d.doc_type),
+                "description": d.description,
+                "size": d.size,
+            })
+        })
+        .collect();
+    json!({
+        "uri": filing_uri(cik, accession),
+        "cik": cik,
+        "accession_number": accession,
+        "document_count": documents.len(),
+        "documents": documents,
+    })
+}
+
+/// Contents of an `edgar-index://` resource: the company's most recent
+/// filings from its EDGAR submissions, which list them as parallel arrays
+/// under `filings.recent`
+pub fn index_contents(cik: &str, submissions: &Value) -> Value {
+    let recent = &submissions["filings"]["recent"];
+    let column = |name: &str, i: usize| recent[name].get(i).and_then(|v| v.as_str()).filter(|s| !s.is_empty());
+    let count = recent["accessionNumber"].as_array().map_or(0, Vec::len);
+
+    let filings: Vec<Value> = (0..count.min(MAX_INDEX_FILINGS))
+        .filter_map(|i| {
+            let accession = column("accessionNumber", i)?;
+            let primary = column("primaryDocument", i);
+            Some(json!({
+                "accession_number": accession,
+                "form": column("form", i),
+                "filing_date": column("filingDate", i),
+                "report_date": column("reportDate", i),
+                "primary_document": primary,
+                "description": column("primaryDocDescription", i),
+                "uri": filing_uri(cik, accession),
+                "primary_document_uri": primary.map(|p| sec_uri(cik, accession, p)),
+            }))
+        })
+        .collect();
+    json!({
+        "uri": index_uri(cik),
+        "cik": cik,
+        "name": submissions.get("name"),
+        "filing_count": filings.len(),
+        "filings": filings,
+    })
+}
+
+/// Add resource URIs to each filing in a `get_company_filings` result:
+/// `resource_uri` for the filing, and `document_uri` for its primary
+/// document when the record names one. Filings without their own CIK use
+/// `cik`.
+pub fn annotate_filings(result: &mut Value, cik: Option<&str>) {
+    let filings = match result {
+        Value::Array(filings) => filings,
+        Value::Object(object) => match ["data", "filings", "results"]
+            .iter()
+            .find_map(|key| object.get(*key).filter(|v| v.is_array()).map(|_| *key))
+        {
+            Some(key) => object.get_mut(key).and_then(Value::as_array_mut).unwrap(),
+            None => return,
+        },
+        _ => return,
+    };
+
+    for filing in filings {
+        let Some(cik) = str_field(filing, &["cik"]).or(cik).and_then(|c| normalize_cik(c).ok()) else {
+            continue;
+        };
+        let Some(accession) = str_field(filing, &["accession_number", "accession_no", "accession"])
+            .and_then(|a| normalize_accession(a).ok())
+        else {
+            continue;
+        };
+        let document = str_field(filing, &["primary_document", "primary_doc"]).map(|d| sec_uri(&cik, &accession, d));
+        if let Some(object) = filing.as_object_mut() {
+            object.insert("resource_uri".to_string(), json!(filing_uri(&cik, &accession)));
+            if let Some(document) = document {
+                object.insert("document_uri".to_string(), json!(document));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filing_uri() {
+        assert_eq!(
+            ResourceUri::parse("filing://320193/000032019324000123").unwrap(),
+            ResourceUri::Filing {
+                cik: "0000320193".to_string(),
+                accession: "0000320193-24-000123".to_string(),
+            }
+        );
+        assert!(ResourceUri::parse("filing://320193/0000320193-24-000123/extra.htm").is_err());
+    }
+
+    #[test]
+    fn test_parse_document_uri() {
+        assert_eq!(
+            ResourceUri::parse("document://0000320193/0000320193-24-000123/aapl-20240928.htm").unwrap(),
+            ResourceUri::Document {
+                cik: "0000320193".to_string(),
+                accession: "0000320193-24-000123".to_string(),
+                filename: "aapl-20240928.htm".to_string(),
+            }
+        );
+        assert_eq!(
+            ResourceUri::parse("sec://320193/000032019324000123/aapl-20240928.htm").unwrap(),
+            ResourceUri::parse("document://0000320193/0000320193-24-000123/aapl-20240928.htm").unwrap()
+        );
+        assert!(ResourceUri::parse("document://320193/0000320193-24-000123").is_err());
+        assert!(ResourceUri::parse("sec://320193/0000320193-24-000123").is_err());
+        assert!(ResourceUri::parse("document://AAPL/0000320193-24-000123/a.htm").is_err());
+        assert!(ResourceUri::parse("https://www.sec.gov/Archives").is_err());
+        assert!(ResourceUri::parse("not a uri").is_err());
+    }
+
+    #[test]
+    fn test_filing_contents() {
+        let documents = vec![IndexDocument {
+            seq: Some(2),
+            description: Some("Press release".to_string()),
+            document: "ex99-1.htm".to_string(),
+            doc_type: "EX-99.1".to_string(),
+            size: Some(1024),
+        }];
+        let contents = filing_contents("0000320193", "0000320193-24-000123", &documents);
+        assert_eq!(contents["document_count"], 1);
+        assert_eq!(
+            contents["documents"][0]["uri"],
+            "document://0000320193/0000320193-24-000123/ex99-1.htm"
+        );
+        assert_eq!(
+            ResourceUri::parse(contents["documents"][0]["uri"].as_str().unwrap()).unwrap(),
+            ResourceUri::Document {
+                cik: "0000320193".to_string(),
+                accession: "0000320193-24-000123".to_string(),
+                filename: "ex99-1.htm".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_index_uri() {
+        assert_eq!(
+            ResourceUri::parse("edgar-index://320193").unwrap(),
+            ResourceUri::Index { cik: "0000320193".to_string() }
+        );
+        assert!(ResourceUri::parse("edgar-index://").is_err());
+        assert!(ResourceUri::parse("edgar-index://320193/0000320193-24-000123").is_err());
+    }
+
+    #[test]
+    fn test_index_contents() {
+        let submissions = json!({
+            "cik": "320193",
+            "name": "Apple Inc.",
+            "filings": {"recent": {
+                "accessionNumber": ["0000320193-24-000123", "0000320193-24-000120"],
+                "form": ["10-K", "8-K"],
+                "filingDate": ["2024-11-01", "2024-10-31"],
+                "reportDate": ["2024-09-28", ""],
+                "primaryDocument": ["aapl-20240928.htm", ""],
+                "primaryDocDescription": ["10-K", "8-K"]
+            }}
+        });
+        let contents = index_contents("0000320193", &submissions);
+        assert_eq!(contents["uri"], "edgar-index://0000320193");
+        assert_eq!(contents["filing_count"], 2);
+        let filing = &contents["filings"][0];
+        assert_eq!(filing["uri"], "filing://0000320193/0000320193-24-000123");
+        assert_eq!(
+            filing["primary_document_uri"],
+            "sec://0000320193/0000320193-24-000123/aapl-20240928.htm"
+        );
+        assert!(ResourceUri::parse(filing["primary_document_uri"].as_str().unwrap()).is_ok());
+        assert_eq!(contents["filings"][1]["report_date"], Value::Null);
+        assert_eq!(contents["filings"][1]["primary_document_uri"], Value::Null);
+    }
+
+    #[test]
+    fn test_annotate_filings() {
+        let mut result = json!({
+            "data": [
+                {"attributes": {"accession_number": "000032019324000123", "primary_document": "aapl-20240928.htm"}},
+                {"cik": "789019", "accession_number": "0000950170-24-087843"},
+                {"form_type": "4"}
+            ]
+        });
+        annotate_filings(&mut result, Some("320193"));
+        assert_eq!(result["data"][0]["resource_uri"], "filing://0000320193/0000320193-24-000123");
+        assert_eq!(
+            result["data"][0]["document_uri"],
+            "sec://0000320193/0000320193-24-000123/aapl-20240928.htm"
+        );
+        assert_eq!(result["data"][1]["resource_uri"], "filing://0000789019/0000950170-24-087843");
+        assert!(result["data"][1].get("document_uri").is_none());
+        assert!(result["data"][2].get("resource_uri").is_none());
+    }
+}
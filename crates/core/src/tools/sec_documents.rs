@@ -0,0 +1,449 @@
+//! SEC document helpers: picking a filing, extracting its text, and
+//! locating a section within it.
+//!
+//! Operating company filings (10-K, 10-Q, 8-K) and fund shareholder reports
+//! (N-CSR) are organized by "Item" headings (e.g. "Item 1A. Risk Factors",
+//! "Item 2.02"). Fund prospectuses (485BPOS, 497, N-1A) use titled sections
+//! instead ("Fees and Expenses of the Fund", "Principal Risks"), so those are
+//! found from a list of standard N-1A headings. A table of contents repeats
+//! the same headings, so the longest matching span is taken as the section.
+
+use super::fields::{records, str_field};
+use crate::sec_client::ContentType;
+use crate::text_extraction::{
+    extract_text_from_html, extract_text_from_pdf, extract_text_from_xml, is_pdf, ExtractionError,
+};
+use serde_json::Value;
+
+/// Common names for 10-K / 10-Q sections that don't appear verbatim in
+/// their headings
+const SECTION_ALIASES: &[(&str, &str)] = &[
+    ("mda", "managements discussion"),
+    ("md and a", "managements discussion"),
+    ("risks", "risk factors"),
+    ("legal", "legal proceedings"),
+    ("financials", "financial statements"),
+    ("controls", "controls and procedures"),
+    ("market risk", "quantitative and qualitative"),
+];
+
+/// Common names for fund document sections
+const FUND_SECTION_ALIASES: &[(&str, &str)] = &[
+    ("fee table", "fees and expenses"),
+    ("fees", "fees and expenses"),
+    ("expenses", "annual fund operating expenses"),
+    ("expense ratio", "annual fund operating expenses"),
+    ("objective", "investment objective"),
+    ("strategy", "principal investment strategies"),
+    ("strategies", "principal investment strategies"),
+    ("risks", "risk"),
+    ("turnover", "portfolio turnover"),
+    ("managers", "portfolio managers"),
+    ("adviser", "investment adviser"),
+    ("purchases", "purchase and sale of fund shares"),
+    ("redemptions", "purchase and sale of fund shares"),
+    ("taxes", "tax information"),
+    ("sai", "statement of additional information"),
+    ("shareholder report", "reports to stockholders"),
+    ("holdings", "schedule of investments"),
+];
+
+/// Standard section titles in N-1A prospectuses and N-CSR shareholder
+/// reports, matched at the start of a heading line
+const FUND_HEADINGS: &[&str] = &[
+    "investment objective",
+    "fees and expenses",
+    "annual fund operating expenses",
+    "portfolio turnover",
+    "principal investment strategies",
+    "principal investment risks",
+    "principal risks",
+    "performance",
+    "investment adviser",
+    "portfolio managers",
+    "purchase and sale of fund shares",
+    "tax information",
+    "payments to broker dealers and other financial intermediaries",
+    "financial highlights",
+    "statement of additional information",
+    "schedule of investments",
+    "statement of assets and liabilities",
+    "statement of operations",
+    "statements of changes in net assets",
+    "notes to financial statements",
+    "report of independent registered public accounting firm",
+    "managements discussion of fund performance",
+];
+
+/// Titled headings are short; longer lines starting with a title are prose
+const MAX_TITLED_HEADING_CHARS: usize = 80;
+
+/// How a document is divided into sections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    /// 10-K, 10-Q, 8-K, 20-F and similar: "Item" headings
+    Periodic,
+    /// N-CSR / N-CSRS shareholder reports: "Item" headings around titled
+    /// financial statements
+    FundReport,
+    /// 485APOS / 485BPOS / 497 / N-1A prospectuses and SAIs: titled headings
+    Prospectus,
+}
+
+impl DocumentKind {
+    pub fn from_form(form_type: &str) -> Self {
+        let form = form_type.trim().to_uppercase();
+        if form.starts_with("N-CSR") {
+            Self::FundReport
+        } else if form.starts_with("485") || form.starts_with("497") || form.starts_with("N-1A") {
+            Self::Prospectus
+        } else {
+            Self::Periodic
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Periodic => "periodic_report",
+            Self::FundReport => "fund_shareholder_report",
+            Self::Prospectus => "fund_prospectus",
+        }
+    }
+
+    fn uses_items(&self) -> bool {
+        matches!(self, Self::Periodic | Self::FundReport)
+    }
+
+    fn uses_titles(&self) -> bool {
+        matches!(self, Self::FundReport | Self::Prospectus)
+    }
+
+    fn aliases(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Periodic => SECTION_ALIASES,
+            Self::FundReport | Self::Prospectus => FUND_SECTION_ALIASES,
+        }
+    }
+}
+
+/// A section of a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// The heading line the section starts with
+    pub heading: &'a str,
+    /// The section text, including its heading
+    pub text: &'a str,
+}
+
+/// Pick the most recent filing of `form_type` from a filings list response.
+///
+/// Amendments (e.g. 10-K/A) only match when asked for explicitly.
+pub fn latest_filing<'a>(response: &'a Value, form_type: &str) -> Option<&'a Value> {
+    records(response, &["data", "filings", "results"])
+        .iter()
+        .filter(|filing| {
+            str_field(filing, &["form_type", "form"])
+                .is_none_or(|form| form.trim().eq_ignore_ascii_case(form_type.trim()))
+        })
+        .max_by_key(|filing| super::company::filing_date(filing).unwrap_or(""))
+}
+
+/// Accession number of a filing record
+pub fn accession_number(filing: &Value) -> Option<&str> {
+    str_field(filing, &["accession_number", "accession_no", "accessionNumber"])
+}
+
+/// CIK of the company a filing record belongs to
+pub fn filer_cik(filing: &Value) -> Option<&str> {
+    str_field(filing, &["cik", "company_cik"])
+}
+
+/// Primary document filename of a filing record, if known
+pub fn primary_document(filing: &Value) -> Option<&str> {
+    str_field(filing, &["primary_document", "primary_doc", "primaryDocument"])
+        .filter(|name| !name.is_empty())
+}
+
+/// Extract readable text from a fetched document
+pub fn extract_document_text(bytes: &[u8], content_type: &ContentType) -> Result<String, ExtractionError> {
+    match content_type {
+        ContentType::Pdf => extract_text_from_pdf(bytes),
+        ContentType::Html => extract_text_from_html(&String::from_utf8_lossy(bytes)),
+        ContentType::Xml => extract_text_from_xml(&String::from_utf8_lossy(bytes)),
+        ContentType::Text | ContentType::Unknown => {
+            if is_pdf(bytes) {
+                return extract_text_from_pdf(bytes);
+            }
+            // Full submission .txt files wrap the HTML documents in SGML
+            let text = String::from_utf8_lossy(bytes);
+            if text.contains("<html") || text.contains("<HTML") {
+                extract_text_from_html(&text)
+            } else {
+                Ok(text.into_owned())
+            }
+        }
+    }
+}
+
+/// Lowercase, drop punctuation, and collapse whitespace, so "Management's
+/// Discussion & Analysis" matches "managements discussion and analysis"
+fn normalize(text: &str) -> String {
+    let text = text.to_lowercase().replace('&', " and ");
+    let kept: String = text
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace() || *c == '.')
+        .collect();
+    kept.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The item id of an "Item ..." heading line ("1a", "7", "2.02")
+fn heading_item(line: &str) -> Option<String> {
+    let normalized = normalize(line);
+    let rest = normalized.strip_prefix("item ")?;
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+    // Digits, then an optional ".NN" (8-K items), then an optional letter
+    let mut len = digits(rest);
+    if len == 0 {
+        return None;
+    }
+    if let Some(after_dot) = rest[len..].strip_prefix('.') {
+        let decimals = digits(after_dot);
+        if decimals > 0 {
+            len += 1 + decimals;
+        }
+    }
+    if rest[len..].starts_with(|c: char| c.is_ascii_lowercase())
+        && !rest[len + 1..].starts_with(|c: char| c.is_alphanumeric())
+    {
+        len += 1;
+    }
+    Some(rest[..len].to_string())
+}
+
+/// The standard fund section title a heading line starts with
+fn heading_title(line: &str) -> Option<&'static str> {
+    if line.chars().count() > MAX_TITLED_HEADING_CHARS {
+        return None;
+    }
+    let normalized = normalize(line);
+    FUND_HEADINGS.iter().copied().find(|title| normalized.starts_with(title))
+}
+
+/// Heading lines with their byte offsets and item id or standard title.
+/// Table rows (fee tables, tables of contents) are never headings.
+fn headings(text: &str, kind: DocumentKind) -> Vec<(usize, &str, String)> {
+    let mut offset = 0;
+    let mut found = Vec::new();
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        let key = if trimmed.starts_with('|') {
+            None
+        } else {
+            kind.uses_items()
+                .then(|| heading_item(trimmed))
+                .flatten()
+                .or_else(|| kind.uses_titles().then(|| heading_title(trimmed)).flatten().map(str::to_string))
+        };
+        if let Some(key) = key {
+            found.push((offset + (line.len() - line.trim_start().len()), trimmed, key));
+        }
+        offset += line.len();
+    }
+    found
+}
+
+/// The distinct section headings in a document, for telling the caller
+/// what sections are available
+pub fn section_headings(text: &str, kind: DocumentKind) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    headings(text, kind)
+        .into_iter()
+        .filter(|(_, _, key)| seen.insert(key.clone()))
+        .map(|(_, heading, _)| heading.to_string())
+        .collect()
+}
+
+/// Find a section by item ("Item 1A", "1A", "2.02") or by title ("Risk
+/// Factors", "MD&A", "fee table"). Returns the longest match, which skips
+/// table of contents entries.
+pub fn find_section<'a>(text: &'a str, kind: DocumentKind, query: &str) -> Option<Section<'a>> {
+    let query = normalize(query);
+    let query = query.strip_prefix("item ").unwrap_or(&query).trim_end_matches('.');
+    if query.is_empty() {
+        return None;
+    }
+    let by_item = query.starts_with(|c: char| c.is_ascii_digit()) && !query.contains(' ');
+    let title = kind
+        .aliases()
+        .iter()
+        .find(|(alias, _)| *alias == query)
+        .map(|(_, title)| *title)
+        .unwrap_or(query);
+
+    let headings = headings(text, kind);
+    headings
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, heading, key))| {
+            if by_item {
+                key == query
+            } else {
+                normalize(heading).contains(title)
+            }
+        })
+        .map(|(i, (start, heading, _))| {
+            let end = headings.get(i + 1).map(|(next, _, _)| *next).unwrap_or(text.len());
+            Section {
+                heading,
+                text: text[*start..end].trim_end(),
+            }
+        })
+        .max_by_key(|section| section.text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TEN_K: &str = "Table of Contents\n\
+        Item 1. Business 3\n\
+        Item 1A. Risk Factors 10\n\
+        Item 7. Management's Discussion and Analysis 40\n\
+        PART I\n\
+        Item 1. Business\n\
+        We make things.\n\
+        Item 1A. Risk Factors\n\
+        Our business is risky. Supply chains may fail.\n\
+        Competition is intense.\n\
+        Item 1B. Unresolved Staff Comments\n\
+        None.\n\
+        Item 7. Management's Discussion and Analysis of Financial Condition\n\
+        Revenue grew.\n";
+
+    #[test]
+    fn test_find_section_by_item_skips_toc() {
+        let section = find_section(TEN_K, DocumentKind::Periodic, "Item 1A").unwrap();
+        assert_eq!(section.heading, "Item 1A. Risk Factors");
+        assert!(section.text.contains("Competition is intense."));
+        assert!(!section.text.contains("Unresolved"));
+
+        assert_eq!(find_section(TEN_K, DocumentKind::Periodic, "1a").unwrap(), section);
+        assert!(find_section(TEN_K, DocumentKind::Periodic, "Item 9").is_none());
+    }
+
+    #[test]
+    fn test_find_section_by_title_and_alias() {
+        let section = find_section(TEN_K, DocumentKind::Periodic, "risk factors").unwrap();
+        assert!(section.text.starts_with("Item 1A. Risk Factors\nOur business"));
+
+        let section = find_section(TEN_K, DocumentKind::Periodic, "MD&A").unwrap();
+        assert!(section.text.ends_with("Revenue grew."));
+    }
+
+    #[test]
+    fn test_find_section_8k_items() {
+        let text = "Item 2.02 Results of Operations\nEarnings.\nItem 9.01 Financial Statements and Exhibits\n99.1";
+        let section = find_section(text, DocumentKind::Periodic, "2.02").unwrap();
+        assert_eq!(section.text, "Item 2.02 Results of Operations\nEarnings.");
+    }
+
+    #[test]
+    fn test_section_headings() {
+        let headings = section_headings(TEN_K, DocumentKind::Periodic);
+        assert_eq!(headings.len(), 4);
+        assert_eq!(headings[0], "Item 1. Business 3");
+        // Prose that merely mentions an item isn't a heading
+        assert!(section_headings("See Item 7 for details\nItems in stock", DocumentKind::Periodic).is_empty());
+    }
+
+    #[test]
+    fn test_latest_filing() {
+        let response = json!({"data": [
+            {"form_type": "10-K", "filing_date": "2023-11-03", "accession_number": "0000320193-23-000106"},
+            {"form_type": "10-K/A", "filing_date": "2024-01-10"},
+            {"attributes": {"form_type": "10-K", "filing_date": "2024-11-01", "accession_number": "0000320193-24-000123", "primary_document": "aapl-20240928.htm"}},
+        ]});
+
+        let filing = latest_filing(&response, "10-k").unwrap();
+        assert_eq!(accession_number(filing), Some("0000320193-24-000123"));
+        assert_eq!(primary_document(filing), Some("aapl-20240928.htm"));
+        assert_eq!(filer_cik(filing), None);
+
+        let amendment = latest_filing(&response, "10-K/A").unwrap();
+        assert_eq!(primary_document(amendment), None);
+        assert!(latest_filing(&response, "8-K").is_none());
+    }
+
+    #[test]
+    fn test_extract_document_text() {
+        let html = b"<html><body><p>Item 1A. Risk Factors</p><p>Risky.</p></body></html>";
+        let text = extract_document_text(html, &ContentType::Html).unwrap();
+        assert_eq!(find_section(&text, DocumentKind::Periodic, "1A").unwrap().heading, "Item 1A. Risk Factors");
+
+        let plain = extract_document_text(b"plain text", &ContentType::Text).unwrap();
+        assert_eq!(plain, "plain text");
+    }
+
+    const PROSPECTUS: &str = "Table of Contents\n\
+        | Fees and Expenses of the Fund | 2 |\n\
+        | Principal Risks | 4 |\n\
+        Investment Objective\n\
+        The Fund seeks to track the S&P 500.\n\
+        Fees and Expenses of the Fund\n\
+        This table describes the fees you may pay.\n\
+        Annual Fund Operating Expenses\n\
+        | Management Fees | 0.03% |\n\
+        | Total Annual Fund Operating Expenses | 0.03% |\n\
+        Principal Investment Strategies\n\
+        The Fund invests in stocks.\n\
+        Principal Risks\n\
+        Stock market risk. Performance may lag the index because the fund has costs.\n\
+        Performance\n\
+        The bar chart shows returns.\n";
+
+    #[test]
+    fn test_document_kind_from_form() {
+        assert_eq!(DocumentKind::from_form("10-K"), DocumentKind::Periodic);
+        assert_eq!(DocumentKind::from_form("n-csrs"), DocumentKind::FundReport);
+        assert_eq!(DocumentKind::from_form("485BPOS"), DocumentKind::Prospectus);
+        assert_eq!(DocumentKind::from_form("497K"), DocumentKind::Prospectus);
+    }
+
+    #[test]
+    fn test_prospectus_sections() {
+        let kind = DocumentKind::Prospectus;
+        let fees = find_section(PROSPECTUS, kind, "fees and expenses").unwrap();
+        assert_eq!(fees.heading, "Fees and Expenses of the Fund");
+        assert_eq!(fees.text, "Fees and Expenses of the Fund\nThis table describes the fees you may pay.");
+
+        let expenses = find_section(PROSPECTUS, kind, "expense ratio").unwrap();
+        assert!(expenses.text.ends_with("| Total Annual Fund Operating Expenses | 0.03% |"));
+
+        let risks = find_section(PROSPECTUS, kind, "risks").unwrap();
+        assert!(risks.text.starts_with("Principal Risks\nStock market risk."));
+
+        assert_eq!(section_headings(PROSPECTUS, kind).len(), 6);
+        // Prospectuses don't use Item headings
+        assert!(find_section(PROSPECTUS, kind, "Item 1").is_none());
+    }
+
+    #[test]
+    fn test_fund_report_sections() {
+        let report = "Item 1. Reports to Stockholders\n\
+            Schedule of Investments\n\
+            | Apple Inc | 7.1% |\n\
+            Financial Highlights\n\
+            Net asset value, end of period 450.12\n\
+            Item 2. Code of Ethics\n\
+            Adopted.\n";
+        let kind = DocumentKind::FundReport;
+        assert!(find_section(report, kind, "holdings").unwrap().text.contains("Apple Inc"));
+        assert_eq!(
+            find_section(report, kind, "financial highlights").unwrap().text,
+            "Financial Highlights\nNet asset value, end of period 450.12"
+        );
+        assert_eq!(find_section(report, kind, "Item 2").unwrap().heading, "Item 2. Code of Ethics");
+    }
+}
@@ -0,0 +1,592 @@
+//! Watchlist helpers.
+//!
+//! Builds the per-item enrichment (latest filing, next expected report,
+//! recent insider activity) that `get_list` attaches when `enrich` is set,
+//! the upcoming 10-Q/10-K calendar across a watchlist's companies, and the
+//! changes needed to restore a list from a saved snapshot.
+
+use super::company::filing_date;
+use super::fields::{records, str_field};
+use super::institutional::decode_form4_transactions;
+use crate::error::CoreError;
+use crate::models::parse_date;
+use chrono::{Duration, NaiveDate};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// How far back to look for insider activity when enriching items
+pub const INSIDER_LOOKBACK_DAYS: i64 = 90;
+
+/// Find the items array in a `get_list` response
+pub fn list_items_mut(list: &mut Value) -> Option<&mut Vec<Value>> {
+    if list.get("items").is_some_and(|v| v.is_array()) {
+        return list.get_mut("items").and_then(|v| v.as_array_mut());
+    }
+    if list.pointer("/data/items").is_some_and(|v| v.is_array()) {
+        return list.pointer_mut("/data/items").and_then(|v| v.as_array_mut());
+    }
+    None
+}
+
+/// CIK of a watchlist item, if it refers to a company or filer
+pub fn item_cik(item: &Value) -> Option<String> {
+    str_field(item, &["cik", "company_cik", "filer_cik"])
+        .map(|s| s.to_string())
+        .or_else(|| {
+            item.get("cik")
+                .and_then(|v| v.as_u64())
+                .map(|n| format!("{:010}", n))
+        })
+}
+
+/// Display name of a watchlist item
+pub fn item_name(item: &Value) -> Option<String> {
+    str_field(item, &["name", "company_name", "title", "ticker", "symbol"]).map(str::to_string)
+}
+
+/// Summarize the most recent filing from a filings response
+pub fn latest_filing_summary(filings: &Value) -> Value {
+    match records(filings, &["data", "filings", "results"]).first() {
+        Some(filing) => json!({
+            "filing_date": str_field(filing, &["filing_date", "filed_at"]),
+            "form_type": str_field(filing, &["form_type", "form"]),
+            "accession_number": str_field(filing, &["accession_number"])
+        }),
+        None => Value::Null,
+    }
+}
+
+/// Find the earliest date on or after `today` anywhere in a fiscal calendar
+/// response, used as the next expected reporting date.
+pub fn next_report_date(calendar: &Value, today: NaiveDate) -> Option<NaiveDate> {
+    fn walk(value: &Value, today: NaiveDate, best: &mut Option<NaiveDate>) {
+        match value {
+            Value::String(s) => {
+                if let Some(date) = parse_date(s) {
+                    if date >= today && !matches!(best, Some(b) if *b <= date) {
+                        *best = Some(date);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|v| walk(v, today, best)),
+            Value::Object(obj) => obj.values().for_each(|v| walk(v, today, best)),
+            _ => {}
+        }
+    }
+
+    let mut best = None;
+    walk(calendar, today, &mut best);
+    best
+}
+
+/// Summarize recent Form 4 activity as filing count plus per-code totals
+pub fn insider_activity_summary(form4: Value) -> Value {
+    let filing_count = records(&form4, &["data", "filings", "results"]).len();
+    let decoded = decode_form4_transactions(form4);
+    json!({
+        "lookback_days": INSIDER_LOOKBACK_DAYS,
+        "filing_count": filing_count,
+        "transactions": decoded["transaction_summary"]
+    })
+}
+
+/// Default number of weeks ahead to list upcoming reports for
+pub const DEFAULT_UPCOMING_WEEKS: i64 = 8;
+
+/// Maximum number of weeks ahead to list upcoming reports for
+pub const MAX_UPCOMING_WEEKS: i64 = 26;
+
+/// Filing history fetched per company to project its reporting cadence:
+/// a year plus the longest gap between periodic reports
+pub const CADENCE_LOOKBACK_DAYS: i64 = 400;
+
+/// Forms whose filing dates predict the next periodic report
+const PERIODIC_FORMS: &[&str] = &["10-K", "10-Q", "20-F", "40-F"];
+
+/// A report is filed on roughly the same date each year; 52 weeks keeps
+/// the weekday
+const CADENCE_DAYS: i64 = 364;
+
+/// A projected report counts as already filed if the same form was filed
+/// within this many days before the projection
+const EARLY_FILING_DAYS: i64 = 45;
+
+/// Calendar dates and cadence projections this close are the same report
+const SAME_REPORT_DAYS: i64 = 21;
+
+/// Calendar fields whose dates are reporting dates rather than period ends
+const CALENDAR_REPORT_KEYS: &[&str] = &["filing", "report", "earnings", "due"];
+
+/// One watchlist company's filing history and fiscal calendar responses
+#[derive(Debug)]
+pub struct CompanyReports {
+    pub cik: String,
+    pub name: Option<String>,
+    pub filings: Result<Value, CoreError>,
+    pub calendar: Result<Value, CoreError>,
+}
+
+/// An expected periodic report
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ExpectedReport {
+    form_type: Option<String>,
+    date: NaiveDate,
+    /// What the prediction is based on: "fiscal_calendar", "filing_cadence"
+    basis: Vec<&'static str>,
+    /// The year-earlier filing a cadence projection came from
+    prior_filing: Option<NaiveDate>,
+}
+
+/// Periodic filings in a filings response as (form, date)
+fn periodic_filings(filings: &Value) -> Vec<(String, NaiveDate)> {
+    records(filings, &["data", "filings", "results"])
+        .iter()
+        .filter_map(|filing| {
+            let form = str_field(filing, &["form_type", "form"])?.trim().to_uppercase();
+            PERIODIC_FORMS.contains(&form.as_str()).then_some(())?;
+            Some((form, parse_date(filing_date(filing)?)?))
+        })
+        .collect()
+}
+
+/// Project each periodic filing a year ahead, keeping projections in
+/// `[from, to]` that haven't already been filed early
+fn cadence_projections(filings: &Value, from: NaiveDate, to: NaiveDate) -> Vec<ExpectedReport> {
+    let history = periodic_filings(filings);
+    let mut projections: Vec<ExpectedReport> = history
+        .iter()
+        .filter_map(|(form, filed)| {
+            let date = *filed + Duration::days(CADENCE_DAYS);
+            let filed_early = history
+                .iter()
+                .any(|(f, d)| f == form && *d >= date - Duration::days(EARLY_FILING_DAYS) && *d < date);
+            (date >= from && date <= to && !filed_early).then(|| ExpectedReport {
+                form_type: Some(form.clone()),
+                date,
+                basis: vec!["filing_cadence"],
+                prior_filing: Some(*filed),
+            })
+        })
+        .collect();
+    projections.sort_by_key(|p| p.date);
+    projections.dedup_by(|a, b| a.form_type == b.form_type && (a.date - b.date).num_days().abs() <= SAME_REPORT_DAYS);
+    projections
+}
+
+/// Reporting dates in `[from, to]` from a fiscal calendar response: dates
+/// under keys like "expected_filing" or "next_report_date"
+fn calendar_dates(calendar: &Value, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    fn walk(value: &Value, reporting: bool, from: NaiveDate, to: NaiveDate, dates: &mut Vec<NaiveDate>) {
+        match value {
+            Value::String(s) if reporting => {
+                if let Some(date) = parse_date(s).filter(|d| *d >= from && *d <= to) {
+                    dates.push(date);
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|v| walk(v, reporting, from, to, dates)),
+            Value::Object(obj) => obj.iter().for_each(|(key, v)| {
+                let key = key.to_lowercase();
+                let reporting = reporting || CALENDAR_REPORT_KEYS.iter().any(|k| key.contains(k));
+                walk(v, reporting, from, to, dates)
+            }),
+            _ => {}
+        }
+    }
+
+    let mut dates = Vec::new();
+    walk(calendar, false, from, to, &mut dates);
+    dates.sort();
+    dates.dedup();
+    dates
+}
+
+/// Expected periodic reports in `[from, to]`, combining fiscal calendar
+/// dates with year-ago filing projections. A calendar date confirms a
+/// nearby projection rather than adding a second entry.
+fn expected_reports(filings: &Value, calendar: Option<&Value>, from: NaiveDate, to: NaiveDate) -> Vec<ExpectedReport> {
+    let mut reports = cadence_projections(filings, from, to);
+    for date in calendar.map(|c| calendar_dates(c, from, to)).unwrap_or_default() {
+        match reports
+            .iter_mut()
+            .filter(|r| (r.date - date).num_days().abs() <= SAME_REPORT_DAYS)
+            .min_by_key(|r| (r.date - date).num_days().abs())
+        {
+            Some(report) if !report.basis.contains(&"fiscal_calendar") => {
+                report.date = date;
+                report.basis.insert(0, "fiscal_calendar");
+            }
+            Some(_) => {}
+            None => reports.push(ExpectedReport {
+                form_type: None,
+                date,
+                basis: vec!["fiscal_calendar"],
+                prior_filing: None,
+            }),
+        }
+    }
+    reports
+}
+
+/// Upcoming 10-Q/10-K (and 20-F/40-F) reports for watchlist companies in
+/// `[from, to]`, sorted chronologically. Companies whose filing history
+/// couldn't be fetched are listed under `errors`.
+pub fn upcoming_reports(companies: Vec<CompanyReports>, from: NaiveDate, to: NaiveDate) -> Value {
+    let mut reports = Vec::new();
+    let mut errors = serde_json::Map::new();
+    let companies_checked = companies.len();
+
+    for company in companies {
+        let filings = match company.filings {
+            Ok(filings) => filings,
+            Err(e) => {
+                errors.insert(company.cik, e.to_json());
+                continue;
+            }
+        };
+        let calendar = company.calendar.ok();
+        for report in expected_reports(&filings, calendar.as_ref(), from, to) {
+            reports.push((report, company.cik.clone(), company.name.clone()));
+        }
+    }
+    reports.sort_by(|a, b| (a.0.date, &a.1).cmp(&(b.0.date, &b.1)));
+
+    let reports: Vec<Value> = reports
+        .into_iter()
+        .map(|(report, cik, name)| {
+            json!({
+                "expected_date": report.date.to_string(),
+                "days_away": (report.date - from).num_days(),
+                "cik": cik,
+                "name": name,
+                "form_type": report.form_type,
+                "basis": report.basis,
+                "prior_year_filing": report.prior_filing.map(|d| d.to_string()),
+            })
+        })
+        .collect();
+
+    let mut result = json!({
+        "window": { "from": from.to_string(), "to": to.to_string() },
+        "companies_checked": companies_checked,
+        "report_count": reports.len(),
+        "reports": reports,
+    });
+    if !errors.is_empty() {
+        result["errors"] = Value::Object(errors);
+    }
+    result
+}
+
+/// The list record in a `get_list` response, unwrapping `data`
+fn list_record(list: &Value) -> &Value {
+    list.get("data").filter(|v| v.is_object()).unwrap_or(list)
+}
+
+/// ID of a list or list item, which may be a number or a string
+fn record_id(record: &Value) -> Option<String> {
+    match record.get("id")? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// ID of the list in a `get_list` or `create_list` response
+pub fn list_id(list: &Value) -> Option<String> {
+    record_id(list_record(list))
+}
+
+/// Name of the list in a `get_list` response
+pub fn list_name(list: &Value) -> Option<String> {
+    str_field(list_record(list), &["name"]).map(str::to_string)
+}
+
+/// The list's name and notes, as an `update_list`/`create_list` body
+pub fn list_details(list: &Value) -> Value {
+    let record = list_record(list);
+    json!({
+        "name": str_field(record, &["name"]),
+        "notes": str_field(record, &["notes"]),
+    })
+}
+
+/// Items in a `get_list` response
+pub fn list_items(list: &Value) -> &[Value] {
+    list.get("items")
+        .or_else(|| list.pointer("/data/items"))
+        .and_then(|v| v.as_array())
+        .map(|v| v.as_slice())
+        .unwrap_or(&[])
+}
+
+/// What an item tracks, so the same security matches across snapshots even
+/// though item IDs change when it is re-added
+fn item_key(item: &Value) -> Option<String> {
+    if let Some(cik) = item_cik(item) {
+        return Some(format!("cik:{}", cik.trim_start_matches('0')));
+    }
+    let symbol = str_field(item, &["symbol", "ticker"])?;
+    let exchange = str_field(item, &["exchange"]).unwrap_or_default();
+    Some(format!("symbol:{}:{}", symbol.to_uppercase(), exchange.to_uppercase()))
+}
+
+/// Item calls that bring a list's current items back to a snapshot's
+#[derive(Debug, Default, PartialEq)]
+pub struct RestorePlan {
+    /// `add_list_item` bodies for items missing from the list
+    pub add: Vec<Value>,
+    /// IDs of items not in the snapshot
+    pub remove: Vec<String>,
+    /// (item ID, notes) for items whose notes changed
+    pub update_notes: Vec<(String, Option<String>)>,
+}
+
+impl RestorePlan {
+    /// The item calls the plan would make, for a dry run
+    pub fn changes(&self) -> Value {
+        let update_notes: Vec<Value> = self
+            .update_notes
+            .iter()
+            .map(|(id, notes)| json!({ "item_id": id, "notes": notes }))
+            .collect();
+        json!({ "add": self.add, "remove": self.remove, "update_notes": update_notes })
+    }
+}
+
+/// What a watchlist change would send, reported in place of sending it
+/// when the call is a dry run
+pub fn dry_run_preview(method: &str, endpoint: &str, body: Option<&Value>) -> Value {
+    json!({
+        "success": true,
+        "dry_run": true,
+        "message": format!("Dry run: would send {} {}; nothing was changed", method, endpoint),
+        "request": { "method": method, "endpoint": endpoint, "body": body },
+    })
+}
+
+/// Diff a snapshot's items against the list's current items. Items that
+/// identify neither a CIK nor a symbol can't be matched and are left alone.
+pub fn restore_plan(snapshot_items: &[Value], current_items: &[Value]) -> RestorePlan {
+    let mut current: HashMap<String, &Value> = current_items
+        .iter()
+        .filter_map(|item| Some((item_key(item)?, item)))
+        .collect();
+    let mut plan = RestorePlan::default();
+
+    for item in snapshot_items {
+        let Some(key) = item_key(item) else {
+            continue;
+        };
+        let notes = str_field(item, &["notes"]);
+        match current.remove(&key) {
+            Some(existing) => {
+                if str_field(existing, &["notes"]) != notes {
+                    if let Some(id) = record_id(existing) {
+                        plan.update_notes.push((id, notes.map(str::to_string)));
+                    }
+                }
+            }
+            None => {
+                let mut body = serde_json::Map::new();
+                for name in ["symbol", "exchange", "cik", "notes"] {
+                    if let Some(value) = str_field(item, &[name]) {
+                        body.insert(name.to_string(), json!(value));
+                    }
+                }
+                if !body.contains_key("cik") {
+                    if let Some(cik) = item_cik(item) {
+                        body.insert("cik".to_string(), json!(cik));
+                    }
+                }
+                plan.add.push(Value::Object(body));
+            }
+        }
+    }
+
+    let mut remove: Vec<String> = current.into_values().filter_map(record_id).collect();
+    remove.sort();
+    plan.remove = remove;
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_list_items_mut() {
+        let mut list = json!({"id": 1, "items": [{"cik": "1"}]});
+        assert_eq!(list_items_mut(&mut list).unwrap().len(), 1);
+
+        let mut list = json!({"data": {"items": [{}, {}]}});
+        assert_eq!(list_items_mut(&mut list).unwrap().len(), 2);
+
+        let mut list = json!({"id": 1});
+        assert!(list_items_mut(&mut list).is_none());
+    }
+
+    #[test]
+    fn test_list_fields() {
+        let list = json!({"data": {"id": 7, "name": "Tech", "notes": "core", "items": [{"cik": "1"}]}});
+        assert_eq!(list_id(&list).as_deref(), Some("7"));
+        assert_eq!(list_name(&list).as_deref(), Some("Tech"));
+        assert_eq!(list_details(&list), json!({"name": "Tech", "notes": "core"}));
+        assert_eq!(list_items(&list).len(), 1);
+        assert!(list_items(&json!({"id": "a"})).is_empty());
+    }
+
+    #[test]
+    fn test_restore_plan() {
+        let snapshot = vec![
+            json!({"id": 1, "cik": "0000320193", "notes": "hold"}),
+            json!({"id": 2, "symbol": "nvda", "exchange": "NASDAQ", "notes": "watch"}),
+            json!({"id": 3, "symbol": "TSLA"}),
+        ];
+        let current = vec![
+            json!({"id": 10, "cik": 320193, "notes": "sell"}),
+            json!({"id": 11, "symbol": "TSLA"}),
+            json!({"id": 12, "symbol": "GME"}),
+        ];
+        let plan = restore_plan(&snapshot, &current);
+        assert_eq!(plan.add, vec![json!({"symbol": "nvda", "exchange": "NASDAQ", "notes": "watch"})]);
+        assert_eq!(plan.remove, vec!["12".to_string()]);
+        assert_eq!(plan.update_notes, vec![("10".to_string(), Some("hold".to_string()))]);
+
+        assert_eq!(restore_plan(&snapshot, &snapshot), RestorePlan::default());
+    }
+
+    #[test]
+    fn test_item_cik() {
+        assert_eq!(item_cik(&json!({"cik": "0000320193"})), Some("0000320193".to_string()));
+        assert_eq!(item_cik(&json!({"cik": 320193})), Some("0000320193".to_string()));
+        assert_eq!(item_cik(&json!({"symbol": "AAPL"})), None);
+    }
+
+    #[test]
+    fn test_latest_filing_summary() {
+        let filings = json!({"data": [
+            {"filing_date": "2024-02-01", "form_type": "10-Q", "accession_number": "0000320193-24-000006"}
+        ]});
+        let summary = latest_filing_summary(&filings);
+        assert_eq!(summary["filing_date"], "2024-02-01");
+        assert_eq!(summary["form_type"], "10-Q");
+        assert!(latest_filing_summary(&json!({"data": []})).is_null());
+    }
+
+    #[test]
+    fn test_next_report_date() {
+        let calendar = json!({
+            "fiscal_year_end": "2023-09-30",
+            "quarters": [
+                {"period_end": "2024-03-30", "expected_filing": "2024-05-03T00:00:00Z"},
+                {"period_end": "2024-06-29"}
+            ]
+        });
+        assert_eq!(
+            next_report_date(&calendar, date("2024-04-01")),
+            Some(date("2024-05-03"))
+        );
+        assert_eq!(next_report_date(&calendar, date("2025-01-01")), None);
+    }
+
+    #[test]
+    fn test_insider_activity_summary() {
+        let form4 = json!({"data": [
+            {"transactions": [{"transaction_code": "S"}, {"transaction_code": "S"}]}
+        ]});
+        let summary = insider_activity_summary(form4);
+        assert_eq!(summary["filing_count"], 1);
+        assert_eq!(summary["transactions"][0]["code"], "S");
+        assert_eq!(summary["transactions"][0]["count"], 2);
+    }
+
+    #[test]
+    fn test_cadence_projections() {
+        let filings = json!({"data": [
+            {"form_type": "10-Q", "filing_date": "2023-05-05"},
+            {"form_type": "10-Q", "filing_date": "2023-08-04"},
+            {"form_type": "10-K", "filing_date": "2023-11-03"},
+            {"form_type": "8-K", "filing_date": "2023-05-04"},
+            // This year's Q2 10-Q came in early
+            {"form_type": "10-Q", "filing_date": "2024-07-26"}
+        ]});
+        let projections = cadence_projections(&filings, date("2024-05-01"), date("2024-11-30"));
+        let found: Vec<(Option<&str>, String)> = projections
+            .iter()
+            .map(|p| (p.form_type.as_deref(), p.date.to_string()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![(Some("10-Q"), "2024-05-03".to_string()), (Some("10-K"), "2024-11-01".to_string())]
+        );
+        assert_eq!(projections[0].prior_filing, Some(date("2023-05-05")));
+    }
+
+    #[test]
+    fn test_calendar_dates_skip_period_ends() {
+        let calendar = json!({
+            "fiscal_year_end": "2024-09-28",
+            "quarters": [{"period_end": "2024-06-29", "expected_filing": "2024-08-02"}],
+            "next_earnings": {"date": "2024-07-30"}
+        });
+        assert_eq!(
+            calendar_dates(&calendar, date("2024-06-01"), date("2024-12-31")),
+            vec![date("2024-07-30"), date("2024-08-02")]
+        );
+    }
+
+    #[test]
+    fn test_upcoming_reports_merges_and_sorts() {
+        let apple = CompanyReports {
+            cik: "0000320193".to_string(),
+            name: Some("Apple Inc.".to_string()),
+            filings: Ok(json!({"data": [{"form_type": "10-Q", "filing_date": "2023-08-04"}]})),
+            calendar: Ok(json!({"quarters": [{"expected_filing": "2024-08-01"}]})),
+        };
+        let msft = CompanyReports {
+            cik: "0000789019".to_string(),
+            name: None,
+            filings: Ok(json!({"data": [{"form_type": "10-K", "filing_date": "2023-07-27"}]})),
+            calendar: Err(CoreError::from(crate::api_client::ApiError::NotFound)),
+        };
+        let broken = CompanyReports {
+            cik: "0000000001".to_string(),
+            name: None,
+            filings: Err(CoreError::from(crate::api_client::ApiError::NotFound)),
+            calendar: Ok(json!({})),
+        };
+
+        let result = upcoming_reports(vec![apple, msft, broken], date("2024-07-01"), date("2024-08-26"));
+        assert_eq!(result["companies_checked"], 3);
+        assert_eq!(result["report_count"], 2);
+        assert_eq!(result["reports"][0]["cik"], "0000789019");
+        assert_eq!(result["reports"][0]["expected_date"], "2024-07-25");
+        assert_eq!(result["reports"][1]["expected_date"], "2024-08-01");
+        assert_eq!(result["reports"][1]["form_type"], "10-Q");
+        assert_eq!(result["reports"][1]["basis"], json!(["fiscal_calendar", "filing_cadence"]));
+        assert!(result["errors"]["0000000001"].is_object());
+    }
+
+    #[test]
+    fn test_dry_run_preview() {
+        let body = json!({ "name": "Tech" });
+        let preview = dry_run_preview("POST", "lists", Some(&body));
+        assert_eq!(preview["dry_run"], true);
+        assert_eq!(preview["request"]["body"], body);
+        assert_eq!(preview["message"], "Dry run: would send POST lists; nothing was changed");
+
+        let plan = RestorePlan {
+            add: vec![json!({ "symbol": "AAPL" })],
+            remove: vec!["7".to_string()],
+            update_notes: vec![("3".to_string(), None)],
+        };
+        assert_eq!(
+            plan.changes(),
+            json!({ "add": [{ "symbol": "AAPL" }], "remove": ["7"], "update_notes": [{ "item_id": "3", "notes": null }] })
+        );
+    }
+}
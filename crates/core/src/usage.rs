@@ -0,0 +1,232 @@
+//! Local record of tool usage.
+//!
+//! Every tool call the MCP server finishes is counted in a small JSON file
+//! in the config directory: calls, failures by error code, time taken, and
+//! result size, per tool. Nothing leaves the machine; the server's
+//! `get_usage_stats` tool and the settings app read the file to show which
+//! tools get used and which fail most.
+
+use crate::config::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// File in the config dir that holds the usage counts
+const USAGE_FILE_NAME: &str = "usage.json";
+
+/// One finished tool call
+#[derive(Debug, Clone)]
+pub struct ToolCall<'a> {
+    pub tool: &'a str,
+    pub duration: Duration,
+    /// Error code of a failed call, None if it succeeded
+    pub error: Option<&'a str>,
+    pub result_bytes: usize,
+}
+
+/// Totals for one tool
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolUsage {
+    pub calls: u64,
+    pub errors: u64,
+    /// Failed calls by error code
+    pub error_codes: BTreeMap<String, u64>,
+    pub total_duration_ms: u64,
+    pub max_duration_ms: u64,
+    pub total_result_bytes: u64,
+    pub last_used: Option<String>,
+}
+
+impl ToolUsage {
+    fn add(&mut self, call: &ToolCall, at: &str) {
+        let ms = call.duration.as_millis() as u64;
+        self.calls += 1;
+        self.total_duration_ms += ms;
+        self.max_duration_ms = self.max_duration_ms.max(ms);
+        self.total_result_bytes += call.result_bytes as u64;
+        if let Some(code) = call.error {
+            self.errors += 1;
+            *self.error_codes.entry(code.to_string()).or_default() += 1;
+        }
+        self.last_used = Some(at.to_string());
+    }
+
+    fn summary(&self, tool: &str) -> Value {
+        let calls = self.calls.max(1);
+        json!({
+            "tool": tool,
+            "calls": self.calls,
+            "errors": self.errors,
+            "error_rate": (self.errors as f64 / calls as f64 * 1000.0).round() / 1000.0,
+            "error_codes": self.error_codes,
+            "avg_duration_ms": self.total_duration_ms / calls,
+            "max_duration_ms": self.max_duration_ms,
+            "avg_result_bytes": self.total_result_bytes / calls,
+            "last_used": self.last_used,
+        })
+    }
+}
+
+/// Everything in the usage file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageStats {
+    /// When counting started
+    pub since: Option<String>,
+    pub tools: BTreeMap<String, ToolUsage>,
+}
+
+impl UsageStats {
+    pub fn record(&mut self, call: &ToolCall, at: &str) {
+        self.since.get_or_insert_with(|| at.to_string());
+        self.tools.entry(call.tool.to_string()).or_default().add(call, at);
+    }
+
+    /// Report of the `limit` most used tools and the `limit` with the most
+    /// failures
+    pub fn report(&self, limit: usize) -> Value {
+        let mut by_calls: Vec<_> = self.tools.iter().collect();
+        by_calls.sort_by(|a, b| b.1.calls.cmp(&a.1.calls).then(a.0.cmp(b.0)));
+        let mut by_errors: Vec<_> = by_calls.iter().filter(|(_, u)| u.errors > 0).copied().collect();
+        by_errors.sort_by(|a, b| b.1.errors.cmp(&a.1.errors).then(a.0.cmp(b.0)));
+
+        json!({
+            "since": self.since,
+            "total_calls": self.tools.values().map(|u| u.calls).sum::<u64>(),
+            "total_errors": self.tools.values().map(|u| u.errors).sum::<u64>(),
+            "tools_used": self.tools.len(),
+            "most_used": by_calls.iter().take(limit).map(|(t, u)| u.summary(t)).collect::<Vec<_>>(),
+            "most_failing": by_errors.iter().take(limit).map(|(t, u)| u.summary(t)).collect::<Vec<_>>(),
+        })
+    }
+}
+
+pub struct UsageStore {
+    path: PathBuf,
+    /// Serializes read-modify-write of the file within this process
+    lock: Mutex<()>,
+}
+
+impl UsageStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// The usage file beside the config file at `config_path`
+    pub fn beside(config_path: &Path) -> Self {
+        Self::new(config_path.parent().unwrap_or(Path::new(".")).join(USAGE_FILE_NAME))
+    }
+
+    /// The usage file in the platform config directory
+    pub fn open_default() -> Result<Self, ConfigError> {
+        Ok(Self::beside(&Config::config_path()?))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Counts so far; empty if nothing has been recorded. An unreadable
+    /// file counts as empty so a damaged one is replaced on the next call.
+    pub fn load(&self) -> UsageStats {
+        fs::read(&self.path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Add a finished call to the file
+    pub fn record(&self, call: &ToolCall) -> io::Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats = self.load();
+        stats.record(call, &chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+        self.save(&stats)
+    }
+
+    /// Forget everything recorded
+    pub fn reset(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Write through a temporary file so a reader never sees half a file
+    fn save(&self, stats: &UsageStats) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(stats)?)?;
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn call<'a>(tool: &'a str, ms: u64, error: Option<&'a str>) -> ToolCall<'a> {
+        ToolCall {
+            tool,
+            duration: Duration::from_millis(ms),
+            error,
+            result_bytes: 100,
+        }
+    }
+
+    #[test]
+    fn test_record_and_report() {
+        let mut stats = UsageStats::default();
+        stats.record(&call("get_company_filings", 100, None), "t1");
+        stats.record(&call("get_company_filings", 300, Some("UPSTREAM_ERROR")), "t2");
+        stats.record(&call("get_etf_holdings", 50, None), "t3");
+        stats.record(&call("get_form4_filings", 10, Some("NOT_FOUND")), "t4");
+        stats.record(&call("get_form4_filings", 10, Some("NOT_FOUND")), "t5");
+
+        let filings = &stats.tools["get_company_filings"];
+        assert_eq!((filings.calls, filings.errors, filings.max_duration_ms), (2, 1, 300));
+        assert_eq!(filings.last_used.as_deref(), Some("t2"));
+
+        let report = stats.report(2);
+        assert_eq!(report["since"], "t1");
+        assert_eq!(report["total_calls"], 5);
+        assert_eq!(report["total_errors"], 3);
+        assert_eq!(report["most_used"].as_array().unwrap().len(), 2);
+        assert_eq!(report["most_used"][0]["tool"], "get_company_filings");
+        assert_eq!(report["most_used"][0]["avg_duration_ms"], 200);
+        assert_eq!(report["most_failing"][0]["tool"], "get_form4_filings");
+        assert_eq!(report["most_failing"][0]["error_codes"]["NOT_FOUND"], 2);
+        assert_eq!(report["most_failing"][1]["error_rate"], 0.5);
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let store = UsageStore::new(dir.path().join("usage.json"));
+        assert_eq!(store.load(), UsageStats::default());
+
+        store.record(&call("get_lists", 5, None)).unwrap();
+        store.record(&call("get_lists", 5, Some("NOT_CONFIGURED"))).unwrap();
+        let stats = store.load();
+        assert_eq!(stats.tools["get_lists"].calls, 2);
+        assert!(stats.since.is_some());
+
+        fs::write(store.path(), "{").unwrap();
+        assert_eq!(store.load(), UsageStats::default());
+
+        store.reset().unwrap();
+        store.reset().unwrap();
+        assert!(!store.path().exists());
+    }
+}
@@ -0,0 +1,219 @@
+//! In-process TTL caches.
+//!
+//! [`ResponseCache`] holds upstream API responses for fan-out heavy tools
+//! (e.g. watchlist enrichment) so repeated lookups for the same company
+//! within a session don't re-hit the API. [`ResultCache`] holds whole tool
+//! results, keyed by tool and arguments, so a question asked again in a
+//! conversation is answered without any upstream call. How long a result is
+//! kept depends on the tool's category; see [`result_ttl`].
+
+use filing_explorer_core::plugins::{self, PluginTarget};
+use filing_explorer_core::tools::{registry, Category};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Filing lists, holdings, and other filed data change as new filings arrive
+const FILINGS_TTL: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Financial statements change once a quarter, and filed documents never
+const FINANCIALS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Most tool results kept at once
+const MAX_RESULTS: usize = 256;
+
+pub struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, Value)>>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build a cache key from an endpoint and its query parameters
+    pub fn key(endpoint: &str, params: &HashMap<String, String>) -> String {
+        let mut pairs: Vec<_> = params.iter().collect();
+        pairs.sort();
+        let query: Vec<String> = pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        format!("{}?{}", endpoint, query.join("&"))
+    }
+
+    /// Get a cached value if it hasn't expired
+    pub fn get(&self, key: &str) -> Option<Value> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((stored_at, value)) if stored_at.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn insert(&self, key: String, value: Value) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key, (Instant::now(), value));
+    }
+
+    /// Drop every entry, returning how many there were
+    pub fn clear(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let count = entries.len();
+        entries.clear();
+        count
+    }
+}
+
+/// How long a registry tool's results are reused, or None if they never
+/// are. Watchlists are the user's own and change at any time, and a plugin
+/// command can compute anything.
+pub fn result_ttl(tool: &str) -> Option<Duration> {
+    if plugins::get(tool).is_some_and(|p| matches!(p.target, PluginTarget::Command(_))) {
+        return None;
+    }
+    match registry::get_tool_category(tool)? {
+        Category::Watchlists | Category::WatchlistItems => None,
+        Category::SecDocuments => Some(FINANCIALS_TTL),
+        _ if tool.contains("financials") => Some(FINANCIALS_TTL),
+        _ => Some(FILINGS_TTL),
+    }
+}
+
+/// Tool results, each kept until its own expiry
+#[derive(Default)]
+pub struct ResultCache {
+    entries: Mutex<HashMap<String, (Instant, String)>>,
+}
+
+impl ResultCache {
+    /// Build a cache key from a tool and its arguments. Objects serialize
+    /// with sorted keys, and null arguments are left out, so equivalent
+    /// calls share a key.
+    pub fn key(tool: &str, args: &Value) -> String {
+        let args: serde_json::Map<String, Value> = args
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(_, v)| !v.is_null())
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        format!("{}:{}", tool, Value::Object(args))
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((expires_at, result)) if Instant::now() < *expires_at => Some(result.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Keep `result` for `ttl`, making room by dropping expired entries and
+    /// then those closest to expiry
+    pub fn insert(&self, key: String, result: String, ttl: Duration) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= MAX_RESULTS && !entries.contains_key(&key) {
+            entries.retain(|_, (expires_at, _)| now < *expires_at);
+            while entries.len() >= MAX_RESULTS {
+                let Some(soonest) = entries.iter().min_by_key(|(_, (expires_at, _))| *expires_at) else {
+                    break;
+                };
+                let soonest = soonest.0.clone();
+                entries.remove(&soonest);
+            }
+        }
+        entries.insert(key, (now + ttl, result));
+    }
+
+    /// Drop the results of `tool`, or of every tool, returning how many
+    /// were dropped
+    pub fn clear(&self, tool: Option<&str>) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        match tool {
+            Some(tool) => {
+                let prefix = format!("{}:", tool);
+                entries.retain(|key, _| !key.starts_with(&prefix));
+            }
+            None => entries.clear(),
+        }
+        before - entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_key_is_order_independent() {
+        let mut a = HashMap::new();
+        a.insert("b".to_string(), "2".to_string());
+        a.insert("a".to_string(), "1".to_string());
+        assert_eq!(ResponseCache::key("companies/1/filings", &a), "companies/1/filings?a=1&b=2");
+    }
+
+    #[test]
+    fn test_get_and_expiry() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        cache.insert("k".to_string(), json!(1));
+        assert_eq!(cache.get("k"), Some(json!(1)));
+        assert_eq!(cache.get("missing"), None);
+
+        let cache = ResponseCache::new(Duration::ZERO);
+        cache.insert("k".to_string(), json!(1));
+        assert_eq!(cache.get("k"), None);
+    }
+
+    #[test]
+    fn test_result_ttl() {
+        assert_eq!(result_ttl("get_company_filings"), Some(FILINGS_TTL));
+        assert_eq!(result_ttl("get_company_financials"), Some(FINANCIALS_TTL));
+        assert_eq!(result_ttl("compare_financials_timeseries"), Some(FINANCIALS_TTL));
+        assert_eq!(result_ttl("get_list"), None);
+        assert_eq!(result_ttl("get_usage_stats"), None);
+    }
+
+    #[test]
+    fn test_result_cache() {
+        let cache = ResultCache::default();
+        let key = ResultCache::key("get_etf_holdings", &json!({"ticker": "SPY", "cursor": null}));
+        assert_eq!(key, ResultCache::key("get_etf_holdings", &json!({"ticker": "SPY"})));
+
+        cache.insert(key.clone(), "holdings".to_string(), Duration::from_secs(60));
+        cache.insert("get_lists:{}".to_string(), "lists".to_string(), Duration::ZERO);
+        assert_eq!(cache.get(&key).as_deref(), Some("holdings"));
+        assert_eq!(cache.get("get_lists:{}"), None);
+
+        cache.insert("get_list:{}".to_string(), "list".to_string(), Duration::from_secs(60));
+        assert_eq!(cache.clear(Some("get_etf_holdings")), 1);
+        assert_eq!(cache.get(&key), None);
+        assert_eq!(cache.clear(None), 1);
+    }
+
+    #[test]
+    fn test_result_cache_is_bounded() {
+        let cache = ResultCache::default();
+        for i in 0..MAX_RESULTS + 10 {
+            cache.insert(format!("t:{}", i), String::new(), Duration::from_secs(60 + i as u64));
+        }
+        assert_eq!(cache.entries.lock().unwrap().len(), MAX_RESULTS);
+        assert_eq!(cache.get("t:0"), None);
+        assert!(cache.get(&format!("t:{}", MAX_RESULTS + 9)).is_some());
+    }
+}
@@ -0,0 +1,228 @@
+//! Command line arguments.
+//!
+//! MCP clients launch the server with a fixed `command` and `args`, so
+//! everything a client config may need to set is exposed as a flag here.
+
+use crate::framing::Framing;
+use clap::{Parser, ValueEnum};
+use filing_explorer_core::{config::ConfigError, Config};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use tracing::Level;
+
+#[derive(Debug, Parser)]
+#[command(name = "mcp-server", version, about = "FilingExplorer MCP server")]
+pub struct Cli {
+    /// Config file to use instead of the platform default
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// FilingExplorer API token, overriding the one in the config file
+    #[arg(long, value_name = "TOKEN", conflicts_with = "profile")]
+    pub api_token: Option<String>,
+
+    /// Log level for stderr output (RUST_LOG directives still apply)
+    #[arg(long, value_name = "LEVEL", default_value_t = Level::INFO)]
+    pub log_level: Level,
+
+    /// Transport to serve MCP over
+    #[arg(long, value_enum, default_value_t = Transport::Stdio)]
+    pub transport: Transport,
+
+    /// Address the HTTP transport listens on
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1")]
+    pub host: IpAddr,
+
+    /// Port the HTTP transport listens on
+    #[arg(long, default_value_t = 8765)]
+    pub port: u16,
+
+    /// Unix domain socket (named pipe on Windows) the socket transport
+    /// listens on
+    #[arg(long, value_name = "PATH", required_if_eq("transport", "socket"))]
+    pub path: Option<PathBuf>,
+
+    /// Message framing for the stdio and socket transports
+    #[arg(long, value_enum, default_value_t = Framing::Auto)]
+    pub framing: Framing,
+
+    /// Account label from the config file to use instead of the active one
+    #[arg(long, value_name = "LABEL")]
+    pub profile: Option<String>,
+
+    /// List every tool directly instead of the discovery meta-tools
+    #[arg(long)]
+    pub expose_all_tools: bool,
+
+    /// Report what watchlist changes would do without making them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Write every JSON-RPC message, credentials redacted, to a rotating
+    /// trace file in the config directory
+    #[arg(long)]
+    pub trace: bool,
+
+    /// FilingExplorer API base URL, for testing against a local server
+    #[arg(long, value_name = "URL", hide = true)]
+    pub api_base_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Transport {
+    /// JSON-RPC over stdin/stdout
+    Stdio,
+    /// HTTP with server-sent events
+    Http,
+    /// JSON-RPC over a Unix domain socket or Windows named pipe
+    Socket,
+}
+
+impl Cli {
+    pub fn config_source(&self) -> ConfigSource {
+        ConfigSource {
+            path: self.config.clone(),
+            api_token: self.api_token.clone(),
+            profile: self.profile.clone(),
+            api_base_url: self.api_base_url.clone(),
+            expose_all_tools: self.expose_all_tools,
+            dry_run: self.dry_run,
+        }
+    }
+}
+
+/// Where the server reads its config from, with credential environment
+/// variables and then command line overrides applied on top. Kept for the
+/// life of the server so reloads honor them.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSource {
+    path: Option<PathBuf>,
+    api_token: Option<String>,
+    profile: Option<String>,
+    api_base_url: Option<String>,
+    expose_all_tools: bool,
+    dry_run: bool,
+}
+
+impl ConfigSource {
+    /// The config file this source reads
+    pub fn path(&self) -> Option<PathBuf> {
+        self.path.clone().or_else(|| Config::config_path().ok())
+    }
+
+    /// API base URL to use instead of the production one
+    pub fn api_base_url(&self) -> Option<&str> {
+        self.api_base_url.as_deref()
+    }
+
+    /// Load the config file, or defaults if it is missing, and apply the
+    /// overrides. Fails only if the requested profile doesn't exist.
+    pub fn load(&self) -> Result<Config, ConfigError> {
+        self.load_with_env(env_var).map(|(config, _)| config)
+    }
+
+    /// Like [`ConfigSource::load`], looking up environment variables with
+    /// `var` and also returning the names of those applied
+    pub fn load_with_env(
+        &self,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<(Config, Vec<&'static str>), ConfigError> {
+        let mut config = match &self.path {
+            Some(path) => Config::load_from(path).unwrap_or_default(),
+            None => Config::load_or_default(),
+        };
+        let env = config.apply_env(var);
+        if let Some(profile) = &self.profile {
+            config.switch_account(profile)?;
+        }
+        if let Some(token) = &self.api_token {
+            config.api_token = Some(token.clone());
+        }
+        config.expose_all_tools |= self.expose_all_tools;
+        config.dry_run |= self.dry_run;
+        Ok((config, env))
+    }
+}
+
+/// Process environment lookup for [`ConfigSource::load_with_env`]
+pub fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filing_explorer_core::config::ENV_API_TOKEN;
+    use tempfile::TempDir;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("mcp-server").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_defaults() {
+        let cli = parse(&[]).unwrap();
+        assert_eq!(cli.log_level, Level::INFO);
+        assert_eq!(cli.transport, Transport::Stdio);
+        assert_eq!(cli.framing, Framing::Auto);
+        assert!(cli.config.is_none());
+        assert!(!cli.expose_all_tools);
+    }
+
+    #[test]
+    fn test_flags() {
+        let cli = parse(&["--config", "/tmp/fe.json", "--log-level", "debug", "--profile", "work"]).unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("/tmp/fe.json")));
+        assert_eq!(cli.log_level, Level::DEBUG);
+        assert_eq!(cli.profile.as_deref(), Some("work"));
+
+        assert!(parse(&["--api-token", "t", "--profile", "work"]).is_err());
+        assert!(parse(&["--transport", "websocket"]).is_err());
+        let cli = parse(&["--transport", "http", "--port", "9000"]).unwrap();
+        assert_eq!(cli.transport, Transport::Http);
+        assert_eq!((cli.host, cli.port), (IpAddr::from([127, 0, 0, 1]), 9000));
+        assert!(parse(&["--transport", "socket"]).is_err());
+        let cli = parse(&["--transport", "socket", "--path", "/tmp/fe.sock"]).unwrap();
+        assert_eq!((cli.transport, cli.path), (Transport::Socket, Some(PathBuf::from("/tmp/fe.sock"))));
+        assert!(parse(&["--log-level", "loud"]).is_err());
+        assert_eq!(parse(&["--framing", "content-length"]).unwrap().framing, Framing::ContentLength);
+        let cli = parse(&["--api-base-url", "http://127.0.0.1:8080"]).unwrap();
+        assert_eq!(cli.config_source().api_base_url(), Some("http://127.0.0.1:8080"));
+    }
+
+    #[test]
+    fn test_config_source_overrides() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.json");
+        let mut config = Config::default();
+        config.add_account("personal", "tok_personal").unwrap();
+        config.add_account("work", "tok_work").unwrap();
+        std::fs::write(&path, serde_json::to_string(&config).unwrap()).unwrap();
+
+        let base = parse(&["--config", path.to_str().unwrap()]).unwrap().config_source();
+        assert_eq!(base.path(), Some(path.clone()));
+        assert_eq!(base.load().unwrap().api_token.as_deref(), Some("tok_personal"));
+
+        let profile = ConfigSource { profile: Some("work".to_string()), ..base.clone() };
+        assert_eq!(profile.load().unwrap().api_token.as_deref(), Some("tok_work"));
+
+        let token = ConfigSource { api_token: Some("tok_cli".to_string()), ..base.clone() };
+        assert_eq!(token.load().unwrap().api_token.as_deref(), Some("tok_cli"));
+
+        assert!(!base.load().unwrap().expose_all_tools);
+        let all_tools = parse(&["--config", path.to_str().unwrap(), "--expose-all-tools"]).unwrap();
+        assert!(all_tools.config_source().load().unwrap().expose_all_tools);
+        let dry_run = parse(&["--config", path.to_str().unwrap(), "--dry-run"]).unwrap();
+        assert!(dry_run.config_source().load().unwrap().dry_run);
+
+        let env = |name: &str| (name == ENV_API_TOKEN).then(|| "tok_env".to_string());
+        let (config, applied) = base.load_with_env(env).unwrap();
+        assert_eq!(config.api_token.as_deref(), Some("tok_env"));
+        assert_eq!(applied, vec![ENV_API_TOKEN]);
+        let (config, _) = token.load_with_env(env).unwrap();
+        assert_eq!(config.api_token.as_deref(), Some("tok_cli"));
+
+        let missing = ConfigSource { profile: Some("nope".to_string()), ..base };
+        assert!(matches!(missing.load(), Err(ConfigError::AccountNotFound(_))));
+    }
+}
@@ -0,0 +1,307 @@
+//! Server logs forwarded to clients.
+//!
+//! MCP's logging capability lets a client receive the server's diagnostics
+//! as `notifications/message` at a level it picks with `logging/setLevel`.
+//! [`ClientLogs::layer`] is a tracing layer that hands events from this
+//! server and core to every connected client's [`ClientLog`], which sends
+//! those at or above the client's level. Logs are server-wide, so over HTTP
+//! one session can see events from another's calls.
+
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Target of protocol traffic logs. They are never forwarded, since sending
+/// a log notification would log it again.
+pub const WIRE_TARGET: &str = "mcp_server::wire";
+
+/// Targets whose events are forwarded
+const FORWARDED_TARGETS: &[&str] = &["mcp_server", "filing_explorer_core"];
+
+/// Level forwarded until the client picks one
+const DEFAULT_LEVEL: LogLevel = LogLevel::Warning;
+
+/// MCP log levels (RFC 5424 severities), least severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+impl LogLevel {
+    const ALL: [LogLevel; 8] = [
+        Self::Debug,
+        Self::Info,
+        Self::Notice,
+        Self::Warning,
+        Self::Error,
+        Self::Critical,
+        Self::Alert,
+        Self::Emergency,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Notice => "notice",
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::Critical => "critical",
+            Self::Alert => "alert",
+            Self::Emergency => "emergency",
+        }
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|level| level.as_str() == s).ok_or_else(|| {
+            let names: Vec<&str> = Self::ALL.iter().map(LogLevel::as_str).collect();
+            format!("Unknown log level '{}'. Expected one of: {}", s, names.join(", "))
+        })
+    }
+}
+
+impl From<Level> for LogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::ERROR => Self::Error,
+            Level::WARN => Self::Warning,
+            Level::INFO => Self::Info,
+            _ => Self::Debug,
+        }
+    }
+}
+
+/// One client's log destination and level
+pub struct ClientLog {
+    // Weak so a registered log doesn't keep the writer running at shutdown
+    output: OnceLock<mpsc::WeakUnboundedSender<String>>,
+    level: AtomicU8,
+}
+
+impl ClientLog {
+    /// Send logs through the transport's outgoing message channel
+    pub fn attach(&self, output: &mpsc::UnboundedSender<String>) {
+        let _ = self.output.set(output.downgrade());
+    }
+
+    pub fn set_level(&self, level: LogLevel) {
+        self.level.store(level as u8, Ordering::Relaxed);
+    }
+
+    pub fn level(&self) -> LogLevel {
+        LogLevel::ALL[self.level.load(Ordering::Relaxed) as usize]
+    }
+
+    fn send(&self, level: LogLevel, logger: &str, data: &Value) {
+        if level < self.level() {
+            return;
+        }
+        let Some(output) = self.output.get().and_then(|output| output.upgrade()) else {
+            return;
+        };
+        let message = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/message",
+            "params": { "level": level.as_str(), "logger": logger, "data": data }
+        });
+        let _ = output.send(message.to_string());
+    }
+}
+
+/// Every connected client's log
+#[derive(Default)]
+pub struct ClientLogs {
+    clients: Mutex<Vec<Weak<ClientLog>>>,
+}
+
+impl ClientLogs {
+    /// The registry the server's tracing layer forwards to
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<ClientLogs> = OnceLock::new();
+        GLOBAL.get_or_init(Self::default)
+    }
+
+    /// A log for a new client; it stops receiving events once dropped
+    pub fn register(&self) -> Arc<ClientLog> {
+        let log = Arc::new(ClientLog {
+            output: OnceLock::new(),
+            level: AtomicU8::new(DEFAULT_LEVEL as u8),
+        });
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|client| client.strong_count() > 0);
+        clients.push(Arc::downgrade(&log));
+        log
+    }
+
+    /// Tracing layer forwarding this server's and core's events, other than
+    /// protocol traffic, to the registered clients
+    pub fn layer<S>(&'static self) -> impl Layer<S>
+    where
+        S: Subscriber + for<'span> LookupSpan<'span>,
+    {
+        ForwardLayer { logs: self }.with_filter(filter_fn(|metadata| {
+            let target = metadata.target();
+            *metadata.level() <= Level::DEBUG
+                && target != WIRE_TARGET
+                && FORWARDED_TARGETS.iter().any(|prefix| target.starts_with(prefix))
+        }))
+    }
+}
+
+struct ForwardLayer {
+    logs: &'static ClientLogs,
+}
+
+impl<S: Subscriber> Layer<S> for ForwardLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let level = LogLevel::from(*event.metadata().level());
+        let clients: Vec<Arc<ClientLog>> = self
+            .logs
+            .clients
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|client| level >= client.level())
+            .collect();
+        if clients.is_empty() {
+            return;
+        }
+
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let data = fields.into_data();
+        for client in clients {
+            client.send(level, event.metadata().target(), &data);
+        }
+    }
+}
+
+/// An event's message and fields
+#[derive(Default)]
+struct Fields {
+    message: Option<String>,
+    fields: Map<String, Value>,
+}
+
+impl Fields {
+    /// The bare message, or an object with the message and the fields
+    fn into_data(self) -> Value {
+        let message = self.message.unwrap_or_default();
+        if self.fields.is_empty() {
+            return json!(message);
+        }
+        let mut data = self.fields;
+        data.insert("message".to_string(), json!(message));
+        Value::Object(data)
+    }
+
+    fn insert(&mut self, field: &Field, value: Value) {
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            self.insert(field, json!(format!("{:?}", value)));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.insert(field, json!(value));
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, json!(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn notifications(rx: &mut mpsc::UnboundedReceiver<String>) -> Vec<Value> {
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|message| serde_json::from_str(&message).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_forwards_at_client_level() {
+        let logs: &'static ClientLogs = Box::leak(Box::default());
+        let log = logs.register();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        log.attach(&tx);
+
+        let subscriber = tracing_subscriber::registry().with(logs.layer());
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("below the default level");
+            tracing::warn!(list_id = 42, "Could not snapshot list");
+            tracing::error!(target: WIRE_TARGET, "Sending: {{}}");
+            tracing::error!(target: "hyper::proto", "connection error");
+
+            log.set_level(LogLevel::Debug);
+            tracing::debug!("now forwarded");
+        });
+
+        let sent = notifications(&mut rx);
+        assert_eq!(sent.len(), 2, "{:?}", sent);
+        assert_eq!(sent[0]["method"], "notifications/message");
+        assert_eq!(sent[0]["params"]["level"], "warning");
+        assert_eq!(sent[0]["params"]["data"], json!({ "message": "Could not snapshot list", "list_id": 42 }));
+        assert_eq!(sent[1]["params"]["level"], "debug");
+        assert_eq!(sent[1]["params"]["data"], "now forwarded");
+    }
+
+    #[test]
+    fn test_log_levels() {
+        assert_eq!("notice".parse::<LogLevel>(), Ok(LogLevel::Notice));
+        assert!("verbose".parse::<LogLevel>().unwrap_err().contains("emergency"));
+        assert!(LogLevel::Debug < LogLevel::Warning);
+        assert_eq!(LogLevel::from(Level::TRACE), LogLevel::Debug);
+
+        let log = ClientLogs::default().register();
+        assert_eq!(log.level(), DEFAULT_LEVEL);
+        log.set_level(LogLevel::Alert);
+        assert_eq!(log.level(), LogLevel::Alert);
+    }
+}
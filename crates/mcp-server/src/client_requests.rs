@@ -0,0 +1,236 @@
+//! Requests from the server to the client.
+//!
+//! MCP lets a server ask the client for work such as sampling from the
+//! client's model (`sampling/createMessage`) or asking the user for input
+//! (`elicitation/create`). Requests go out through the stdout writer with
+//! server-assigned ids, and the stdin loop routes the client's responses
+//! back to the waiting caller.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tracing::debug;
+
+/// How long to wait for the client to answer; sampling may wait on the user
+/// approving the request
+const CLIENT_REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Prefix of server-assigned request ids, keeping them apart from the
+/// client's own
+const REQUEST_ID_PREFIX: &str = "filing-explorer-";
+
+type Pending = Mutex<HashMap<String, oneshot::Sender<Result<Value, Value>>>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientRequestError {
+    /// No transport is attached, or it has shut down
+    Closed,
+    TimedOut,
+    /// The client answered with a JSON-RPC error
+    Rejected(String),
+}
+
+impl fmt::Display for ClientRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => write!(f, "the client connection is closed"),
+            Self::TimedOut => write!(f, "the client did not respond in time"),
+            Self::Rejected(message) => write!(f, "the client declined the request: {}", message),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ClientRequests {
+    // Weak so an outstanding handle doesn't keep the writer running at shutdown
+    output: OnceLock<mpsc::WeakUnboundedSender<String>>,
+    next_id: AtomicU64,
+    pending: Pending,
+    sampling: AtomicBool,
+    elicitation: AtomicBool,
+}
+
+/// Removes the pending entry if the caller stops waiting
+struct PendingGuard<'a> {
+    pending: &'a Pending,
+    id: String,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl ClientRequests {
+    /// Send requests through the transport's outgoing message channel
+    pub fn attach(&self, output: &mpsc::UnboundedSender<String>) {
+        let _ = self.output.set(output.downgrade());
+    }
+
+    /// Record what the client supports from its `initialize` capabilities
+    pub fn set_capabilities(&self, capabilities: &Value) {
+        let sampling = capabilities.get("sampling").is_some_and(|v| !v.is_null());
+        self.sampling.store(sampling, Ordering::Relaxed);
+        let elicitation = capabilities.get("elicitation").is_some_and(|v| !v.is_null());
+        self.elicitation.store(elicitation, Ordering::Relaxed);
+    }
+
+    /// Whether the client accepts `sampling/createMessage` requests
+    pub fn supports_sampling(&self) -> bool {
+        self.sampling.load(Ordering::Relaxed)
+    }
+
+    /// Whether the client accepts `elicitation/create` requests
+    pub fn supports_elicitation(&self) -> bool {
+        self.elicitation.load(Ordering::Relaxed)
+    }
+
+    /// Send a request to the client and wait for its result
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value, ClientRequestError> {
+        let output = self
+            .output
+            .get()
+            .and_then(|output| output.upgrade())
+            .ok_or(ClientRequestError::Closed)?;
+        let id = format!("{}{}", REQUEST_ID_PREFIX, self.next_id.fetch_add(1, Ordering::Relaxed));
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id.clone(), tx);
+        let _guard = PendingGuard {
+            pending: &self.pending,
+            id: id.clone(),
+        };
+
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        output
+            .send(message.to_string())
+            .map_err(|_| ClientRequestError::Closed)?;
+        drop(output);
+
+        match tokio::time::timeout(CLIENT_REQUEST_TIMEOUT, rx).await {
+            Ok(Ok(Ok(result))) => Ok(result),
+            Ok(Ok(Err(error))) => Err(ClientRequestError::Rejected(
+                error["message"].as_str().unwrap_or("unknown error").to_string(),
+            )),
+            Ok(Err(_)) => Err(ClientRequestError::Closed),
+            Err(_) => Err(ClientRequestError::TimedOut),
+        }
+    }
+
+    /// Send a notification, which the client doesn't answer. Returns false
+    /// if no transport is attached.
+    pub fn notify(&self, method: &str) -> bool {
+        let Some(output) = self.output.get().and_then(|output| output.upgrade()) else {
+            return false;
+        };
+        let message = json!({ "jsonrpc": "2.0", "method": method });
+        output.send(message.to_string()).is_ok()
+    }
+
+    /// Hand a response from the client to the request waiting on it.
+    /// Returns false if `message` isn't a response at all.
+    pub fn handle_response(&self, message: &Value) -> bool {
+        if message.get("method").is_some() || (message.get("result").is_none() && message.get("error").is_none()) {
+            return false;
+        }
+        let id = message.get("id").and_then(|v| v.as_str()).unwrap_or_default();
+        match self.pending.lock().unwrap().remove(id) {
+            Some(tx) => {
+                let outcome = match message.get("error") {
+                    Some(error) => Err(error.clone()),
+                    None => Ok(message["result"].clone()),
+                };
+                let _ = tx.send(outcome);
+            }
+            None => debug!("Ignoring response to unknown request {}", message["id"]),
+        }
+        true
+    }
+
+    /// Fail every outstanding request, once the client has gone away
+    pub fn close(&self) {
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_request_round_trip() {
+        let requests = Arc::new(ClientRequests::default());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        requests.attach(&tx);
+
+        let call = tokio::spawn({
+            let requests = requests.clone();
+            async move { requests.request("sampling/createMessage", json!({"maxTokens": 10})).await }
+        });
+        let sent: Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(sent["method"], "sampling/createMessage");
+        assert_eq!(sent["params"]["maxTokens"], 10);
+
+        let response = json!({"jsonrpc": "2.0", "id": sent["id"], "result": {"content": {"type": "text", "text": "ok"}}});
+        assert!(requests.handle_response(&response));
+        assert_eq!(call.await.unwrap().unwrap()["content"]["text"], "ok");
+        assert!(requests.pending.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_notify() {
+        let requests = ClientRequests::default();
+        assert!(!requests.notify("notifications/tools/list_changed"));
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        requests.attach(&tx);
+        assert!(requests.notify("notifications/tools/list_changed"));
+        let sent: Value = serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+        assert_eq!(sent, json!({"jsonrpc": "2.0", "method": "notifications/tools/list_changed"}));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_and_closed() {
+        let requests = Arc::new(ClientRequests::default());
+        assert_eq!(requests.request("ping", json!({})).await, Err(ClientRequestError::Closed));
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        requests.attach(&tx);
+        let call = tokio::spawn({
+            let requests = requests.clone();
+            async move { requests.request("sampling/createMessage", json!({})).await }
+        });
+        let sent: Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        let response = json!({"id": sent["id"], "error": {"code": -1, "message": "User rejected sampling request"}});
+        assert!(requests.handle_response(&response));
+        assert_eq!(
+            call.await.unwrap(),
+            Err(ClientRequestError::Rejected("User rejected sampling request".to_string()))
+        );
+
+        let call = tokio::spawn({
+            let requests = requests.clone();
+            async move { requests.request("sampling/createMessage", json!({})).await }
+        });
+        rx.recv().await.unwrap();
+        requests.close();
+        assert_eq!(call.await.unwrap(), Err(ClientRequestError::Closed));
+    }
+
+    #[test]
+    fn test_handle_response_ignores_requests() {
+        let requests = ClientRequests::default();
+        assert!(!requests.handle_response(&json!({"id": 1, "method": "tools/list"})));
+        assert!(requests.handle_response(&json!({"id": "filing-explorer-9", "result": {}})));
+        requests.set_capabilities(&json!({"sampling": {}}));
+        assert!(requests.supports_sampling());
+        assert!(!requests.supports_elicitation());
+        requests.set_capabilities(&json!({"elicitation": {}}));
+        assert!(requests.supports_elicitation());
+    }
+}
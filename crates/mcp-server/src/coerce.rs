@@ -0,0 +1,147 @@
+//! Lenient argument coercion.
+//!
+//! Models often send `"limit": "10"` or `"aggregate": "true"`. Before a tool
+//! runs, scalar arguments are converted to the type the tool's input schema
+//! declares, so handlers don't reject otherwise valid calls.
+
+use serde_json::{Map, Number, Value};
+
+/// Coerce `args` in place to match `schema`'s declared property types.
+///
+/// Returns a description of each coercion made, for debug logging.
+pub fn coerce_args(schema: &Value, args: &mut Value) -> Vec<String> {
+    let (Some(properties), Some(args)) = (
+        schema.get("properties").and_then(|p| p.as_object()),
+        args.as_object_mut(),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut notes = Vec::new();
+    for (name, value) in args.iter_mut() {
+        let Some(expected) = properties
+            .get(name)
+            .and_then(|p| p.get("type"))
+            .and_then(|t| t.as_str())
+        else {
+            continue;
+        };
+        if let Some(coerced) = coerce_value(expected, value) {
+            notes.push(format!("{}: {} -> {}", name, value, coerced));
+            *value = coerced;
+        }
+    }
+    notes
+}
+
+/// The value converted to `expected`, or None if it already matches or
+/// can't be converted (leaving the handler to report the error).
+fn coerce_value(expected: &str, value: &Value) -> Option<Value> {
+    match (expected, value) {
+        ("integer", Value::String(s)) => {
+            let s = s.trim();
+            s.parse::<i64>()
+                .ok()
+                .or_else(|| {
+                    s.parse::<f64>()
+                        .ok()
+                        .filter(|f| f.fract() == 0.0 && f.abs() < i64::MAX as f64)
+                        .map(|f| f as i64)
+                })
+                .map(Value::from)
+        }
+        ("integer", Value::Number(n)) if n.as_i64().is_none() && n.as_u64().is_none() => n
+            .as_f64()
+            .filter(|f| f.fract() == 0.0 && f.abs() < i64::MAX as f64)
+            .map(|f| Value::from(f as i64)),
+        ("number", Value::String(s)) => s
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        ("boolean", Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "1" => Some(Value::Bool(true)),
+            "false" | "no" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        ("boolean", Value::Number(n)) => match n.as_u64() {
+            Some(1) => Some(Value::Bool(true)),
+            Some(0) => Some(Value::Bool(false)),
+            _ => None,
+        },
+        ("string", Value::Number(n)) => Some(Value::String(n.to_string())),
+        ("string", Value::String(s)) if s.trim().len() != s.len() => {
+            Some(Value::String(s.trim().to_string()))
+        }
+        ("array", Value::String(_) | Value::Number(_)) => Some(Value::Array(vec![value.clone()])),
+        ("object", Value::String(s)) => serde_json::from_str::<Map<String, Value>>(s)
+            .ok()
+            .map(Value::Object),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "limit": { "type": "integer" },
+                "min_value": { "type": "number" },
+                "aggregate": { "type": "boolean" },
+                "cik": { "type": "string" },
+                "form_types": { "type": "array", "items": { "type": "string" } }
+            }
+        })
+    }
+
+    #[test]
+    fn test_coerces_strings_to_schema_types() {
+        let mut args = json!({
+            "limit": " 10 ",
+            "min_value": "1.5",
+            "aggregate": "True",
+            "cik": 320193,
+            "form_types": "10-K"
+        });
+        let notes = coerce_args(&schema(), &mut args);
+        assert_eq!(
+            args,
+            json!({
+                "limit": 10,
+                "min_value": 1.5,
+                "aggregate": true,
+                "cik": "320193",
+                "form_types": ["10-K"]
+            })
+        );
+        assert_eq!(notes.len(), 5);
+    }
+
+    #[test]
+    fn test_leaves_matching_and_unconvertible_values() {
+        let mut args = json!({
+            "limit": "ten",
+            "aggregate": false,
+            "unknown": "5"
+        });
+        let original = args.clone();
+        assert!(coerce_args(&schema(), &mut args).is_empty());
+        assert_eq!(args, original);
+    }
+
+    #[test]
+    fn test_whole_floats_become_integers() {
+        let mut args = json!({ "limit": 25.0 });
+        coerce_args(&schema(), &mut args);
+        assert_eq!(args["limit"], json!(25));
+
+        let mut args = json!({ "limit": 2.5 });
+        assert!(coerce_args(&schema(), &mut args).is_empty());
+    }
+}
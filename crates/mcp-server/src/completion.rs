@@ -0,0 +1,119 @@
+//! Argument completion.
+//!
+//! `completion/complete` suggests values for prompt arguments and resource
+//! template variables, and for tool arguments too when a client refers to
+//! a tool with `ref/tool`. What is suggested depends on the argument's name:
+//! companies come from SEC's ticker list, categories and tags from the tool
+//! registry, and form types from a list of common SEC forms.
+
+use crate::tickers::TickerResolver;
+use filing_explorer_core::tools::{registry, Category};
+use filing_explorer_core::Config;
+use serde_json::{json, Value};
+
+/// Most values in one completion result, as the spec allows
+const MAX_VALUES: usize = 100;
+
+/// Form types suggested for `form_type` arguments, most common first
+const FORM_TYPES: &[&str] = &[
+    "10-K", "10-Q", "8-K", "20-F", "40-F", "6-K", "10-K/A", "10-Q/A", "8-K/A", "DEF 14A", "DEFA14A", "S-1", "S-3",
+    "S-4", "S-8", "F-1", "424B2", "424B3", "424B4", "424B5", "SC 13D", "SC 13D/A", "SC 13G", "SC 13G/A", "13F-HR",
+    "3", "4", "5", "144", "11-K", "N-CSR", "N-CSRS", "N-PORT", "485BPOS", "497", "497K",
+];
+
+/// What an argument holds, judged by its name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgumentKind {
+    Company,
+    Category,
+    Tag,
+    FormType,
+}
+
+fn argument_kind(name: &str) -> Option<ArgumentKind> {
+    match name {
+        "cik" | "ticker" | "company_cik" | "company_id" | "issuer_cik" | "with_cik" => Some(ArgumentKind::Company),
+        "category" => Some(ArgumentKind::Category),
+        "tag" => Some(ArgumentKind::Tag),
+        "form_type" => Some(ArgumentKind::FormType),
+        _ => None,
+    }
+}
+
+/// Suggested values for argument `name` starting with `value`. Companies are
+/// suggested as tickers, or as CIKs when `ciks` is set, for resource URIs.
+pub async fn complete(tickers: &TickerResolver, config: &Config, name: &str, value: &str, ciks: bool) -> Vec<String> {
+    match argument_kind(name) {
+        Some(ArgumentKind::Company) => {
+            let matches = tickers.matching(config, value).await.into_iter();
+            let mut values: Vec<String> = if ciks {
+                matches.map(|(_, cik)| cik).collect()
+            } else {
+                matches.map(|(ticker, _)| ticker).collect()
+            };
+            // Share classes map several tickers to one CIK
+            let mut seen = std::collections::HashSet::new();
+            values.retain(|v| seen.insert(v.clone()));
+            values
+        }
+        Some(ArgumentKind::Category) => with_prefix(
+            Category::all().iter().map(Category::as_str).chain(registry::category_groups().iter().map(|g| g.id)),
+            value,
+        ),
+        Some(ArgumentKind::Tag) => with_prefix(registry::TAGS.iter().map(|(tag, _)| *tag), value),
+        Some(ArgumentKind::FormType) => with_prefix(FORM_TYPES.iter().copied(), value),
+        None => Vec::new(),
+    }
+}
+
+/// Candidates starting with `prefix`, ignoring case
+fn with_prefix<'a>(candidates: impl Iterator<Item = &'a str>, prefix: &str) -> Vec<String> {
+    let prefix = prefix.trim().to_lowercase();
+    candidates
+        .filter(|c| c.to_lowercase().starts_with(&prefix))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The `completion/complete` result for `values`, capped at [`MAX_VALUES`]
+pub fn result(mut values: Vec<String>) -> Value {
+    let total = values.len();
+    values.truncate(MAX_VALUES);
+    json!({
+        "completion": {
+            "values": values,
+            "total": total,
+            "hasMore": total > MAX_VALUES
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_complete() {
+        let tickers = TickerResolver::new();
+        let config = Config::default();
+        let complete = |name: &'static str, value: &'static str| complete(&tickers, &config, name, value, false);
+
+        assert_eq!(complete("category", "sec").await, vec!["sec_documents"]);
+        assert!(complete("category", "form_adv").await.contains(&"form_adv".to_string()));
+        assert_eq!(complete("tag", "re").await, vec!["read-only", "requires-token"]);
+        assert_eq!(complete("form_type", "10-k").await, vec!["10-K", "10-K/A"]);
+        assert!(complete("form_type", "").await.len() > 10);
+        assert!(complete("detail_level", "").await.is_empty());
+        // No SEC identity, so no ticker list
+        assert!(complete("cik", "AA").await.is_empty());
+    }
+
+    #[test]
+    fn test_result() {
+        let capped = result((0..150).map(|i| i.to_string()).collect());
+        assert_eq!(capped["completion"]["values"].as_array().unwrap().len(), MAX_VALUES);
+        assert_eq!(capped["completion"]["total"], 150);
+        assert_eq!(capped["completion"]["hasMore"], true);
+        assert_eq!(result(vec![])["completion"]["hasMore"], false);
+    }
+}
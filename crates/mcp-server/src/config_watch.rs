@@ -0,0 +1,87 @@
+//! Picking up config file changes while the server runs.
+//!
+//! The settings app rewrites the config file when the user saves, so the
+//! server polls the file's modification time and size and reloads when they
+//! change. Polling needs no platform file-watching support and a check every
+//! couple of seconds costs one `stat`.
+
+use filing_explorer_core::config::ConfigError;
+use filing_explorer_core::Config;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How often the config file is checked for changes
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Modification time and size of the file, or None while it doesn't exist
+type Stamp = Option<(Option<SystemTime>, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let meta = fs::metadata(path).ok()?;
+    Some((meta.modified().ok(), meta.len()))
+}
+
+/// Tracks whether the config file changed since it was last applied
+pub struct ConfigWatcher {
+    path: PathBuf,
+    seen: Stamp,
+}
+
+impl ConfigWatcher {
+    /// Watch `path`, treating its current contents as already applied
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let seen = stamp(&path);
+        Self { path, seen }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check whether the file changed and is ready to apply. A file that
+    /// doesn't parse, such as one caught halfway through being written, is
+    /// left for the next poll rather than reloaded as defaults.
+    pub fn poll(&mut self) -> Result<bool, ConfigError> {
+        let current = stamp(&self.path);
+        if current == self.seen {
+            return Ok(false);
+        }
+        match Config::load_from(&self.path) {
+            Ok(_) | Err(ConfigError::NotFound) => {
+                self.seen = current;
+                Ok(true)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_poll() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.json");
+        let mut watcher = ConfigWatcher::new(&path);
+        assert!(!watcher.poll().unwrap());
+
+        fs::write(&path, r#"{"api_token": "tok"}"#).unwrap();
+        assert!(watcher.poll().unwrap());
+        assert!(!watcher.poll().unwrap());
+
+        // Half-written files are retried until they parse
+        fs::write(&path, r#"{"api_token": "#).unwrap();
+        assert!(watcher.poll().is_err());
+        fs::write(&path, r#"{"api_token": "tok2"}"#).unwrap();
+        assert!(watcher.poll().unwrap());
+
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.poll().unwrap());
+        assert!(!watcher.poll().unwrap());
+    }
+}
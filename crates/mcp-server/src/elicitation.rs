@@ -0,0 +1,177 @@
+//! Asking the user for missing configuration.
+//!
+//! When a tool fails because the API token or SEC EDGAR identity is not set
+//! and the client supports elicitation, the server asks for the values with
+//! `elicitation/create` instead of pointing the user at the settings app.
+//! Accepted values are saved to the config file so they outlast the session.
+
+use crate::error::ToolError;
+use filing_explorer_core::config::ConfigError;
+use filing_explorer_core::sec_client::sec_user_agent;
+use filing_explorer_core::Config;
+use serde_json::{json, Value};
+use std::path::Path;
+
+/// `details.setting` of a not-configured error for a missing API token
+pub const API_TOKEN_SETTING: &str = "api_token";
+
+/// `details.setting` of a not-configured error for an unusable SEC identity
+pub const SEC_IDENTITY_SETTING: &str = "sec_identity";
+
+/// Configuration a tool needed but could not find
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingSetting {
+    ApiToken,
+    SecIdentity,
+}
+
+impl MissingSetting {
+    /// The setting a not-configured tool error is about, if any
+    pub fn from_error(error: &ToolError) -> Option<Self> {
+        let setting = error.details.as_ref()?.get("setting")?.as_str()?;
+        match setting {
+            API_TOKEN_SETTING => Some(Self::ApiToken),
+            SEC_IDENTITY_SETTING => Some(Self::SecIdentity),
+            _ => None,
+        }
+    }
+
+    /// Params of the `elicitation/create` request asking for the setting
+    pub fn request(self) -> Value {
+        match self {
+            Self::ApiToken => json!({
+                "message": "FilingExplorer needs an API token. It will be saved to your FilingExplorer config file.",
+                "requestedSchema": {
+                    "type": "object",
+                    "properties": {
+                        "api_token": {
+                            "type": "string",
+                            "title": "API token",
+                            "description": "Your FilingExplorer API token"
+                        }
+                    },
+                    "required": ["api_token"]
+                }
+            }),
+            Self::SecIdentity => json!({
+                "message": "SEC EDGAR requires a name and contact email with every request. They will be saved to your FilingExplorer config file.",
+                "requestedSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": {
+                            "type": "string",
+                            "title": "Name or organization",
+                            "description": "Sent to SEC EDGAR in the User-Agent header"
+                        },
+                        "email": {
+                            "type": "string",
+                            "title": "Contact email",
+                            "format": "email",
+                            "description": "Where SEC can reach you about your traffic"
+                        }
+                    },
+                    "required": ["name", "email"]
+                }
+            }),
+        }
+    }
+
+    /// Copy the values from an accepted elicitation into `config`
+    pub fn apply(self, content: &Value, config: &mut Config) -> Result<(), String> {
+        let field = |key: &str| {
+            content
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        match self {
+            Self::ApiToken => {
+                let token = field("api_token").ok_or("No API token was entered")?;
+                config.set_api_token(Some(token));
+            }
+            Self::SecIdentity => {
+                let (name, email) = (field("name"), field("email"));
+                sec_user_agent(name.as_deref(), email.as_deref()).map_err(|e| e.to_string())?;
+                config.sec_user_agent_name = name;
+                config.sec_user_agent_email = email;
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the values from an accepted elicitation to the config file at
+    /// `path`, leaving everything else in it as it was
+    pub fn save(self, content: &Value, path: &Path) -> Result<(), String> {
+        let mut config = match Config::load_from(path) {
+            Ok(config) => config,
+            Err(ConfigError::NotFound) => Config::default(),
+            // Don't replace a file we can't read with defaults
+            Err(e) => return Err(e.to_string()),
+        };
+        self.apply(content, &mut config)?;
+        config.save_to(path).map_err(|e| e.to_string())
+    }
+}
+
+/// The submitted values of an `elicitation/create` result, or None if the
+/// user declined or cancelled
+pub fn accepted_content(result: &Value) -> Option<&Value> {
+    (result.get("action")?.as_str()? == "accept").then(|| &result["content"])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use filing_explorer_core::sec_client::{SecConfigIssue, SecError};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_from_error() {
+        assert_eq!(MissingSetting::from_error(&ToolError::not_configured()), Some(MissingSetting::ApiToken));
+        let sec = ToolError::from(SecError::NotConfigured(SecConfigIssue::MissingEmail));
+        assert_eq!(MissingSetting::from_error(&sec), Some(MissingSetting::SecIdentity));
+        assert_eq!(MissingSetting::from_error(&ToolError::new(ErrorCode::NotConfigured, "x")), None);
+    }
+
+    #[test]
+    fn test_accepted_content() {
+        let content = json!({ "api_token": "tok" });
+        assert_eq!(accepted_content(&json!({ "action": "accept", "content": content })), Some(&content));
+        assert_eq!(accepted_content(&json!({ "action": "decline" })), None);
+        assert_eq!(accepted_content(&json!({ "action": "cancel" })), None);
+    }
+
+    #[test]
+    fn test_save_keeps_other_settings() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.json");
+        let existing = Config {
+            sec_user_agent_name: Some("Acme".to_string()),
+            ..Config::default()
+        };
+        existing.save_to(&path).unwrap();
+
+        MissingSetting::ApiToken.save(&json!({ "api_token": " tok " }), &path).unwrap();
+        let saved = Config::load_from(&path).unwrap();
+        assert_eq!(saved.api_token.as_deref(), Some("tok"));
+        assert_eq!(saved.sec_user_agent_name.as_deref(), Some("Acme"));
+
+        assert!(MissingSetting::ApiToken.save(&json!({ "api_token": "" }), &path).is_err());
+    }
+
+    #[test]
+    fn test_sec_identity_is_validated() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.json");
+        let invalid = json!({ "name": "Acme", "email": "not-an-email" });
+        assert!(MissingSetting::SecIdentity.save(&invalid, &path).is_err());
+        assert!(!path.exists());
+
+        let valid = json!({ "name": "Acme", "email": "ops@acme.com" });
+        MissingSetting::SecIdentity.save(&valid, &path).unwrap();
+        assert!(Config::load_from(&path).unwrap().is_sec_configured());
+    }
+}
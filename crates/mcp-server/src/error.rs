@@ -0,0 +1,294 @@
+//! Structured tool errors.
+//!
+//! Every tool handler fails with a [`ToolError`], which is returned to the
+//! client as a JSON envelope (`{code, message, details, remediation,
+//! is_retryable, retry_after_seconds}`) so models and client UIs can branch
+//! on the error type instead of parsing prose. Codes are the stable
+//! [`ErrorCode`]s shared with the core library.
+
+use crate::elicitation::{API_TOKEN_SETTING, SEC_IDENTITY_SETTING};
+use filing_explorer_core::api_client::ApiError;
+use filing_explorer_core::sec_client::SecError;
+use filing_explorer_core::{CoreError, ErrorContext};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt;
+use std::time::Duration;
+
+pub use filing_explorer_core::ErrorCode;
+
+/// Suggested wait before retrying a transient failure without a server hint
+const DEFAULT_RETRY_AFTER_SECS: u64 = 5;
+
+/// Error envelope returned by tool handlers
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolError {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    /// Whether retrying the same call may succeed (429, 5xx, timeouts)
+    pub is_retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_seconds: Option<u64>,
+}
+
+impl ToolError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+            remediation: None,
+            is_retryable: false,
+            retry_after_seconds: None,
+        }
+    }
+
+    /// Mark the error as transient, suggesting a wait before retrying
+    pub fn retryable(mut self, retry_after_seconds: Option<u64>) -> Self {
+        self.is_retryable = true;
+        self.retry_after_seconds = Some(retry_after_seconds.unwrap_or(DEFAULT_RETRY_AFTER_SECS));
+        self
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn with_remediation(mut self, remediation: impl Into<String>) -> Self {
+        self.remediation = Some(remediation.into());
+        self
+    }
+
+    /// Record where the error happened under `details.context`
+    pub fn with_context(mut self, context: ErrorContext) -> Self {
+        if context.is_empty() {
+            return self;
+        }
+        let mut details = match self.details.take() {
+            Some(Value::Object(map)) => map,
+            Some(other) => {
+                let mut map = serde_json::Map::new();
+                map.insert("value".to_string(), other);
+                map
+            }
+            None => serde_json::Map::new(),
+        };
+        let merged = match details.remove("context") {
+            Some(Value::Object(mut existing)) => {
+                for (key, value) in json!(context).as_object().into_iter().flatten() {
+                    existing.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+                Value::Object(existing)
+            }
+            _ => json!(context),
+        };
+        details.insert("context".to_string(), merged);
+        self.details = Some(Value::Object(details));
+        self
+    }
+
+    pub fn missing_param(name: &str) -> Self {
+        Self::new(
+            ErrorCode::MissingParam,
+            format!("Missing required parameter: {}", name),
+        )
+        .with_details(json!({ "parameter": name }))
+        .with_remediation(format!("Call the tool again with the '{}' argument set.", name))
+    }
+
+    pub fn invalid_param(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::InvalidParam, message)
+            .with_remediation("Check the tool's input schema with search_tools.")
+    }
+
+    /// A tool call that ran past its time budget
+    pub fn timeout(budget: Duration, elapsed: Duration) -> Self {
+        Self::new(
+            ErrorCode::Timeout,
+            format!("Tool call timed out after {} seconds", budget.as_secs()),
+        )
+        .with_details(json!({
+            "timeout_seconds": budget.as_secs(),
+            "elapsed_ms": elapsed.as_millis() as u64,
+        }))
+        .with_remediation("Retry with narrower arguments, or raise tool_timeout_secs in the config file.")
+        .retryable(None)
+    }
+
+    /// Whether the call failed because the API or SEC couldn't be reached
+    /// or answered with a transient failure, rather than because of the
+    /// call itself
+    pub fn is_upstream_unavailable(&self) -> bool {
+        self.is_retryable
+            && matches!(
+                self.code,
+                ErrorCode::UpstreamError | ErrorCode::UpstreamRateLimited | ErrorCode::Timeout
+            )
+    }
+
+    pub fn not_configured() -> Self {
+        Self::new(ErrorCode::NotConfigured, "API token not configured.")
+            .with_details(json!({ "setting": API_TOKEN_SETTING }))
+            .with_remediation("Open the FilingExplorer settings app and save an API token.")
+    }
+}
+
+impl fmt::Display for ToolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ToolError {}
+
+impl From<CoreError> for ToolError {
+    fn from(err: CoreError) -> Self {
+        let code = err.code();
+        let mut error = Self::new(code, err.to_string());
+
+        let remediation = match err.root() {
+            CoreError::Api(ApiError::Unauthorized) => {
+                Some("Update the API token in the FilingExplorer settings app.")
+            }
+            CoreError::Api(ApiError::RequestError(_)) => {
+                Some("Check network connectivity to the FilingExplorer API.")
+            }
+            CoreError::Sec(SecError::NotConfigured(_)) => {
+                Some("Set your name and email for SEC EDGAR in the FilingExplorer settings app.")
+            }
+            CoreError::Sec(SecError::RequestError(_)) => Some("Check network connectivity to SEC EDGAR."),
+            CoreError::Config(_) => Some("Open the FilingExplorer settings app and save your settings."),
+            _ => match code {
+                ErrorCode::NotFound => Some("Check the identifier, or use search to look it up."),
+                ErrorCode::UpstreamRateLimited => Some("Wait for retry_after_seconds before retrying."),
+                _ => None,
+            },
+        };
+        if let Some(remediation) = remediation {
+            error = error.with_remediation(remediation);
+        }
+        // Which service failed tells a rate-limited SEC apart from the API
+        let mut details = serde_json::Map::new();
+        if let Some(upstream) = err.upstream() {
+            details.insert("upstream".to_string(), json!(upstream.as_str()));
+        }
+        if let CoreError::Api(ApiError::ApiError { status, .. }) = err.root() {
+            details.insert("status".to_string(), json!(status));
+        }
+        // Lets the server ask the user for the missing identity
+        if let CoreError::Sec(SecError::NotConfigured(_)) = err.root() {
+            details.insert("setting".to_string(), json!(SEC_IDENTITY_SETTING));
+        }
+        if !details.is_empty() {
+            error = error.with_details(Value::Object(details));
+        }
+        if let Some(context) = err.context() {
+            error = error.with_context(context.clone());
+        }
+
+        if err.is_retryable() {
+            error.retryable(err.retry_after())
+        } else {
+            error
+        }
+    }
+}
+
+impl From<ApiError> for ToolError {
+    fn from(err: ApiError) -> Self {
+        CoreError::from(err).into()
+    }
+}
+
+impl From<SecError> for ToolError {
+    fn from(err: SecError) -> Self {
+        CoreError::from(err).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_serialization() {
+        let value = serde_json::to_value(ToolError::missing_param("cik")).unwrap();
+        assert_eq!(value["code"], "MISSING_PARAM");
+        assert_eq!(value["message"], "Missing required parameter: cik");
+        assert_eq!(value["details"]["parameter"], "cik");
+        assert!(value["remediation"].is_string());
+
+        let value = serde_json::to_value(ToolError::new(ErrorCode::NotFound, "gone")).unwrap();
+        assert!(value.get("details").is_none());
+        assert!(value.get("remediation").is_none());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let value = serde_json::to_value(ToolError::timeout(Duration::from_secs(30), Duration::from_millis(30_004))).unwrap();
+        assert_eq!(value["code"], "TIMEOUT");
+        assert_eq!(value["details"], json!({ "timeout_seconds": 30, "elapsed_ms": 30_004 }));
+        assert_eq!(value["is_retryable"], true);
+    }
+
+    #[test]
+    fn test_from_api_error() {
+        assert_eq!(ToolError::from(ApiError::Unauthorized).code, ErrorCode::Unauthorized);
+        assert_eq!(ToolError::from(ApiError::NotFound).code, ErrorCode::NotFound);
+
+        let err = ToolError::from(ApiError::ApiError {
+            status: 502,
+            message: "Bad gateway".to_string(),
+        });
+        assert_eq!(err.code, ErrorCode::UpstreamError);
+        assert!(err.is_upstream_unavailable());
+        assert_eq!(err.details.unwrap(), json!({ "upstream": "api", "status": 502 }));
+        assert!(!ToolError::from(ApiError::ApiError { status: 400, message: String::new() }).is_upstream_unavailable());
+
+        let err = ToolError::from(SecError::RateLimited);
+        assert_eq!(err.code, ErrorCode::UpstreamRateLimited);
+        assert_eq!(err.details.unwrap()["upstream"], "sec");
+        assert!(err.is_retryable);
+    }
+
+    #[test]
+    fn test_context_in_details() {
+        let err = ToolError::from(
+            CoreError::from(ApiError::ApiError {
+                status: 500,
+                message: "boom".to_string(),
+            })
+            .with_context(ErrorContext::endpoint("forms/13f")),
+        )
+        .with_context(ErrorContext::default().with_tool("get_form13f_submissions"));
+
+        let details = err.details.unwrap();
+        assert_eq!(details["status"], 500);
+        assert_eq!(details["context"]["endpoint"], "forms/13f");
+        assert_eq!(details["context"]["tool"], "get_form13f_submissions");
+    }
+
+    #[test]
+    fn test_retry_hints() {
+        let err = ToolError::from(ApiError::RateLimited { retry_after: Some(30) });
+        assert_eq!(err.code, ErrorCode::UpstreamRateLimited);
+        assert!(err.is_retryable);
+        assert_eq!(err.retry_after_seconds, Some(30));
+
+        let err = ToolError::from(ApiError::ApiError {
+            status: 503,
+            message: "Unavailable".to_string(),
+        });
+        assert!(err.is_retryable);
+        assert_eq!(err.retry_after_seconds, Some(DEFAULT_RETRY_AFTER_SECS));
+
+        let value = serde_json::to_value(ToolError::from(ApiError::NotFound)).unwrap();
+        assert_eq!(value["is_retryable"], false);
+        assert!(value.get("retry_after_seconds").is_none());
+    }
+}
@@ -0,0 +1,178 @@
+//! Message framing for the stdio transport.
+//!
+//! MCP over stdio is newline-delimited JSON, but some clients frame
+//! messages the way LSP does, with `Content-Length` headers. In auto mode
+//! the first message decides, and responses are written in the same style.
+
+use clap::ValueEnum;
+use std::io;
+use std::sync::{Arc, OnceLock};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+
+/// Largest framed message accepted, to bound allocation on a bad header
+const MAX_FRAMED_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Framing {
+    /// Detect from the first message
+    Auto,
+    /// One JSON message per line
+    Newline,
+    /// `Content-Length` headers followed by the message body
+    ContentLength,
+}
+
+impl Framing {
+    /// Framing of a stream whose first non-empty line is `line`
+    fn detect(line: &str) -> Self {
+        let line = line.to_ascii_lowercase();
+        if line.starts_with("content-length:") || line.starts_with("content-type:") {
+            Self::ContentLength
+        } else {
+            Self::Newline
+        }
+    }
+
+    /// Bytes to write for one message
+    pub fn encode(self, message: &str) -> Vec<u8> {
+        match self {
+            Self::ContentLength => {
+                let mut out = format!("Content-Length: {}\r\n\r\n", message.len()).into_bytes();
+                out.extend_from_slice(message.as_bytes());
+                out
+            }
+            Self::Auto | Self::Newline => {
+                let mut out = Vec::with_capacity(message.len() + 1);
+                out.extend_from_slice(message.as_bytes());
+                out.push(b'\n');
+                out
+            }
+        }
+    }
+}
+
+/// Reads JSON-RPC messages in either framing.
+///
+/// The resolved framing is shared through [`MessageReader::framing`] so the
+/// writer can answer in kind.
+pub struct MessageReader<R> {
+    reader: R,
+    framing: Arc<OnceLock<Framing>>,
+}
+
+impl<R: AsyncBufRead + Unpin> MessageReader<R> {
+    pub fn new(reader: R, framing: Framing) -> Self {
+        let resolved = OnceLock::new();
+        if framing != Framing::Auto {
+            let _ = resolved.set(framing);
+        }
+        Self {
+            reader,
+            framing: Arc::new(resolved),
+        }
+    }
+
+    /// The framing in use, unset until auto mode has seen a message
+    pub fn framing(&self) -> Arc<OnceLock<Framing>> {
+        self.framing.clone()
+    }
+
+    /// Next message body, or None at end of input
+    pub async fn next_message(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line).await? == 0 {
+                return Ok(None);
+            }
+            let trimmed = line.trim_end_matches(['\r', '\n']);
+            if trimmed.is_empty() {
+                continue;
+            }
+            let framing = *self.framing.get_or_init(|| Framing::detect(trimmed));
+            return match framing {
+                Framing::ContentLength => {
+                    let first = trimmed.to_string();
+                    self.read_framed(first).await.map(Some)
+                }
+                Framing::Auto | Framing::Newline => Ok(Some(trimmed.to_string())),
+            };
+        }
+    }
+
+    /// Read the rest of a header block starting with `first`, then the body
+    async fn read_framed(&mut self, first: String) -> io::Result<String> {
+        let mut length = None;
+        let mut header = first;
+        loop {
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse::<usize>().ok();
+                }
+            }
+            header.clear();
+            if self.reader.read_line(&mut header).await? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let trimmed_len = header.trim_end_matches(['\r', '\n']).len();
+            if trimmed_len == 0 {
+                break;
+            }
+            header.truncate(trimmed_len);
+        }
+
+        let length = length
+            .filter(|&n| n <= MAX_FRAMED_MESSAGE_BYTES)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing or invalid Content-Length header"))?;
+        let mut body = vec![0; length];
+        self.reader.read_exact(&mut body).await?;
+        String::from_utf8(body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read_all(input: &[u8], framing: Framing) -> (Vec<String>, Option<Framing>) {
+        let mut reader = MessageReader::new(input, framing);
+        let mut messages = Vec::new();
+        while let Some(message) = reader.next_message().await.unwrap() {
+            messages.push(message);
+        }
+        (messages, reader.framing().get().copied())
+    }
+
+    #[tokio::test]
+    async fn test_auto_detects_newline() {
+        let (messages, framing) = read_all(b"{\"id\":1}\n\n{\"id\":2}\r\n", Framing::Auto).await;
+        assert_eq!(messages, vec![r#"{"id":1}"#, r#"{"id":2}"#]);
+        assert_eq!(framing, Some(Framing::Newline));
+    }
+
+    #[tokio::test]
+    async fn test_auto_detects_content_length() {
+        let body = r#"{"id":1,"text":"a\nb"}"#;
+        let input = format!(
+            "Content-Length: {}\r\nContent-Type: application/json\r\n\r\n{}Content-Length: 8\r\n\r\n{{\"id\":2}}",
+            body.len(),
+            body
+        );
+        let (messages, framing) = read_all(input.as_bytes(), Framing::Auto).await;
+        assert_eq!(messages, vec![body, r#"{"id":2}"#]);
+        assert_eq!(framing, Some(Framing::ContentLength));
+    }
+
+    #[tokio::test]
+    async fn test_missing_content_length_is_an_error() {
+        let mut reader = MessageReader::new(&b"Content-Type: application/json\r\n\r\n{}"[..], Framing::ContentLength);
+        let err = reader.next_message().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(Framing::Newline.encode("{}"), b"{}\n");
+        assert_eq!(Framing::ContentLength.encode("{}"), b"Content-Length: 2\r\n\r\n{}");
+    }
+}
@@ -0,0 +1,147 @@
+//! Dispatch table for registry tools.
+//!
+//! Each implemented tool in the core registry has one handler here, keyed by
+//! the same name. Adding a tool means writing its `McpServer` method and
+//! registering it in [`TOOL_HANDLERS`]; a test keeps the table and the
+//! registry in step, so every handler belongs to a registry tool and the
+//! registry tools still without one are the known few that answer
+//! `NOT_IMPLEMENTED`.
+
+use crate::error::ToolError;
+use crate::McpServer;
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+
+/// The future a handler returns, borrowing the server for the call
+pub type HandlerFuture<'a> = Pin<Box<dyn Future<Output = Result<String, ToolError>> + Send + 'a>>;
+
+/// Runs one registry tool
+pub trait ToolHandler: Sync {
+    fn call<'a>(&self, server: &'a McpServer, args: Value) -> HandlerFuture<'a>;
+}
+
+type HandlerFn = for<'a> fn(&'a McpServer, Value) -> HandlerFuture<'a>;
+
+impl ToolHandler for HandlerFn {
+    fn call<'a>(&self, server: &'a McpServer, args: Value) -> HandlerFuture<'a> {
+        self(server, args)
+    }
+}
+
+/// Build the table from `"tool_name" => method` pairs, where `method` is an
+/// `async fn(&self, Value) -> Result<String, ToolError>` on `McpServer`
+macro_rules! tool_handlers {
+    ($($name:literal => $method:ident,)*) => {
+        static TOOL_HANDLERS: &[(&str, HandlerFn)] = &[
+            $(($name, {
+                fn handler(server: &McpServer, args: Value) -> HandlerFuture<'_> {
+                    Box::pin(server.$method(args))
+                }
+                handler
+            }),)*
+        ];
+    };
+}
+
+tool_handlers! {
+    // Company Data
+    "get_company_financials" => get_company_financials,
+    "get_company_calendar" => get_company_calendar,
+    "get_company_filings" => get_company_filings,
+    "compare_financials_timeseries" => compare_financials_timeseries,
+    "get_red_flags" => get_red_flags,
+
+    // SEC Documents
+    "extract_document_text" => extract_document_text,
+    "read_filing_section" => read_filing_section,
+    "search_company_filings_text" => search_company_filings_text,
+    "get_filing_exhibits" => get_filing_exhibits,
+
+    // Institutional Filings
+    "get_form13f_submissions" => get_form13f_submissions,
+    "get_form13f_submission" => get_form13f_submission,
+    "get_13f_activity" => get_13f_activity,
+    "get_form4_filing" => get_form4_filing,
+    "get_form4_filings" => get_form4_filings,
+    "get_insider_sentiment" => get_insider_sentiment,
+
+    // ETF Data
+    "get_etf_holdings" => get_etf_holdings,
+    "resolve_etf_identifiers" => resolve_etf_identifiers,
+
+    // Form ADV
+    "get_form_adv_firms" => get_form_adv_firms,
+    "get_form_adv_firm" => get_form_adv_firm,
+    "get_form_adv_firm_direct_owners" => get_form_adv_firm_direct_owners,
+    "get_form_adv_firm_indirect_owners" => get_form_adv_firm_indirect_owners,
+    "get_form_adv_firm_ownership_chain" => get_form_adv_firm_ownership_chain,
+    "get_form_adv_owners" => get_form_adv_owners,
+    "get_form_adv_firm_private_funds" => get_form_adv_firm_private_funds,
+    "get_form_adv_funds" => get_form_adv_funds,
+    "get_form_adv_firm_disclosures" => get_form_adv_firm_disclosures,
+    "get_form_adv_firm_brochures" => get_form_adv_firm_brochures,
+    "get_form_adv_firm_filings" => get_form_adv_firm_filings,
+    "get_form_adv_filing" => get_form_adv_filing,
+    "get_form_adv_firm_addresses" => get_form_adv_firm_addresses,
+    "get_form_adv_firm_notice_filings" => get_form_adv_firm_notice_filings,
+    "get_form_adv_firm_related_persons" => get_form_adv_firm_related_persons,
+    "get_form_adv_firm_other_names" => get_form_adv_firm_other_names,
+    "get_form_adv_firm_sma_data" => get_form_adv_firm_sma_data,
+    "get_form_adv_firm_aum_history" => get_form_adv_firm_aum_history,
+
+    // Lobbying
+    "get_lobbying_client_performance" => get_lobbying_client_performance,
+    "get_lobbying_trends" => get_lobbying_trends,
+    "get_lobbying_clients_search" => get_lobbying_clients_search,
+    "get_lobbying_client_detail" => get_lobbying_client_detail,
+
+    // Watchlists
+    "get_lists" => get_lists,
+    "create_list" => create_list,
+    "get_list" => get_list,
+    "get_upcoming_reports" => get_upcoming_reports,
+    "update_list" => update_list,
+    "delete_list" => delete_list,
+    "get_list_history" => get_list_history,
+    "restore_list_snapshot" => restore_list_snapshot,
+
+    // Watchlist Items
+    "add_list_item" => add_list_item,
+    "toggle_list_item" => toggle_list_item,
+    "update_list_item" => update_list_item,
+    "delete_list_item" => delete_list_item,
+}
+
+/// The handler for a registry tool
+pub fn handler(name: &str) -> Option<&'static dyn ToolHandler> {
+    TOOL_HANDLERS
+        .iter()
+        .find(|(tool, _)| *tool == name)
+        .map(|(_, handler)| handler as &dyn ToolHandler)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filing_explorer_core::tools::registry;
+    use std::collections::BTreeSet;
+
+    /// Registry tools that are advertised but not implemented yet
+    const UNIMPLEMENTED: &[&str] = &["fetch_sec_document_direct", "get_sec_document", "get_sec_document_metadata"];
+
+    #[test]
+    fn test_handlers_match_the_registry() {
+        let registered: BTreeSet<String> = registry::tool_definitions()
+            .iter()
+            .filter_map(|tool| tool["name"].as_str().map(str::to_string))
+            .collect();
+        let handled: BTreeSet<String> = TOOL_HANDLERS.iter().map(|(name, _)| name.to_string()).collect();
+        assert_eq!(handled.len(), TOOL_HANDLERS.len(), "a tool is registered twice");
+        assert!(handled.is_subset(&registered), "a handler has no registry tool");
+        let unhandled: Vec<&str> = registered.difference(&handled).map(String::as_str).collect();
+        assert_eq!(unhandled, UNIMPLEMENTED);
+        assert!(handler("get_company_filings").is_some());
+        assert!(handler("search").is_none());
+    }
+}
@@ -0,0 +1,317 @@
+//! Server self-test behind the `server_health` tool.
+//!
+//! Each check reports pass/warn/fail with a remediation hint, so a user (or
+//! model) can tell at a glance why tool calls are failing.
+
+use filing_explorer_core::{
+    api_client::ApiError, sec_client::SecError, text_cache::TextCache, ApiClient, Config, SecClient,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// Result of a single health check
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            message: message.into(),
+            remediation: None,
+        }
+    }
+
+    fn warn(name: &'static str, message: impl Into<String>, remediation: &str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            message: message.into(),
+            remediation: Some(remediation.to_string()),
+        }
+    }
+
+    fn fail(name: &'static str, message: impl Into<String>, remediation: &str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            message: message.into(),
+            remediation: Some(remediation.to_string()),
+        }
+    }
+}
+
+/// Combine checks into a report with an overall status
+pub fn report(checks: Vec<Check>) -> Value {
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    let (passed, warnings, failures) = (
+        count(CheckStatus::Pass),
+        count(CheckStatus::Warn),
+        count(CheckStatus::Fail),
+    );
+    let overall = if failures > 0 {
+        "unhealthy"
+    } else if warnings > 0 {
+        "degraded"
+    } else {
+        "healthy"
+    };
+
+    json!({
+        "status": overall,
+        "version": env!("CARGO_PKG_VERSION"),
+        "summary": { "passed": passed, "warnings": warnings, "failures": failures },
+        "checks": checks
+    })
+}
+
+/// Check that the config file exists and parses
+pub fn check_config(path: Option<&Path>, env: &[&str]) -> Check {
+    const NAME: &str = "config";
+    const FIX: &str = "Open the FilingExplorer settings app and save your settings.";
+
+    let from_env = format!("using {} from the environment", env.join(", "));
+    let path = match path {
+        Some(path) if path.exists() => path,
+        // Credentials injected through the client's env block need no file
+        _ if !env.is_empty() => return Check::pass(NAME, format!("No config file; {}", from_env)),
+        Some(path) => return Check::fail(NAME, format!("No config file at {}", path.display()), FIX),
+        None => return Check::fail(NAME, "Could not determine the config directory", FIX),
+    };
+    match fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|s| serde_json::from_str::<Config>(&s).map_err(|e| e.to_string()))
+    {
+        Ok(_) if !env.is_empty() => Check::pass(NAME, format!("Loaded {}; {}", path.display(), from_env)),
+        Ok(_) => Check::pass(NAME, format!("Loaded {}", path.display())),
+        Err(e) => Check::fail(NAME, format!("Could not read {}: {}", path.display(), e), FIX),
+    }
+}
+
+/// Check that the cache directory used for large results is writable
+pub fn check_cache_dir(dir: Option<&Path>) -> Check {
+    const NAME: &str = "cache_directory";
+    const FIX: &str = "Make sure the user cache directory exists and is writable.";
+
+    let Some(dir) = dir else {
+        return Check::warn(NAME, "Could not determine the cache directory", FIX);
+    };
+    let probe = dir.join(".health-check");
+    let result = fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&probe, b"ok"))
+        .and_then(|_| fs::remove_file(&probe));
+    match result {
+        Ok(()) => Check::pass(NAME, format!("{} is writable", dir.display())),
+        Err(e) => Check::warn(NAME, format!("{} is not writable: {}", dir.display(), e), FIX),
+    }
+}
+
+/// Check that the extracted-text cache can be written, and report its size
+pub fn check_text_cache(cache: Option<&TextCache>) -> Check {
+    const NAME: &str = "text_cache";
+    const FIX: &str = "Make sure the user cache directory is writable, or clear the cache in the FilingExplorer settings app.";
+
+    let Some(cache) = cache else {
+        return Check::warn(NAME, "Could not determine the extracted-text cache directory", FIX);
+    };
+    let probe = cache.dir().join(".health-check");
+    let result = fs::create_dir_all(cache.dir())
+        .and_then(|_| fs::write(&probe, b"ok"))
+        .and_then(|_| fs::remove_file(&probe));
+    if let Err(e) = result {
+        return Check::warn(NAME, format!("{} is not writable: {}", cache.dir().display(), e), FIX);
+    }
+    let stats = cache.stats();
+    Check::pass(
+        NAME,
+        format!(
+            "{} documents from {} filings ({} bytes) in {}",
+            stats.documents,
+            stats.filings,
+            stats.bytes,
+            cache.dir().display()
+        ),
+    )
+}
+
+/// Validate the API token, which doubles as the FilingExplorer connectivity check
+pub async fn check_api(config: &Config) -> Vec<Check> {
+    const TOKEN: &str = "api_token";
+    const CONNECTIVITY: &str = "api_connectivity";
+    const TOKEN_FIX: &str = "Set a valid API token in the FilingExplorer settings app.";
+    const NETWORK_FIX: &str = "Check your network connection, proxy, or firewall settings.";
+
+    let token = match config.api_token.as_deref().filter(|t| !t.is_empty()) {
+        Some(token) => token,
+        None => {
+            return vec![
+                Check::fail(TOKEN, "API token not configured", TOKEN_FIX),
+                Check::warn(CONNECTIVITY, "Skipped: no API token", TOKEN_FIX),
+            ]
+        }
+    };
+    let client = match ApiClient::new(token) {
+        Ok(client) => client,
+        Err(e) => {
+            return vec![
+                Check::fail(TOKEN, format!("Could not create API client: {}", e), TOKEN_FIX),
+                Check::warn(CONNECTIVITY, "Skipped: no API client", TOKEN_FIX),
+            ]
+        }
+    };
+
+    match client.validate_token().await {
+        Ok(true) => vec![
+            Check::pass(TOKEN, "API token is valid"),
+            Check::pass(CONNECTIVITY, "FilingExplorer API is reachable"),
+        ],
+        Ok(false) => vec![
+            Check::fail(TOKEN, "API token was rejected", TOKEN_FIX),
+            Check::pass(CONNECTIVITY, "FilingExplorer API is reachable"),
+        ],
+        Err(ApiError::RequestError(e)) => vec![
+            Check::warn(TOKEN, "Could not verify API token", NETWORK_FIX),
+            Check::fail(CONNECTIVITY, format!("FilingExplorer API unreachable: {}", e), NETWORK_FIX),
+        ],
+        Err(e) => vec![
+            Check::warn(TOKEN, format!("Could not verify API token: {}", e), "Try again shortly."),
+            Check::pass(CONNECTIVITY, "FilingExplorer API is reachable"),
+        ],
+    }
+}
+
+/// Check the SEC EDGAR identity and, if set, that EDGAR accepts it
+pub async fn check_sec(config: &Config) -> Vec<Check> {
+    const IDENTITY: &str = "sec_identity";
+    const CONNECTIVITY: &str = "sec_connectivity";
+    const FIX: &str = "Set your name and email for SEC EDGAR in the FilingExplorer settings app.";
+    const NETWORK_FIX: &str = "Check your network connection, proxy, or firewall settings.";
+
+    let client = match SecClient::from_config(config) {
+        Ok(client) => client,
+        Err(SecError::NotConfigured(issue)) => {
+            return vec![
+                Check::warn(IDENTITY, format!("SEC EDGAR identity not usable: {}", issue), FIX),
+                Check::warn(CONNECTIVITY, "Skipped: no SEC identity", FIX),
+            ];
+        }
+        Err(e) => {
+            return vec![
+                Check::pass(IDENTITY, "SEC EDGAR identity is set"),
+                Check::fail(CONNECTIVITY, format!("Could not create SEC client: {}", e), FIX),
+            ];
+        }
+    };
+
+    let identity = Check::pass(IDENTITY, format!("User-Agent: {}", client.user_agent()));
+    let connectivity = match client.check_connectivity().await {
+        Ok(403) => Check::warn(
+            CONNECTIVITY,
+            "SEC EDGAR rejected the User-Agent (HTTP 403)",
+            FIX,
+        ),
+        Ok(status) if status < 500 => Check::pass(CONNECTIVITY, "SEC EDGAR is reachable"),
+        Ok(status) => Check::warn(
+            CONNECTIVITY,
+            format!("SEC EDGAR returned HTTP {}", status),
+            "EDGAR may be having issues; try again later.",
+        ),
+        Err(e) => Check::fail(CONNECTIVITY, format!("SEC EDGAR unreachable: {}", e), NETWORK_FIX),
+    };
+
+    vec![identity, connectivity]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_report_status() {
+        let healthy = report(vec![Check::pass("a", "ok")]);
+        assert_eq!(healthy["status"], "healthy");
+
+        let degraded = report(vec![Check::pass("a", "ok"), Check::warn("b", "hmm", "fix")]);
+        assert_eq!(degraded["status"], "degraded");
+        assert_eq!(degraded["summary"]["warnings"], 1);
+        assert_eq!(degraded["checks"][1]["remediation"], "fix");
+
+        let unhealthy = report(vec![Check::warn("b", "hmm", "fix"), Check::fail("c", "no", "fix")]);
+        assert_eq!(unhealthy["status"], "unhealthy");
+    }
+
+    #[test]
+    fn test_check_config() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.json");
+        assert_eq!(check_config(Some(&path), &[]).status, CheckStatus::Fail);
+        let env = ["FILINGEXPLORER_API_TOKEN"];
+        assert_eq!(check_config(Some(&path), &env).status, CheckStatus::Pass);
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(check_config(Some(&path), &[]).status, CheckStatus::Fail);
+
+        fs::write(&path, r#"{"api_token": "t"}"#).unwrap();
+        assert_eq!(check_config(Some(&path), &[]).status, CheckStatus::Pass);
+        assert_eq!(check_config(None, &[]).status, CheckStatus::Fail);
+        assert_eq!(check_config(None, &env).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_check_cache_dir() {
+        let dir = TempDir::new().unwrap();
+        let cache = dir.path().join("cache");
+        assert_eq!(check_cache_dir(Some(&cache)).status, CheckStatus::Pass);
+        assert!(!cache.join(".health-check").exists());
+    }
+
+    #[test]
+    fn test_check_text_cache() {
+        let dir = TempDir::new().unwrap();
+        let cache = TextCache::new(dir.path().join("text"));
+        cache.put("0000320193-24-000123", None, "full").unwrap();
+        let check = check_text_cache(Some(&cache));
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(check.message.starts_with("1 documents from 1 filings (4 bytes)"));
+        assert_eq!(check_text_cache(None).status, CheckStatus::Warn);
+    }
+
+    #[tokio::test]
+    async fn test_unconfigured_checks_skip_network() {
+        let config = Config::default();
+        let api = check_api(&config).await;
+        assert_eq!(api[0].status, CheckStatus::Fail);
+        assert_eq!(api[1].status, CheckStatus::Warn);
+
+        let sec = check_sec(&config).await;
+        assert_eq!(sec[0].status, CheckStatus::Warn);
+        assert_eq!(sec[1].status, CheckStatus::Warn);
+
+        let config = Config {
+            sec_user_agent_name: Some("Acme".to_string()),
+            sec_user_agent_email: Some("not-an-email".to_string()),
+            ..Config::default()
+        };
+        let sec = check_sec(&config).await;
+        assert!(sec[0].message.contains("'not-an-email' is not a valid contact email"));
+        assert_eq!(sec[1].status, CheckStatus::Warn);
+    }
+}
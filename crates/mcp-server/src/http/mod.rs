@@ -0,0 +1,321 @@
+//! HTTP transports.
+//!
+//! One listener serves both of MCP's HTTP transports: Streamable HTTP from
+//! the 2025-03-26 revision at `/mcp`, and the older HTTP+SSE transport at
+//! `/sse` for clients that predate it. Every client session gets its own
+//! [`Connection`](crate::Connection), so sessions don't see each other's
+//! responses, while all of them share the server's state and caches.
+//! Server metrics are served at `/metrics` in the Prometheus text format.
+
+mod sse;
+mod streamable;
+
+use crate::{shutdown_signal, McpServer, SHUTDOWN_GRACE, WIRE_TARGET};
+use anyhow::Result;
+use futures::stream::{self, StreamExt};
+use http_body_util::{combinators::BoxBody, BodyExt, Full, Limited, StreamBody};
+use hyper::body::{Bytes, Frame, Incoming};
+use hyper::header::{HeaderValue, ACCEPT, CACHE_CONTROL, CONTENT_TYPE, ORIGIN};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{HeaderMap, Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::hash::BuildHasher;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_util::task::TaskTracker;
+use tracing::{debug, error, info, warn};
+
+/// Where Prometheus scrapes server metrics
+const METRICS_PATH: &str = "/metrics";
+
+/// Largest message body accepted
+const MAX_MESSAGE_BYTES: usize = 4 * 1024 * 1024;
+
+/// Idle time after which a comment is sent to keep proxies from closing
+/// an event stream
+const SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
+type Body = BoxBody<Bytes, Infallible>;
+
+struct HttpState {
+    server: McpServer,
+    /// Streamable HTTP sessions, keyed by session id
+    sessions: Mutex<HashMap<String, Arc<streamable::Session>>>,
+    /// Inboxes of open HTTP+SSE sessions, keyed by session id
+    sse_sessions: Mutex<HashMap<String, mpsc::UnboundedSender<String>>>,
+    /// Whether the listener is bound to a loopback address, in which case
+    /// only local web origins may connect
+    loopback: bool,
+    /// Every session's connection task, waited on at shutdown
+    connections: TaskTracker,
+}
+
+/// Serve MCP over HTTP on `listener` until asked to shut down
+pub async fn serve(server: McpServer, listener: TcpListener) -> Result<()> {
+    let address = listener.local_addr()?;
+    let state = Arc::new(HttpState {
+        server,
+        sessions: Mutex::default(),
+        sse_sessions: Mutex::default(),
+        loopback: address.ip().is_loopback(),
+        connections: TaskTracker::new(),
+    });
+    info!(
+        "MCP Server ready. Listening on http://{}{} (HTTP+SSE at {})",
+        address,
+        streamable::MCP_PATH,
+        sse::SSE_PATH
+    );
+
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    error!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            },
+            _ = shutdown_signal() => break,
+        };
+        debug!("Connection from {}", peer);
+        let state = state.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| handle(state.clone(), request));
+            if let Err(e) = http1::Builder::new().serve_connection(TokioIo::new(stream), service).await {
+                debug!("Connection from {} ended: {}", peer, e);
+            }
+        });
+    }
+
+    // Closing the sessions' inboxes lets their connections finish in-flight
+    // requests; connections still open after their grace period are dropped
+    info!("Shutting down");
+    state.sessions.lock().unwrap().clear();
+    state.sse_sessions.lock().unwrap().clear();
+    state.connections.close();
+    if tokio::time::timeout(SHUTDOWN_GRACE * 2, state.connections.wait()).await.is_err() {
+        warn!("{} sessions still open at shutdown", state.connections.len());
+    }
+    info!("Shut down cleanly");
+    Ok(())
+}
+
+async fn handle(state: Arc<HttpState>, request: Request<Incoming>) -> Result<Response<Body>, Infallible> {
+    let origin = request.headers().get(ORIGIN).and_then(|v| v.to_str().ok());
+    if !origin_allowed(origin, state.loopback) {
+        warn!("Rejected request from origin {:?}", origin);
+        return Ok(text(StatusCode::FORBIDDEN, "Origin not allowed"));
+    }
+
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::POST, streamable::MCP_PATH) => streamable::post(state, request).await,
+        (&Method::GET, streamable::MCP_PATH) => streamable::get(state, request),
+        (&Method::DELETE, streamable::MCP_PATH) => streamable::delete(state, request),
+        (&Method::GET, sse::SSE_PATH) => sse::open_session(state),
+        (&Method::POST, sse::MESSAGES_PATH) => sse::post_message(state, request).await,
+        (&Method::GET, METRICS_PATH) => metrics(&state),
+        (_, streamable::MCP_PATH | sse::SSE_PATH | sse::MESSAGES_PATH | METRICS_PATH) => {
+            text(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed")
+        }
+        _ => text(StatusCode::NOT_FOUND, "Not found"),
+    };
+    Ok(response)
+}
+
+fn metrics(state: &HttpState) -> Response<Body> {
+    let mut response = Response::new(Full::new(Bytes::from(state.server.metrics.prometheus())).boxed());
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain; version=0.0.4"));
+    response
+}
+
+/// A request's body as UTF-8, or the error response to send instead
+async fn read_body(request: Request<Incoming>) -> Result<String, Response<Body>> {
+    let body = match Limited::new(request.into_body(), MAX_MESSAGE_BYTES).collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(_) => return Err(text(StatusCode::PAYLOAD_TOO_LARGE, "Message too large")),
+    };
+    String::from_utf8(body.to_vec()).map_err(|_| text(StatusCode::BAD_REQUEST, "Message is not UTF-8"))
+}
+
+/// An event stream sending `preamble`, then each message from `outgoing` as
+/// a `message` event, with keepalive comments while idle. It ends once every
+/// sender is dropped; `guard` is dropped with it, including when the client
+/// disconnects.
+fn event_stream<G: Send + Sync + 'static>(
+    preamble: Option<Bytes>,
+    outgoing: mpsc::UnboundedReceiver<String>,
+    guard: G,
+) -> Response<Body> {
+    let events = stream::unfold((outgoing, guard), |(mut outgoing, guard)| async move {
+        let bytes = match tokio::time::timeout(SSE_KEEPALIVE, outgoing.recv()).await {
+            Ok(Some(message)) => {
+                debug!(target: WIRE_TARGET, "Sending: {}", message);
+                event("message", &message)
+            }
+            Ok(None) => return None,
+            Err(_) => Bytes::from_static(b": keepalive\n\n"),
+        };
+        Some((bytes, (outgoing, guard)))
+    });
+    let body = stream::iter(preamble)
+        .chain(events)
+        .map(|bytes| Ok::<_, Infallible>(Frame::data(bytes)));
+
+    let mut response = Response::new(BodyExt::boxed(StreamBody::new(body)));
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    response
+}
+
+/// One server-sent event. Messages are compact JSON, so `data` is one line.
+fn event(name: &str, data: &str) -> Bytes {
+    Bytes::from(format!("event: {}\ndata: {}\n\n", name, data))
+}
+
+fn text(status: StatusCode, body: &'static str) -> Response<Body> {
+    let mut response = Response::new(Full::new(Bytes::from_static(body.as_bytes())).boxed());
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+    response
+}
+
+fn json(status: StatusCode, body: String) -> Response<Body> {
+    let mut response = Response::new(Full::new(Bytes::from(body)).boxed());
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+/// Whether the `Accept` header lists `media_type`
+fn accepts(headers: &HeaderMap, media_type: &str) -> bool {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.contains(media_type))
+}
+
+fn query_param(query: Option<&str>, name: &str) -> Option<String> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Unguessable session id: the process's random hasher keys applied to the
+/// current time and a counter
+fn new_session_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let keys = RandomState::new();
+    format!("{:016x}{:016x}", keys.hash_one((nanos, 0u8)), keys.hash_one((nanos, 1u8)))
+}
+
+/// Browsers send `Origin`; when listening on loopback only local pages may
+/// connect, which blocks DNS rebinding attacks from remote sites. Clients
+/// other than browsers send no origin.
+fn origin_allowed(origin: Option<&str>, loopback: bool) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    if !loopback {
+        return true;
+    }
+    let host = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    let host = match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::ConfigSource;
+
+    /// Serve on a free loopback port, returning its base URL
+    pub(super) async fn start() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = McpServer::new(ConfigSource::default()).unwrap();
+        tokio::spawn(serve(server, listener));
+        base
+    }
+
+    /// Read the stream until a whole event arrives, returning (name, data)
+    pub(super) async fn next_event(response: &mut reqwest::Response, buffer: &mut String) -> (String, String) {
+        loop {
+            if let Some(end) = buffer.find("\n\n") {
+                let raw: String = buffer.drain(..end + 2).collect();
+                let field = |name: &str| {
+                    raw.lines()
+                        .find_map(|line| line.strip_prefix(name))
+                        .unwrap_or_default()
+                        .to_string()
+                };
+                return (field("event: "), field("data: "));
+            }
+            let chunk = response.chunk().await.unwrap().expect("stream ended");
+            buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_routing() {
+        let base = start().await;
+        let client = reqwest::Client::new();
+        let missing = client.get(format!("{}/nope", base)).send().await.unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        let wrong_method = client.put(format!("{}{}", base, sse::SSE_PATH)).send().await.unwrap();
+        assert_eq!(wrong_method.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let rebinding = client
+            .post(format!("{}{}", base, streamable::MCP_PATH))
+            .header(ORIGIN, "http://evil.example")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(rebinding.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_metrics() {
+        let base = start().await;
+        let response = reqwest::get(format!("{}{}", base, METRICS_PATH)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/plain; version=0.0.4");
+        let body = response.text().await.unwrap();
+        assert!(body.contains("# TYPE filingexplorer_uptime_seconds gauge"));
+        assert!(body.contains("# TYPE filingexplorer_tool_calls_total counter"));
+    }
+
+    #[test]
+    fn test_origin_allowed() {
+        assert!(origin_allowed(None, true));
+        assert!(origin_allowed(Some("http://localhost:3000"), true));
+        assert!(origin_allowed(Some("http://127.0.0.1"), true));
+        assert!(origin_allowed(Some("http://[::1]:8765"), true));
+        assert!(!origin_allowed(Some("https://localhost.evil.example"), true));
+        assert!(origin_allowed(Some("https://agents.example.com"), false));
+        assert_ne!(new_session_id(), new_session_id());
+        assert_eq!(query_param(Some("a=1&sessionId=x"), "sessionId").as_deref(), Some("x"));
+    }
+}
@@ -0,0 +1,108 @@
+//! HTTP+SSE transport of the 2024-11-05 MCP revision.
+//!
+//! A client opens an event stream with `GET /sse` and is sent an `endpoint`
+//! event naming the URL to POST its messages to. Each POST is acknowledged
+//! with 202 Accepted, and the server's messages arrive on the stream as
+//! `message` events. The session ends when the stream closes.
+
+use super::{event, event_stream, new_session_id, query_param, read_body, text, Body, HttpState};
+use crate::Connection;
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+/// Path clients open the event stream on
+pub const SSE_PATH: &str = "/sse";
+
+/// Path clients POST their messages to
+pub const MESSAGES_PATH: &str = "/messages";
+
+/// Removes a session once its event stream is dropped, which closes its
+/// inbox and ends its connection
+struct SessionGuard {
+    state: Arc<HttpState>,
+    id: String,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        debug!("SSE session {} closed", self.id);
+        self.state.sse_sessions.lock().unwrap().remove(&self.id);
+    }
+}
+
+/// Start a session and return its event stream
+pub(super) fn open_session(state: Arc<HttpState>) -> Response<Body> {
+    let id = new_session_id();
+    let (output, outgoing) = mpsc::unbounded_channel::<String>();
+    let inbox = Connection::spawn(state.server.for_connection(), output, &state.connections);
+    state.sse_sessions.lock().unwrap().insert(id.clone(), inbox);
+    info!("SSE session {} opened", id);
+
+    let endpoint = event("endpoint", &format!("{}?sessionId={}", MESSAGES_PATH, id));
+    event_stream(Some(endpoint), outgoing, SessionGuard { state, id })
+}
+
+/// Queue a POSTed message on its session
+pub(super) async fn post_message(state: Arc<HttpState>, request: Request<Incoming>) -> Response<Body> {
+    let Some(id) = query_param(request.uri().query(), "sessionId") else {
+        return text(StatusCode::BAD_REQUEST, "Missing sessionId");
+    };
+    let Some(inbox) = state.sse_sessions.lock().unwrap().get(&id).cloned() else {
+        return text(StatusCode::NOT_FOUND, "Unknown session");
+    };
+
+    let message = match read_body(request).await {
+        Ok(message) => message,
+        Err(response) => return response,
+    };
+    match inbox.send(message.trim().to_string()) {
+        Ok(()) => text(StatusCode::ACCEPTED, "Accepted"),
+        Err(_) => text(StatusCode::NOT_FOUND, "Unknown session"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{next_event, start};
+    use super::*;
+    use hyper::header::CONTENT_TYPE;
+    use serde_json::{json, Value};
+
+    #[tokio::test]
+    async fn test_sse_session() {
+        let base = start().await;
+        let client = reqwest::Client::new();
+        let mut stream = client.get(format!("{}{}", base, SSE_PATH)).send().await.unwrap();
+        assert_eq!(stream.headers()[CONTENT_TYPE], "text/event-stream");
+
+        let mut buffer = String::new();
+        let (name, endpoint) = next_event(&mut stream, &mut buffer).await;
+        assert_eq!(name, "endpoint");
+        assert!(endpoint.starts_with("/messages?sessionId="));
+
+        let post = client
+            .post(format!("{}{}", base, endpoint))
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(post.status(), StatusCode::ACCEPTED);
+
+        let (name, data) = next_event(&mut stream, &mut buffer).await;
+        assert_eq!(name, "message");
+        let response: Value = serde_json::from_str(&data).unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["result"], json!({}));
+
+        let unknown = client
+            .post(format!("{}{}?sessionId=nope", base, MESSAGES_PATH))
+            .body("{}")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,329 @@
+//! Streamable HTTP transport of the 2025-03-26 MCP revision.
+//!
+//! Everything goes through one endpoint. Clients POST a message or a batch
+//! of them; requests are answered on that POST, as an event stream when the
+//! client accepts one and as plain JSON otherwise, while notifications and
+//! responses get 202 Accepted. The `initialize` response carries an
+//! `Mcp-Session-Id` header that later requests must echo. A GET opens a
+//! stream for server messages unrelated to any request, and a DELETE ends
+//! the session.
+
+use super::{accepts, event_stream, json, new_session_id, read_body, text, Body, HttpState};
+use crate::{Connection, JsonRpcResponse, WIRE_TARGET};
+use hyper::body::Incoming;
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::{HeaderMap, Request, Response, StatusCode};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+/// The transport's single endpoint
+pub const MCP_PATH: &str = "/mcp";
+
+const SESSION_HEADER: &str = "mcp-session-id";
+
+const EVENT_STREAM: &str = "text/event-stream";
+
+pub(super) struct Session {
+    inbox: mpsc::UnboundedSender<String>,
+    routes: Arc<Mutex<Routes>>,
+}
+
+/// Where the session's outgoing messages go
+#[derive(Default)]
+struct Routes {
+    /// The POST each unanswered request arrived on, keyed by JSON-RPC id
+    requests: HashMap<String, Route>,
+    /// Stream the client opened with GET
+    standalone: Option<mpsc::UnboundedSender<String>>,
+}
+
+struct Route {
+    output: mpsc::UnboundedSender<String>,
+    /// Whether the POST is answered with an event stream, which can also
+    /// carry the server's own requests
+    events: bool,
+}
+
+impl Routes {
+    /// Send a response to the POST its request came on. Server requests and
+    /// notifications go to the GET stream, or else any open request stream.
+    fn dispatch(&mut self, message: String) {
+        self.requests.retain(|_, route| !route.output.is_closed());
+        let value: Value = serde_json::from_str(&message).unwrap_or_default();
+        if value.get("method").is_none() {
+            match self.requests.remove(&value["id"].to_string()) {
+                Some(route) => {
+                    let _ = route.output.send(message);
+                }
+                None => debug!(target: WIRE_TARGET, "No open request for response {}", value["id"]),
+            }
+            return;
+        }
+
+        let output = self
+            .standalone
+            .iter()
+            .filter(|output| !output.is_closed())
+            .chain(self.requests.values().filter(|route| route.events).map(|route| &route.output))
+            .next();
+        match output {
+            Some(output) => {
+                let _ = output.send(message);
+            }
+            None => debug!(target: WIRE_TARGET, "No open stream for {}", value["method"]),
+        }
+    }
+}
+
+/// Start a session whose outgoing messages are routed by [`Routes`]
+fn open_session(state: &HttpState) -> (String, Arc<Session>) {
+    let id = new_session_id();
+    let (output, mut outgoing) = mpsc::unbounded_channel::<String>();
+    let routes = Arc::new(Mutex::new(Routes::default()));
+    let session = Arc::new(Session {
+        inbox: Connection::spawn(state.server.for_connection(), output, &state.connections),
+        routes: routes.clone(),
+    });
+    tokio::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            routes.lock().unwrap().dispatch(message);
+        }
+    });
+    state.sessions.lock().unwrap().insert(id.clone(), session.clone());
+    info!("Session {} opened", id);
+    (id, session)
+}
+
+/// The session named by the request's session header, or the status and
+/// message to respond with instead
+fn find_session(state: &HttpState, headers: &HeaderMap) -> Result<Arc<Session>, (StatusCode, &'static str)> {
+    let Some(id) = headers.get(SESSION_HEADER).and_then(|v| v.to_str().ok()) else {
+        return Err((StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header"));
+    };
+    state
+        .sessions
+        .lock()
+        .unwrap()
+        .get(id)
+        .cloned()
+        .ok_or((StatusCode::NOT_FOUND, "Unknown session"))
+}
+
+fn rpc_error(code: i32, message: &str) -> Response<Body> {
+    let error = JsonRpcResponse::error(Some(Value::Null), code, message);
+    json(StatusCode::BAD_REQUEST, serde_json::to_string(&error).unwrap())
+}
+
+fn is_request(message: &Value) -> bool {
+    message.get("method").is_some() && message.get("id").is_some()
+}
+
+/// Requests, notifications, and responses are all objects with either a
+/// method or an id
+fn is_message(message: &Value) -> bool {
+    message.is_object() && (message.get("method").is_some() || message.get("id").is_some())
+}
+
+pub(super) async fn post(state: Arc<HttpState>, request: Request<Incoming>) -> Response<Body> {
+    let events = accepts(request.headers(), EVENT_STREAM);
+    let headers = request.headers().clone();
+    let body = match read_body(request).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let (batch, messages) = match serde_json::from_str::<Value>(&body) {
+        Ok(Value::Array(messages)) if !messages.is_empty() => (true, messages),
+        Ok(message) if !message.is_array() => (false, vec![message]),
+        Ok(_) => return rpc_error(-32600, "Invalid Request"),
+        Err(_) => return rpc_error(-32700, "Parse error"),
+    };
+    if !messages.iter().all(is_message) {
+        return rpc_error(-32600, "Invalid Request");
+    }
+
+    let initialize = messages.iter().any(|m| m["method"] == "initialize");
+    let (session_id, session) = if initialize {
+        if batch {
+            return rpc_error(-32600, "initialize must not be part of a batch");
+        }
+        let (id, session) = open_session(&state);
+        (Some(id), session)
+    } else {
+        match find_session(&state, &headers) {
+            Ok(session) => (None, session),
+            Err((status, message)) => return text(status, message),
+        }
+    };
+
+    let request_ids: Vec<String> = messages.iter().filter(|m| is_request(m)).map(|m| m["id"].to_string()).collect();
+    if request_ids.is_empty() {
+        for message in &messages {
+            let _ = session.inbox.send(message.to_string());
+        }
+        return text(StatusCode::ACCEPTED, "Accepted");
+    }
+
+    // The POST's answer ends once every request in it has been answered and
+    // its routes dropped
+    let (output, mut answers) = mpsc::unbounded_channel();
+    {
+        let mut routes = session.routes.lock().unwrap();
+        for id in request_ids {
+            let route = Route {
+                output: output.clone(),
+                events,
+            };
+            routes.requests.insert(id, route);
+        }
+    }
+    drop(output);
+    for message in &messages {
+        let _ = session.inbox.send(message.to_string());
+    }
+
+    let mut response = if events {
+        event_stream(None, answers, ())
+    } else {
+        let mut responses = Vec::new();
+        while let Some(answer) = answers.recv().await {
+            responses.push(serde_json::from_str::<Value>(&answer).unwrap_or_default());
+        }
+        match (batch, responses.pop()) {
+            (false, Some(response)) => json(StatusCode::OK, response.to_string()),
+            (true, Some(last)) => {
+                responses.push(last);
+                json(StatusCode::OK, Value::Array(responses).to_string())
+            }
+            // Every request was cancelled
+            (_, None) => text(StatusCode::ACCEPTED, "Accepted"),
+        }
+    };
+    if let Some(id) = session_id {
+        let header = HeaderValue::from_str(&id).expect("session ids are hex");
+        response.headers_mut().insert(HeaderName::from_static(SESSION_HEADER), header);
+    }
+    response
+}
+
+/// Open the stream for server messages unrelated to a request, replacing
+/// any earlier one
+pub(super) fn get(state: Arc<HttpState>, request: Request<Incoming>) -> Response<Body> {
+    if !accepts(request.headers(), EVENT_STREAM) {
+        return text(StatusCode::NOT_ACCEPTABLE, "GET must accept text/event-stream");
+    }
+    let session = match find_session(&state, request.headers()) {
+        Ok(session) => session,
+        Err((status, message)) => return text(status, message),
+    };
+    let (output, outgoing) = mpsc::unbounded_channel();
+    session.routes.lock().unwrap().standalone = Some(output);
+    event_stream(None, outgoing, ())
+}
+
+/// End a session. Its connection lets running tool calls finish.
+pub(super) fn delete(state: Arc<HttpState>, request: Request<Incoming>) -> Response<Body> {
+    let Some(id) = request.headers().get(SESSION_HEADER).and_then(|v| v.to_str().ok()) else {
+        return text(StatusCode::BAD_REQUEST, "Missing Mcp-Session-Id header");
+    };
+    match state.sessions.lock().unwrap().remove(id) {
+        Some(_) => {
+            info!("Session {} closed", id);
+            text(StatusCode::OK, "Session closed")
+        }
+        None => text(StatusCode::NOT_FOUND, "Unknown session"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{next_event, start};
+    use super::*;
+    use hyper::header::CONTENT_TYPE;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let base = start().await;
+        let url = format!("{}{}", base, MCP_PATH);
+        let client = reqwest::Client::new();
+
+        let init = client
+            .post(&url)
+            .header("Accept", "application/json")
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": { "protocolVersion": "2025-03-26", "capabilities": {} }
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(init.status(), StatusCode::OK);
+        let session = init.headers()[SESSION_HEADER].to_str().unwrap().to_string();
+        let body: Value = init.json().await.unwrap();
+        assert_eq!(body["result"]["protocolVersion"], "2025-03-26");
+
+        let post = |body: Value, accept: &'static str| {
+            client
+                .post(&url)
+                .header("Accept", accept)
+                .header(SESSION_HEADER, &session)
+                .json(&body)
+                .send()
+        };
+        let initialized = post(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}), "application/json");
+        assert_eq!(initialized.await.unwrap().status(), StatusCode::ACCEPTED);
+
+        let batch = json!([
+            {"jsonrpc": "2.0", "id": 2, "method": "ping"},
+            {"jsonrpc": "2.0", "id": "three", "method": "ping"}
+        ]);
+        let answers: Value = post(batch, "application/json").await.unwrap().json().await.unwrap();
+        let mut ids: Vec<String> = answers.as_array().unwrap().iter().map(|a| a["id"].to_string()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["\"three\"", "2"]);
+
+        let mut stream = post(json!({"jsonrpc": "2.0", "id": 4, "method": "ping"}), "application/json, text/event-stream")
+            .await
+            .unwrap();
+        assert_eq!(stream.headers()[CONTENT_TYPE], EVENT_STREAM);
+        let (_, data) = next_event(&mut stream, &mut String::new()).await;
+        assert_eq!(serde_json::from_str::<Value>(&data).unwrap()["id"], 4);
+        assert!(stream.chunk().await.unwrap().is_none(), "stream ends after its responses");
+
+        let deleted = client.delete(&url).header(SESSION_HEADER, &session).send().await.unwrap();
+        assert_eq!(deleted.status(), StatusCode::OK);
+        let gone = post(json!({"jsonrpc": "2.0", "id": 5, "method": "ping"}), "application/json");
+        assert_eq!(gone.await.unwrap().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bad_requests() {
+        let base = start().await;
+        let url = format!("{}{}", base, MCP_PATH);
+        let client = reqwest::Client::new();
+
+        let no_session = client
+            .post(&url)
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(no_session.status(), StatusCode::BAD_REQUEST);
+
+        let unparseable = client.post(&url).body("{not json").send().await.unwrap();
+        assert_eq!(unparseable.status(), StatusCode::BAD_REQUEST);
+        let error: Value = unparseable.json().await.unwrap();
+        assert_eq!(error["error"]["code"], -32700);
+
+        let empty_batch = client.post(&url).body("[]").send().await.unwrap();
+        assert_eq!(empty_batch.status(), StatusCode::BAD_REQUEST);
+
+        let get = client.get(&url).header(SESSION_HEADER, "nope").send().await.unwrap();
+        assert_eq!(get.status(), StatusCode::NOT_ACCEPTABLE);
+    }
+}
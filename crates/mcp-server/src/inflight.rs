@@ -0,0 +1,126 @@
+//! Coalescing of identical in-flight tool calls.
+//!
+//! When the same read-only call arrives again while the first is still
+//! running (typically a client retry), the second caller waits for and
+//! shares the first call's result instead of hitting the API again.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// Key identifying a tool call by name and arguments.
+///
+/// `serde_json` objects serialize with sorted keys, so argument order
+/// doesn't affect the key.
+pub fn call_key(tool: &str, args: &Value) -> String {
+    format!("{}:{}", tool, args)
+}
+
+pub struct InFlight<T> {
+    pending: Mutex<HashMap<String, watch::Receiver<Option<T>>>>,
+}
+
+/// Removes the pending entry even if the leading call is cancelled
+struct PendingGuard<'a, T> {
+    inflight: &'a InFlight<T>,
+    key: &'a str,
+}
+
+impl<T> Drop for PendingGuard<'_, T> {
+    fn drop(&mut self) {
+        self.inflight.pending.lock().unwrap().remove(self.key);
+    }
+}
+
+impl<T: Clone> InFlight<T> {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `f` unless an identical call is already running, in which case
+    /// wait for that call and return a copy of its result.
+    pub async fn run<F, Fut>(&self, key: String, f: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let existing = {
+            let mut pending = self.pending.lock().unwrap();
+            match pending.get(&key) {
+                Some(rx) => Err(rx.clone()),
+                None => {
+                    let (tx, rx) = watch::channel(None);
+                    pending.insert(key.clone(), rx);
+                    Ok(tx)
+                }
+            }
+        };
+
+        match existing {
+            Ok(tx) => {
+                let _guard = PendingGuard {
+                    inflight: self,
+                    key: &key,
+                };
+                let result = f().await;
+                let _ = tx.send(Some(result.clone()));
+                result
+            }
+            Err(mut rx) => {
+                tracing::debug!("Coalescing duplicate in-flight call: {}", key);
+                let shared = rx.wait_for(|v| v.is_some()).await.ok().and_then(|v| v.clone());
+                match shared {
+                    Some(result) => result,
+                    // The original call was cancelled; run our own
+                    None => f().await,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_call_key_ignores_argument_order() {
+        let a = call_key("get_etf_holdings", &json!({"identifier": "SPY", "limit": 10}));
+        let b = call_key("get_etf_holdings", &json!({"limit": 10, "identifier": "SPY"}));
+        assert_eq!(a, b);
+        assert_ne!(a, call_key("get_etf_holdings", &json!({"identifier": "QQQ", "limit": 10})));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_result() {
+        let inflight = Arc::new(InFlight::<usize>::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let run = |inflight: Arc<InFlight<usize>>, calls: Arc<AtomicUsize>| async move {
+            inflight
+                .run("k".to_string(), || async {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    calls.fetch_add(1, Ordering::SeqCst) + 1
+                })
+                .await
+        };
+
+        let (a, b) = tokio::join!(
+            run(inflight.clone(), calls.clone()),
+            run(inflight.clone(), calls.clone())
+        );
+        assert_eq!((a, b), (1, 1));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Completed calls are not cached
+        assert_eq!(run(inflight.clone(), calls.clone()).await, 2);
+    }
+}
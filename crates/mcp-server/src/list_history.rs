@@ -0,0 +1,175 @@
+//! Local watchlist history.
+//!
+//! Before a tool changes a watchlist, the list's current contents are saved
+//! as a JSON snapshot under the config directory, one folder per list. The
+//! snapshots let `get_list_history` show what a list looked like before a
+//! change and `restore_list_snapshot` roll it back.
+
+use chrono::Utc;
+use filing_explorer_core::tools::watchlists;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Subdirectory of the config dir that holds watchlist snapshots
+const HISTORY_DIR: &str = "list-history";
+
+/// Snapshots kept per list; older ones are pruned as new ones are saved
+pub const MAX_SNAPSHOTS_PER_LIST: usize = 50;
+
+/// Directory used for watchlist snapshots
+pub fn history_dir() -> Option<PathBuf> {
+    filing_explorer_core::Config::config_dir()
+        .ok()
+        .map(|dir| dir.join(HISTORY_DIR))
+}
+
+/// A watchlist as it was before a change
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub snapshot_id: String,
+    pub list_id: String,
+    pub list_name: Option<String>,
+    /// RFC 3339 time the snapshot was taken
+    pub taken_at: String,
+    /// Tool whose call triggered the snapshot
+    pub reason: String,
+    /// The `get_list` response
+    pub list: Value,
+}
+
+impl Snapshot {
+    /// History entry without the list contents
+    pub fn summary(&self) -> Value {
+        json!({
+            "snapshot_id": self.snapshot_id,
+            "list_id": self.list_id,
+            "list_name": self.list_name,
+            "taken_at": self.taken_at,
+            "reason": self.reason,
+            "item_count": watchlists::list_items(&self.list).len(),
+        })
+    }
+}
+
+/// Keep IDs usable as directory names
+fn safe_name(id: &str) -> String {
+    id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '-' })
+        .collect()
+}
+
+/// Save `list` before `reason` changes it. `requested` is the ID or name the
+/// tool was called with, used when the response doesn't include the ID.
+pub fn record(dir: &Path, requested: &str, reason: &str, list: &Value) -> std::io::Result<Snapshot> {
+    let list_id = watchlists::list_id(list).unwrap_or_else(|| requested.to_string());
+    let list_dir = dir.join(safe_name(&list_id));
+    fs::create_dir_all(&list_dir)?;
+
+    let now = Utc::now();
+    let mut stamp = now.timestamp_millis();
+    while list_dir.join(format!("{}.json", stamp)).exists() {
+        stamp += 1;
+    }
+    let snapshot = Snapshot {
+        snapshot_id: stamp.to_string(),
+        list_id,
+        list_name: watchlists::list_name(list),
+        taken_at: now.to_rfc3339(),
+        reason: reason.to_string(),
+        list: list.clone(),
+    };
+    let path = list_dir.join(format!("{}.json", snapshot.snapshot_id));
+    fs::write(&path, serde_json::to_string_pretty(&snapshot)?)?;
+
+    let mut saved = snapshot_files(&list_dir);
+    while saved.len() > MAX_SNAPSHOTS_PER_LIST {
+        let _ = fs::remove_file(saved.remove(0));
+    }
+    Ok(snapshot)
+}
+
+/// Snapshot files in a list's directory, oldest first
+fn snapshot_files(list_dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<(i64, PathBuf)> = fs::read_dir(list_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let stamp = path.file_stem()?.to_str()?.parse().ok()?;
+            (path.extension()? == "json").then_some((stamp, path))
+        })
+        .collect();
+    files.sort();
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+fn read_snapshot(path: &Path) -> Option<Snapshot> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Snapshots of the list with ID or name `id_or_name`, newest first. Names
+/// match case-insensitively, so a deleted list can still be found by name.
+pub fn history(dir: &Path, id_or_name: &str) -> Vec<Snapshot> {
+    let mut snapshots: Vec<Snapshot> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .flat_map(|entry| snapshot_files(&entry.path()))
+        .filter_map(|path| read_snapshot(&path))
+        .filter(|snapshot| {
+            snapshot.list_id == id_or_name
+                || snapshot
+                    .list_name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(id_or_name))
+        })
+        .collect();
+    snapshots.sort_by(|a, b| b.taken_at.cmp(&a.taken_at).then(b.snapshot_id.cmp(&a.snapshot_id)));
+    snapshots
+}
+
+/// One snapshot of the list with ID or name `id_or_name`
+pub fn load(dir: &Path, id_or_name: &str, snapshot_id: &str) -> Option<Snapshot> {
+    history(dir, id_or_name)
+        .into_iter()
+        .find(|snapshot| snapshot.snapshot_id == snapshot_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_load() {
+        let dir = TempDir::new().unwrap();
+        let before = json!({"data": {"id": 7, "name": "Tech", "items": [{"cik": "1"}, {"cik": "2"}]}});
+        let first = record(dir.path(), "Tech", "delete_list_item", &before).unwrap();
+        assert_eq!(first.list_id, "7");
+        let after = json!({"data": {"id": 7, "name": "Tech", "items": [{"cik": "1"}]}});
+        let second = record(dir.path(), "7", "delete_list", &after).unwrap();
+        assert_ne!(first.snapshot_id, second.snapshot_id);
+
+        let by_name = history(dir.path(), "tech");
+        assert_eq!(by_name.len(), 2);
+        assert_eq!(by_name[0].reason, "delete_list");
+        assert_eq!(by_name[1].summary()["item_count"], 2);
+        assert_eq!(history(dir.path(), "7"), by_name);
+        assert!(history(dir.path(), "other").is_empty());
+
+        assert_eq!(load(dir.path(), "7", &first.snapshot_id), Some(first));
+        assert!(load(dir.path(), "7", "1").is_none());
+    }
+
+    #[test]
+    fn test_old_snapshots_pruned() {
+        let dir = TempDir::new().unwrap();
+        let list = json!({"id": "abc", "items": []});
+        for _ in 0..MAX_SNAPSHOTS_PER_LIST + 3 {
+            record(dir.path(), "abc", "update_list", &list).unwrap();
+        }
+        assert_eq!(history(dir.path(), "abc").len(), MAX_SNAPSHOTS_PER_LIST);
+    }
+}
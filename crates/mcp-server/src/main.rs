@@ -1,24 +1,99 @@
 //! FilingExplorer MCP Server
 //!
-//! A headless MCP server that communicates via stdio (stdin/stdout).
-//! Spawned by Claude Desktop as a subprocess.
+//! A headless MCP server that communicates via stdio (stdin/stdout), or
+//! over HTTP with `--transport http`. Spawned by Claude Desktop as a
+//! subprocess.
 //!
-//! Implements the Progressive Discovery pattern with 3 meta-tools:
+//! Implements the Progressive Discovery pattern with 4 meta-tools:
 //! - list_tool_categories
 //! - search_tools
+//! - describe_tool
 //! - execute_tool
 
+mod cache;
+mod cli;
+mod client_log;
+mod client_requests;
+mod coerce;
+mod completion;
+mod config_watch;
+mod elicitation;
+mod error;
+mod framing;
+mod handlers;
+mod health;
+mod http;
+mod inflight;
+mod list_history;
+mod metadata;
+mod metrics;
+mod output;
+mod retry;
+mod scheduler;
+mod socket;
+mod tickers;
+mod trace;
+mod validate;
+
 use anyhow::Result;
+use cache::{ResponseCache, ResultCache};
+use clap::Parser;
+use config_watch::ConfigWatcher;
+use cli::{Cli, ConfigSource, Transport};
+use client_log::{ClientLog, ClientLogs, LogLevel, WIRE_TARGET};
+use client_requests::ClientRequests;
+use elicitation::MissingSetting;
+use error::{ErrorCode, ToolError};
+use framing::{Framing, MessageReader};
+use inflight::InFlight;
+use metadata::{CacheStatus, ResultMeta};
+use metrics::Metrics;
+use retry::RetryPolicy;
+use scheduler::Scheduler;
+use tickers::TickerResolver;
+use trace::{ConnectionTrace, Direction, Trace};
 use filing_explorer_core::{
-    tools::{get_categories, search_tools, DetailLevel},
-    ApiClient, Config,
+    call_stats::{self, CallStats, Source},
+    cancellation,
+    params::{self, DateBound},
+    plugins::{self, PluginTarget, PluginTool},
+    tools::{
+        company, digest, etf, exhibits, filing_search, financials, get_categories, institutional, lobbying,
+        markdown::{self, OutputFormat},
+        red_flags,
+        paged_stream::{PagedStream, DEFAULT_STREAM_PAGE_SIZE},
+        pagination::{self, PageRequest, PageStyle},
+        prompts,
+        registry,
+        resources::{self, ResourceUri},
+        search_tools, sec_documents, watchlists, DetailLevel,
+    },
+    api_client::ApiError,
+    config::ConfigError,
+    result_store::{ResultStore, StoredResult},
+    text_cache::TextCache,
+    text_extraction::truncate_for_llm,
+    usage::{ToolCall, UsageStore},
+    ApiClient, Config, CoreError, ErrorContext, SecClient,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::io::{self, BufRead, Write};
-use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{debug, error, info, warn};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, RwLock, Semaphore};
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{debug, error, field, info, info_span, warn, Instrument};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
 // ============================================================================
 // JSON-RPC TYPES (MCP is JSON-RPC 2.0 over stdio)
@@ -73,12 +148,20 @@ impl JsonRpcResponse {
             }),
         }
     }
+
+    fn with_data(mut self, data: Value) -> Self {
+        if let Some(error) = &mut self.error {
+            error.data = Some(data);
+        }
+        self
+    }
 }
 
 // ============================================================================
 // MCP TOOL DEFINITIONS
 // ============================================================================
 
+/// The server's own tools. Only built once, by [`own_tool_definitions`].
 fn build_tool_definitions() -> Value {
     json!([
         {
@@ -105,26 +188,143 @@ fn build_tool_definitions() -> Value {
                     }
                 },
                 "required": ["q"]
+            },
+            "outputSchema": {
+                "type": "object",
+                "description": "Matches grouped by entity type, as returned by the API"
+            }
+        },
+        {
+            "name": "server_health",
+            "description": "Run diagnostics on server configuration and connectivity: config file, API token validity, SEC EDGAR identity, cache directories, and outbound network access. Run this first when tools are failing.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            },
+            "outputSchema": {
+                "type": "object",
+                "properties": {
+                    "status": { "type": "string", "enum": ["healthy", "degraded", "unhealthy"] },
+                    "version": { "type": "string" },
+                    "summary": {
+                        "type": "object",
+                        "properties": {
+                            "passed": { "type": "integer" },
+                            "warnings": { "type": "integer" },
+                            "failures": { "type": "integer" }
+                        }
+                    },
+                    "checks": { "type": "array", "items": { "type": "object" } }
+                },
+                "required": ["status", "version", "summary", "checks"]
+            }
+        },
+        {
+            "name": "get_rate_limit_status",
+            "description": "Show the FilingExplorer API request budget from the latest response: limit, remaining requests, and when it resets. Check this before large batches of calls.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            },
+            "outputSchema": {
+                "type": "object",
+                "properties": {
+                    "nearly_exhausted": { "type": "boolean" },
+                    "warning": { "type": ["string", "null"] },
+                    "limit": { "type": ["integer", "null"] },
+                    "remaining": { "type": ["integer", "null"] },
+                    "reset_at": {},
+                    "observed_at": {},
+                    "message": { "type": "string", "description": "Set instead of the budget before the first API response" }
+                }
+            }
+        },
+        {
+            "name": "continue_result",
+            "description": "Fetch the next part of a tool result that was truncated for size. Pass the continuation from the result's _truncated summary or truncation note.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "continuation": {
+                        "type": "string",
+                        "description": "Continuation token from a truncated result, passed unchanged"
+                    }
+                },
+                "required": ["continuation"]
+            }
+        },
+        {
+            "name": "get_usage_stats",
+            "description": "Report which tools have been called most and which fail most, from the usage counts kept beside the config file. Counts stay on this machine.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "limit": {
+                        "type": "integer",
+                        "default": 10,
+                        "description": "Number of tools in each ranking"
+                    }
+                }
+            }
+        },
+        {
+            "name": "get_server_metrics",
+            "description": "Report this server process's uptime, calls and error rates per tool, and latency percentiles over each tool's recent calls. Counts start when the server starts.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {}
+            }
+        },
+        {
+            "name": "clear_cache",
+            "description": "Forget cached tool results so the next calls fetch fresh data. Results are otherwise reused for a while: filings and holdings for 2 hours, financials and filed documents for a day; watchlists are never cached.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tool": {
+                        "type": "string",
+                        "description": "Only forget this tool's results (default: every cached result)"
+                    }
+                }
             }
         },
         {
             "name": "list_tool_categories",
-            "description": "List all available tool categories. Use this first to discover what capabilities are available.",
+            "description": "List all available tool categories. Use this first to discover what capabilities are available. Each category has a tool_count of callable tools and a planned_count of tools not available yet; categories with implemented false have none to call.",
             "inputSchema": {
                 "type": "object",
                 "properties": {
                     "detail_level": {
                         "type": "string",
-                        "enum": ["summary", "with_tool_names", "with_descriptions"],
+                        "enum": ["summary", "with_tool_names", "with_descriptions", "tree"],
                         "default": "with_descriptions",
-                        "description": "Level of detail to return"
+                        "description": "Level of detail to return; tree nests related categories, such as the Form ADV ones, under their group"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum categories to return (default: 20, max: 100)"
+                    },
+                    "cursor": {
+                        "type": "string",
+                        "description": "next_cursor from a previous call; repeat the other arguments unchanged to fetch the following page"
                     }
                 }
+            },
+            "outputSchema": {
+                "type": "object",
+                "properties": {
+                    "total_categories": { "type": "integer" },
+                    "total_tools": { "type": "integer" },
+                    "total_planned_tools": { "type": "integer", "description": "Announced tools that can't be called yet" },
+                    "categories": { "type": "array", "items": { "type": "object" } },
+                    "next_cursor": { "type": ["string", "null"] }
+                },
+                "required": ["total_categories", "total_tools", "categories"]
             }
         },
         {
             "name": "search_tools",
-            "description": "Search for tools by keyword. Returns matching tools with relevance scores.",
+            "description": "Search for tools by keyword. Returns matching tools with relevance scores. Misspelled words are corrected, with the corrected query in did_you_mean, and common phrases such as \"insider trades\" also match the tools for them (listed in expanded_terms).",
             "inputSchema": {
                 "type": "object",
                 "properties": {
@@ -134,16 +334,70 @@ fn build_tool_definitions() -> Value {
                     },
                     "category": {
                         "type": "string",
-                        "description": "Optional category filter"
+                        "description": "Optional category filter; a category group such as form_adv covers every category in it"
+                    },
+                    "tag": {
+                        "type": "string",
+                        "enum": registry::TAGS.iter().map(|(tag, _)| *tag).collect::<Vec<_>>(),
+                        "description": "Optional tag filter, e.g. read-only for tools that change nothing, or requires-token for tools that call the FilingExplorer API"
                     },
                     "detail_level": {
                         "type": "string",
                         "enum": ["names_only", "with_descriptions", "full_schema"],
                         "default": "with_descriptions",
                         "description": "Level of detail to return"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "description": "Maximum matches to return (default: 20, max: 100)"
+                    },
+                    "cursor": {
+                        "type": "string",
+                        "description": "next_cursor from a previous call; repeat the other arguments unchanged to fetch the following page"
                     }
                 },
                 "required": ["query"]
+            },
+            "outputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "category_filter": { "type": ["string", "null"] },
+                    "tag_filter": { "type": "string" },
+                    "match_count": { "type": "integer" },
+                    "matches": { "type": "array", "items": { "type": "object" } },
+                    "next_cursor": { "type": ["string", "null"] },
+                    "error": { "type": "string" }
+                },
+                "required": ["query", "match_count", "matches"]
+            }
+        },
+        {
+            "name": "describe_tool",
+            "description": "Get one tool's description, examples, tags, and full input and output schemas by name. Cheaper than searching again with full_schema when you already know which tool to call.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tool_name": {
+                        "type": "string",
+                        "description": "Name of the tool to describe"
+                    }
+                },
+                "required": ["tool_name"]
+            },
+            "outputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "category": { "type": "string" },
+                    "description": { "type": "string" },
+                    "keywords": { "type": "array", "items": { "type": "string" } },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "examples": { "type": "array", "items": { "type": "string" } },
+                    "inputSchema": { "type": "object" },
+                    "outputSchema": { "type": "object" }
+                },
+                "required": ["name", "category", "inputSchema"]
             }
         },
         {
@@ -159,39 +413,167 @@ fn build_tool_definitions() -> Value {
                     "arguments": {
                         "type": "object",
                         "description": "Arguments to pass to the tool"
-                    }
+                    },
+                    "format": format_property()
                 },
                 "required": ["tool_name"]
             }
+        },
+        {
+            "name": BATCH_TOOL,
+            "description": "Run several tools at once, e.g. the same tool for several companies, instead of one call per tool. Calls run concurrently and each succeeds or fails on its own; results come back in the order given.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "calls": {
+                        "type": "array",
+                        "minItems": 1,
+                        "maxItems": MAX_BATCH_CALLS,
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "tool_name": {
+                                    "type": "string",
+                                    "description": "Name of the tool to execute"
+                                },
+                                "arguments": {
+                                    "type": "object",
+                                    "description": "Arguments to pass to the tool"
+                                },
+                                "format": format_property()
+                            },
+                            "required": ["tool_name"]
+                        },
+                        "description": "Tools to run, each with its arguments"
+                    },
+                    "format": format_property()
+                },
+                "required": ["calls"]
+            }
         }
     ])
 }
 
+/// The `format` argument every tool call accepts
+fn format_property() -> Value {
+    json!({
+        "type": "string",
+        "enum": ["json", "markdown"],
+        "description": "Return the result as JSON or as Markdown tables, which take less context. Defaults to the configured output format, or json."
+    })
+}
+
+/// Meta-tool that runs several registry tools in one call
+const BATCH_TOOL: &str = "execute_tools_batch";
+
+/// Most calls one batch may hold
+const MAX_BATCH_CALLS: usize = 20;
+
+/// A batch call's result as JSON, and how the caches answered it
+type BatchCallFuture<'a> = Pin<Box<dyn Future<Output = (Result<Value, ToolError>, Option<CacheStatus>)> + Send + 'a>>;
+
+/// Meta-tools for progressive discovery, left out of tools/list when every
+/// tool is listed directly
+const DISCOVERY_TOOLS: &[&str] = &["list_tool_categories", "search_tools", "describe_tool", "execute_tool"];
+
+/// The tools tools/list returns under `config`. Both listings are built on
+/// first use; plugins are installed at startup, before any listing.
+fn listed_tools(config: &Config) -> &'static [Value] {
+    static ALL_TOOLS: OnceLock<Vec<Value>> = OnceLock::new();
+    if config.expose_all_tools {
+        ALL_TOOLS.get_or_init(build_all_tool_definitions)
+    } else {
+        own_tool_definitions()
+    }
+}
+
+/// Tools listed when `expose_all_tools` is set: the server's own tools other
+/// than the discovery meta-tools, then every registry tool with its schemas
+fn build_all_tool_definitions() -> Vec<Value> {
+    let own = own_tool_definitions()
+        .iter()
+        .filter(|t| !DISCOVERY_TOOLS.iter().any(|name| t["name"] == *name))
+        .cloned();
+    let registered = registry::tool_definitions().into_iter().map(|mut tool| {
+        if let Some(properties) = tool.pointer_mut("/inputSchema/properties").and_then(|p| p.as_object_mut()) {
+            properties.insert("format".to_string(), format_property());
+        }
+        tool
+    });
+    own.chain(registered).collect()
+}
+
 // ============================================================================
 // SERVER STATE
 // ============================================================================
 
+/// Settings and the clients built from them. Cheap to clone, so handlers
+/// take a snapshot instead of holding the lock across upstream calls.
+#[derive(Clone)]
 struct ServerState {
-    #[allow(dead_code)]
-    config: Config,
-    api_client: Option<ApiClient>,
+    source: ConfigSource,
+    config: Arc<Config>,
+    api_client: Option<Arc<ApiClient>>,
+    /// The SEC client, or the error tools report when the settings lack a
+    /// usable identity
+    sec_client: Result<Arc<SecClient>, ToolError>,
+    scheduler: Arc<Scheduler>,
 }
 
 impl ServerState {
-    fn new() -> Self {
-        let config = Config::load_or_default();
-        let api_client = config
-            .api_token
-            .as_ref()
-            .and_then(|token| ApiClient::new(token).ok());
+    fn new(source: ConfigSource) -> Result<Self, ConfigError> {
+        let config = source.load()?;
+        Ok(Self::from_config(source, config))
+    }
+
+    fn from_config(source: ConfigSource, config: Config) -> Self {
+        let api_client = config.api_token.as_ref().and_then(|token| {
+            match source.api_base_url() {
+                Some(base_url) => ApiClient::with_base_url(token, base_url),
+                None => ApiClient::new(token),
+            }
+            .ok()
+            .map(Arc::new)
+        });
+        let sec_client = SecClient::from_config(&config)
+            .map(Arc::new)
+            .map_err(|e| ToolError::from(CoreError::from(e)));
+        let scheduler = Arc::new(Scheduler::from_limits(&config.category_limits));
 
-        Self { config, api_client }
+        Self {
+            source,
+            config: Arc::new(config),
+            api_client,
+            sec_client,
+            scheduler,
+        }
     }
 
-    fn ensure_api_client(&self) -> Result<&ApiClient, String> {
-        self.api_client
-            .as_ref()
-            .ok_or_else(|| "API token not configured. Please run the settings app.".to_string())
+    /// Apply settings loaded from the config file. Clients are only rebuilt
+    /// when credentials changed (or no API client exists yet), so their rate
+    /// limiters and connection pools survive unrelated edits. Returns true if
+    /// a new API client was built.
+    fn apply(&mut self, config: Config) -> bool {
+        if self.api_client.is_none()
+            || config.api_token != self.config.api_token
+            || config.sec_user_agent() != self.config.sec_user_agent()
+        {
+            *self = Self::from_config(self.source.clone(), config);
+            return self.api_client.is_some();
+        }
+        if config.category_limits != self.config.category_limits {
+            self.scheduler = Arc::new(Scheduler::from_limits(&config.category_limits));
+        }
+        self.config = Arc::new(config);
+        false
+    }
+
+    fn ensure_api_client(&self) -> Result<&ApiClient, ToolError> {
+        self.api_client.as_deref().ok_or_else(ToolError::not_configured)
+    }
+
+    fn ensure_sec_client(&self) -> Result<&SecClient, ToolError> {
+        self.sec_client.as_deref().map_err(Clone::clone)
     }
 }
 
@@ -199,23 +581,143 @@ impl ServerState {
 // MCP SERVER
 // ============================================================================
 
+/// How long enrichment lookups stay cached
+const ENRICHMENT_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Default cap on document text returned by read_filing_section
+const DEFAULT_MAX_DOCUMENT_CHARS: u64 = 100_000;
+
+/// Maximum number of [`CONCURRENT_METHODS`] requests executed at the same
+/// time; later ones wait for a slot without holding up the connection
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Requests that may wait on upstream APIs and are independent of each
+/// other, so they run concurrently instead of holding up the connection
+const CONCURRENT_METHODS: &[&str] = &["tools/call", "resources/read"];
+
+/// How long in-flight requests get to finish once a connection closes or the
+/// server is asked to shut down
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Tools returned per tools/list page
+const TOOLS_PAGE_SIZE: u64 = 100;
+
+/// Default and largest number of search_tools matches or tool categories
+/// returned at once
+const DISCOVERY_PAGE_SIZE: u64 = 20;
+const MAX_DISCOVERY_PAGE_SIZE: u64 = 100;
+
+/// MCP revisions the server speaks, newest first. Clients asking for one of
+/// these get it; anything else is offered the newest.
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+#[derive(Clone)]
 struct McpServer {
     state: Arc<RwLock<ServerState>>,
+    cache: Arc<ResponseCache>,
+    /// Whole tool results, reused while their category's TTL lasts
+    results: Arc<ResultCache>,
+    /// The last result of each read-only call, returned when the API or SEC
+    /// can't be reached
+    offline: Option<Arc<ResultStore>>,
+    inflight: Arc<InFlight<Result<String, ToolError>>>,
+    tickers: Arc<TickerResolver>,
+    client: Arc<ClientRequests>,
+    log: Arc<ClientLog>,
+    /// Where protocol traffic is traced, when `--trace` is given
+    trace: Option<Arc<Trace>>,
+    /// Held while asking a user for missing settings, so concurrent calls
+    /// don't prompt for the same value twice
+    elicitation: Arc<tokio::sync::Mutex<()>>,
+    /// Where finished tool calls are counted, beside the config file
+    usage: Option<Arc<UsageStore>>,
+    /// Every connection's channel to its client, for notifications that go
+    /// to all of them
+    clients: Arc<Mutex<Vec<Weak<ClientRequests>>>>,
+    /// Call counts and latencies since the server started
+    metrics: Arc<Metrics>,
 }
 
 impl McpServer {
-    fn new() -> Self {
+    fn new(source: ConfigSource) -> Result<Self, ConfigError> {
+        let usage = source.path().map(|path| Arc::new(UsageStore::beside(&path)));
+        let client = Arc::new(ClientRequests::default());
+        Ok(Self {
+            state: Arc::new(RwLock::new(ServerState::new(source)?)),
+            cache: Arc::new(ResponseCache::new(ENRICHMENT_CACHE_TTL)),
+            results: Arc::new(ResultCache::default()),
+            offline: ResultStore::open_default().ok().map(Arc::new),
+            inflight: Arc::new(InFlight::new()),
+            tickers: Arc::new(TickerResolver::new()),
+            clients: Arc::new(Mutex::new(vec![Arc::downgrade(&client)])),
+            client,
+            log: ClientLogs::global().register(),
+            trace: None,
+            elicitation: Arc::new(tokio::sync::Mutex::new(())),
+            usage,
+            metrics: Arc::new(Metrics::default()),
+        })
+    }
+
+    /// Trace every connection's messages to `trace`
+    fn with_trace(self, trace: Arc<Trace>) -> Self {
+        Self {
+            trace: Some(trace),
+            ..self
+        }
+    }
+
+    /// A handle for another client connection, sharing state and caches but
+    /// with its own channel for requests to the client and log level
+    fn for_connection(&self) -> Self {
+        let client = Arc::new(ClientRequests::default());
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain(|client| client.strong_count() > 0);
+        clients.push(Arc::downgrade(&client));
+        drop(clients);
         Self {
-            state: Arc::new(RwLock::new(ServerState::new())),
+            client,
+            log: ClientLogs::global().register(),
+            ..self.clone()
+        }
+    }
+
+    /// Change the server state, telling every client if the tools it lists
+    /// changed as a result, e.g. after `expose_all_tools` was switched on
+    async fn update_state<T>(&self, change: impl FnOnce(&mut ServerState) -> T) -> T {
+        let mut state = self.state.write().await;
+        // The listed tools only depend on this setting, so there's no need
+        // to build the lists while holding the lock
+        let before = state.config.expose_all_tools;
+        let result = change(&mut state);
+        let changed = state.config.expose_all_tools != before;
+        drop(state);
+        if changed {
+            info!("Tool list changed; notifying clients");
+            let clients: Vec<Arc<ClientRequests>> =
+                self.clients.lock().unwrap().iter().filter_map(Weak::upgrade).collect();
+            for client in clients {
+                client.notify("notifications/tools/list_changed");
+            }
         }
+        result
     }
 
     async fn handle_request(&self, request: JsonRpcRequest) -> JsonRpcResponse {
         match request.method.as_str() {
             "initialize" => self.handle_initialize(request.id, request.params).await,
             "initialized" => JsonRpcResponse::success(request.id, json!({})),
-            "tools/list" => self.handle_list_tools(request.id).await,
+            "tools/list" => self.handle_list_tools(request.id, request.params).await,
             "tools/call" => self.handle_call_tool(request.id, request.params).await,
+            "prompts/list" => self.handle_list_prompts(request.id),
+            "prompts/get" => self.handle_get_prompt(request.id, request.params),
+            "resources/list" => JsonRpcResponse::success(request.id, json!({ "resources": [] })),
+            "resources/templates/list" => JsonRpcResponse::success(request.id, json!({
+                "resourceTemplates": resources::resource_templates()
+            })),
+            "resources/read" => self.handle_read_resource(request.id, request.params).await,
+            "logging/setLevel" => self.handle_set_log_level(request.id, request.params),
+            "completion/complete" => self.handle_complete(request.id, request.params).await,
             "ping" => JsonRpcResponse::success(request.id, json!({})),
             _ => {
                 warn!("Unknown method: {}", request.method);
@@ -224,11 +726,21 @@ impl McpServer {
         }
     }
 
-    async fn handle_initialize(&self, id: Option<Value>, _params: Value) -> JsonRpcResponse {
+    async fn handle_initialize(&self, id: Option<Value>, params: Value) -> JsonRpcResponse {
+        self.client.set_capabilities(&params["capabilities"]);
+        let requested = params["protocolVersion"].as_str().unwrap_or_default();
+        let version = SUPPORTED_PROTOCOL_VERSIONS
+            .iter()
+            .find(|v| **v == requested)
+            .unwrap_or(&SUPPORTED_PROTOCOL_VERSIONS[0]);
         JsonRpcResponse::success(id, json!({
-            "protocolVersion": "2024-11-05",
+            "protocolVersion": version,
             "capabilities": {
-                "tools": {}
+                "tools": { "listChanged": true },
+                "prompts": {},
+                "resources": {},
+                "logging": {},
+                "completions": {}
             },
             "serverInfo": {
                 "name": "filing-explorer",
@@ -237,135 +749,817 @@ impl McpServer {
         }))
     }
 
-    async fn handle_list_tools(&self, id: Option<Value>) -> JsonRpcResponse {
+    async fn handle_list_tools(&self, id: Option<Value>, params: Value) -> JsonRpcResponse {
+        let tools = listed_tools(&self.state.read().await.config);
+        let cursor = params.get("cursor").and_then(|v| v.as_str());
+        let page = match PageRequest::from_args("tools/list", PageStyle::LIMIT_OFFSET, TOOLS_PAGE_SIZE, cursor, None, None) {
+            Ok(page) => page,
+            Err(e) => return JsonRpcResponse::error(id, -32602, e),
+        };
+        let (tools, next) = page.slice(tools.to_vec());
+        let mut result = json!({ "tools": tools });
+        if let Some(next) = next {
+            result["nextCursor"] = json!(next.cursor("tools/list"));
+        }
+        JsonRpcResponse::success(id, result)
+    }
+
+    fn handle_set_log_level(&self, id: Option<Value>, params: Value) -> JsonRpcResponse {
+        let Some(level) = params.get("level").and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, -32602, "Missing 'level' parameter");
+        };
+        match level.parse::<LogLevel>() {
+            Ok(level) => {
+                self.log.set_level(level);
+                JsonRpcResponse::success(id, json!({}))
+            }
+            Err(e) => JsonRpcResponse::error(id, -32602, e),
+        }
+    }
+
+    fn handle_list_prompts(&self, id: Option<Value>) -> JsonRpcResponse {
+        JsonRpcResponse::success(id, json!({
+            "prompts": [{
+                "name": prompts::PLAYBOOK_PROMPT_NAME,
+                "description": "Starter playbook of example FilingExplorer prompts, grouped by category",
+                "arguments": [{
+                    "name": "category",
+                    "description": "Only include one category (e.g. 'sec_documents')",
+                    "required": false
+                }]
+            }]
+        }))
+    }
+
+    /// Suggest values for an argument of a prompt, a resource template, or
+    /// a tool
+    async fn handle_complete(&self, id: Option<Value>, params: Value) -> JsonRpcResponse {
+        let reference = &params["ref"];
+        let known = match reference["type"].as_str() {
+            Some("ref/prompt") => reference["name"] == prompts::PLAYBOOK_PROMPT_NAME,
+            Some("ref/resource") => {
+                let uri = reference["uri"].as_str().unwrap_or_default();
+                [
+                    resources::INDEX_URI_TEMPLATE,
+                    resources::FILING_URI_TEMPLATE,
+                    resources::DOCUMENT_URI_TEMPLATE,
+                    resources::SEC_URI_TEMPLATE,
+                ]
+                .contains(&uri)
+            }
+            Some("ref/tool") => {
+                let name = reference["name"].as_str().unwrap_or_default();
+                registry::tool_exists(name) || own_tools().contains_key(name)
+            }
+            _ => return JsonRpcResponse::error(id, -32602, "ref must be a ref/prompt, ref/resource, or ref/tool"),
+        };
+        if !known {
+            return JsonRpcResponse::error(id, -32602, format!("Unknown reference: {}", reference));
+        }
+        let Some(name) = params.pointer("/argument/name").and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, -32602, "Missing argument name");
+        };
+        let value = params.pointer("/argument/value").and_then(|v| v.as_str()).unwrap_or_default();
+
+        // Resource URIs need CIKs; tools and prompts take tickers too
+        let ciks = reference["type"] == "ref/resource";
+        let config = self.state.read().await.config.clone();
+        let values = completion::complete(&self.tickers, &config, name, value, ciks).await;
+        JsonRpcResponse::success(id, completion::result(values))
+    }
+
+    fn handle_get_prompt(&self, id: Option<Value>, params: Value) -> JsonRpcResponse {
+        let name = params.get("name").and_then(|v| v.as_str()).unwrap_or_default();
+        if name != prompts::PLAYBOOK_PROMPT_NAME {
+            return JsonRpcResponse::error(id, -32602, format!("Unknown prompt: {}", name));
+        }
+        let category = match params.pointer("/arguments/category").and_then(|v| v.as_str()) {
+            Some(c) => match c.parse() {
+                Ok(category) => Some(category),
+                Err(e) => return JsonRpcResponse::error(id, -32602, e),
+            },
+            None => None,
+        };
+
+        let playbook = prompts::format_playbook(&prompts::example_prompts(category));
         JsonRpcResponse::success(id, json!({
-            "tools": build_tool_definitions()
+            "description": "FilingExplorer starter playbook",
+            "messages": [{
+                "role": "user",
+                "content": { "type": "text", "text": playbook }
+            }]
         }))
     }
 
+    async fn handle_read_resource(&self, id: Option<Value>, params: Value) -> JsonRpcResponse {
+        let Some(uri) = params.get("uri").and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, -32602, "Missing 'uri' parameter");
+        };
+        let resource = match ResourceUri::parse(uri) {
+            Ok(resource) => resource,
+            Err(e) => return JsonRpcResponse::error(id, -32602, e),
+        };
+
+        match self.read_resource(&resource).await {
+            Ok((mime_type, text)) => JsonRpcResponse::success(id, json!({
+                "contents": [{ "uri": uri, "mimeType": mime_type, "text": text }]
+            })),
+            Err(e) => {
+                // -32002 is MCP's "resource not found"
+                let code = match e.code {
+                    ErrorCode::NotFound => -32002,
+                    ErrorCode::InvalidParam | ErrorCode::MissingParam => -32602,
+                    _ => -32603,
+                };
+                let message = e.message.clone();
+                JsonRpcResponse::error(id, code, message).with_data(json!(e))
+            }
+        }
+    }
+
+    /// Fetch a resource from EDGAR, returning its MIME type and text
+    async fn read_resource(&self, resource: &ResourceUri) -> Result<(&'static str, String), ToolError> {
+        let state = self.snapshot().await;
+        let sec_client = state.ensure_sec_client()?;
+
+        match resource {
+            ResourceUri::Index { cik } => {
+                let submissions = sec_client.fetch_submissions(cik).await?;
+                let contents = resources::index_contents(cik, &submissions);
+                Ok(("application/json", serde_json::to_string_pretty(&contents).unwrap()))
+            }
+            ResourceUri::Filing { cik, accession } => {
+                let index = format!("{}-index.htm", accession);
+                let (bytes, _) = sec_client.fetch_document(cik, accession, Some(&index)).await?;
+                let documents = exhibits::parse_filing_index(&String::from_utf8_lossy(&bytes));
+                if documents.is_empty() {
+                    return Err(ToolError::new(
+                        ErrorCode::UpstreamError,
+                        format!("No documents found in the filing index for {}", accession),
+                    ));
+                }
+                let contents = resources::filing_contents(cik, accession, &documents);
+                Ok(("application/json", serde_json::to_string_pretty(&contents).unwrap()))
+            }
+            ResourceUri::Document { cik, accession, filename } => {
+                let text = fetch_document_text(sec_client, cik, accession, Some(filename)).await?;
+                Ok(("text/plain", text))
+            }
+        }
+    }
+
     async fn handle_call_tool(&self, id: Option<Value>, params: Value) -> JsonRpcResponse {
         let name = match params.get("name").and_then(|v| v.as_str()) {
             Some(n) => n,
             None => return JsonRpcResponse::error(id, -32602, "Missing 'name' parameter"),
         };
 
-        let arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+        let mut arguments = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+        let format = take_output_format(name, &mut arguments, &self.state.read().await.config);
+        let mut context = ErrorContext::default().with_tool(target_tool(name, &arguments));
+        if let Some(id) = &id {
+            context = context.with_request_id(id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string()));
+        }
 
-        match self.execute_tool(name, arguments).await {
-            Ok(result) => JsonRpcResponse::success(id, json!({
-                "content": [{
-                    "type": "text",
-                    "text": result
-                }]
-            })),
+        let span = info_span!(
+            "tool_call",
+            tool = context.tool.as_deref().unwrap_or(name),
+            request_id = context.request_id.as_deref().unwrap_or(""),
+            args = %args_fingerprint(&arguments),
+            duration_ms = field::Empty,
+            outcome = field::Empty,
+        );
+
+        let started = Instant::now();
+        let stats = Arc::new(CallStats::default());
+        let result = match &format {
+            Ok(_) => {
+                call_stats::scope(stats.clone(), self.execute_tool_cached(name, arguments))
+                    .instrument(span.clone())
+                    .await
+            }
+            Err(e) => (Err(e.clone()), None),
+        };
+        let (result, cache) = result;
+
+        let outcome = match &result {
+            Ok(_) => "ok",
+            Err(e) => e.code.as_str(),
+        };
+        span.record("duration_ms", started.elapsed().as_millis() as u64);
+        span.record("outcome", outcome);
+        span.in_scope(|| info!("Tool call finished"));
+        self.metrics.record(
+            context.tool.as_deref().unwrap_or(name),
+            started.elapsed(),
+            result.as_ref().err().map(|e| e.code.as_str()),
+        );
+        self.record_usage(
+            context.tool.as_deref().unwrap_or(name),
+            started.elapsed(),
+            result.as_ref().err().map(|e| e.code.as_str()),
+            result.as_ref().map_or(0, String::len),
+        );
+
+        let tool = context.tool.clone().unwrap_or_else(|| name.to_string());
+        match result.map_err(|e| e.with_context(context)) {
+            Ok(result) => {
+                let mut meta = ResultMeta::new(started.elapsed(), &stats);
+                if let Some(status) = cache {
+                    meta.set_cache(status);
+                }
+                meta.record_count = metadata::record_count(&result, LIST_RECORD_KEYS);
+                if meta.api_requests > 0 {
+                    meta.warnings.extend(self.rate_limit_warning().await);
+                }
+                // Object results also go out as structuredContent, whatever
+                // format the text is in; prose and spilled previews are text only
+                let structured = match serde_json::from_str::<Value>(&result) {
+                    Ok(value @ Value::Object(_)) => Some(value),
+                    _ => None,
+                };
+                if let Some(warning) = structured.as_ref().and_then(|value| result_shape_warning(&tool, value)) {
+                    warn!("{}", warning);
+                    meta.warnings.push(warning);
+                }
+                let result = markdown::render(result, format.unwrap_or_default());
+                let (text, truncated) = self.limit_output_size(name, &params, result).await;
+                meta.truncated = truncated;
+                let structured = structured.filter(|_| !truncated);
+                let mut response = json!({
+                    "content": [{
+                        "type": "text",
+                        "text": metadata::attach(text, &meta)
+                    }]
+                });
+                if let Some(structured) = structured {
+                    response["structuredContent"] = structured;
+                }
+                JsonRpcResponse::success(id, response)
+            }
             Err(e) => JsonRpcResponse::success(id, json!({
                 "content": [{
                     "type": "text",
-                    "text": format!("Error: {}", e)
+                    "text": serde_json::to_string_pretty(&json!({ "error": e })).unwrap()
                 }],
                 "isError": true
             })),
         }
     }
 
-    async fn execute_tool(&self, name: &str, args: Value) -> Result<String, String> {
-        match name {
-            "search" => self.search(args).await,
-            "list_tool_categories" => self.handle_list_tool_categories(args).await,
-            "search_tools" => self.handle_search_tools(args).await,
-            "execute_tool" => self.handle_execute_tool(args).await,
-            _ => Err(format!("Unknown tool: {}", name)),
-        }
+    /// Count a finished call in the usage file, off the request path
+    fn record_usage(&self, tool: &str, duration: Duration, error: Option<&str>, result_bytes: usize) {
+        let Some(usage) = self.usage.clone() else {
+            return;
+        };
+        let (tool, error) = (tool.to_string(), error.map(str::to_string));
+        tokio::task::spawn_blocking(move || {
+            let call = ToolCall {
+                tool: &tool,
+                duration,
+                error: error.as_deref(),
+                result_bytes,
+            };
+            if let Err(e) = usage.record(&call) {
+                debug!("Could not record tool usage in {}: {}", usage.path().display(), e);
+            }
+        });
     }
 
-    async fn handle_list_tool_categories(&self, args: Value) -> Result<String, String> {
-        let detail_level = args
-            .get("detail_level")
-            .and_then(|v| v.as_str())
-            .unwrap_or("with_descriptions")
-            .parse::<DetailLevel>()
-            .unwrap_or(DetailLevel::WithDescriptions);
+    /// Spill results above the configured size limit to a file, returning a
+    /// preview with the file path instead. The flag is true if the result was
+    /// truncated.
+    async fn limit_output_size(&self, name: &str, params: &Value, result: String) -> (String, bool) {
+        let max_bytes = self.state.read().await.config.max_output_bytes();
+        // Continuations are already cut to size from a saved result
+        if result.len() <= max_bytes || name == output::CONTINUE_TOOL {
+            return (result, false);
+        }
 
-        let result = get_categories(detail_level);
-        Ok(serde_json::to_string_pretty(&result).unwrap())
+        // Name the file after the underlying tool rather than the meta-tool
+        let tool_name = params
+            .pointer("/arguments/tool_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or(name);
+        let Some(dir) = output::output_dir() else {
+            warn!("No cache directory available; returning {} byte result inline", result.len());
+            return (result, false);
+        };
+        match output::spill_large_output(tool_name, &result, max_bytes, &dir) {
+            Ok(text) => (text, true),
+            Err(e) => {
+                warn!("Failed to save large result for {}: {}", tool_name, e);
+                (result, false)
+            }
+        }
     }
 
-    async fn handle_search_tools(&self, args: Value) -> Result<String, String> {
-        let query = args
-            .get("query")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: query")?;
+    /// Run a call, answering read-only registry tools from the result cache
+    /// while it holds them, and from their saved copy on disk when the
+    /// upstream can't be reached. Also returns how the caches answered.
+    async fn execute_tool_cached(&self, name: &str, args: Value) -> (Result<String, ToolError>, Option<CacheStatus>) {
+        let tool = target_tool(name, &args).to_string();
+        if !registry::tool_exists(&tool) || !is_read_only_tool(&tool) {
+            return (self.execute_tool(name, args).await, None);
+        }
+        let ttl = cache::result_ttl(&tool);
 
-        let category = args.get("category").and_then(|v| v.as_str());
+        let mut tool_args = match name {
+            "execute_tool" => args.get("arguments").cloned().unwrap_or_else(|| json!({})),
+            _ => args.clone(),
+        };
+        coerce_call_args(&tool, &mut tool_args);
+        let key = ResultCache::key(&tool, &tool_args);
+        if let Some(result) = ttl.and_then(|_| self.results.get(&key)) {
+            return (Ok(result), Some(CacheStatus::Hit));
+        }
 
-        let detail_level = args
-            .get("detail_level")
-            .and_then(|v| v.as_str())
-            .unwrap_or("with_descriptions")
-            .parse::<DetailLevel>()
-            .unwrap_or(DetailLevel::WithDescriptions);
+        let result = self.execute_tool(name, args).await;
+        match &result {
+            Ok(text) => {
+                if let Some(ttl) = ttl {
+                    self.results.insert(key.clone(), text.clone(), ttl);
+                }
+                self.save_offline_copy(key, text.clone()).await;
+            }
+            Err(e) if e.is_upstream_unavailable() => {
+                if let Some(stored) = self.offline_copy(key).await {
+                    warn!("{} failed ({}); returning the result saved at {}", tool, e, stored.cached_at);
+                    let status = CacheStatus::Offline {
+                        cached_at: stored.cached_at,
+                        reason: e.message.clone(),
+                    };
+                    return (Ok(stored.result), Some(status));
+                }
+            }
+            Err(_) => {}
+        }
+        (result, ttl.map(|_| CacheStatus::Miss))
+    }
 
-        let result = search_tools(query, category, detail_level);
-        Ok(serde_json::to_string_pretty(&result).unwrap())
+    /// Save a result on disk for use while offline
+    async fn save_offline_copy(&self, key: String, result: String) {
+        let Some(store) = self.offline.clone() else {
+            return;
+        };
+        let _ = tokio::task::spawn_blocking(move || {
+            if let Err(e) = store.put(&key, &result) {
+                debug!("Could not save result in {}: {}", store.dir().display(), e);
+            }
+        })
+        .await;
     }
 
-    async fn handle_execute_tool(&self, args: Value) -> Result<String, String> {
-        let tool_name = args
-            .get("tool_name")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: tool_name")?;
+    async fn offline_copy(&self, key: String) -> Option<StoredResult> {
+        let store = self.offline.clone()?;
+        tokio::task::spawn_blocking(move || store.get(&key)).await.ok().flatten()
+    }
+
+    async fn execute_tool(&self, name: &str, mut args: Value) -> Result<String, ToolError> {
+        coerce_call_args(name, &mut args);
+        validate_call_args(name, &args)?;
+
+        // Identical concurrent reads (e.g. client retries) share one upstream call
+        if is_read_only_tool(target_tool(name, &args)) {
+            let key = inflight::call_key(name, &args);
+            return self
+                .inflight
+                .run(key, || self.execute_tool_uncoalesced(name, args))
+                .await;
+        }
+        self.execute_tool_uncoalesced(name, args).await
+    }
+
+    async fn execute_tool_uncoalesced(&self, name: &str, args: Value) -> Result<String, ToolError> {
+        // Pick up a token configured after startup without needing a restart
+        if self.state.read().await.api_client.is_none() {
+            self.reload_credentials().await;
+        }
+
+        // Held until the call finishes so category concurrency limits apply
+        let scheduler = self.state.read().await.scheduler.clone();
+        let _admission = scheduler
+            .admit(registry::get_tool_category(target_tool(name, &args)))
+            .await;
+
+        let result = self.dispatch_with_retry(name, &args).await;
+
+        // The user may have replaced a bad token since we loaded the config
+        if matches!(&result, Err(e) if e.code == ErrorCode::Unauthorized)
+            && self.reload_credentials().await
+        {
+            return self.dispatch_with_retry(name, &args).await;
+        }
+
+        // Ask the user for a missing token or SEC identity and try again
+        let missing = match &result {
+            Err(e) if e.code == ErrorCode::NotConfigured => MissingSetting::from_error(e),
+            _ => None,
+        };
+        if let Some(setting) = missing {
+            if self.elicit_setting(setting).await {
+                return self.dispatch_with_retry(name, &args).await;
+            }
+        }
+        result
+    }
+
+    /// Dispatch a call, waiting and running it again while the upstream
+    /// rate limits it, within the bounds of the default [`RetryPolicy`] and
+    /// the configured time budget
+    async fn dispatch_with_retry(&self, name: &str, args: &Value) -> Result<String, ToolError> {
+        // Each call of a batch gets a budget of its own
+        if name == BATCH_TOOL {
+            return self.dispatch_tool(name, args.clone()).await;
+        }
+        self.with_retry(name, || self.dispatch_tool(name, args.clone())).await
+    }
+
+    /// Run `call`, and again while it is rate limited, failing once the
+    /// attempts and the waits between them exceed the configured time budget
+    /// so a hung upstream call can't stall the session
+    async fn with_retry<F, Fut>(&self, name: &str, call: F) -> Result<String, ToolError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<String, ToolError>>,
+    {
+        let policy = RetryPolicy::default();
+        let budget = self.state.read().await.config.tool_timeout();
+        let started = Instant::now();
+        let deadline = tokio::time::Instant::from_std(started + budget);
+        let mut attempt = 0;
+        loop {
+            let Ok(result) = tokio::time::timeout_at(deadline, call()).await else {
+                warn!("{} timed out after {:?}", name, budget);
+                return Err(ToolError::timeout(budget, started.elapsed()));
+            };
+            let wait = match &result {
+                Err(e) => policy.delay(attempt, e),
+                Ok(_) => None,
+            };
+            // A retry that can't start within the budget isn't worth the wait
+            let Some(wait) = wait.filter(|wait| started.elapsed() + *wait < budget) else {
+                return result;
+            };
+            attempt += 1;
+            info!("{} was rate limited; retry {} of {} in {:?}", name, attempt, policy.max_retries, wait);
+            call_stats::record_retry(wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Ask the user for a missing setting through the client and save it to
+    /// the config file. Returns true once the setting is available.
+    async fn elicit_setting(&self, setting: MissingSetting) -> bool {
+        if !self.client.supports_elicitation() {
+            return false;
+        }
+        let _prompt = self.elicitation.lock().await;
+        // Another call may have asked while this one waited
+        if self.setting_configured(setting).await {
+            return true;
+        }
+
+        let Some(path) = self.state.read().await.source.path() else {
+            return false;
+        };
+        let result = match self.client.request("elicitation/create", setting.request()).await {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("Could not ask for {:?}: {}", setting, e);
+                return false;
+            }
+        };
+        let Some(content) = elicitation::accepted_content(&result) else {
+            info!("User declined to provide {:?}", setting);
+            return false;
+        };
+        if let Err(e) = setting.save(content, &path) {
+            warn!("Could not save {:?} to {}: {}", setting, path.display(), e);
+            return false;
+        }
+        self.reload_credentials().await;
+        self.setting_configured(setting).await
+    }
+
+    async fn setting_configured(&self, setting: MissingSetting) -> bool {
+        let state = self.snapshot().await;
+        match setting {
+            MissingSetting::ApiToken => state.api_client.is_some(),
+            MissingSetting::SecIdentity => state.sec_client.is_ok(),
+        }
+    }
+
+    /// Warning for a nearly exhausted API request budget, if any
+    async fn rate_limit_warning(&self) -> Option<String> {
+        let state = self.snapshot().await;
+        state.api_client.as_ref()?.rate_limit_status()?.warning()
+    }
+
+    /// The current state, cloned out of the lock. A guard held across an
+    /// upstream call would queue a config reload behind the slowest call, and
+    /// every later call behind the reload.
+    async fn snapshot(&self) -> ServerState {
+        self.state.read().await.clone()
+    }
+
+    /// Read the config file again and apply it, returning true if a new API
+    /// client was built. The file is read before the state is locked.
+    async fn reload_config(&self) -> Result<bool, ConfigError> {
+        let source = self.state.read().await.source.clone();
+        let config = source.load()?;
+        Ok(self.update_state(|state| state.apply(config)).await)
+    }
+
+    /// Reload the config from disk, returning true if a new API client was built
+    async fn reload_credentials(&self) -> bool {
+        match self.reload_config().await {
+            Ok(true) => {
+                info!("Loaded updated API credentials from config");
+                true
+            }
+            Ok(false) => false,
+            Err(e) => {
+                warn!("Could not reload config: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Normalize a CIK or ticker argument to a 10-digit CIK
+    async fn resolve_company(&self, config: &Config, value: &str, key: &str) -> Result<String, ToolError> {
+        self.tickers
+            .resolve(config, value)
+            .await
+            .map_err(|e| ToolError::invalid_param(e).with_details(json!({ "parameter": key })))
+    }
+
+    /// Optional company identifier argument, normalized with `resolve_company`
+    async fn company_arg(&self, config: &Config, args: &Value, key: &str) -> Result<Option<String>, ToolError> {
+        match args.get(key).and_then(|v| v.as_str()) {
+            Some(value) => self.resolve_company(config, value, key).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn dispatch_tool(&self, name: &str, args: Value) -> Result<String, ToolError> {
+        let expose_all_tools = self.state.read().await.config.expose_all_tools;
+        match name {
+            "search" => self.search(args).await,
+            // Also answers to the name users tend to ask for when debugging
+            "server_health" | "run_diagnostics" => self.handle_server_health().await,
+            "get_rate_limit_status" => self.handle_rate_limit_status().await,
+            "continue_result" => self.handle_continue_result(args).await,
+            "get_usage_stats" => self.handle_usage_stats(args).await,
+            "get_server_metrics" => Ok(serde_json::to_string_pretty(&self.metrics.report()).unwrap()),
+            "clear_cache" => self.handle_clear_cache(args).await,
+            "list_tool_categories" => self.handle_list_tool_categories(args).await,
+            "search_tools" => self.handle_search_tools(args).await,
+            "describe_tool" => self.handle_describe_tool(args).await,
+            "execute_tool" => self.handle_execute_tool(args).await,
+            BATCH_TOOL => self.handle_execute_tools_batch(args).await,
+            // Listed directly, so called without execute_tool
+            _ if expose_all_tools && registry::tool_exists(name) => self.execute_actual_tool(name, args).await,
+            _ => Err(ToolError::new(ErrorCode::UnknownTool, format!("Unknown tool: {}", name))),
+        }
+    }
+
+    async fn handle_rate_limit_status(&self) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+        let result = match client.rate_limit_status() {
+            Some(status) => json!({
+                "nearly_exhausted": status.is_nearly_exhausted(),
+                "warning": status.warning(),
+                "limit": status.limit,
+                "remaining": status.remaining,
+                "reset_at": status.reset_at,
+                "observed_at": status.observed_at,
+            }),
+            None => json!({
+                "message": "No rate-limit headers seen yet; they are recorded from the first API response this session",
+            }),
+        };
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn handle_server_health(&self) -> Result<String, ToolError> {
+        // Check what is on disk now, not what the server loaded at startup
+        let source = self.state.read().await.source.clone();
+        let config_path = source.path();
+        let (config, env) = source.load_with_env(cli::env_var).map_err(CoreError::from)?;
+        let output_dir = output::output_dir();
+
+        let (api, sec) = tokio::join!(health::check_api(&config), health::check_sec(&config));
+        let mut checks = vec![health::check_config(config_path.as_deref(), &env)];
+        checks.extend(api);
+        checks.extend(sec);
+        checks.push(health::check_cache_dir(output_dir.as_deref()));
+        checks.push(health::check_text_cache(TextCache::open_default().ok().as_ref()));
+
+        Ok(serde_json::to_string_pretty(&health::report(checks)).unwrap())
+    }
+
+    async fn handle_continue_result(&self, args: Value) -> Result<String, ToolError> {
+        let continuation = args
+            .get("continuation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("continuation"))?;
+        let max_bytes = self.state.read().await.config.max_output_bytes();
+        let dir = output::output_dir()
+            .ok_or_else(|| ToolError::new(ErrorCode::NotFound, "No cache directory holds truncated results"))?;
+        output::continue_output(continuation, max_bytes, &dir)
+    }
+
+    async fn handle_usage_stats(&self, args: Value) -> Result<String, ToolError> {
+        let usage = self
+            .usage
+            .as_ref()
+            .ok_or_else(|| ToolError::new(ErrorCode::NotFound, "No config directory to record usage in"))?;
+        let limit = args.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+        Ok(serde_json::to_string_pretty(&usage.load().report(limit)).unwrap())
+    }
+
+    async fn handle_clear_cache(&self, args: Value) -> Result<String, ToolError> {
+        let tool = args.get("tool").and_then(|v| v.as_str());
+        if let Some(tool) = tool.filter(|tool| !registry::tool_exists(tool)) {
+            return Err(ToolError::new(ErrorCode::UnknownTool, format!("Unknown tool: {}", tool)));
+        }
+        let mut cleared = self.results.clear(tool);
+        // Enrichment lookups are shared between tools, so go only with a full clear
+        if tool.is_none() {
+            cleared += self.cache.clear();
+        }
+        Ok(serde_json::to_string_pretty(&json!({ "cleared": cleared, "tool": tool })).unwrap())
+    }
+
+    async fn handle_list_tool_categories(&self, args: Value) -> Result<String, ToolError> {
+        let detail_level = args
+            .get("detail_level")
+            .and_then(|v| v.as_str())
+            .unwrap_or("with_descriptions")
+            .parse::<DetailLevel>()
+            .unwrap_or(DetailLevel::WithDescriptions);
+
+        let page = discovery_page("list_tool_categories", &args)?;
+        let result = pagination::paginate("list_tool_categories", get_categories(detail_level), "categories", &page);
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn handle_search_tools(&self, args: Value) -> Result<String, ToolError> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("query"))?;
+
+        let category = args.get("category").and_then(|v| v.as_str());
+        let tag = args.get("tag").and_then(|v| v.as_str());
+
+        let detail_level = args
+            .get("detail_level")
+            .and_then(|v| v.as_str())
+            .unwrap_or("with_descriptions")
+            .parse::<DetailLevel>()
+            .unwrap_or(DetailLevel::WithDescriptions);
+
+        let page = discovery_page("search_tools", &args)?;
+        let result = pagination::paginate("search_tools", search_tools(query, category, tag, detail_level), "matches", &page);
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn handle_describe_tool(&self, args: Value) -> Result<String, ToolError> {
+        let tool_name = args
+            .get("tool_name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("tool_name"))?;
+        if !registry::tool_exists(tool_name) {
+            return Err(ToolError::new(ErrorCode::UnknownTool, format!("Unknown tool '{}'", tool_name))
+                .with_remediation("Use search_tools to find available tools."));
+        }
+
+        let result = registry::get_tool_metadata(tool_name, DetailLevel::FullSchema);
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn handle_execute_tool(&self, args: Value) -> Result<String, ToolError> {
+        let tool_name = args
+            .get("tool_name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("tool_name"))?;
 
         let tool_args = args.get("arguments").cloned().unwrap_or_else(|| json!({}));
 
         self.execute_actual_tool(tool_name, tool_args).await
     }
 
-    async fn execute_actual_tool(&self, name: &str, args: Value) -> Result<String, String> {
+    /// Run each call in a batch as its own execute_tool call, so each is
+    /// validated, cached, scheduled, and retried as if made alone
+    async fn handle_execute_tools_batch(&self, args: Value) -> Result<String, ToolError> {
+        let calls = args
+            .get("calls")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| ToolError::missing_param("calls"))?
+            .clone();
+
+        let server = self.clone();
+        let outcomes = fan_out(calls, move |call| {
+            let server = server.clone();
+            async move { server.run_batch_call(call).await }
+        })
+        .await;
+
+        let mut failed = 0;
+        let calls = args["calls"].as_array().into_iter().flatten();
+        let results: Vec<Value> = calls
+            .zip(outcomes)
+            .enumerate()
+            .map(|(index, (call, outcome))| {
+                let mut entry = json!({ "index": index, "tool_name": call["tool_name"] });
+                let (result, cache) = outcome.unwrap_or_else(|e| (Err(e.into()), None));
+                match result {
+                    Ok(result) => entry["result"] = result,
+                    Err(e) => {
+                        failed += 1;
+                        entry["error"] = json!(e);
+                    }
+                }
+                if let Some(status) = cache {
+                    entry["cache"] = json!(status.as_str());
+                }
+                entry
+            })
+            .collect();
+        Ok(serde_json::to_string_pretty(&json!({
+            "succeeded": results.len() - failed,
+            "failed": failed,
+            "results": results,
+        }))
+        .unwrap())
+    }
+
+    /// One call of a batch, run like an execute_tool call of its own and
+    /// counted in the metrics and usage under the tool it runs. A call that
+    /// names its own format gets its result in that format; the batch's
+    /// format applies to the rest.
+    ///
+    /// Boxed, as the call comes back through `dispatch_tool` to the batch
+    /// that spawns it.
+    fn run_batch_call(&self, mut call: Value) -> BatchCallFuture<'_> {
+        Box::pin(async move {
+            let own_format = call.get("format").is_some() || call.pointer("/arguments/format").is_some();
+            let format = match take_output_format("execute_tool", &mut call, &self.state.read().await.config) {
+                Ok(format) => format,
+                Err(e) => return (Err(e), None),
+            };
+            let tool = target_tool("execute_tool", &call).to_string();
+
+            let started = Instant::now();
+            let (result, cache) = self.execute_tool_cached("execute_tool", call).await;
+            let error = result.as_ref().err().map(|e| e.code.as_str());
+            self.metrics.record(&tool, started.elapsed(), error);
+            self.record_usage(&tool, started.elapsed(), error, result.as_ref().map_or(0, String::len));
+
+            let result = result.map(|text| match format {
+                OutputFormat::Markdown if own_format => Value::String(markdown::render(text, format)),
+                _ => serde_json::from_str(&text).unwrap_or(Value::String(text)),
+            });
+            (result, cache)
+        })
+    }
+
+    /// Run a registry tool. Callers apply the retry policy and time budget.
+    async fn execute_actual_tool(&self, name: &str, args: Value) -> Result<String, ToolError> {
         debug!("Executing tool: {} with args: {:?}", name, args);
 
-        match name {
-            // Company Data
-            "get_company_financials" => self.get_company_financials(args).await,
-            "get_company_calendar" => self.get_company_calendar(args).await,
-            "get_company_filings" => self.get_company_filings(args).await,
-
-            // Institutional Filings
-            "get_form13f_submissions" => self.get_form13f_submissions(args).await,
-            "get_form13f_submission" => self.get_form13f_submission(args).await,
-            "get_form4_filing" => self.get_form4_filing(args).await,
-
-            // ETF Data
-            "get_etf_holdings" => self.get_etf_holdings(args).await,
-
-            // Form ADV
-            "get_form_adv_firms" => self.get_form_adv_firms(args).await,
-            "get_form_adv_firm" => self.get_form_adv_firm(args).await,
-
-            // Lobbying
-            "get_lobbying_client_performance" => self.get_lobbying_client_performance(args).await,
-            "get_lobbying_clients_search" => self.get_lobbying_clients_search(args).await,
-            "get_lobbying_client_detail" => self.get_lobbying_client_detail(args).await,
-
-            // Watchlists
-            "get_lists" => self.get_lists().await,
-            "create_list" => self.create_list(args).await,
-            "get_list" => self.get_list(args).await,
-            "update_list" => self.update_list(args).await,
-            "delete_list" => self.delete_list(args).await,
-
-            // Watchlist Items
-            "add_list_item" => self.add_list_item(args).await,
-            "toggle_list_item" => self.toggle_list_item(args).await,
-            "update_list_item" => self.update_list_item(args).await,
-            "delete_list_item" => self.delete_list_item(args).await,
+        if let Some(plugin) = plugins::get(name) {
+            return self.run_plugin(plugin, args).await;
+        }
+        match handlers::handler(name) {
+            Some(handler) => handler.call(self, args).await,
+            None if registry::tool_exists(name) => Err(ToolError::new(
+                ErrorCode::NotImplemented,
+                format!("Tool '{}' exists but is not yet implemented", name),
+            )),
+            None => Err(ToolError::new(ErrorCode::UnknownTool, format!("Unknown tool '{}'", name))
+                .with_remediation("Use search_tools to find available tools.")),
+        }
+    }
 
-            _ => {
-                if filing_explorer_core::tools::registry::tool_exists(name) {
-                    Err(format!("Tool '{}' exists but is not yet implemented", name))
-                } else {
-                    Err(format!("Unknown tool '{}'. Use search_tools to find available tools.", name))
-                }
+    /// Run a tool defined in `plugins.json`
+    async fn run_plugin(&self, plugin: &PluginTool, args: Value) -> Result<String, ToolError> {
+        match &plugin.target {
+            PluginTarget::Endpoint(template) => {
+                let (endpoint, params) = plugin
+                    .endpoint_request(template, &args)
+                    .map_err(|name| ToolError::missing_param(&name))?;
+                let state = self.snapshot().await;
+                let client = state.ensure_api_client()?;
+                let result: Value = client.get(&endpoint, Some(params)).await?;
+                Ok(serde_json::to_string_pretty(&result).unwrap())
             }
+            PluginTarget::Command(argv) => PluginTool::run_command(argv, &args)
+                .await
+                .map_err(|e| ToolError::new(ErrorCode::UpstreamError, e.to_string())),
         }
     }
 
@@ -373,14 +1567,14 @@ impl McpServer {
     // TOOL IMPLEMENTATIONS
     // =========================================================================
 
-    async fn search(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn search(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
         let q = args
             .get("q")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: q")?;
+            .ok_or_else(|| ToolError::missing_param("q"))?;
 
         let mut params = std::collections::HashMap::new();
         params.insert("q".to_string(), q.to_string());
@@ -395,150 +1589,1006 @@ impl McpServer {
             params.insert("include_inactive".to_string(), v.to_string());
         }
 
-        let result: Value = client.get("search", Some(params)).await.map_err(|e| e.to_string())?;
+        let result: Value = client.get("search", Some(params)).await?;
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn get_company_financials(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_company_financials(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
-        let company_id = args
-            .get("company_id")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: company_id")?;
+        let company_id = self
+            .company_arg(&state.config, &args, "company_id")
+            .await?
+            .ok_or_else(|| ToolError::missing_param("company_id"))?;
 
         let mut params = std::collections::HashMap::new();
-        if let Some(v) = args.get("period_of_report_date").and_then(|v| v.as_str()) {
-            params.insert("period_of_report_date".to_string(), v.to_string());
+        for key in ["period_of_report_date", "filing_date"] {
+            if let Some(v) = date_arg(&args, key, DateBound::End)? {
+                params.insert(key.to_string(), v);
+            }
         }
         if let Some(v) = args.get("timeframe").and_then(|v| v.as_str()) {
             params.insert("timeframe".to_string(), v.to_string());
         }
-        if let Some(v) = args.get("limit").and_then(|v| v.as_i64()) {
-            params.insert("limit".to_string(), v.to_string());
-        }
+        let style = PageStyle::PageNumber {
+            page: "page",
+            size: "limit",
+        };
+        let page = page_request("get_company_financials", style, 10, &args, "page", "limit")?;
+        page.apply(&mut params);
 
         let endpoint = format!("companies/{}/financials", company_id);
-        let result: Value = client
-            .get(&endpoint, Some(params))
-            .await
-            .map_err(|e| e.to_string())?;
+        let result: Value = client.get(&endpoint, Some(params)).await?;
+        let next = page.next(&result, LIST_RECORD_KEYS);
+        let result = pagination::attach_next_cursor("get_company_financials", result, next);
 
         let count = result.get("count").and_then(|v| v.as_i64()).unwrap_or(0);
         let summary = format!("Found {} financial statement(s) for {}\n\n", count, company_id);
         Ok(format!("{}{}", summary, serde_json::to_string_pretty(&result).unwrap()))
     }
 
-    async fn get_company_calendar(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_company_calendar(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
-        let cik = args
-            .get("company_cik")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: company_cik")?;
+        let cik = self
+            .company_arg(&state.config, &args, "company_cik")
+            .await?
+            .ok_or_else(|| ToolError::missing_param("company_cik"))?;
 
         let endpoint = format!("companies/{}/calendar", cik);
-        let result: Value = client.get(&endpoint, None).await.map_err(|e| e.to_string())?;
+        let result: Value = client.get(&endpoint, None).await?;
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn get_company_filings(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_company_filings(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
-        let cik = args
-            .get("cik")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: cik")?;
-
         let mut params = std::collections::HashMap::new();
         if let Some(v) = args.get("form_type").and_then(|v| v.as_str()) {
             params.insert("form_type".to_string(), v.to_string());
         }
-        if let Some(v) = args.get("page_size").and_then(|v| v.as_i64()) {
+        if let Some(v) = date_arg(&args, "filed_after", DateBound::Start)? {
+            params.insert("filed_after".to_string(), v);
+        }
+        if let Some(v) = date_arg(&args, "filed_before", DateBound::End)? {
+            params.insert("filed_before".to_string(), v);
+        }
+        let all_pages = all_pages_arg(&args);
+        if let (Some(v), false) = (args.get("page_size").and_then(|v| v.as_i64()), all_pages) {
             params.insert("page[size]".to_string(), v.to_string());
         }
 
+        // An array of CIKs fans out into one request, or one stream of
+        // pages, per company
+        if let Some(ciks) = args.get("cik").and_then(|v| v.as_array()) {
+            let mut resolved = Vec::new();
+            for cik in ciks.iter().filter_map(|v| v.as_str()) {
+                resolved.push(self.resolve_company(&state.config, cik, "cik").await?);
+            }
+            let ciks = resolved;
+            if ciks.is_empty() {
+                return Err(ToolError::invalid_param("Parameter cik must contain at least one CIK"));
+            }
+            if ciks.len() > company::MAX_BATCH_COMPANIES {
+                return Err(ToolError::invalid_param(format!(
+                    "Too many CIKs: {} (max {})",
+                    ciks.len(),
+                    company::MAX_BATCH_COMPANIES
+                )));
+            }
+
+            let first = match all_pages {
+                true => Some(page_request(
+                    "get_company_filings",
+                    PageStyle::JSON_API,
+                    DEFAULT_STREAM_PAGE_SIZE,
+                    &args,
+                    "page_offset",
+                    "page_size",
+                )?),
+                false => None,
+            };
+            let client = client.clone();
+            let results = fan_out(ciks.clone(), move |cik| {
+                let client = client.clone();
+                let params = params.clone();
+                let first = first.clone();
+                async move {
+                    let endpoint = format!("companies/{}/filings", cik);
+                    match first {
+                        Some(first) => fetch_all_pages(&client, &endpoint, params, first).await,
+                        None => client.get::<Value>(&endpoint, Some(params)).await,
+                    }
+                    .map_err(|e| CoreError::from(e).with_context(ErrorContext::endpoint(endpoint)))
+                }
+            })
+            .await;
+
+            let results = ciks.into_iter().zip(results).map(|(cik, result)| (cik, result.and_then(|r| r)));
+            let mut merged = company::merge_company_filings(results.collect());
+            resources::annotate_filings(&mut merged, None);
+            return Ok(serde_json::to_string_pretty(&merged).unwrap());
+        }
+
+        let cik = self
+            .company_arg(&state.config, &args, "cik")
+            .await?
+            .ok_or_else(|| ToolError::missing_param("cik"))?;
+
         let endpoint = format!("companies/{}/filings", cik);
-        let result: Value = client.get(&endpoint, Some(params)).await.map_err(|e| e.to_string())?;
+        if all_pages {
+            let first = page_request(
+                "get_company_filings",
+                PageStyle::JSON_API,
+                DEFAULT_STREAM_PAGE_SIZE,
+                &args,
+                "page_offset",
+                "page_size",
+            )?;
+            let mut result = fetch_all_pages(client, &endpoint, params, first).await?;
+            resources::annotate_filings(&mut result, Some(&cik));
+            return Ok(serde_json::to_string_pretty(&result).unwrap());
+        }
+
+        let page = page_request(
+            "get_company_filings",
+            PageStyle::JSON_API,
+            25,
+            &args,
+            "page_offset",
+            "page_size",
+        )?;
+        page.apply(&mut params);
+
+        let result: Value = client.get(&endpoint, Some(params)).await?;
+        let next = page.next(&result, LIST_RECORD_KEYS);
+        let mut result = pagination::attach_next_cursor("get_company_filings", result, next);
+        resources::annotate_filings(&mut result, Some(&cik));
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn get_form13f_submissions(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn compare_financials_timeseries(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
-        let mut params = std::collections::HashMap::new();
-        if let Some(v) = args.get("search").and_then(|v| v.as_str()) {
-            params.insert("search".to_string(), v.to_string());
+        let metric = args
+            .get("metric")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("metric"))?;
+        let keys = financials::metric_keys(metric).ok_or_else(|| {
+            ToolError::invalid_param(format!(
+                "Unknown metric '{}'. Expected one of: {}",
+                metric,
+                financials::metric_names().join(", ")
+            ))
+            .with_details(json!({ "parameter": "metric" }))
+        })?;
+        let timeframe = match args.get("timeframe").and_then(|v| v.as_str()) {
+            Some(v) => v.parse::<financials::Timeframe>().map_err(|e| {
+                ToolError::invalid_param(e).with_details(json!({ "parameter": "timeframe" }))
+            })?,
+            None => financials::Timeframe::Annual,
+        };
+        let since = date_arg(&args, "since", DateBound::Start)?;
+
+        let inputs = args
+            .get("companies")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| ToolError::missing_param("companies"))?;
+        let mut companies = Vec::new();
+        for company in inputs.iter().filter_map(|v| v.as_str()) {
+            companies.push(self.resolve_company(&state.config, company, "companies").await?);
         }
-        if let Some(v) = args.get("limit").and_then(|v| v.as_i64()) {
-            params.insert("limit".to_string(), v.to_string());
+        if companies.is_empty() {
+            return Err(ToolError::invalid_param("Parameter companies must contain at least one company"));
+        }
+        if companies.len() > financials::MAX_COMPARE_COMPANIES {
+            return Err(ToolError::invalid_param(format!(
+                "Too many companies: {} (max {})",
+                companies.len(),
+                financials::MAX_COMPARE_COMPANIES
+            )));
+        }
+
+        let client = client.clone();
+        let responses = fan_out(companies.clone(), move |company| {
+            let client = client.clone();
+            async move {
+                let mut params = std::collections::HashMap::new();
+                params.insert("timeframe".to_string(), timeframe.as_str().to_string());
+                params.insert("limit".to_string(), "50".to_string());
+                let endpoint = format!("companies/{}/financials", company);
+                client
+                    .get(&endpoint, Some(params))
+                    .await
+                    .map_err(|e| CoreError::from(e).with_context(ErrorContext::endpoint(endpoint)))
+            }
+        })
+        .await;
+        let data = companies
+            .into_iter()
+            .zip(responses)
+            .map(|(company, response)| financials::CompanyFinancials {
+                company,
+                response: response.and_then(|r| r),
+            })
+            .collect();
+
+        let comparison = financials::compare_timeseries(metric, keys, timeframe, since.as_deref(), data);
+        Ok(serde_json::to_string_pretty(&comparison).unwrap())
+    }
+
+    /// Run the red flag checks together: Form ADV disclosures, insider
+    /// selling, 8-K auditor changes, and lobbying spend spikes. A check
+    /// without its identifier is skipped.
+    async fn get_red_flags(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+
+        let cik = self.company_arg(&state.config, &args, "cik").await?;
+        let crd = args.get("crd").and_then(|v| v.as_str()).filter(|s| !s.trim().is_empty());
+        let lobbying_client = args
+            .get("lobbying_client")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty());
+        if cik.is_none() && crd.is_none() && lobbying_client.is_none() {
+            return Err(ToolError::invalid_param("Provide at least one of: cik, crd, lobbying_client"));
+        }
+        let days = args
+            .get("lookback_days")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(red_flags::DEFAULT_RED_FLAG_DAYS);
+        if days <= 0 {
+            return Err(ToolError::invalid_param("lookback_days must be positive")
+                .with_details(json!({ "parameter": "lookback_days" })));
         }
+        let now = chrono::Local::now().date_naive();
+        let since = (now - chrono::Duration::days(days)).format("%Y-%m-%d").to_string();
+        let today = now.format("%Y-%m-%d").to_string();
+
+        let insider_selling = async {
+            let Some(cik) = &cik else {
+                return Ok(red_flags::Check::skipped("Pass cik to check insider selling"));
+            };
+            let mut params = std::collections::HashMap::new();
+            params.insert("issuer_cik".to_string(), cik.clone());
+            params.insert("filed_after".to_string(), since.clone());
+            params.insert("limit".to_string(), "100".to_string());
+            let response: Value = client
+                .get("forms/4", Some(params))
+                .await
+                .map_err(|e| CoreError::from(e).with_context(ErrorContext::endpoint("forms/4")))?;
+            Ok::<_, CoreError>(red_flags::insider_selling_check(&institutional::insider_sentiment(&response)))
+        };
+
+        let auditor_changes = async {
+            let Some(cik) = &cik else {
+                return Ok(red_flags::Check::skipped("Pass cik to check for auditor changes"));
+            };
+            let sec_client = match &state.sec_client {
+                Ok(sec_client) => sec_client,
+                Err(e) => return Ok(red_flags::Check::skipped(e.message.clone())),
+            };
+            let forms = vec!["8-K".to_string()];
+            let response = sec_client
+                .full_text_search("\"Item 4.01\"", cik, &forms, Some((&since, &today)))
+                .await?;
+            Ok::<_, CoreError>(red_flags::auditor_change_check(&filing_search::search_hits(&response)))
+        };
+
+        let adv_disclosures = async {
+            let Some(crd) = crd else {
+                return Ok(red_flags::Check::skipped("Pass crd to check Form ADV disclosures"));
+            };
+            let endpoint = format!("forms/adv/firms/{}", crd);
+            let firm: Value = client
+                .get(&endpoint, None)
+                .await
+                .map_err(|e| CoreError::from(e).with_context(ErrorContext::endpoint(endpoint)))?;
+            Ok::<_, CoreError>(red_flags::adv_disclosure_check(&firm))
+        };
+
+        let lobbying_spike = async {
+            let Some(name) = lobbying_client else {
+                return Ok(red_flags::Check::skipped("Pass lobbying_client to check lobbying spend"));
+            };
+            let target = lobbying::TrendTarget::Client(name.to_string());
+            // Most recent first, starting from the current quarter
+            let mut year = chrono::Datelike::year(&now);
+            let mut quarter = (chrono::Datelike::month(&now) - 1) as u8 / 3 + 1;
+            let mut periods = Vec::with_capacity(red_flags::LOBBYING_SPIKE_QUARTERS);
+            for _ in 0..red_flags::LOBBYING_SPIKE_QUARTERS {
+                periods.push((year, quarter));
+                (year, quarter) = if quarter == 1 { (year - 1, 4) } else { (year, quarter - 1) };
+            }
+            let data = fetch_lobbying_periods(client, &target, periods).await;
+            Ok::<_, CoreError>(red_flags::lobbying_spike_check(&lobbying::lobbying_trends(&target, data)))
+        };
 
-        let result: Value = client.get("forms/13f", Some(params)).await.map_err(|e| e.to_string())?;
+        let (insider_selling, auditor_changes, adv_disclosures, lobbying_spike) =
+            tokio::join!(insider_selling, auditor_changes, adv_disclosures, lobbying_spike);
+        let mut report = red_flags::red_flag_report(vec![
+            ("insider_selling", insider_selling),
+            ("auditor_changes", auditor_changes),
+            ("adv_disclosures", adv_disclosures),
+            ("lobbying_spike", lobbying_spike),
+        ]);
+        report["subject"] = json!({ "cik": cik, "crd": crd, "lobbying_client": lobbying_client });
+        report["window"] = json!({ "filed_after": since, "filed_before": today });
+        Ok(serde_json::to_string_pretty(&report).unwrap())
+    }
+
+    async fn extract_document_text(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let sec_client = state.ensure_sec_client()?;
+
+        let cik = self
+            .company_arg(&state.config, &args, "cik")
+            .await?
+            .ok_or_else(|| ToolError::missing_param("cik"))?;
+        let accession_number = args
+            .get("accession_number")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("accession_number"))?;
+        let accession_number = params::normalize_accession(accession_number).map_err(ToolError::invalid_param)?;
+        let filename = args.get("filename").and_then(|v| v.as_str()).filter(|f| !f.is_empty());
+        let max_chars = args
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_MAX_DOCUMENT_CHARS) as usize;
+        let summarize = args.get("summarize").and_then(|v| v.as_bool()).unwrap_or(false);
+        let start_char = args.get("start_char").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+
+        let text = fetch_document_text(sec_client, &cik, &accession_number, filename).await?;
+        let total_chars = text.chars().count();
+        let text = match text.char_indices().nth(start_char) {
+            _ if start_char == 0 => text.as_str(),
+            Some((i, _)) => &text[i..],
+            None => "",
+        };
+        let mut result = json!({
+            "cik": cik,
+            "accession_number": accession_number,
+            "document": filename,
+            "total_chars": total_chars,
+        });
+        if start_char > 0 {
+            result["start_char"] = json!(start_char);
+        }
+        if summarize && total_chars - start_char.min(total_chars) > max_chars {
+            let label = format!("SEC filing {} ({})", accession_number, filename.unwrap_or("full submission"));
+            match self.sample_digest(&label, text).await {
+                Ok((count, digest)) => {
+                    let chunks = digest::digest_chunks(text);
+                    result["chunks_summarized"] = json!(count);
+                    result["digest"] = json!(digest);
+                    result["parts"] = json!(digest::part_pointers(text, &chunks, start_char));
+                    result["note"] = json!(
+                        "To read a part in full, call extract_document_text again with its start_char, and max_chars of at least end_char - start_char"
+                    );
+                    return Ok(serde_json::to_string_pretty(&result).unwrap());
+                }
+                Err(reason) => result["summary_error"] = json!(format!("{}; returning truncated text instead", reason)),
+            }
+        }
+        result["text"] = json!(truncate_for_llm(text, max_chars));
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn get_form13f_submission(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    /// Have the client's model summarize `text` chunk by chunk, returning
+    /// the number of chunks and the joined digest
+    async fn sample_digest(&self, label: &str, text: &str) -> Result<(usize, String), String> {
+        if !self.client.supports_sampling() {
+            return Err("The client does not support sampling".to_string());
+        }
+        let chunks = digest::digest_chunks(text);
+        let mut summaries = Vec::with_capacity(chunks.len());
+        for (i, chunk) in chunks.iter().enumerate() {
+            let request = digest::sampling_request(label, chunk, i, chunks.len());
+            let result = self
+                .client
+                .request("sampling/createMessage", request)
+                .await
+                .map_err(|e| format!("Summarizing part {} of {} failed: {}", i + 1, chunks.len(), e))?;
+            let summary = digest::sampled_text(&result)
+                .ok_or_else(|| format!("The client returned no text for part {} of {}", i + 1, chunks.len()))?;
+            summaries.push(summary);
+        }
+        Ok((chunks.len(), digest::format_digest(&summaries)))
+    }
+
+    /// Latest filing of a form type -> primary document -> text -> section
+    async fn read_filing_section(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
+        let sec_client = state.ensure_sec_client()?;
+
+        let cik = self
+            .company_arg(&state.config, &args, "cik")
+            .await?
+            .ok_or_else(|| ToolError::missing_param("cik"))?;
+        let form_type = args.get("form_type").and_then(|v| v.as_str()).unwrap_or("10-K");
+        let section = args
+            .get("section")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty());
+        let max_chars = args
+            .get("max_chars")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_MAX_DOCUMENT_CHARS) as usize;
+
+        let filing = &latest_filing(client, &cik, form_type).await?;
+        let accession_number = sec_documents::accession_number(filing)
+            .ok_or_else(|| ToolError::new(ErrorCode::UpstreamError, "Filing record has no accession number"))?;
+        let document = sec_documents::primary_document(filing);
+        // Unresolved tickers are passed through, but EDGAR paths need the CIK
+        let filer_cik = sec_documents::filer_cik(filing).unwrap_or(&cik);
+
+        let text = fetch_document_text(sec_client, filer_cik, accession_number, document).await?;
+        let kind = sec_documents::DocumentKind::from_form(form_type);
+
+        let (heading, body) = match section {
+            Some(query) => match sec_documents::find_section(&text, kind, query) {
+                Some(found) => (Some(found.heading), found.text),
+                None => {
+                    return Err(ToolError::new(
+                        ErrorCode::NotFound,
+                        format!("Section '{}' not found in {} {}", query, form_type, accession_number),
+                    )
+                    .with_details(json!({ "available_sections": sec_documents::section_headings(&text, kind) }))
+                    .with_remediation("Pick one of available_sections, or omit section to read the whole document."))
+                }
+            },
+            None => (None, text.as_str()),
+        };
+
+        let total_chars = body.chars().count();
+        Ok(serde_json::to_string_pretty(&json!({
+            "cik": filer_cik,
+            "form_type": form_type,
+            "document_kind": kind.as_str(),
+            "filing_date": company::filing_date(filing),
+            "accession_number": accession_number,
+            "document": document,
+            "section": heading,
+            "total_chars": total_chars,
+            "text": truncate_for_llm(body, max_chars),
+        }))
+        .unwrap())
+    }
+
+    async fn search_company_filings_text(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let sec_client = state.ensure_sec_client()?;
 
-        let filer_cik = args
-            .get("filer_cik")
+        let cik = self
+            .company_arg(&state.config, &args, "cik")
+            .await?
+            .ok_or_else(|| ToolError::missing_param("cik"))?;
+        let query = args
+            .get("query")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: filer_cik")?;
+            .filter(|q| !q.trim().is_empty())
+            .ok_or_else(|| ToolError::missing_param("query"))?;
+        let forms: Vec<String> = match args.get("form_types") {
+            Some(Value::Array(items)) => items.iter().filter_map(|v| v.as_str()).map(str::to_string).collect(),
+            Some(Value::String(s)) => s.split(',').map(|f| f.trim().to_string()).filter(|f| !f.is_empty()).collect(),
+            _ => Vec::new(),
+        };
+        let filed_after = date_arg(&args, "filed_after", DateBound::Start)?;
+        let filed_before = date_arg(&args, "filed_before", DateBound::End)?;
+        let mut page = page_request(
+            "search_company_filings_text",
+            PageStyle::LIMIT_OFFSET,
+            filing_search::DEFAULT_SEARCH_FILINGS as u64,
+            &args,
+            "offset",
+            "limit",
+        )?;
+        page.size = page.size.min(filing_search::MAX_SEARCH_FILINGS as u64);
+        let per_filing = args
+            .get("snippets_per_filing")
+            .and_then(|v| v.as_u64())
+            .map(|n| n as usize)
+            .unwrap_or(filing_search::DEFAULT_SNIPPETS_PER_FILING);
+
+        // EDGAR only filters by date as a range, so fill in an open end
+        let date_range = match (&filed_after, &filed_before) {
+            (None, None) => None,
+            (after, before) => Some((
+                after.clone().unwrap_or_else(|| "2001-01-01".to_string()),
+                before
+                    .clone()
+                    .unwrap_or_else(|| chrono::Local::now().date_naive().format("%Y-%m-%d").to_string()),
+            )),
+        };
+        let response = sec_client
+            .full_text_search(
+                query,
+                &cik,
+                &forms,
+                date_range.as_ref().map(|(a, b)| (a.as_str(), b.as_str())),
+            )
+            .await?;
+
+        let (hits, next) = page.slice(filing_search::search_hits(&response));
+        let terms = Arc::new(filing_search::search_terms(query));
+        let sec_client = sec_client.clone();
+        let filings: Vec<Value> = hits.iter().map(|hit| json!(hit)).collect();
+        let found = fan_out(hits, move |hit| {
+            let sec_client = sec_client.clone();
+            let terms = terms.clone();
+            async move {
+                let text = fetch_document_text(&sec_client, &hit.cik, &hit.accession_number, Some(&hit.document)).await?;
+                let matches = filing_search::keyword_matches(&text, &terms);
+                Ok::<_, CoreError>((matches.len(), filing_search::snippets(&text, &matches, per_filing)))
+            }
+        })
+        .await;
+        let filings: Vec<Value> = filings
+            .into_iter()
+            .zip(found)
+            .map(|(mut filing, found)| {
+                match found.and_then(|r| r) {
+                    Ok((match_count, snippets)) => {
+                        filing["match_count"] = json!(match_count);
+                        filing["snippets"] = json!(snippets);
+                    }
+                    Err(e) => filing["error"] = e.to_json(),
+                }
+                filing
+            })
+            .collect();
+
+        let result = json!({
+            "cik": cik,
+            "query": query,
+            "total_hits": filing_search::total_hits(&response),
+            "returned": filings.len(),
+            "filings": filings,
+        });
+        let result = pagination::attach_next_cursor("search_company_filings_text", result, next);
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn get_filing_exhibits(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let sec_client = state.ensure_sec_client()?;
+
+        let cik = self
+            .company_arg(&state.config, &args, "cik")
+            .await?
+            .ok_or_else(|| ToolError::missing_param("cik"))?;
+        let include_xbrl = args.get("include_xbrl").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        // Without an accession number, list the latest filing of form_type
+        let (cik, accession_number) = match args.get("accession_number").and_then(|v| v.as_str()) {
+            Some(accession_number) => {
+                let accession_number = params::normalize_accession(accession_number).map_err(ToolError::invalid_param)?;
+                (cik, accession_number)
+            }
+            None => {
+                let client = state.ensure_api_client()?;
+                let form_type = args.get("form_type").and_then(|v| v.as_str()).unwrap_or("8-K");
+                let filing = latest_filing(client, &cik, form_type).await?;
+                let accession_number = sec_documents::accession_number(&filing)
+                    .ok_or_else(|| ToolError::new(ErrorCode::UpstreamError, "Filing record has no accession number"))?
+                    .to_string();
+                let filer_cik = sec_documents::filer_cik(&filing).map(str::to_string).unwrap_or(cik);
+                (filer_cik, accession_number)
+            }
+        };
+
+        let index = format!("{}-index.htm", accession_number);
+        let (bytes, _) = sec_client.fetch_document(&cik, &accession_number, Some(&index)).await?;
+        let documents = exhibits::parse_filing_index(&String::from_utf8_lossy(&bytes));
+        if documents.is_empty() {
+            return Err(ToolError::new(
+                ErrorCode::UpstreamError,
+                format!("No documents found in the filing index for {}", accession_number),
+            ));
+        }
+
+        let mut result = exhibits::label_exhibits(&documents, include_xbrl);
+        result["cik"] = json!(cik);
+        result["accession_number"] = json!(accession_number);
+        result["hint"] = json!("Pass an exhibit's document as filename to extract_document_text to read it.");
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn get_form13f_submissions(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
 
         let mut params = std::collections::HashMap::new();
-        if let Some(v) = args.get("period_of_report").and_then(|v| v.as_str()) {
-            params.insert("period_of_report".to_string(), v.to_string());
+        if let Some(v) = args.get("search").and_then(|v| v.as_str()) {
+            params.insert("search".to_string(), v.to_string());
+        }
+        let page = page_request(
+            "get_form13f_submissions",
+            PageStyle::LIMIT_OFFSET,
+            50,
+            &args,
+            "offset",
+            "limit",
+        )?;
+        page.apply(&mut params);
+
+        let result: Value = client.get("forms/13f", Some(params)).await?;
+        let next = page.next(&result, LIST_RECORD_KEYS);
+        let result = pagination::attach_next_cursor("get_form13f_submissions", result, next);
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn get_form13f_submission(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+
+        let filer_cik = self
+            .company_arg(&state.config, &args, "filer_cik")
+            .await?
+            .ok_or_else(|| ToolError::missing_param("filer_cik"))?;
+
+        let mut params = std::collections::HashMap::new();
+        if let Some(v) = date_arg(&args, "period_of_report", DateBound::End)? {
+            params.insert("period_of_report".to_string(), v);
         }
         if let Some(v) = args.get("limit").and_then(|v| v.as_i64()) {
             params.insert("limit".to_string(), v.to_string());
         }
 
+        let aggregate_by = args
+            .get("aggregate_by")
+            .and_then(|v| v.as_str())
+            .map(|s| s.parse::<institutional::AggregateBy>())
+            .transpose()
+            .map_err(ToolError::invalid_param)?;
+
+        // Aggregation covers whatever the API returns, so only page raw holdings
+        let page = match aggregate_by {
+            Some(_) => None,
+            None => Some(page_request(
+                "get_form13f_submission",
+                PageStyle::LIMIT_OFFSET,
+                50,
+                &args,
+                "offset",
+                "limit",
+            )?),
+        };
+        if let Some(page) = &page {
+            page.apply(&mut params);
+        }
+
         let endpoint = format!("forms/13f/{}", filer_cik);
-        let result: Value = client.get(&endpoint, Some(params)).await.map_err(|e| e.to_string())?;
-        Ok(serde_json::to_string_pretty(&result).unwrap())
+        let result: Value = client.get(&endpoint, Some(params)).await?;
+
+        match aggregate_by {
+            Some(by) => {
+                let mut aggregated = institutional::aggregate_holdings(&result, by);
+                aggregated["filer_cik"] = json!(filer_cik);
+                if let Some(period) = args.get("period_of_report") {
+                    aggregated["period_of_report"] = period.clone();
+                }
+                Ok(serde_json::to_string_pretty(&aggregated).unwrap())
+            }
+            None => {
+                let next = page.and_then(|p| p.next(&result, LIST_RECORD_KEYS));
+                let result = pagination::attach_next_cursor("get_form13f_submission", result, next);
+                Ok(serde_json::to_string_pretty(&result).unwrap())
+            }
+        }
     }
 
-    async fn get_form4_filing(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_13f_activity(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+
+        let inputs: Vec<&str> = match args.get("filer_cik") {
+            Some(Value::Array(items)) => items.iter().filter_map(|v| v.as_str()).collect(),
+            Some(Value::String(s)) => vec![s.as_str()],
+            _ => return Err(ToolError::missing_param("filer_cik")),
+        };
+        let mut filers = Vec::new();
+        for filer in inputs {
+            filers.push(self.resolve_company(&state.config, filer, "filer_cik").await?);
+        }
+        if filers.is_empty() {
+            return Err(ToolError::invalid_param("Parameter filer_cik must contain at least one CIK"));
+        }
+        if filers.len() > institutional::MAX_ACTIVITY_FILERS {
+            return Err(ToolError::invalid_param(format!(
+                "Too many filers: {} (max {})",
+                filers.len(),
+                institutional::MAX_ACTIVITY_FILERS
+            )));
+        }
+        let period = date_arg(&args, "period_of_report", DateBound::End)?;
+
+        let client = client.clone();
+        let results = fan_out(filers.clone(), move |filer_cik| {
+            let client = client.clone();
+            let period = period.clone();
+            async move {
+                let endpoint = format!("forms/13f/{}", filer_cik);
+                let fetch = |period: Option<String>| {
+                    let mut params = std::collections::HashMap::new();
+                    params.insert("limit".to_string(), "500".to_string());
+                    if let Some(period) = period {
+                        params.insert("period_of_report".to_string(), period);
+                    }
+                    let client = client.clone();
+                    let endpoint = endpoint.clone();
+                    async move {
+                        client
+                            .get::<Value>(&endpoint, Some(params))
+                            .await
+                            .map_err(|e| CoreError::from(e).with_context(ErrorContext::endpoint(endpoint)))
+                    }
+                };
+
+                let current = fetch(period.clone()).await?;
+                // Without a reported period, assume the last completed quarter
+                let period = period
+                    .or_else(|| institutional::report_period(&current).map(str::to_string))
+                    .unwrap_or_else(|| {
+                        let today = chrono::Local::now().date_naive().format("%Y-%m-%d").to_string();
+                        institutional::prior_quarter_end(&today).unwrap_or(today)
+                    });
+                let prior = fetch(institutional::prior_quarter_end(&period)).await?;
+                Ok::<_, CoreError>((period, current, prior))
+            }
+        })
+        .await;
+        let data = filers
+            .into_iter()
+            .zip(results)
+            .map(|(filer_cik, result)| institutional::FilerQuarters {
+                filer_cik,
+                result: result.and_then(|r| r),
+            })
+            .collect();
+
+        let activity = institutional::filer_activity(data);
+        Ok(serde_json::to_string_pretty(&activity).unwrap())
+    }
+
+    async fn get_form4_filing(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
         let accession = args
             .get("accession_number")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: accession_number")?;
+            .ok_or_else(|| ToolError::missing_param("accession_number"))?;
+        let accession = params::normalize_accession(accession).map_err(|e| {
+            ToolError::invalid_param(e).with_details(json!({ "parameter": "accession_number" }))
+        })?;
 
         let endpoint = format!("forms/4/{}", accession);
-        let result: Value = client.get(&endpoint, None).await.map_err(|e| e.to_string())?;
-        Ok(serde_json::to_string_pretty(&result).unwrap())
+        let result: Value = client.get(&endpoint, None).await?;
+        let decoded = institutional::decode_form4_transactions(result);
+        Ok(serde_json::to_string_pretty(&decoded).unwrap())
+    }
+
+    async fn get_form4_filings(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+
+        let mut params = std::collections::HashMap::new();
+        for key in ["issuer_cik", "reporting_owner_cik"] {
+            if let Some(cik) = self.company_arg(&state.config, &args, key).await? {
+                params.insert(key.to_string(), cik);
+            }
+        }
+        for key in ["reporting_owner", "transaction_code"] {
+            if let Some(v) = args.get(key).and_then(|v| v.as_str()) {
+                params.insert(key.to_string(), v.to_string());
+            }
+        }
+        if params.is_empty() {
+            return Err(ToolError::invalid_param(
+                "Provide at least one of: issuer_cik, reporting_owner_cik, reporting_owner",
+            ));
+        }
+        if let Some(v) = date_arg(&args, "filed_after", DateBound::Start)? {
+            params.insert("filed_after".to_string(), v);
+        }
+        if let Some(v) = date_arg(&args, "filed_before", DateBound::End)? {
+            params.insert("filed_before".to_string(), v);
+        }
+        let page = page_request(
+            "get_form4_filings",
+            PageStyle::LIMIT_OFFSET,
+            25,
+            &args,
+            "offset",
+            "limit",
+        )?;
+        page.apply(&mut params);
+
+        let result: Value = client.get("forms/4", Some(params)).await?;
+        let next = page.next(&result, LIST_RECORD_KEYS);
+        let decoded = institutional::decode_form4_transactions(result);
+        let decoded = pagination::attach_next_cursor("get_form4_filings", decoded, next);
+        Ok(serde_json::to_string_pretty(&decoded).unwrap())
+    }
+
+    async fn get_insider_sentiment(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+
+        let issuer_cik = self
+            .company_arg(&state.config, &args, "issuer_cik")
+            .await?
+            .ok_or_else(|| ToolError::missing_param("issuer_cik"))?;
+        let filed_after = match date_arg(&args, "filed_after", DateBound::Start)? {
+            Some(date) => date,
+            None => (chrono::Local::now().date_naive()
+                - chrono::Duration::days(institutional::DEFAULT_SENTIMENT_DAYS))
+            .format("%Y-%m-%d")
+            .to_string(),
+        };
+        let filed_before = date_arg(&args, "filed_before", DateBound::End)?;
+
+        let mut params = std::collections::HashMap::new();
+        params.insert("issuer_cik".to_string(), issuer_cik.clone());
+        params.insert("filed_after".to_string(), filed_after.clone());
+        if let Some(v) = &filed_before {
+            params.insert("filed_before".to_string(), v.clone());
+        }
+        params.insert("limit".to_string(), "100".to_string());
+
+        let result: Value = client.get("forms/4", Some(params)).await?;
+        let mut summary = institutional::insider_sentiment(&result);
+        summary["issuer_cik"] = json!(issuer_cik);
+        summary["window"] = json!({ "filed_after": filed_after, "filed_before": filed_before });
+        Ok(serde_json::to_string_pretty(&summary).unwrap())
     }
 
-    async fn get_etf_holdings(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_etf_holdings(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
         let identifier = args
             .get("identifier")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: identifier")?;
+            .ok_or_else(|| ToolError::missing_param("identifier"))?;
 
         let mut params = std::collections::HashMap::new();
-        if let Some(v) = args.get("limit").and_then(|v| v.as_i64()) {
+        if let Some(v) = args.get("quarter").and_then(|v| v.as_str()) {
+            params.insert("quarter".to_string(), v.to_string());
+        }
+        // Every page, of both quarters when comparing
+        let first = match all_pages_arg(&args) {
+            true => Some(page_request(
+                "get_etf_holdings",
+                PageStyle::LIMIT_OFFSET,
+                DEFAULT_STREAM_PAGE_SIZE,
+                &args,
+                "offset",
+                "limit",
+            )?),
+            false => None,
+        };
+        if let (Some(v), None) = (args.get("limit").and_then(|v| v.as_i64()), &first) {
             params.insert("limit".to_string(), v.to_string());
         }
 
         let endpoint = format!("etfs/{}/holdings", identifier);
-        let result: Value = client.get(&endpoint, Some(params)).await.map_err(|e| e.to_string())?;
-        Ok(serde_json::to_string_pretty(&result).unwrap())
+        let fetch = |params| {
+            let (endpoint, first) = (&endpoint, first.clone());
+            async move {
+                match first {
+                    Some(first) => fetch_all_pages(client, endpoint, params, first).await,
+                    None => client.get(endpoint, Some(params)).await,
+                }
+            }
+        };
+        let Some(prior_quarter) = args.get("compare_with_quarter").and_then(|v| v.as_str()) else {
+            if first.is_some() {
+                let result = fetch(params).await.map_err(etf_lookup_error)?;
+                return Ok(serde_json::to_string_pretty(&result).unwrap());
+            }
+            let page = page_request(
+                "get_etf_holdings",
+                PageStyle::LIMIT_OFFSET,
+                10,
+                &args,
+                "offset",
+                "limit",
+            )?;
+            page.apply(&mut params);
+
+            let result: Value = client.get(&endpoint, Some(params)).await.map_err(etf_lookup_error)?;
+            let next = page.next(&result, LIST_RECORD_KEYS);
+            let result = pagination::attach_next_cursor("get_etf_holdings", result, next);
+            return Ok(serde_json::to_string_pretty(&result).unwrap());
+        };
+
+        let result = fetch(params.clone()).await.map_err(etf_lookup_error)?;
+
+        params.insert("quarter".to_string(), prior_quarter.to_string());
+        let prior = fetch(params).await.map_err(|e| {
+            let err = ToolError::from(e);
+            let message = format!("Failed to fetch holdings for {}: {}", prior_quarter, err.message);
+            ToolError { message, ..err }
+        })?;
+
+        let comparison = etf::compare_holdings(
+            &etf::holdings_from_response(&result),
+            &etf::holdings_from_response(&prior),
+        );
+
+        Ok(serde_json::to_string_pretty(&json!({
+            "identifier": identifier,
+            "quarter": args.get("quarter"),
+            "compare_with_quarter": prior_quarter,
+            "comparison": comparison,
+            "holdings": result
+        }))
+        .unwrap())
+    }
+
+    async fn resolve_etf_identifiers(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+        let sec_client = state.ensure_sec_client()?;
+
+        let identifier = args
+            .get("identifier")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("identifier"))?;
+
+        let classes = sec_client.fetch_fund_tickers().await?;
+        let mut resolved = etf::resolve_fund(&classes, identifier);
+        if resolved["matched"] != true {
+            return Err(ToolError::new(
+                ErrorCode::NotFound,
+                format!("No fund series or share class found for '{}'", identifier),
+            )
+            .with_remediation("Check the ticker, or pass an N-PORT series ID (S...) or class ID (C...)."));
+        }
+
+        // Periods are per registrant: a trust files one N-PORT per series
+        let ciks: Vec<String> = resolved["series"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|s| s["cik"].as_str().map(str::to_string))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        let client = client.clone();
+        let periods = fan_out(ciks.clone(), move |cik| {
+            let client = client.clone();
+            async move {
+                let mut params = std::collections::HashMap::new();
+                params.insert("form_type".to_string(), "NPORT-P".to_string());
+                params.insert("page[size]".to_string(), "100".to_string());
+                let endpoint = format!("companies/{}/filings", cik);
+                match client.get::<Value>(&endpoint, Some(params)).await {
+                    Ok(response) => json!(etf::nport_periods(&response)),
+                    Err(e) => CoreError::from(e).with_context(ErrorContext::endpoint(endpoint)).to_json(),
+                }
+            }
+        })
+        .await;
+
+        let periods = ciks
+            .into_iter()
+            .zip(periods)
+            .map(|(cik, periods)| (cik, periods.unwrap_or_else(|e| e.to_json())));
+        resolved["reporting_periods"] = json!(periods.collect::<serde_json::Map<_, _>>());
+        resolved["hint"] = json!("Pass a share class symbol as get_etf_holdings identifier, and a period_of_report as its quarter.");
+        Ok(serde_json::to_string_pretty(&resolved).unwrap())
     }
 
-    async fn get_form_adv_firms(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_form_adv_firms(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
         let mut params = std::collections::HashMap::new();
@@ -548,22 +2598,42 @@ impl McpServer {
         if let Some(v) = args.get("state").and_then(|v| v.as_str()) {
             params.insert("state".to_string(), v.to_string());
         }
-        if let Some(v) = args.get("page_size").and_then(|v| v.as_i64()) {
-            params.insert("page[size]".to_string(), v.to_string());
+        if all_pages_arg(&args) {
+            let first = page_request(
+                "get_form_adv_firms",
+                PageStyle::JSON_API,
+                DEFAULT_STREAM_PAGE_SIZE,
+                &args,
+                "page_offset",
+                "page_size",
+            )?;
+            let result = fetch_all_pages(client, "forms/adv/firms", params, first).await?;
+            return Ok(serde_json::to_string_pretty(&result).unwrap());
         }
-
-        let result: Value = client.get("forms/adv/firms", Some(params)).await.map_err(|e| e.to_string())?;
+        let page = page_request(
+            "get_form_adv_firms",
+            PageStyle::JSON_API,
+            25,
+            &args,
+            "page_offset",
+            "page_size",
+        )?;
+        page.apply(&mut params);
+
+        let result: Value = client.get("forms/adv/firms", Some(params)).await?;
+        let next = page.next(&result, LIST_RECORD_KEYS);
+        let result = pagination::attach_next_cursor("get_form_adv_firms", result, next);
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn get_form_adv_firm(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_form_adv_firm(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
         let crd = args
             .get("crd")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: crd")?;
+            .ok_or_else(|| ToolError::missing_param("crd"))?;
 
         let mut params = std::collections::HashMap::new();
         if let Some(v) = args.get("include").and_then(|v| v.as_str()) {
@@ -571,12 +2641,135 @@ impl McpServer {
         }
 
         let endpoint = format!("forms/adv/firms/{}", crd);
-        let result: Value = client.get(&endpoint, Some(params)).await.map_err(|e| e.to_string())?;
+        let result: Value = client.get(&endpoint, Some(params)).await?;
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn get_lobbying_client_performance(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_form_adv_owners(&self, args: Value) -> Result<String, ToolError> {
+        if args.get("search").and_then(|v| v.as_str()).is_none() {
+            return Err(ToolError::missing_param("search"));
+        }
+        self.form_adv_list("get_form_adv_owners", "forms/adv/owners", &args, &["search", "owner_type", "control_person"])
+            .await
+    }
+
+    async fn get_form_adv_funds(&self, args: Value) -> Result<String, ToolError> {
+        let filters = ["search", "fund_type", "min_gross_asset_value", "max_gross_asset_value"];
+        self.form_adv_list("get_form_adv_funds", "forms/adv/funds", &args, &filters).await
+    }
+
+    async fn get_form_adv_filing(&self, args: Value) -> Result<String, ToolError> {
+        let id = args
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("id"))?;
+        self.form_adv_get(&format!("forms/adv/filings/{}", id)).await
+    }
+
+    async fn get_form_adv_firm_direct_owners(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/direct_owners", crd_arg(&args)?);
+        self.form_adv_list("get_form_adv_firm_direct_owners", &endpoint, &args, &[]).await
+    }
+
+    async fn get_form_adv_firm_indirect_owners(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/indirect_owners", crd_arg(&args)?);
+        self.form_adv_list("get_form_adv_firm_indirect_owners", &endpoint, &args, &[]).await
+    }
+
+    async fn get_form_adv_firm_private_funds(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/private_funds", crd_arg(&args)?);
+        self.form_adv_list("get_form_adv_firm_private_funds", &endpoint, &args, &["fund_type"]).await
+    }
+
+    async fn get_form_adv_firm_disclosures(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/disclosures", crd_arg(&args)?);
+        self.form_adv_list("get_form_adv_firm_disclosures", &endpoint, &args, &["disclosure_type"]).await
+    }
+
+    async fn get_form_adv_firm_filings(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/filings", crd_arg(&args)?);
+        self.form_adv_list("get_form_adv_firm_filings", &endpoint, &args, &[]).await
+    }
+
+    async fn get_form_adv_firm_related_persons(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/related_persons", crd_arg(&args)?);
+        self.form_adv_list("get_form_adv_firm_related_persons", &endpoint, &args, &[]).await
+    }
+
+    async fn get_form_adv_firm_ownership_chain(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/ownership_chain", crd_arg(&args)?);
+        self.form_adv_get(&endpoint).await
+    }
+
+    async fn get_form_adv_firm_brochures(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/brochures", crd_arg(&args)?);
+        self.form_adv_get(&endpoint).await
+    }
+
+    async fn get_form_adv_firm_addresses(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/addresses", crd_arg(&args)?);
+        self.form_adv_get(&endpoint).await
+    }
+
+    async fn get_form_adv_firm_notice_filings(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/notice_filings", crd_arg(&args)?);
+        self.form_adv_get(&endpoint).await
+    }
+
+    async fn get_form_adv_firm_other_names(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/other_names", crd_arg(&args)?);
+        self.form_adv_get(&endpoint).await
+    }
+
+    async fn get_form_adv_firm_sma_data(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/sma_data", crd_arg(&args)?);
+        self.form_adv_get(&endpoint).await
+    }
+
+    async fn get_form_adv_firm_aum_history(&self, args: Value) -> Result<String, ToolError> {
+        let endpoint = format!("forms/adv/firms/{}/aum_history", crd_arg(&args)?);
+        self.form_adv_get(&endpoint).await
+    }
+
+    /// Fetch one page of a Form ADV list endpoint, passing `filters` through
+    /// from the arguments
+    async fn form_adv_list(
+        &self,
+        tool: &str,
+        endpoint: &str,
+        args: &Value,
+        filters: &[&str],
+    ) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+
+        let mut params = std::collections::HashMap::new();
+        for key in filters {
+            match args.get(*key) {
+                Some(Value::String(v)) => params.insert(key.to_string(), v.clone()),
+                Some(v @ (Value::Number(_) | Value::Bool(_))) => params.insert(key.to_string(), v.to_string()),
+                _ => None,
+            };
+        }
+        let page = page_request(tool, PageStyle::JSON_API, 25, args, "page_offset", "page_size")?;
+        page.apply(&mut params);
+
+        let result: Value = client.get(endpoint, Some(params)).await?;
+        let next = page.next(&result, LIST_RECORD_KEYS);
+        let result = pagination::attach_next_cursor(tool, result, next);
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    /// Fetch a Form ADV endpoint that takes no parameters
+    async fn form_adv_get(&self, endpoint: &str) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+        let result: Value = client.get(endpoint, None).await?;
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn get_lobbying_client_performance(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
         let mut params = std::collections::HashMap::new();
@@ -584,24 +2777,77 @@ impl McpServer {
             params.insert("year".to_string(), v.to_string());
         }
         if let Some(v) = args.get("quarter").and_then(|v| v.as_str()) {
-            params.insert("quarter".to_string(), v.to_string());
+            let (year, quarter) = params::normalize_quarter(v, chrono::Local::now().date_naive())
+                .map_err(ToolError::invalid_param)?;
+            params.insert("quarter".to_string(), format!("Q{}", quarter));
+            // "2023-Q4" or "last quarter" also pick the year unless one was given
+            if let Some(year) = year {
+                params.entry("year".to_string()).or_insert_with(|| year.to_string());
+            }
+        }
+        let style = PageStyle::PageNumber {
+            page: "page",
+            size: "per_page",
+        };
+        let page = page_request("get_lobbying_client_performance", style, 20, &args, "page", "per_page")?;
+        page.apply(&mut params);
+
+        let result: Value = client.get("lobbying/client_performance", Some(params)).await?;
+        let next = page.next(&result, LIST_RECORD_KEYS);
+        let result = pagination::attach_next_cursor("get_lobbying_client_performance", result, next);
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn get_lobbying_trends(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+
+        let target = match (
+            args.get("client").and_then(|v| v.as_str()),
+            args.get("industry").and_then(|v| v.as_str()),
+        ) {
+            (Some(c), None) => lobbying::TrendTarget::Client(c.to_string()),
+            (None, Some(i)) => lobbying::TrendTarget::Industry(i.to_string()),
+            _ => return Err(ToolError::invalid_param("Provide exactly one of: client, industry")),
+        };
+
+        let end_year = args
+            .get("end_year")
+            .and_then(|v| v.as_i64())
+            .map(|y| y as i32)
+            .unwrap_or_else(|| chrono::Datelike::year(&chrono::Local::now().date_naive()));
+        let start_year = args
+            .get("start_year")
+            .and_then(|v| v.as_i64())
+            .map(|y| y as i32)
+            .unwrap_or(end_year - 4);
+        if start_year > end_year {
+            return Err(ToolError::invalid_param("start_year must not be after end_year"));
         }
-        if let Some(v) = args.get("page").and_then(|v| v.as_i64()) {
-            params.insert("page".to_string(), v.to_string());
+        if end_year - start_year >= lobbying::MAX_TREND_YEARS {
+            return Err(ToolError::invalid_param(format!(
+                "At most {} years can be analyzed per request",
+                lobbying::MAX_TREND_YEARS
+            )));
         }
 
-        let result: Value = client.get("lobbying/client_performance", Some(params)).await.map_err(|e| e.to_string())?;
-        Ok(serde_json::to_string_pretty(&result).unwrap())
+        let periods: Vec<(i32, u8)> = (start_year..=end_year)
+            .flat_map(|year| (1..=4).map(move |quarter| (year, quarter)))
+            .collect();
+
+        let data = fetch_lobbying_periods(client, &target, periods).await;
+        let trends = lobbying::lobbying_trends(&target, data);
+        Ok(serde_json::to_string_pretty(&trends).unwrap())
     }
 
-    async fn get_lobbying_clients_search(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_lobbying_clients_search(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
         let query = args
             .get("query")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: query")?;
+            .ok_or_else(|| ToolError::missing_param("query"))?;
 
         let mut params = std::collections::HashMap::new();
         params.insert("query".to_string(), query.to_string());
@@ -609,60 +2855,235 @@ impl McpServer {
             params.insert("limit".to_string(), v.to_string());
         }
 
-        let result: Value = client.get("lobbying/clients/search", Some(params)).await.map_err(|e| e.to_string())?;
+        let result: Value = client.get("lobbying/clients/search", Some(params)).await?;
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn get_lobbying_client_detail(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_lobbying_client_detail(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
         let client_id = args
             .get("client_id")
             .and_then(|v| v.as_i64())
-            .ok_or("Missing required parameter: client_id")?;
+            .ok_or_else(|| ToolError::missing_param("client_id"))?;
+
+        let graph = match args.get("output").and_then(|v| v.as_str()).unwrap_or("detail") {
+            "detail" => false,
+            "graph" => true,
+            other => {
+                return Err(ToolError::invalid_param(format!(
+                    "Unknown output '{}'. Valid values: detail, graph",
+                    other
+                )))
+            }
+        };
+
+        let mut params = std::collections::HashMap::new();
+        if let Some(v) = args.get("years").and_then(|v| v.as_i64()) {
+            params.insert("years".to_string(), v.to_string());
+        }
+        for flag in ["include_registrants", "include_activities"] {
+            // The graph is built from registrants and activities, so always request them
+            let value = graph || args.get(flag).and_then(|v| v.as_bool()).unwrap_or(true);
+            params.insert(flag.to_string(), value.to_string());
+        }
 
         let endpoint = format!("lobbying/clients/{}", client_id);
-        let result: Value = client.get(&endpoint, None).await.map_err(|e| e.to_string())?;
+        let result: Value = client.get(&endpoint, Some(params)).await?;
+
+        if graph {
+            return Ok(serde_json::to_string_pretty(&lobbying::client_graph(&result)).unwrap());
+        }
+        Ok(serde_json::to_string_pretty(&result).unwrap())
+    }
+
+    async fn get_lists(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+        // The lists endpoint returns every list at once, so page in memory
+        let page = page_request("get_lists", PageStyle::LIMIT_OFFSET, 50, &args, "offset", "limit")?;
+        let result: Value = client.get("lists", None).await?;
+        let result = pagination::paginate_records("get_lists", result, LIST_RECORD_KEYS, &page);
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn get_lists(&self) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn create_list(&self, mut args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
-        let result: Value = client.get("lists", None).await.map_err(|e| e.to_string())?;
+        if take_dry_run(&mut args, &state.config) {
+            return self.preview_list_change(client, None, "POST", "lists", Some(&args)).await;
+        }
+        let result: Value = client.post("lists", Some(&args)).await?;
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn create_list(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn get_list(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
-        let result: Value = client.post("lists", Some(&args)).await.map_err(|e| e.to_string())?;
+
+        let id_or_name = args
+            .get("id_or_name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("id_or_name"))?;
+
+        let page = page_request("get_list", PageStyle::LIMIT_OFFSET, 50, &args, "offset", "limit")?;
+        let endpoint = format!("lists/{}", id_or_name);
+        let mut result: Value = client.get(&endpoint, None).await?;
+
+        // Items come back all at once; cut them to the page before enriching
+        // so only the returned items cost lookups
+        let mut next = None;
+        if let Some(items) = watchlists::list_items_mut(&mut result) {
+            let (page_items, following) = page.slice(std::mem::take(items));
+            *items = page_items;
+            next = following;
+        }
+        let mut result = pagination::attach_next_cursor("get_list", result, next);
+
+        if args.get("enrich").and_then(|v| v.as_bool()).unwrap_or(false) {
+            self.enrich_list_items(client, &mut result).await;
+        }
+
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn get_list(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    /// Attach latest filing, next expected report date, and recent insider
+    /// activity to every company item in a watchlist.
+    async fn enrich_list_items(&self, client: &ApiClient, list: &mut Value) {
+        let Some(items) = watchlists::list_items_mut(list) else {
+            return;
+        };
+
+        let ciks: Vec<Option<String>> = items.iter().map(watchlists::item_cik).collect();
+        let today = chrono::Local::now().date_naive();
+        let insider_since = today - chrono::Duration::days(watchlists::INSIDER_LOOKBACK_DAYS);
+
+        let client = client.clone();
+        let cache = self.cache.clone();
+        let enrichments = fan_out(ciks, move |cik| {
+            let client = client.clone();
+            let cache = cache.clone();
+            async move {
+                let cik = cik?;
+
+                let mut filings_params = std::collections::HashMap::new();
+                filings_params.insert("page[size]".to_string(), "1".to_string());
+                let mut form4_params = std::collections::HashMap::new();
+                form4_params.insert("issuer_cik".to_string(), cik.clone());
+                form4_params.insert("filed_after".to_string(), insider_since.to_string());
+
+                let filings_endpoint = format!("companies/{}/filings", cik);
+                let calendar_endpoint = format!("companies/{}/calendar", cik);
+                let (filings, calendar, form4) = tokio::join!(
+                    cached_get(&cache, &client, &filings_endpoint, filings_params),
+                    cached_get(&cache, &client, &calendar_endpoint, Default::default()),
+                    cached_get(&cache, &client, "forms/4", form4_params),
+                );
+
+                Some(json!({
+                    "latest_filing": filings
+                        .map(|f| watchlists::latest_filing_summary(&f))
+                        .unwrap_or_else(|e| json!({ "error": e.to_json() })),
+                    "next_expected_report_date": calendar
+                        .map(|c| json!(watchlists::next_report_date(&c, today)))
+                        .unwrap_or_else(|e| json!({ "error": e.to_json() })),
+                    "recent_insider_activity": form4
+                        .map(watchlists::insider_activity_summary)
+                        .unwrap_or_else(|e| json!({ "error": e.to_json() })),
+                }))
+            }
+        })
+        .await;
+
+        for (item, enrichment) in items.iter_mut().zip(enrichments) {
+            let enrichment = enrichment.unwrap_or_else(|e| Some(json!({ "error": e.to_json() })));
+            if let (Some(obj), Some(enrichment)) = (item.as_object_mut(), enrichment) {
+                obj.insert("enrichment".to_string(), enrichment);
+            }
+        }
+    }
+
+    async fn get_upcoming_reports(&self, args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
 
         let id_or_name = args
             .get("id_or_name")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: id_or_name")?;
+            .ok_or_else(|| ToolError::missing_param("id_or_name"))?;
+        let weeks = args
+            .get("weeks")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(watchlists::DEFAULT_UPCOMING_WEEKS);
+        if !(1..=watchlists::MAX_UPCOMING_WEEKS).contains(&weeks) {
+            return Err(ToolError::invalid_param(format!(
+                "weeks must be between 1 and {}",
+                watchlists::MAX_UPCOMING_WEEKS
+            ))
+            .with_details(json!({ "parameter": "weeks" })));
+        }
 
         let endpoint = format!("lists/{}", id_or_name);
-        let result: Value = client.get(&endpoint, None).await.map_err(|e| e.to_string())?;
+        let mut list: Value = client.get(&endpoint, None).await?;
+        let companies: Vec<(String, Option<String>)> = watchlists::list_items_mut(&mut list)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|item| Some((watchlists::item_cik(item)?, watchlists::item_name(item))))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let today = chrono::Local::now().date_naive();
+        let until = today + chrono::Duration::weeks(weeks);
+        let history_since = today - chrono::Duration::days(watchlists::CADENCE_LOOKBACK_DAYS);
+
+        let client = client.clone();
+        let cache = self.cache.clone();
+        let reports = fan_out(companies.clone(), move |(cik, _)| {
+            let client = client.clone();
+            let cache = cache.clone();
+            async move {
+                let mut filings_params = std::collections::HashMap::new();
+                filings_params.insert("filed_after".to_string(), history_since.to_string());
+                filings_params.insert("page[size]".to_string(), "100".to_string());
+                let filings_endpoint = format!("companies/{}/filings", cik);
+                let calendar_endpoint = format!("companies/{}/calendar", cik);
+                let (filings, calendar) = tokio::join!(
+                    cached_get(&cache, &client, &filings_endpoint, filings_params),
+                    cached_get(&cache, &client, &calendar_endpoint, Default::default()),
+                );
+                (filings, calendar)
+            }
+        })
+        .await;
+        let data = companies
+            .into_iter()
+            .zip(reports)
+            .map(|((cik, name), reports)| {
+                let (filings, calendar) = reports.unwrap_or_else(|e| {
+                    let message = e.to_string();
+                    (Err(e), Err(CoreError::Internal(message)))
+                });
+                watchlists::CompanyReports { cik, name, filings, calendar }
+            })
+            .collect();
+
+        let mut result = watchlists::upcoming_reports(data, today, until);
+        result["list"] = json!(id_or_name);
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn update_list(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn update_list(&self, mut args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
+        let dry_run = take_dry_run(&mut args, &state.config);
 
         let id_or_name = args
             .get("id_or_name")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: id_or_name")?;
+            .ok_or_else(|| ToolError::missing_param("id_or_name"))?;
 
         let body = json!({
             "name": args.get("name"),
@@ -670,88 +3091,708 @@ impl McpServer {
         });
 
         let endpoint = format!("lists/{}", id_or_name);
-        let result: Value = client.patch(&endpoint, Some(&body)).await.map_err(|e| e.to_string())?;
+        if dry_run {
+            return self.preview_list_change(client, Some(id_or_name), "PATCH", &endpoint, Some(&body)).await;
+        }
+        self.snapshot_list(client, id_or_name, "update_list").await;
+        let result: Value = client.patch(&endpoint, Some(&body)).await?;
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn delete_list(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn delete_list(&self, mut args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
+        let dry_run = take_dry_run(&mut args, &state.config);
 
         let id_or_name = args
             .get("id_or_name")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: id_or_name")?;
+            .ok_or_else(|| ToolError::missing_param("id_or_name"))?;
 
         let endpoint = format!("lists/{}", id_or_name);
-        client.delete(&endpoint).await.map_err(|e| e.to_string())?;
+        if dry_run {
+            return self.preview_list_change(client, Some(id_or_name), "DELETE", &endpoint, None).await;
+        }
+        self.snapshot_list(client, id_or_name, "delete_list").await;
+        client.delete(&endpoint).await?;
         Ok(json!({"success": true, "message": "List deleted"}).to_string())
     }
 
-    async fn add_list_item(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn add_list_item(&self, mut args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
+        let dry_run = take_dry_run(&mut args, &state.config);
 
         let list_id = args
             .get("list_id")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: list_id")?;
+            .ok_or_else(|| ToolError::missing_param("list_id"))?;
 
         let endpoint = format!("lists/{}/items", list_id);
-        let result: Value = client.post(&endpoint, Some(&args)).await.map_err(|e| e.to_string())?;
+        if dry_run {
+            return self.preview_list_change(client, Some(list_id), "POST", &endpoint, Some(&args)).await;
+        }
+        self.snapshot_list(client, list_id, "add_list_item").await;
+        let result: Value = client.post(&endpoint, Some(&args)).await?;
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn toggle_list_item(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn toggle_list_item(&self, mut args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
+        let dry_run = take_dry_run(&mut args, &state.config);
 
         let list_id = args
             .get("list_id")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: list_id")?;
+            .ok_or_else(|| ToolError::missing_param("list_id"))?;
 
         let endpoint = format!("lists/{}/items/toggle", list_id);
-        let result: Value = client.post(&endpoint, Some(&args)).await.map_err(|e| e.to_string())?;
+        if dry_run {
+            return self.preview_list_change(client, Some(list_id), "POST", &endpoint, Some(&args)).await;
+        }
+        self.snapshot_list(client, list_id, "toggle_list_item").await;
+        let result: Value = client.post(&endpoint, Some(&args)).await?;
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn update_list_item(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn update_list_item(&self, mut args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
+        let dry_run = take_dry_run(&mut args, &state.config);
 
         let list_id = args
             .get("list_id")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: list_id")?;
+            .ok_or_else(|| ToolError::missing_param("list_id"))?;
         let item_id = args
             .get("item_id")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: item_id")?;
+            .ok_or_else(|| ToolError::missing_param("item_id"))?;
 
         let body = json!({ "notes": args.get("notes") });
         let endpoint = format!("lists/{}/items/{}", list_id, item_id);
-        let result: Value = client.patch(&endpoint, Some(&body)).await.map_err(|e| e.to_string())?;
+        if dry_run {
+            return self.preview_list_change(client, Some(list_id), "PATCH", &endpoint, Some(&body)).await;
+        }
+        self.snapshot_list(client, list_id, "update_list_item").await;
+        let result: Value = client.patch(&endpoint, Some(&body)).await?;
         Ok(serde_json::to_string_pretty(&result).unwrap())
     }
 
-    async fn delete_list_item(&self, args: Value) -> Result<String, String> {
-        let state = self.state.read().await;
+    async fn delete_list_item(&self, mut args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
         let client = state.ensure_api_client()?;
+        let dry_run = take_dry_run(&mut args, &state.config);
 
         let list_id = args
             .get("list_id")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: list_id")?;
+            .ok_or_else(|| ToolError::missing_param("list_id"))?;
         let item_id = args
             .get("item_id")
             .and_then(|v| v.as_str())
-            .ok_or("Missing required parameter: item_id")?;
+            .ok_or_else(|| ToolError::missing_param("item_id"))?;
 
         let endpoint = format!("lists/{}/items/{}", list_id, item_id);
-        client.delete(&endpoint).await.map_err(|e| e.to_string())?;
+        if dry_run {
+            return self.preview_list_change(client, Some(list_id), "DELETE", &endpoint, None).await;
+        }
+        self.snapshot_list(client, list_id, "delete_list_item").await;
+        client.delete(&endpoint).await?;
         Ok(json!({"success": true, "message": "Item deleted"}).to_string())
     }
+
+    /// Report what a watchlist change would send without sending it, after
+    /// checking that the list it targets exists
+    async fn preview_list_change(
+        &self,
+        client: &ApiClient,
+        list: Option<&str>,
+        method: &str,
+        endpoint: &str,
+        body: Option<&Value>,
+    ) -> Result<String, ToolError> {
+        if let Some(id_or_name) = list {
+            let _: Value = client.get(&format!("lists/{}", id_or_name), None).await?;
+        }
+        Ok(serde_json::to_string_pretty(&watchlists::dry_run_preview(method, endpoint, body)).unwrap())
+    }
+
+    /// Save a watchlist's current contents before `tool` changes it. Failing
+    /// to snapshot doesn't block the change.
+    async fn snapshot_list(&self, client: &ApiClient, id_or_name: &str, tool: &str) {
+        let Some(dir) = list_history::history_dir() else {
+            warn!("No config directory available; not snapshotting list {}", id_or_name);
+            return;
+        };
+        let endpoint = format!("lists/{}", id_or_name);
+        let list: Value = match client.get(&endpoint, None).await {
+            Ok(list) => list,
+            Err(e) => {
+                warn!("Could not fetch list {} to snapshot before {}: {}", id_or_name, tool, e);
+                return;
+            }
+        };
+        if let Err(e) = list_history::record(&dir, id_or_name, tool, &list) {
+            warn!("Failed to snapshot list {} before {}: {}", id_or_name, tool, e);
+        }
+    }
+
+    async fn get_list_history(&self, args: Value) -> Result<String, ToolError> {
+        let list_id = args
+            .get("list_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("list_id"))?;
+        let dir = history_dir()?;
+
+        if let Some(snapshot_id) = args.get("snapshot_id").and_then(|v| v.as_str()) {
+            let snapshot = list_history::load(&dir, list_id, snapshot_id)
+                .ok_or_else(|| snapshot_not_found(list_id, snapshot_id))?;
+            return Ok(serde_json::to_string_pretty(&snapshot).unwrap());
+        }
+
+        let snapshots: Vec<Value> = list_history::history(&dir, list_id)
+            .iter()
+            .map(list_history::Snapshot::summary)
+            .collect();
+        Ok(serde_json::to_string_pretty(&json!({
+            "list": list_id,
+            "snapshot_count": snapshots.len(),
+            "snapshots": snapshots,
+        }))
+        .unwrap())
+    }
+
+    /// Put a watchlist back the way a snapshot recorded it, recreating the
+    /// list if it was deleted. The list's current state is snapshotted first
+    /// so the restore can itself be undone.
+    async fn restore_list_snapshot(&self, mut args: Value) -> Result<String, ToolError> {
+        let state = self.snapshot().await;
+        let client = state.ensure_api_client()?;
+        let dry_run = take_dry_run(&mut args, &state.config);
+
+        let list_id = args
+            .get("list_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("list_id"))?;
+        let snapshot_id = args
+            .get("snapshot_id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::missing_param("snapshot_id"))?;
+        let dir = history_dir()?;
+        let snapshot = list_history::load(&dir, list_id, snapshot_id)
+            .ok_or_else(|| snapshot_not_found(list_id, snapshot_id))?;
+
+        let endpoint = format!("lists/{}", snapshot.list_id);
+        let current: Option<Value> = match client.get(&endpoint, None).await {
+            Ok(list) => Some(list),
+            Err(ApiError::NotFound) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let details = watchlists::list_details(&snapshot.list);
+
+        if dry_run {
+            let current_items = current.as_ref().map(watchlists::list_items).unwrap_or_default();
+            let plan = watchlists::restore_plan(watchlists::list_items(&snapshot.list), current_items);
+            return Ok(serde_json::to_string_pretty(&json!({
+                "success": true,
+                "dry_run": true,
+                "list_id": snapshot.list_id,
+                "restored_from": snapshot.summary(),
+                "recreated": current.is_none(),
+                "items_added": plan.add.len(),
+                "items_removed": plan.remove.len(),
+                "notes_updated": plan.update_notes.len(),
+                "changes": plan.changes(),
+                "errors": [],
+            }))
+            .unwrap());
+        }
+
+        let (target_id, recreated, current_items) = match current {
+            Some(list) => {
+                if let Err(e) = list_history::record(&dir, &snapshot.list_id, "restore_list_snapshot", &list) {
+                    warn!("Failed to snapshot list {} before restoring: {}", snapshot.list_id, e);
+                }
+                let _: Value = client.patch(&endpoint, Some(&details)).await?;
+                (snapshot.list_id.clone(), false, watchlists::list_items(&list).to_vec())
+            }
+            None => {
+                let created: Value = client.post("lists", Some(&details)).await?;
+                let id = watchlists::list_id(&created).ok_or_else(|| {
+                    ToolError::new(ErrorCode::UpstreamError, "Recreated list has no ID")
+                })?;
+                (id, true, Vec::new())
+            }
+        };
+
+        let plan = watchlists::restore_plan(watchlists::list_items(&snapshot.list), &current_items);
+        let mut errors = Vec::new();
+        for item_id in &plan.remove {
+            let endpoint = format!("lists/{}/items/{}", target_id, item_id);
+            if let Err(e) = client.delete(&endpoint).await {
+                errors.push(json!({ "action": "remove", "item_id": item_id, "error": e.to_string() }));
+            }
+        }
+        let items_endpoint = format!("lists/{}/items", target_id);
+        for body in &plan.add {
+            if let Err(e) = client.post::<Value>(&items_endpoint, Some(body)).await {
+                errors.push(json!({ "action": "add", "item": body, "error": e.to_string() }));
+            }
+        }
+        for (item_id, notes) in &plan.update_notes {
+            let endpoint = format!("lists/{}/items/{}", target_id, item_id);
+            if let Err(e) = client.patch::<Value>(&endpoint, Some(&json!({ "notes": notes }))).await {
+                errors.push(json!({ "action": "update_notes", "item_id": item_id, "error": e.to_string() }));
+            }
+        }
+
+        Ok(serde_json::to_string_pretty(&json!({
+            "success": errors.is_empty(),
+            "list_id": target_id,
+            "restored_from": snapshot.summary(),
+            "recreated": recreated,
+            "items_added": plan.add.len(),
+            "items_removed": plan.remove.len(),
+            "notes_updated": plan.update_notes.len(),
+            "errors": errors,
+        }))
+        .unwrap())
+    }
+}
+
+// ============================================================================
+// HELPERS
+// ============================================================================
+
+/// Maximum concurrent upstream requests when a tool call fans out
+const MAX_FANOUT_CONCURRENCY: usize = 4;
+
+/// Run `f` over every item concurrently (bounded by MAX_FANOUT_CONCURRENCY),
+/// returning one output per item in input order. An item whose task panicked
+/// gets an error in its place, so outputs always line up with the items.
+async fn fan_out<T, F, Fut>(items: Vec<T>, f: F) -> Vec<Result<Fut::Output, CoreError>>
+where
+    T: Send + 'static,
+    F: Fn(T) -> Fut,
+    Fut: Future + Send + 'static,
+    Fut::Output: Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(MAX_FANOUT_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    let mut indexes = HashMap::new();
+    // Task-locals and spans don't follow spawned tasks, so carry the call's
+    // stats, cancellation token, and span over
+    let stats = call_stats::current().unwrap_or_default();
+    let token = cancellation::current().unwrap_or_default();
+
+    for (index, item) in items.into_iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let fut = f(item);
+        let stats = stats.clone();
+        let token = token.clone();
+        let task = tasks.spawn(
+            call_stats::scope(
+                stats,
+                cancellation::scope(token, async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    fut.await
+                }),
+            )
+            .in_current_span(),
+        );
+        indexes.insert(task.id(), index);
+    }
+
+    let mut outputs: Vec<Option<Result<Fut::Output, CoreError>>> = (0..indexes.len()).map(|_| None).collect();
+    while let Some(joined) = tasks.join_next_with_id().await {
+        let (id, output) = match joined {
+            Ok((id, output)) => (id, Ok(output)),
+            Err(e) => {
+                error!("Fan-out task failed: {}", e);
+                (e.id(), Err(CoreError::Internal(format!("Task failed: {}", e))))
+            }
+        };
+        outputs[indexes[&id]] = Some(output);
+    }
+    outputs.into_iter().map(|output| output.expect("every task was joined")).collect()
+}
+
+/// Short stable hash of a call's arguments, for correlating repeated calls
+/// in logs without recording the arguments themselves
+fn args_fingerprint(args: &Value) -> String {
+    let mut hasher = DefaultHasher::new();
+    args.to_string().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Coerce loosely typed arguments to the tool's schema, including the
+/// nested arguments of an `execute_tool` call
+fn coerce_call_args(name: &str, args: &mut Value) {
+    let log = |tool: &str, notes: Vec<String>| {
+        for note in notes {
+            debug!("Coerced {} argument {}", tool, note);
+        }
+    };
+
+    if let Some(schema) = input_schema(name) {
+        log(name, coerce::coerce_args(&schema, args));
+    }
+
+    if name == "execute_tool" {
+        let tool = args.get("tool_name").and_then(|v| v.as_str()).map(str::to_string);
+        if let (Some(tool), Some(tool_args)) = (tool, args.get_mut("arguments")) {
+            if let Some(schema) = registry::get_tool_schema(&tool) {
+                log(&tool, coerce::coerce_args(&schema, tool_args));
+            }
+        }
+    }
+}
+
+/// Check a call's arguments, and those `execute_tool` passes on, against
+/// their tools' input schemas before anything is fetched. Unknown tools are
+/// left for dispatch to report.
+fn validate_call_args(name: &str, args: &Value) -> Result<(), ToolError> {
+    if let Some(schema) = input_schema(name) {
+        validate::validate_args(&schema, args)?;
+    }
+    if name == "execute_tool" {
+        let tool = target_tool(name, args);
+        let tool_args = args.get("arguments").cloned().unwrap_or_else(|| json!({}));
+        if let Some(schema) = registry::get_tool_schema(tool) {
+            validate::validate_args(&schema, &tool_args)?;
+        }
+    }
+    Ok(())
+}
+
+/// The server's own tool definitions in tools/list order, built on first use
+fn own_tool_definitions() -> &'static [Value] {
+    static TOOLS: OnceLock<Vec<Value>> = OnceLock::new();
+    TOOLS.get_or_init(|| match build_tool_definitions() {
+        Value::Array(tools) => tools,
+        _ => Vec::new(),
+    })
+}
+
+/// The server's own tool definitions by name
+fn own_tools() -> &'static HashMap<String, Value> {
+    static TOOLS: OnceLock<HashMap<String, Value>> = OnceLock::new();
+    TOOLS.get_or_init(|| {
+        own_tool_definitions()
+            .iter()
+            .filter_map(|tool| Some((tool["name"].as_str()?.to_string(), tool.clone())))
+            .collect()
+    })
+}
+
+/// Input schema of one of the server's own tools or a registry tool
+fn input_schema(name: &str) -> Option<Cow<'static, Value>> {
+    match own_tools().get(name) {
+        Some(tool) => Some(Cow::Borrowed(&tool["inputSchema"])),
+        None => registry::get_tool_schema(name).map(Cow::Owned),
+    }
+}
+
+/// Output schema of one of the server's own tools or a registry tool
+fn output_schema(name: &str) -> Option<Cow<'static, Value>> {
+    match own_tools().get(name) {
+        Some(tool) => tool.get("outputSchema").map(Cow::Borrowed),
+        None => registry::get_tool_output_schema(name).map(Cow::Owned),
+    }
+}
+
+/// In debug builds, a warning when a tool's structured result doesn't
+/// match its output schema, which usually means the API changed shape
+fn result_shape_warning(tool: &str, result: &Value) -> Option<String> {
+    if !cfg!(debug_assertions) {
+        return None;
+    }
+    let schema = output_schema(tool)?;
+    let problem = validate::validate_result(&schema, result).err()?;
+    Some(format!("Result doesn't match {}'s output schema: {}", tool, problem))
+}
+
+/// The tool a call actually runs, looking through `execute_tool`
+fn target_tool<'a>(name: &'a str, args: &'a Value) -> &'a str {
+    match name {
+        "execute_tool" => args.get("tool_name").and_then(|v| v.as_str()).unwrap_or(name),
+        _ => name,
+    }
+}
+
+/// Whether a tool only reads data, so duplicate concurrent calls can be merged
+/// and registry tools' results cached: registry tools tagged `read-only`, and
+/// the server's own `search`
+fn is_read_only_tool(name: &str) -> bool {
+    name == "search" || registry::has_tag(name, "read-only")
+}
+
+/// Whether a watchlist change should only report what it would do, from
+/// its `dry_run` argument or the global setting. The argument is removed so
+/// it is never forwarded to the API.
+fn take_dry_run(args: &mut Value, config: &Config) -> bool {
+    let requested = args
+        .as_object_mut()
+        .and_then(|args| args.remove("dry_run"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    requested || config.dry_run
+}
+
+/// The format to return a call's result in: its `format` argument, or the
+/// wrapped tool's when called through execute_tool, else the configured
+/// default. The argument is removed so it is never forwarded to the API.
+fn take_output_format(name: &str, args: &mut Value, config: &Config) -> Result<OutputFormat, ToolError> {
+    let nested = match name {
+        "execute_tool" => args
+            .get_mut("arguments")
+            .and_then(|args| args.as_object_mut())
+            .and_then(|args| args.remove("format")),
+        _ => None,
+    };
+    let requested = args.as_object_mut().and_then(|args| args.remove("format")).or(nested);
+    match requested {
+        None | Some(Value::Null) => Ok(config.output_format.unwrap_or_default()),
+        Some(Value::String(format)) => format.parse().map_err(ToolError::invalid_param),
+        Some(_) => Err(ToolError::invalid_param("format: expected json or markdown")),
+    }
+}
+
+/// Keys under which list endpoints return their records
+const LIST_RECORD_KEYS: &[&str] = pagination::RECORD_KEYS;
+
+/// Page of search_tools matches or tool categories to return, from `cursor`
+/// or `limit`
+fn discovery_page(tool: &str, args: &Value) -> Result<PageRequest, ToolError> {
+    let mut page = page_request(tool, PageStyle::LIMIT_OFFSET, DISCOVERY_PAGE_SIZE, args, "offset", "limit")?;
+    page.size = page.size.min(MAX_DISCOVERY_PAGE_SIZE);
+    Ok(page)
+}
+
+/// Resolve the page to fetch from `cursor` or the tool's own paging arguments
+fn page_request(
+    tool: &str,
+    style: PageStyle,
+    default_size: u64,
+    args: &Value,
+    position_arg: &str,
+    size_arg: &str,
+) -> Result<PageRequest, ToolError> {
+    PageRequest::from_args(
+        tool,
+        style,
+        default_size,
+        args.get("cursor").and_then(|v| v.as_str()),
+        args.get(position_arg).and_then(|v| v.as_u64()),
+        args.get(size_arg).and_then(|v| v.as_u64()),
+    )
+    .map_err(ToolError::invalid_param)
+}
+
+/// Most records an `all_pages` call returns
+const MAX_ALL_PAGES_RECORDS: usize = 1000;
+
+/// Whether a list tool was asked to follow pages itself
+fn all_pages_arg(args: &Value) -> bool {
+    args.get("all_pages").and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// Records of `endpoint` from `first` on, across as many pages as it takes
+/// to reach [`MAX_ALL_PAGES_RECORDS`], as one list response
+async fn fetch_all_pages(
+    client: &ApiClient,
+    endpoint: &str,
+    params: HashMap<String, String>,
+    first: PageRequest,
+) -> Result<Value, ApiError> {
+    let mut records = PagedStream::<Value>::new(client.clone(), endpoint, params, first)
+        .take_items(MAX_ALL_PAGES_RECORDS + 1)
+        .await?;
+    let truncated = records.len() > MAX_ALL_PAGES_RECORDS;
+    records.truncate(MAX_ALL_PAGES_RECORDS);
+    Ok(json!({ "count": records.len(), "truncated": truncated, "data": records }))
+}
+
+/// The required `crd` argument of a Form ADV firm tool
+fn crd_arg(args: &Value) -> Result<&str, ToolError> {
+    args.get("crd")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+        .map(str::trim)
+        .ok_or_else(|| ToolError::missing_param("crd"))
+}
+
+/// Normalize an optional date argument ("2023-Q4", "past 90 days", ...) to
+/// the API's `YYYY-MM-DD` format
+fn date_arg(args: &Value, key: &str, bound: DateBound) -> Result<Option<String>, ToolError> {
+    let Some(value) = args.get(key).and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+    let today = chrono::Local::now().date_naive();
+    params::normalize_date(value, bound, today)
+        .map(Some)
+        .map_err(|e| ToolError::invalid_param(e).with_details(json!({ "parameter": key })))
+}
+
+/// Client performance pages for each (year, quarter), filtered to the
+/// target's industry when it has one
+async fn fetch_lobbying_periods(
+    client: &ApiClient,
+    target: &lobbying::TrendTarget,
+    periods: Vec<(i32, u8)>,
+) -> Vec<lobbying::PeriodData> {
+    let industry = match target {
+        lobbying::TrendTarget::Industry(i) => Some(i.clone()),
+        lobbying::TrendTarget::Client(_) => None,
+    };
+    let client = client.clone();
+    let responses = fan_out(periods.clone(), move |(year, quarter)| {
+        let client = client.clone();
+        let industry = industry.clone();
+        async move {
+            let mut params = std::collections::HashMap::new();
+            params.insert("year".to_string(), year.to_string());
+            params.insert("quarter".to_string(), format!("Q{}", quarter));
+            params.insert("per_page".to_string(), "100".to_string());
+            if let Some(industry) = industry {
+                params.insert("industry".to_string(), industry);
+            }
+            let endpoint = "lobbying/client_performance";
+            client
+                .get(endpoint, Some(params))
+                .await
+                .map_err(|e| CoreError::from(e).with_context(ErrorContext::endpoint(endpoint)))
+        }
+    })
+    .await;
+    periods
+        .into_iter()
+        .zip(responses)
+        .map(|((year, quarter), response)| lobbying::PeriodData {
+            year,
+            quarter,
+            response: response.and_then(|r| r),
+        })
+        .collect()
+}
+
+/// The company's most recent filing of `form_type`
+async fn latest_filing(client: &ApiClient, cik: &str, form_type: &str) -> Result<Value, ToolError> {
+    let mut params = std::collections::HashMap::new();
+    params.insert("form_type".to_string(), form_type.to_string());
+    params.insert("sort".to_string(), "-filing_date".to_string());
+    params.insert("page[size]".to_string(), "10".to_string());
+    let endpoint = format!("companies/{}/filings", cik);
+    let filings: Value = client.get(&endpoint, Some(params)).await?;
+
+    sec_documents::latest_filing(&filings, form_type).cloned().ok_or_else(|| {
+        ToolError::new(ErrorCode::NotFound, format!("No {} filings found for {}", form_type, cik))
+            .with_remediation("Check the form type, or list the company's filings with get_company_filings.")
+    })
+}
+
+/// Point ETF lookups that found nothing at the identifier resolver
+fn etf_lookup_error(err: ApiError) -> ToolError {
+    let err = ToolError::from(err);
+    match err.code {
+        ErrorCode::NotFound => err.with_remediation(
+            "Use resolve_etf_identifiers to find the fund's share class symbols and reporting periods.",
+        ),
+        _ => err,
+    }
+}
+
+/// Where watchlist snapshots are kept. Without a config directory there is
+/// nowhere to read or write them.
+fn history_dir() -> Result<std::path::PathBuf, ToolError> {
+    list_history::history_dir().ok_or_else(|| {
+        ToolError::new(
+            ErrorCode::NotConfigured,
+            "No watchlist history directory is available: the config directory can't be found",
+        )
+    })
+}
+
+fn snapshot_not_found(list_id: &str, snapshot_id: &str) -> ToolError {
+    ToolError::new(
+        ErrorCode::NotFound,
+        format!("No snapshot {} saved for list {}", snapshot_id, list_id),
+    )
+    .with_remediation("List the saved snapshots with get_list_history.")
+}
+
+/// GET through the response cache
+async fn cached_get(
+    cache: &ResponseCache,
+    client: &ApiClient,
+    endpoint: &str,
+    params: std::collections::HashMap<String, String>,
+) -> Result<Value, CoreError> {
+    let key = ResponseCache::key(endpoint, &params);
+    if let Some(hit) = cache.get(&key) {
+        call_stats::record(Source::Cache);
+        return Ok(hit);
+    }
+
+    let value: Value = client
+        .get(endpoint, Some(params))
+        .await
+        .map_err(|e| CoreError::from(e).with_context(ErrorContext::endpoint(endpoint)))?;
+    cache.insert(key, value.clone());
+    Ok(value)
+}
+
+/// Fetch an EDGAR document and extract its text, going through the
+/// extracted-text cache
+async fn fetch_document_text(
+    sec_client: &SecClient,
+    cik: &str,
+    accession_number: &str,
+    filename: Option<&str>,
+) -> Result<String, CoreError> {
+    let cache = TextCache::open_default().ok();
+    if let Some(text) = cache.as_ref().and_then(|cache| cache.get(accession_number, filename)) {
+        call_stats::record(Source::Cache);
+        return Ok(text);
+    }
+
+    let (bytes, content_type) = sec_client.fetch_document(cik, accession_number, filename).await?;
+    let text = sec_documents::extract_document_text(&bytes, &content_type)?;
+    if let Some(cache) = &cache {
+        if let Err(e) = cache.put(accession_number, filename, &text) {
+            warn!("Failed to cache extracted text for {}: {}", accession_number, e);
+        }
+    }
+    Ok(text)
+}
+
+/// Spawn the task that owns stdout.
+///
+/// Responses are sent as serialized JSON over the returned channel so
+/// handlers never block on stdout, and each message is framed, written and
+/// flushed atomically. Messages use the framing the reader resolved, which
+/// is always set by the time a response exists. The task exits once every
+/// sender has been dropped.
+fn spawn_writer<W: AsyncWrite + Unpin + Send + 'static>(
+    mut writer: W,
+    framing: Arc<OnceLock<Framing>>,
+) -> (
+    mpsc::UnboundedSender<String>,
+    tokio::task::JoinHandle<std::io::Result<()>>,
+) {
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let handle = tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            debug!(target: WIRE_TARGET, "Sending: {}", message);
+            let framing = framing.get().copied().unwrap_or(Framing::Newline);
+            writer.write_all(&framing.encode(&message)).await?;
+            writer.flush().await?;
+        }
+        Ok(())
+    });
+    (tx, handle)
 }
 
 // ============================================================================
@@ -760,81 +3801,366 @@ impl McpServer {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging to stderr (stdout is for MCP protocol)
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(tracing::Level::INFO.into()),
+    let cli = Cli::parse();
+
+    // Initialize logging to stderr (stdout is for MCP protocol) and to
+    // clients through the logging capability
+    let stderr_filter = tracing_subscriber::EnvFilter::from_default_env().add_directive(cli.log_level.into());
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .with_filter(stderr_filter),
         )
-        .with_writer(std::io::stderr)
+        .with(ClientLogs::global().layer())
         .init();
 
     info!("Starting FilingExplorer MCP Server");
 
-    let server = McpServer::new();
-    let stdin = io::stdin();
-    let mut stdout = io::stdout();
+    let source = cli.config_source();
+    if let Some(path) = source.path() {
+        install_plugins(&plugins::path_beside(&path));
+    }
+    let mut server = McpServer::new(source.clone())?;
+    if let Some(path) = source.path() {
+        spawn_config_watcher(server.clone(), ConfigWatcher::new(path));
+    }
+    if cli.trace {
+        let config_path = source
+            .path()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine the config directory for --trace"))?;
+        let trace = Trace::open(&config_path)?;
+        info!("Tracing protocol messages to {}", trace.path().display());
+        server = server.with_trace(trace);
+    }
+    match cli.transport {
+        Transport::Stdio => serve_stdio(server, cli.framing).await,
+        Transport::Http => {
+            let listener = tokio::net::TcpListener::bind((cli.host, cli.port)).await?;
+            http::serve(server, listener).await
+        }
+        Transport::Socket => {
+            let path = cli
+                .path
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("--transport socket needs --path"))?;
+            socket::serve(server, path, cli.framing).await
+        }
+    }
+}
+
+/// Add the tools defined in a plugins file to the registry. A broken file
+/// is reported and skipped rather than keeping the server from starting.
+fn install_plugins(path: &std::path::Path) {
+    let loaded = match plugins::load(path) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            error!("Ignoring {}: {}", path.display(), e);
+            return;
+        }
+    };
+    if let Some(plugin) = loaded.iter().find(|p| own_tools().contains_key(&p.name)) {
+        error!("Ignoring {}: plugin '{}' has the same name as a server tool", path.display(), plugin.name);
+        return;
+    }
+    let count = loaded.len();
+    match plugins::install(loaded) {
+        Ok(()) if count > 0 => info!("Loaded {} plugin tool(s) from {}", count, path.display()),
+        Ok(()) => {}
+        Err(e) => error!("Ignoring {}: {}", path.display(), e),
+    }
+}
+
+/// Apply config file changes, such as a token saved in the settings app,
+/// without restarting the server
+fn spawn_config_watcher(server: McpServer, mut watcher: ConfigWatcher) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config_watch::POLL_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match watcher.poll() {
+                Ok(false) => {}
+                Ok(true) => match server.reload_config().await {
+                    Ok(_) => info!("Reloaded config from {}", watcher.path().display()),
+                    Err(e) => warn!("Could not reload config: {}", e),
+                },
+                Err(e) => debug!("Config file not ready to reload yet: {}", e),
+            }
+        }
+    });
+}
+
+/// Serve JSON-RPC requests from stdin until it closes or the server is
+/// asked to shut down
+async fn serve_stdio(server: McpServer, framing: Framing) -> Result<()> {
+    let mut messages = MessageReader::new(BufReader::new(tokio::io::stdin()), framing);
+    let (output, writer) = spawn_writer(tokio::io::stdout(), messages.framing());
+    let mut connection = Connection::new(server, output);
 
     info!("MCP Server ready. Listening on stdio...");
 
-    for line in stdin.lock().lines() {
-        let line = match line {
-            Ok(l) => l,
-            Err(e) => {
-                error!("Error reading stdin: {}", e);
-                continue;
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    loop {
+        let line = tokio::select! {
+            message = messages.next_message() => match message {
+                Ok(Some(l)) => l,
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Error reading stdin: {}", e);
+                    continue;
+                }
+            },
+            _ = &mut shutdown => {
+                info!("Received shutdown signal; no longer reading requests");
+                break;
+            }
+        };
+        connection.handle_message(&line).await?;
+    }
+
+    // Let the writer drain queued responses once every sender is gone
+    info!("Shutting down");
+    connection.finish().await;
+    writer.await??;
+
+    info!("Shut down cleanly");
+    Ok(())
+}
+
+/// Resolves on Ctrl-C (SIGINT), or SIGTERM where there are Unix signals
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
             }
+            return;
+        }
+    }
+    if tokio::signal::ctrl_c().await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+/// One client's session, whatever the transport: routes the client's
+/// responses to server-initiated requests, handles notifications, and runs
+/// slow requests concurrently while everything else stays in order. Outgoing
+/// messages are serialized JSON sent over `output`.
+///
+/// This is the seam between transports and [`McpServer`]: a transport only
+/// moves message strings in and out, feeding either
+/// [`Connection::handle_message`] directly, as stdio does, or the inbox of
+/// [`Connection::spawn`], as the HTTP transports do.
+struct Connection {
+    server: McpServer,
+    output: mpsc::UnboundedSender<String>,
+    request_slots: Arc<Semaphore>,
+    in_flight: JoinSet<()>,
+    /// Cancellation tokens of running requests, keyed by JSON-RPC id
+    cancellations: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    trace: Option<Arc<ConnectionTrace>>,
+}
+
+impl Connection {
+    fn new(server: McpServer, output: mpsc::UnboundedSender<String>) -> Self {
+        let trace = server.trace.as_ref().map(|trace| Arc::new(trace.connection()));
+        let output = match &trace {
+            Some(trace) => Self::traced(&server, trace.clone(), output),
+            None => output,
         };
+        server.client.attach(&output);
+        server.log.attach(&output);
+        Self {
+            server,
+            output,
+            request_slots: Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS)),
+            in_flight: JoinSet::new(),
+            cancellations: Arc::default(),
+            trace,
+        }
+    }
+
+    /// A sender that traces each outgoing message before passing it on to
+    /// `output`, which stays open until the returned sender's clones are gone
+    fn traced(
+        server: &McpServer,
+        trace: Arc<ConnectionTrace>,
+        output: mpsc::UnboundedSender<String>,
+    ) -> mpsc::UnboundedSender<String> {
+        let (traced, mut outgoing) = mpsc::unbounded_channel::<String>();
+        let state = server.state.clone();
+        tokio::spawn(async move {
+            while let Some(message) = outgoing.recv().await {
+                let token = state.read().await.config.api_token.clone();
+                trace.record(Direction::Sent, &message, token.as_deref());
+                if output.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        traced
+    }
+
+    /// Run a connection on a task tracked by `tasks`, handling messages sent
+    /// to the returned inbox in order until every sender is dropped
+    fn spawn(
+        server: McpServer,
+        output: mpsc::UnboundedSender<String>,
+        tasks: &TaskTracker,
+    ) -> mpsc::UnboundedSender<String> {
+        let (inbox, mut messages) = mpsc::unbounded_channel::<String>();
+        let mut connection = Self::new(server, output);
+        tasks.spawn(async move {
+            while let Some(message) = messages.recv().await {
+                if let Err(e) = connection.handle_message(&message).await {
+                    error!("Failed to handle message: {}", e);
+                }
+            }
+            connection.finish().await;
+        });
+        inbox
+    }
+
+    fn send(&self, response: &JsonRpcResponse) {
+        let _ = self.output.send(serde_json::to_string(response).unwrap());
+    }
 
+    /// Handle one incoming message
+    async fn handle_message(&mut self, line: &str) -> Result<()> {
         if line.is_empty() {
-            continue;
+            return Ok(());
         }
 
-        debug!("Received: {}", line);
+        debug!(target: WIRE_TARGET, "Received: {}", line);
+        if let Some(trace) = &self.trace {
+            let token = self.server.state.read().await.config.api_token.clone();
+            trace.record(Direction::Received, line, token.as_deref());
+        }
 
-        let request: JsonRpcRequest = match serde_json::from_str(&line) {
-            Ok(r) => r,
+        let request: JsonRpcRequest = match serde_json::from_str::<Value>(line) {
+            Ok(message) if self.server.client.handle_response(&message) => return Ok(()),
+            Ok(message) => match serde_json::from_value(message) {
+                Ok(r) => r,
+                Err(e) => {
+                    error!("Invalid JSON-RPC request: {}", e);
+                    self.send(&JsonRpcResponse::error(Some(Value::Null), -32600, "Invalid Request"));
+                    return Ok(());
+                }
+            },
             Err(e) => {
                 error!("Invalid JSON-RPC request: {}", e);
                 // For parse errors, we can't know the id, so use Value::Null
                 // but we still need to respond
-                let response = JsonRpcResponse::error(Some(Value::Null), -32700, "Parse error");
-                let output = serde_json::to_string(&response).unwrap();
-                writeln!(stdout, "{}", output)?;
-                stdout.flush()?;
-                continue;
+                self.send(&JsonRpcResponse::error(Some(Value::Null), -32700, "Parse error"));
+                return Ok(());
             }
         };
 
         // JSON-RPC 2.0: Notifications (requests without id) should not receive a response
-        let is_notification = request.id.is_none();
-
-        // Handle notifications silently (no response)
-        if is_notification {
-            debug!("Received notification: {}", request.method);
-            // Process known notifications
-            match request.method.as_str() {
-                "notifications/initialized" | "initialized" => {
-                    debug!("Client initialized");
-                }
-                "notifications/cancelled" => {
-                    debug!("Request cancelled");
+        if request.id.is_none() {
+            self.handle_notification(&request);
+            return Ok(());
+        }
+
+        // Slow independent requests run concurrently and the client matches
+        // responses by id. Everything else stays in order, so a document
+        // download never holds up a cheap call.
+        if CONCURRENT_METHODS.contains(&request.method.as_str()) {
+            let slots = self.request_slots.clone();
+            let server = self.server.clone();
+            let output = self.output.clone();
+            let cancellations = self.cancellations.clone();
+            let id = request.id.as_ref().map(Value::to_string).unwrap_or_default();
+            let token = CancellationToken::new();
+            cancellations.lock().unwrap().insert(id.clone(), token.clone());
+            self.in_flight.spawn(async move {
+                // Waiting for a slot here rather than in the reader keeps
+                // notifications and client responses flowing while every
+                // slot is taken. Dropping the call on cancel also drops its
+                // open connections; cancelled requests get no response, even
+                // when the call notices the cancellation and returns first.
+                let run = async {
+                    let _permit = slots.acquire().await;
+                    cancellation::scope(token.clone(), server.handle_request(request)).await
+                };
+                tokio::select! {
+                    biased;
+                    _ = token.cancelled() => debug!("Request {} cancelled", id),
+                    response = run => {
+                        // Cancelled on another thread while this poll ran the call
+                        if !token.is_cancelled() {
+                            let _ = output.send(serde_json::to_string(&response).unwrap());
+                        }
+                    }
                 }
-                _ => {
-                    debug!("Unknown notification: {}", request.method);
+                cancellations.lock().unwrap().remove(&id);
+            });
+            while let Some(result) = self.in_flight.try_join_next() {
+                if let Err(e) = result {
+                    error!("Request task failed: {}", e);
                 }
             }
-            continue;
+            return Ok(());
         }
 
-        let response = server.handle_request(request).await;
-        let output = serde_json::to_string(&response).unwrap();
+        let response = self.server.handle_request(request).await;
+        self.send(&response);
+        Ok(())
+    }
 
-        debug!("Sending: {}", output);
-        writeln!(stdout, "{}", output)?;
-        stdout.flush()?;
+    fn handle_notification(&self, request: &JsonRpcRequest) {
+        debug!("Received notification: {}", request.method);
+        match request.method.as_str() {
+            "notifications/initialized" | "initialized" => {
+                debug!("Client initialized");
+            }
+            "notifications/cancelled" => {
+                let id = request.params.get("requestId").map(Value::to_string);
+                let token = id.and_then(|id| self.cancellations.lock().unwrap().remove(&id));
+                match token {
+                    Some(token) => {
+                        debug!("Cancelling request {}", request.params["requestId"]);
+                        token.cancel();
+                    }
+                    None => debug!("Cancelled request is not running"),
+                }
+            }
+            _ => {
+                debug!("Unknown notification: {}", request.method);
+            }
+        }
     }
 
-    info!("Shutting down");
-    Ok(())
+    /// Fail the server's own outstanding requests, since nothing can answer
+    /// them any more, and give in-flight requests [`SHUTDOWN_GRACE`] to
+    /// finish. Any still running are then stopped and answered with an error.
+    async fn finish(mut self) {
+        self.server.client.close();
+        let in_flight = &mut self.in_flight;
+        let drained = tokio::time::timeout(SHUTDOWN_GRACE, async {
+            while let Some(result) = in_flight.join_next().await {
+                if let Err(e) = result {
+                    error!("Request task failed: {}", e);
+                }
+            }
+        })
+        .await;
+        if drained.is_ok() {
+            return;
+        }
+
+        warn!("Stopping {} requests still running after {:?}", self.in_flight.len(), SHUTDOWN_GRACE);
+        self.in_flight.shutdown().await;
+        // Tasks remove their ids once answered, so these never were
+        let unanswered: Vec<String> = self.cancellations.lock().unwrap().drain().map(|(id, _)| id).collect();
+        for id in unanswered {
+            let id = serde_json::from_str(&id).ok();
+            self.send(&JsonRpcResponse::error(id, -32603, "Server shut down before the request finished"));
+        }
+    }
 }
@@ -0,0 +1,186 @@
+//! Result metadata.
+//!
+//! Successful tool results carry a small `_meta` header with the elapsed
+//! time, where the data came from, how many records were returned, and
+//! whether the output was truncated, so callers can judge freshness and
+//! completeness.
+
+use filing_explorer_core::call_stats::{CallStats, Source};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Metadata for one tool result
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultMeta {
+    pub elapsed_ms: u64,
+    /// Data sources used ("api", "sec", "cache"); empty for local-only tools
+    pub sources: Vec<&'static str>,
+    pub api_requests: u32,
+    pub sec_requests: u32,
+    pub cache_hits: u32,
+    /// "hit" if the whole result came from the result cache, "miss" if it
+    /// was fetched and cached, "offline" if it is a saved copy returned
+    /// because the upstream couldn't be reached; absent for tools that
+    /// aren't cached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<&'static str>,
+    /// When an offline result was saved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub record_count: Option<usize>,
+    pub truncated: bool,
+    /// Retries after the upstream rate limited the call, and how long they
+    /// waited in total
+    #[serde(skip_serializing_if = "is_zero")]
+    pub retries: u32,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub retry_wait_ms: u64,
+    /// Conditions the caller should know about, e.g. a nearly exhausted
+    /// API request budget
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl ResultMeta {
+    pub fn new(elapsed: Duration, stats: &CallStats) -> Self {
+        Self {
+            elapsed_ms: elapsed.as_millis() as u64,
+            sources: stats.sources().iter().map(Source::as_str).collect(),
+            api_requests: stats.count(Source::Api),
+            sec_requests: stats.count(Source::Sec),
+            cache_hits: stats.count(Source::Cache),
+            cache: None,
+            cached_at: None,
+            record_count: None,
+            truncated: false,
+            retries: stats.retries(),
+            retry_wait_ms: stats.retry_wait().as_millis() as u64,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+impl ResultMeta {
+    pub fn set_cache(&mut self, status: CacheStatus) {
+        self.cache = Some(status.as_str());
+        if let CacheStatus::Offline { cached_at, reason } = status {
+            self.warnings.push(format!(
+                "Offline: {}. This is a saved result from {} and may be out of date.",
+                reason.trim_end_matches('.'),
+                cached_at
+            ));
+            self.cached_at = Some(cached_at);
+        }
+    }
+}
+
+/// How the result caches answered a call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+    /// The upstream failed with `reason`, so the copy saved at `cached_at`
+    /// was returned
+    Offline { cached_at: String, reason: String },
+}
+
+impl CacheStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hit => "hit",
+            Self::Miss => "miss",
+            Self::Offline { .. } => "offline",
+        }
+    }
+}
+
+fn is_zero<T: Default + PartialEq>(n: &T) -> bool {
+    *n == T::default()
+}
+
+/// Number of records in a list-shaped JSON result, if it is one
+pub fn record_count(text: &str, keys: &[&str]) -> Option<usize> {
+    let value: Value = serde_json::from_str(text).ok()?;
+    if let Some(arr) = value.as_array() {
+        return Some(arr.len());
+    }
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(|v| v.as_array()))
+        .map(|arr| arr.len())
+}
+
+/// Add `meta` to a result: as a `_meta` field of JSON object results, and as
+/// a leading `_meta:` line for anything else (text, arrays, truncated output)
+pub fn attach(text: String, meta: &ResultMeta) -> String {
+    if let Ok(Value::Object(mut obj)) = serde_json::from_str::<Value>(&text) {
+        obj.insert("_meta".to_string(), json!(meta));
+        return serde_json::to_string_pretty(&obj).unwrap();
+    }
+    format!("_meta: {}\n\n{}", json!(meta), text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta() -> ResultMeta {
+        ResultMeta::new(Duration::from_millis(42), &CallStats::default())
+    }
+
+    #[test]
+    fn test_record_count() {
+        assert_eq!(record_count("[1, 2, 3]", &["data"]), Some(3));
+        assert_eq!(record_count(r#"{"data": [1]}"#, &["data"]), Some(1));
+        assert_eq!(record_count(r#"{"name": "x"}"#, &["data"]), None);
+        assert_eq!(record_count("Found 2 results", &["data"]), None);
+    }
+
+    #[test]
+    fn test_attach_to_object() {
+        let text = attach(r#"{"data": []}"#.to_string(), &meta());
+        let value: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(value["_meta"]["elapsed_ms"], 42);
+        assert_eq!(value["_meta"]["sources"], json!([]));
+        assert_eq!(value["data"], json!([]));
+        assert!(value["_meta"].get("retries").is_none());
+    }
+
+    #[test]
+    fn test_attach_warnings() {
+        let text = attach("{}".to_string(), &meta());
+        assert!(!text.contains("warnings"));
+
+        let mut meta = meta();
+        meta.warnings.push("5 of 100 API requests remaining".to_string());
+        let value: Value = serde_json::from_str(&attach("{}".to_string(), &meta)).unwrap();
+        assert_eq!(value["_meta"]["warnings"], json!(["5 of 100 API requests remaining"]));
+    }
+
+    #[test]
+    fn test_offline_cache_status() {
+        let mut meta = meta();
+        meta.set_cache(CacheStatus::Offline {
+            cached_at: "2026-01-02T03:04:05Z".to_string(),
+            reason: "HTTP request failed: connection refused.".to_string(),
+        });
+        let value: Value = serde_json::from_str(&attach("{}".to_string(), &meta)).unwrap();
+        assert_eq!(value["_meta"]["cache"], "offline");
+        assert_eq!(value["_meta"]["cached_at"], "2026-01-02T03:04:05Z");
+        assert_eq!(
+            value["_meta"]["warnings"][0],
+            "Offline: HTTP request failed: connection refused. This is a saved result from 2026-01-02T03:04:05Z and may be out of date."
+        );
+    }
+
+    #[test]
+    fn test_attach_to_text() {
+        let mut meta = meta();
+        meta.truncated = true;
+        let text = attach("Found 1 statement\n\n{}".to_string(), &meta);
+        assert!(text.starts_with("_meta: {"));
+        assert!(text.contains("\"truncated\":true"));
+        assert!(text.ends_with("Found 1 statement\n\n{}"));
+    }
+}
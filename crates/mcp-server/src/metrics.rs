@@ -0,0 +1,221 @@
+//! In-process server metrics.
+//!
+//! Counts every tool call since the server started, with failures by error
+//! code and latency percentiles over each tool's recent calls. Reported by
+//! the `get_server_metrics` tool and, on the HTTP transport, at `/metrics` in
+//! the Prometheus text format for anyone running the server long-lived.
+
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Calls per tool whose latencies are kept for percentiles
+const LATENCY_WINDOW: usize = 1_000;
+
+/// Percentiles reported for each tool
+const QUANTILES: &[f64] = &[0.5, 0.9, 0.99];
+
+#[derive(Debug, Default)]
+struct ToolMetrics {
+    calls: u64,
+    errors: BTreeMap<&'static str, u64>,
+    total_duration: Duration,
+    /// Latencies of the most recent calls, oldest first
+    recent: VecDeque<Duration>,
+}
+
+impl ToolMetrics {
+    fn errors(&self) -> u64 {
+        self.errors.values().sum()
+    }
+
+    /// Latency percentiles over the recent calls
+    fn quantiles(&self) -> Vec<(f64, Duration)> {
+        let mut sorted: Vec<Duration> = self.recent.iter().copied().collect();
+        sorted.sort();
+        if sorted.is_empty() {
+            return Vec::new();
+        }
+        QUANTILES
+            .iter()
+            .map(|q| {
+                let rank = ((sorted.len() as f64 * q).ceil() as usize).clamp(1, sorted.len());
+                (*q, sorted[rank - 1])
+            })
+            .collect()
+    }
+}
+
+pub struct Metrics {
+    started: Instant,
+    tools: Mutex<BTreeMap<String, ToolMetrics>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            tools: Mutex::default(),
+        }
+    }
+}
+
+impl Metrics {
+    /// Count a finished call; `error` is the error code of a failed one
+    pub fn record(&self, tool: &str, duration: Duration, error: Option<&'static str>) {
+        let mut tools = self.tools.lock().unwrap();
+        let metrics = tools.entry(tool.to_string()).or_default();
+        metrics.calls += 1;
+        metrics.total_duration += duration;
+        if let Some(code) = error {
+            *metrics.errors.entry(code).or_default() += 1;
+        }
+        if metrics.recent.len() == LATENCY_WINDOW {
+            metrics.recent.pop_front();
+        }
+        metrics.recent.push_back(duration);
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Metrics as the `get_server_metrics` tool returns them
+    pub fn report(&self) -> Value {
+        let tools = self.tools.lock().unwrap();
+        let per_tool: Vec<Value> = tools
+            .iter()
+            .map(|(name, m)| {
+                let latency: serde_json::Map<String, Value> = m
+                    .quantiles()
+                    .into_iter()
+                    .map(|(q, d)| (format!("p{}", (q * 100.0).round()), json!(d.as_millis() as u64)))
+                    .collect();
+                json!({
+                    "tool": name,
+                    "calls": m.calls,
+                    "errors": m.errors(),
+                    "error_rate": (m.errors() as f64 / m.calls as f64 * 1000.0).round() / 1000.0,
+                    "errors_by_code": m.errors,
+                    "latency_ms": latency,
+                })
+            })
+            .collect();
+        json!({
+            "uptime_seconds": self.uptime().as_secs(),
+            "total_calls": tools.values().map(|m| m.calls).sum::<u64>(),
+            "total_errors": tools.values().map(ToolMetrics::errors).sum::<u64>(),
+            "latency_window": LATENCY_WINDOW,
+            "tools": per_tool,
+        })
+    }
+
+    /// Metrics in the Prometheus text exposition format
+    pub fn prometheus(&self) -> String {
+        let tools = self.tools.lock().unwrap();
+        let mut out = String::new();
+        let _ = writeln!(out, "# HELP filingexplorer_uptime_seconds Time since the server started.");
+        let _ = writeln!(out, "# TYPE filingexplorer_uptime_seconds gauge");
+        let _ = writeln!(out, "filingexplorer_uptime_seconds {:.3}", self.uptime().as_secs_f64());
+
+        let _ = writeln!(out, "# HELP filingexplorer_tool_calls_total Tool calls finished.");
+        let _ = writeln!(out, "# TYPE filingexplorer_tool_calls_total counter");
+        for (name, m) in tools.iter() {
+            let _ = writeln!(out, "filingexplorer_tool_calls_total{{tool=\"{}\"}} {}", label(name), m.calls);
+        }
+
+        let _ = writeln!(out, "# HELP filingexplorer_tool_errors_total Tool calls that failed, by error code.");
+        let _ = writeln!(out, "# TYPE filingexplorer_tool_errors_total counter");
+        for (name, m) in tools.iter() {
+            for (code, count) in &m.errors {
+                let _ = writeln!(
+                    out,
+                    "filingexplorer_tool_errors_total{{tool=\"{}\",code=\"{}\"}} {}",
+                    label(name),
+                    code,
+                    count
+                );
+            }
+        }
+
+        let _ = writeln!(out, "# HELP filingexplorer_tool_duration_seconds Tool call latency over recent calls.");
+        let _ = writeln!(out, "# TYPE filingexplorer_tool_duration_seconds summary");
+        for (name, m) in tools.iter() {
+            let name = label(name);
+            for (q, d) in m.quantiles() {
+                let _ = writeln!(
+                    out,
+                    "filingexplorer_tool_duration_seconds{{tool=\"{}\",quantile=\"{}\"}} {:.6}",
+                    name,
+                    q,
+                    d.as_secs_f64()
+                );
+            }
+            let _ = writeln!(
+                out,
+                "filingexplorer_tool_duration_seconds_sum{{tool=\"{}\"}} {:.6}",
+                name,
+                m.total_duration.as_secs_f64()
+            );
+            let _ = writeln!(out, "filingexplorer_tool_duration_seconds_count{{tool=\"{}\"}} {}", name, m.calls);
+        }
+        out
+    }
+}
+
+/// Escape a Prometheus label value
+fn label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let metrics = Metrics::default();
+        for ms in 1..=10 {
+            metrics.record("get_company_filings", Duration::from_millis(ms * 10), None);
+        }
+        metrics.record("get_company_filings", Duration::from_millis(500), Some("UPSTREAM_ERROR"));
+
+        let report = metrics.report();
+        assert_eq!(report["total_calls"], 11);
+        assert_eq!(report["total_errors"], 1);
+        let tool = &report["tools"][0];
+        assert_eq!(tool["tool"], "get_company_filings");
+        assert_eq!(tool["errors_by_code"]["UPSTREAM_ERROR"], 1);
+        assert_eq!(tool["latency_ms"]["p50"], 60);
+        assert_eq!(tool["latency_ms"]["p99"], 500);
+    }
+
+    #[test]
+    fn test_latency_window() {
+        let metrics = Metrics::default();
+        metrics.record("t", Duration::from_secs(60), None);
+        for _ in 0..LATENCY_WINDOW {
+            metrics.record("t", Duration::from_millis(1), None);
+        }
+        assert_eq!(metrics.report()["tools"][0]["latency_ms"]["p99"], 1);
+        assert_eq!(metrics.report()["tools"][0]["calls"], LATENCY_WINDOW as u64 + 1);
+    }
+
+    #[test]
+    fn test_prometheus() {
+        let metrics = Metrics::default();
+        metrics.record("get_lists", Duration::from_millis(250), None);
+        metrics.record("get_lists", Duration::from_millis(750), Some("NOT_FOUND"));
+
+        let text = metrics.prometheus();
+        assert!(text.contains("# TYPE filingexplorer_tool_calls_total counter\n"));
+        assert!(text.contains("filingexplorer_tool_calls_total{tool=\"get_lists\"} 2\n"));
+        assert!(text.contains("filingexplorer_tool_errors_total{tool=\"get_lists\",code=\"NOT_FOUND\"} 1\n"));
+        assert!(text.contains("filingexplorer_tool_duration_seconds{tool=\"get_lists\",quantile=\"0.5\"} 0.250000\n"));
+        assert!(text.contains("filingexplorer_tool_duration_seconds_sum{tool=\"get_lists\"} 1.000000\n"));
+        assert!(text.contains("filingexplorer_uptime_seconds "));
+        assert_eq!(label("a\"b"), "a\\\"b");
+    }
+}
@@ -0,0 +1,273 @@
+//! Large tool output handling.
+//!
+//! Results above the configured size limit are written to the cache
+//! directory and replaced with the first slice that fits, plus the path to
+//! the full payload, so a single oversized response doesn't flood the
+//! model's context. List results are cut between records and keep a
+//! `_truncated` summary; anything else is cut at a byte offset. Either way a
+//! continuation token lets `continue_result` return the following slice.
+
+use crate::error::{ErrorCode, ToolError};
+use filing_explorer_core::tools::pagination::{decode_token, encode_token, RECORD_KEYS};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Tool that returns the next slice of a truncated result
+pub const CONTINUE_TOOL: &str = "continue_result";
+
+/// Subdirectory of the cache dir that holds spilled tool results
+const OUTPUT_DIR: &str = "tool-output";
+
+/// Directory used for spilled tool results
+pub fn output_dir() -> Option<PathBuf> {
+    filing_explorer_core::Config::cache_dir()
+        .ok()
+        .map(|dir| dir.join(OUTPUT_DIR))
+}
+
+/// Truncate `text` to at most `max_bytes`, backing off to a char boundary
+fn preview(text: &str, max_bytes: usize) -> &str {
+    let mut end = max_bytes.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// Return `text` unchanged if it fits in `max_bytes`; otherwise write it to
+/// `dir` and return the first slice that fits, pointing at the saved file.
+pub fn spill_large_output(
+    tool_name: &str,
+    text: &str,
+    max_bytes: usize,
+    dir: &Path,
+) -> std::io::Result<String> {
+    if text.len() <= max_bytes {
+        return Ok(text.to_string());
+    }
+
+    fs::create_dir_all(dir)?;
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let safe_name: String = tool_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '-' })
+        .collect();
+    let file = format!("{}-{}-{}.json", safe_name, stamp, std::process::id());
+    let path = dir.join(&file);
+    fs::write(&path, text)?;
+
+    Ok(slice(text, &path, &file, 0, max_bytes))
+}
+
+/// The slice of a spilled result that follows the one `continuation` was
+/// issued with
+pub fn continue_output(continuation: &str, max_bytes: usize, dir: &Path) -> Result<String, ToolError> {
+    let invalid = || {
+        ToolError::invalid_param("Invalid continuation; pass it unchanged from a truncated result")
+            .with_details(json!({ "parameter": "continuation" }))
+    };
+    let token: Continuation = decode_token(continuation).ok_or_else(invalid)?;
+    // Only bare file names, so a token can't reach outside the output directory
+    if token.file.contains(['/', '\\']) || token.file.starts_with('.') {
+        return Err(invalid());
+    }
+    let path = dir.join(&token.file);
+    let text = fs::read_to_string(&path).map_err(|_| {
+        ToolError::new(ErrorCode::NotFound, format!("The full result is no longer at {}", path.display()))
+            .with_remediation("Run the original tool call again.")
+    })?;
+    if !text.is_char_boundary(token.offset.min(text.len())) {
+        return Err(invalid());
+    }
+    Ok(slice(&text, &path, &token.file, token.offset, max_bytes))
+}
+
+/// Where the next slice of a spilled result starts: a record index for
+/// list results, a byte offset for anything else
+#[derive(Debug, Serialize, Deserialize)]
+struct Continuation {
+    #[serde(rename = "f")]
+    file: String,
+    #[serde(rename = "o")]
+    offset: usize,
+}
+
+/// The part of a spilled result starting at `offset` that fits in about
+/// `max_bytes`
+fn slice(text: &str, path: &Path, file: &str, offset: usize, max_bytes: usize) -> String {
+    let token = |offset| {
+        encode_token(&Continuation {
+            file: file.to_string(),
+            offset,
+        })
+    };
+    if let Some(slice) = record_slice(text, path, offset, max_bytes, token) {
+        return slice;
+    }
+
+    let start = offset.min(text.len());
+    let part = preview(&text[start..], max_bytes);
+    let end = start + part.len();
+    let shown = if start == 0 {
+        format!("showing {} of {} bytes", part.len(), text.len())
+    } else {
+        format!("showing bytes {} to {} of {}", start, end, text.len())
+    };
+    let next = if end < text.len() {
+        format!(". For the next part, call {} with continuation \"{}\"", CONTINUE_TOOL, token(end))
+    } else {
+        String::new()
+    };
+    format!(
+        "{}\n\n[Output truncated: {}. Full result saved to {}{}]",
+        part,
+        shown,
+        path.display(),
+        next
+    )
+}
+
+/// Space left for the `_truncated` summary and result metadata
+const SUMMARY_RESERVE: usize = 1024;
+
+/// For a list result, the records from `offset` that fit in the budget with
+/// a `_truncated` summary of what was left out; None for other results
+fn record_slice(
+    text: &str,
+    path: &Path,
+    offset: usize,
+    max_bytes: usize,
+    token: impl Fn(usize) -> String,
+) -> Option<String> {
+    let value: Value = serde_json::from_str(text).ok()?;
+    let (mut shell, key, records) = match value {
+        Value::Array(records) => (serde_json::Map::new(), "data", records),
+        Value::Object(mut obj) => {
+            let key = *RECORD_KEYS.iter().find(|k| obj.get(**k).is_some_and(Value::is_array))?;
+            let Some(Value::Array(records)) = obj.remove(key) else {
+                return None;
+            };
+            (obj, key, records)
+        }
+        _ => return None,
+    };
+
+    let total = records.len();
+    let start = offset.min(total);
+    let mut size = serde_json::to_string_pretty(&shell).unwrap().len() + SUMMARY_RESERVE;
+    let mut page = Vec::new();
+    for record in records.into_iter().skip(start) {
+        let pretty = serde_json::to_string_pretty(&record).unwrap();
+        // Nested two levels deep, each line gains four spaces of indent
+        let record_size = pretty.len() + 4 * pretty.lines().count() + 2;
+        // Always return at least one record so paging makes progress
+        if !page.is_empty() && size + record_size > max_bytes {
+            break;
+        }
+        size += record_size;
+        page.push(record);
+    }
+
+    let end = start + page.len();
+    let returned = page.len();
+    shell.insert(key.to_string(), Value::Array(page));
+    shell.insert(
+        "_truncated".to_string(),
+        json!({
+            "records_key": key,
+            "first_record": start,
+            "returned_records": returned,
+            "total_records": total,
+            "remaining_records": total - end,
+            "full_result": path.display().to_string(),
+            "continuation": (end < total).then(|| token(end)),
+            "note": format!("Output exceeded the size limit. Call {} with the continuation for the next records.", CONTINUE_TOOL),
+        }),
+    );
+    Some(serde_json::to_string_pretty(&shell).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_small_output_unchanged() {
+        let dir = TempDir::new().unwrap();
+        let text = spill_large_output("search", "short", 100, dir.path()).unwrap();
+        assert_eq!(text, "short");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_large_output_spilled() {
+        let dir = TempDir::new().unwrap();
+        let full = "é".repeat(100);
+        let text = spill_large_output("get_form13f_submission", &full, 11, dir.path()).unwrap();
+
+        assert!(text.starts_with("ééééé\n\n[Output truncated: showing 10 of 200 bytes."));
+        let saved: Vec<_> = fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(saved.len(), 1);
+        let path = saved[0].as_ref().unwrap().path();
+        assert!(text.contains(&path.display().to_string()));
+        assert_eq!(fs::read_to_string(path).unwrap(), full);
+    }
+
+    #[test]
+    fn test_text_continuation() {
+        let dir = TempDir::new().unwrap();
+        let full = "abcdefghij".repeat(3);
+        let first = spill_large_output("extract_document_text", &full, 12, dir.path()).unwrap();
+        assert!(first.starts_with("abcdefghijab\n\n[Output truncated: showing 12 of 30 bytes."));
+
+        let token = first.split('"').nth(1).unwrap();
+        let second = continue_output(token, 12, dir.path()).unwrap();
+        assert!(second.starts_with("cdefghijabcd\n\n[Output truncated: showing bytes 12 to 24 of 30."));
+        let token = second.split('"').nth(1).unwrap();
+        let last = continue_output(token, 12, dir.path()).unwrap();
+        assert!(last.starts_with("efghij\n\n"));
+        assert!(!last.contains(CONTINUE_TOOL));
+    }
+
+    #[test]
+    fn test_record_continuation() {
+        let dir = TempDir::new().unwrap();
+        let holdings: Vec<Value> = (0..200).map(|i| json!({ "cusip": format!("{:09}", i), "value": i })).collect();
+        let full = serde_json::to_string_pretty(&json!({ "fund": "VXUS", "holdings": holdings })).unwrap();
+        let first: Value =
+            serde_json::from_str(&spill_large_output("get_etf_holdings", &full, 4096, dir.path()).unwrap()).unwrap();
+        assert_eq!(first["fund"], "VXUS");
+        let returned = first["_truncated"]["returned_records"].as_u64().unwrap() as usize;
+        assert!(returned > 0 && returned < 200);
+        assert_eq!(first["holdings"].as_array().unwrap().len(), returned);
+        assert_eq!(first["_truncated"]["remaining_records"], 200 - returned);
+
+        let token = first["_truncated"]["continuation"].as_str().unwrap();
+        let second: Value = serde_json::from_str(&continue_output(token, 4096, dir.path()).unwrap()).unwrap();
+        assert_eq!(second["_truncated"]["first_record"], returned);
+        assert_eq!(second["holdings"][0]["value"], returned);
+    }
+
+    #[test]
+    fn test_invalid_continuation() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(continue_output("zz", 10, dir.path()).unwrap_err().code, ErrorCode::InvalidParam);
+        let escape = encode_token(&Continuation {
+            file: "../config.json".to_string(),
+            offset: 0,
+        });
+        assert_eq!(continue_output(&escape, 10, dir.path()).unwrap_err().code, ErrorCode::InvalidParam);
+        let gone = encode_token(&Continuation {
+            file: "search-1-1.json".to_string(),
+            offset: 0,
+        });
+        assert_eq!(continue_output(&gone, 10, dir.path()).unwrap_err().code, ErrorCode::NotFound);
+    }
+}
@@ -0,0 +1,79 @@
+//! Retrying rate-limited tool calls.
+//!
+//! A 429 from the FilingExplorer API or SEC EDGAR usually clears within
+//! seconds, so instead of handing the error straight back, the server waits
+//! and runs the call again a few times. The wait honors the upstream's
+//! `Retry-After` hint and otherwise backs off exponentially; a hint longer
+//! than the policy will wait ends the retries at once.
+
+use crate::error::{ErrorCode, ToolError};
+use std::time::Duration;
+
+/// Bounds on how a rate-limited call is retried
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for each one after it
+    pub base_delay: Duration,
+    /// Longest single wait
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// How long to wait before retry number `attempt` (counting from 0) of
+    /// a call that failed with `error`, or None to give up
+    pub fn delay(&self, attempt: u32, error: &ToolError) -> Option<Duration> {
+        if error.code != ErrorCode::UpstreamRateLimited || attempt >= self.max_retries {
+            return None;
+        }
+        let backoff = self.base_delay.saturating_mul(1 << attempt.min(16)).min(self.max_delay);
+        let hint = error.retry_after_seconds.map(Duration::from_secs).unwrap_or_default();
+        (hint <= self.max_delay).then(|| backoff.max(hint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filing_explorer_core::api_client::ApiError;
+
+    fn rate_limited(retry_after: Option<u64>) -> ToolError {
+        ToolError::from(ApiError::RateLimited { retry_after })
+    }
+
+    #[test]
+    fn test_backoff_honors_hint() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(30),
+        };
+        let error = rate_limited(Some(3));
+        assert_eq!(policy.delay(0, &error), Some(Duration::from_secs(3)));
+        assert_eq!(policy.delay(1, &error), Some(Duration::from_secs(4)));
+        assert_eq!(policy.delay(2, &error), Some(Duration::from_secs(8)));
+        assert_eq!(policy.delay(3, &error), None);
+    }
+
+    #[test]
+    fn test_gives_up() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(0, &rate_limited(Some(120))), None);
+        assert_eq!(policy.delay(0, &ToolError::from(ApiError::NotFound)), None);
+        let unavailable = ToolError::from(ApiError::ApiError {
+            status: 503,
+            message: "Unavailable".to_string(),
+        });
+        assert_eq!(policy.delay(0, &unavailable), None);
+    }
+}
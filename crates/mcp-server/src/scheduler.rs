@@ -0,0 +1,124 @@
+//! Per-category concurrency and rate limits.
+//!
+//! Limits come from `category_limits` in the config file, e.g.
+//!
+//! ```json
+//! "category_limits": {
+//!     "sec_documents": { "max_concurrent": 2 },
+//!     "company_data": { "max_per_minute": 60 }
+//! }
+//! ```
+//!
+//! so heavy document workflows can't starve interactive lookups.
+
+use filing_explorer_core::config::CategoryLimit;
+use filing_explorer_core::tools::Category;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+
+struct CategorySlot {
+    concurrency: Option<Arc<Semaphore>>,
+    rate: Option<DefaultDirectRateLimiter>,
+}
+
+/// Shared scheduler that gates tool calls by category
+#[derive(Default)]
+pub struct Scheduler {
+    slots: HashMap<Category, CategorySlot>,
+}
+
+/// Held for the duration of a tool call; releases the concurrency slot on drop
+pub struct Admission {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Scheduler {
+    pub fn from_limits(limits: &HashMap<String, CategoryLimit>) -> Self {
+        let mut slots = HashMap::new();
+        for (name, limit) in limits {
+            let Ok(category) = name.parse::<Category>() else {
+                warn!("Ignoring limits for unknown category '{}'", name);
+                continue;
+            };
+            let concurrency = limit
+                .max_concurrent
+                .filter(|n| *n > 0)
+                .map(|n| Arc::new(Semaphore::new(n)));
+            let rate = limit
+                .max_per_minute
+                .and_then(NonZeroU32::new)
+                .map(|n| RateLimiter::direct(Quota::per_minute(n)));
+            slots.insert(category, CategorySlot { concurrency, rate });
+        }
+        Self { slots }
+    }
+
+    /// Wait until a call in `category` may start
+    pub async fn admit(&self, category: Option<Category>) -> Admission {
+        let Some(slot) = category.and_then(|c| self.slots.get(&c)) else {
+            return Admission { _permit: None };
+        };
+
+        if let Some(rate) = &slot.rate {
+            rate.until_ready().await;
+        }
+        let permit = match &slot.concurrency {
+            // The semaphore is never closed, so acquiring can't fail
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+        Admission { _permit: permit }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limits(json: &str) -> HashMap<String, CategoryLimit> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit() {
+        let scheduler = Scheduler::from_limits(&limits(r#"{"sec_documents": {"max_concurrent": 1}}"#));
+
+        let first = scheduler.admit(Some(Category::SecDocuments)).await;
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(20),
+            scheduler.admit(Some(Category::SecDocuments)),
+        )
+        .await;
+        assert!(blocked.is_err());
+
+        // Other categories are unaffected
+        let _other = scheduler.admit(Some(Category::CompanyData)).await;
+
+        drop(first);
+        let _second = scheduler.admit(Some(Category::SecDocuments)).await;
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        let scheduler = Scheduler::from_limits(&limits(r#"{"lobbying": {"max_per_minute": 1}}"#));
+
+        let _first = scheduler.admit(Some(Category::Lobbying)).await;
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(20),
+            scheduler.admit(Some(Category::Lobbying)),
+        )
+        .await;
+        assert!(blocked.is_err());
+    }
+
+    #[test]
+    fn test_unknown_categories_ignored() {
+        let scheduler = Scheduler::from_limits(&limits(r#"{"bogus": {"max_concurrent": 1}}"#));
+        assert!(scheduler.slots.is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+//! Local socket transport.
+//!
+//! `--transport socket --path PATH` listens on a Unix domain socket at PATH,
+//! or a named pipe such as `\\.\pipe\filingexplorer` on Windows, so local
+//! supervisors and tests can talk to the server without owning its stdio.
+//! Each connection speaks the same JSON-RPC protocol as stdio and gets its
+//! own [`Connection`], sharing the server's state and caches.
+
+use crate::framing::{Framing, MessageReader};
+use crate::{shutdown_signal, spawn_writer, Connection, McpServer, SHUTDOWN_GRACE};
+use anyhow::Result;
+use std::io;
+use std::path::Path;
+use tokio::io::{AsyncRead, AsyncWrite, BufReader};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::{debug, error, info, warn};
+
+/// Serve MCP on the socket or pipe at `path` until asked to shut down
+pub async fn serve(server: McpServer, path: &Path, framing: Framing) -> Result<()> {
+    let connections = TaskTracker::new();
+    let stop = CancellationToken::new();
+    let result = listen(&server, path, framing, &connections, &stop).await;
+
+    // Connections stop reading and get the usual grace period to answer
+    // what they already received
+    info!("Shutting down");
+    stop.cancel();
+    connections.close();
+    if tokio::time::timeout(SHUTDOWN_GRACE * 2, connections.wait()).await.is_err() {
+        warn!("{} connections still open at shutdown", connections.len());
+    }
+    info!("Shut down cleanly");
+    result
+}
+
+#[cfg(unix)]
+async fn listen(
+    server: &McpServer,
+    path: &Path,
+    framing: Framing,
+    connections: &TaskTracker,
+    stop: &CancellationToken,
+) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixListener;
+
+    remove_stale_socket(path)?;
+    let listener = UnixListener::bind(path)?;
+    // The socket runs tools with the configured token, so keep it to its owner
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    info!("MCP Server ready. Listening on {}", path.display());
+
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    error!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+        debug!("Connection on {}", path.display());
+        connections.spawn(serve_connection(server.for_connection(), stream, framing, stop.clone()));
+    }
+
+    drop(listener);
+    if let Err(e) = std::fs::remove_file(path) {
+        debug!("Could not remove {}: {}", path.display(), e);
+    }
+    Ok(())
+}
+
+/// Remove a socket file left behind by a server that didn't exit cleanly.
+/// Anything that isn't a socket, or a socket another server is listening
+/// on, is left alone and reported.
+#[cfg(unix)]
+fn remove_stale_socket(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixStream;
+
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists and is not a socket", path.display()),
+        ));
+    }
+    if UnixStream::connect(path).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("Another server is listening on {}", path.display()),
+        ));
+    }
+    std::fs::remove_file(path)
+}
+
+#[cfg(windows)]
+async fn listen(
+    server: &McpServer,
+    path: &Path,
+    framing: Framing,
+    connections: &TaskTracker,
+    stop: &CancellationToken,
+) -> Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    // Each client takes the waiting pipe instance, and a new one is created
+    // for the next
+    let mut pipe = ServerOptions::new().first_pipe_instance(true).create(path)?;
+    info!("MCP Server ready. Listening on {}", path.display());
+
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            connected = pipe.connect() => {
+                if let Err(e) = connected {
+                    error!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            }
+            _ = &mut shutdown => break,
+        }
+        debug!("Connection on {}", path.display());
+        let client = std::mem::replace(&mut pipe, ServerOptions::new().create(path)?);
+        connections.spawn(serve_connection(server.for_connection(), client, framing, stop.clone()));
+    }
+    Ok(())
+}
+
+/// Handle one client's messages until it disconnects or `stop` is cancelled
+async fn serve_connection<S>(server: McpServer, stream: S, framing: Framing, stop: CancellationToken)
+where
+    S: AsyncRead + AsyncWrite + Send + 'static,
+{
+    let (read, write) = tokio::io::split(stream);
+    let mut messages = MessageReader::new(BufReader::new(read), framing);
+    let (output, writer) = spawn_writer(write, messages.framing());
+    let mut connection = Connection::new(server, output);
+
+    loop {
+        let message = tokio::select! {
+            message = messages.next_message() => message,
+            _ = stop.cancelled() => break,
+        };
+        match message {
+            Ok(Some(message)) => {
+                if let Err(e) = connection.handle_message(&message).await {
+                    error!("Failed to handle message: {}", e);
+                    break;
+                }
+            }
+            Ok(None) => break,
+            // A malformed frame is skipped, as on stdio
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => error!("Error reading socket: {}", e),
+            Err(e) => {
+                debug!("Connection closed: {}", e);
+                break;
+            }
+        }
+    }
+
+    connection.finish().await;
+    match writer.await {
+        Ok(Err(e)) => debug!("Connection closed while writing: {}", e),
+        Err(e) => error!("Socket writer failed: {}", e),
+        Ok(Ok(())) => {}
+    }
+}
@@ -0,0 +1,157 @@
+//! Company identifier resolution.
+//!
+//! Tools that take a CIK accept tickers too: tickers are mapped to CIKs with
+//! SEC's `company_tickers.json`, fetched on first use and kept for a day.
+//! Without an SEC identity configured (or if SEC is unreachable) tickers are
+//! passed through unchanged for the API to resolve.
+
+use filing_explorer_core::params::{self, CompanyId};
+use filing_explorer_core::{Config, SecClient};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+/// How long the SEC ticker mapping is reused before refetching
+const TICKER_MAP_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+type TickerMap = Arc<HashMap<String, String>>;
+
+#[derive(Default)]
+pub struct TickerResolver {
+    map: RwLock<Option<(Instant, TickerMap)>>,
+}
+
+impl TickerResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(test)]
+    fn with_map(map: HashMap<String, String>) -> Self {
+        Self {
+            map: RwLock::new(Some((Instant::now(), Arc::new(map)))),
+        }
+    }
+
+    /// Normalize a company identifier to a 10-digit CIK where possible
+    pub async fn resolve(&self, config: &Config, input: &str) -> Result<String, String> {
+        let ticker = match params::parse_company_id(input)? {
+            CompanyId::Cik(cik) => return Ok(cik),
+            CompanyId::Ticker(ticker) => ticker,
+        };
+
+        match self.ticker_map(config).await {
+            Some(map) => match params::lookup_ticker(&map, &ticker) {
+                Some(cik) => {
+                    debug!("Resolved ticker {} to CIK {}", ticker, cik);
+                    Ok(cik.to_string())
+                }
+                None => Ok(ticker),
+            },
+            None => Ok(ticker),
+        }
+    }
+
+    /// Known companies whose ticker starts with `prefix`, or whose CIK does
+    /// when it is numeric, as (ticker, CIK) pairs with the shortest tickers
+    /// first. Empty without an SEC identity, as the list comes from SEC.
+    pub async fn matching(&self, config: &Config, prefix: &str) -> Vec<(String, String)> {
+        let Some(map) = self.ticker_map(config).await else {
+            return Vec::new();
+        };
+        let prefix = prefix.trim().to_uppercase();
+        let numeric = !prefix.is_empty() && prefix.bytes().all(|b| b.is_ascii_digit());
+        let cik_prefix = prefix.trim_start_matches('0');
+        let mut matches: Vec<(String, String)> = map
+            .iter()
+            .filter(|(ticker, cik)| {
+                if numeric {
+                    cik.trim_start_matches('0').starts_with(cik_prefix)
+                } else {
+                    ticker.starts_with(&prefix)
+                }
+            })
+            .map(|(ticker, cik)| (ticker.clone(), cik.clone()))
+            .collect();
+        matches.sort_by(|a, b| a.0.len().cmp(&b.0.len()).then_with(|| a.0.cmp(&b.0)));
+        matches
+    }
+
+    async fn ticker_map(&self, config: &Config) -> Option<TickerMap> {
+        if let Some((loaded, map)) = self.map.read().await.as_ref() {
+            if loaded.elapsed() < TICKER_MAP_TTL {
+                return Some(map.clone());
+            }
+        }
+
+        let Ok(client) = SecClient::from_config(config) else {
+            return self.map.read().await.as_ref().map(|(_, map)| map.clone());
+        };
+
+        let mut slot = self.map.write().await;
+        // Another call may have refreshed the map while we waited for the lock
+        if let Some((loaded, map)) = slot.as_ref() {
+            if loaded.elapsed() < TICKER_MAP_TTL {
+                return Some(map.clone());
+            }
+        }
+
+        match client.fetch_company_tickers().await {
+            Ok(map) => {
+                let map = Arc::new(map);
+                *slot = Some((Instant::now(), map.clone()));
+                Some(map)
+            }
+            Err(e) => {
+                // Keep serving a stale map rather than none at all
+                warn!("Failed to fetch SEC ticker mapping: {}", e);
+                slot.as_ref().map(|(_, map)| map.clone())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let resolver = TickerResolver::with_map(HashMap::from([(
+            "AAPL".to_string(),
+            "0000320193".to_string(),
+        )]));
+        let config = Config::default();
+
+        assert_eq!(resolver.resolve(&config, "320193").await.unwrap(), "0000320193");
+        assert_eq!(resolver.resolve(&config, " aapl ").await.unwrap(), "0000320193");
+        // Unknown tickers are left for the API to resolve
+        assert_eq!(resolver.resolve(&config, "zzzz").await.unwrap(), "ZZZZ");
+        assert!(resolver.resolve(&config, "two words").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_matching() {
+        let resolver = TickerResolver::with_map(HashMap::from([
+            ("AAPL".to_string(), "0000320193".to_string()),
+            ("AA".to_string(), "0001675149".to_string()),
+            ("MSFT".to_string(), "0000789019".to_string()),
+        ]));
+        let config = Config::default();
+
+        let tickers: Vec<String> = resolver.matching(&config, "aa").await.into_iter().map(|(t, _)| t).collect();
+        assert_eq!(tickers, vec!["AA", "AAPL"]);
+        assert_eq!(resolver.matching(&config, "3201").await, vec![("AAPL".to_string(), "0000320193".to_string())]);
+        assert_eq!(resolver.matching(&config, "").await.len(), 3);
+        assert!(TickerResolver::new().matching(&config, "AA").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_passes_tickers_through_without_sec_identity() {
+        let resolver = TickerResolver::new();
+        let config = Config::default();
+        assert_eq!(resolver.resolve(&config, "msft").await.unwrap(), "MSFT");
+    }
+}
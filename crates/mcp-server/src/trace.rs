@@ -0,0 +1,231 @@
+//! Protocol trace files.
+//!
+//! With `--trace` every JSON-RPC message a client sends or receives is
+//! appended to `traces/trace.jsonl` next to the config file, one JSON
+//! record per line. Records carry a correlation id made of the connection
+//! number and the JSON-RPC id, so a request can be matched with its
+//! response when a user reports that a tool failed. Credentials are
+//! redacted before anything is written. The file is rotated once it grows
+//! past [`MAX_FILE_BYTES`], keeping [`KEPT_FILES`] older ones.
+
+use chrono::{SecondsFormat, Utc};
+use serde_json::{json, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Subdirectory of the config dir that holds trace files
+const TRACE_DIR: &str = "traces";
+
+const FILE_NAME: &str = "trace.jsonl";
+
+/// Size at which the trace file is rotated
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Rotated files kept, as `trace.jsonl.1` (newest) through `trace.jsonl.N`
+const KEPT_FILES: usize = 3;
+
+/// Object keys whose values are always redacted, compared case-insensitively
+const SECRET_KEYS: &[&str] = &["authorization", "api_token", "apitoken", "token", "password", "secret"];
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Which way a message went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Received,
+    Sent,
+}
+
+impl Direction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Received => "received",
+            Self::Sent => "sent",
+        }
+    }
+}
+
+/// The trace file, shared by every connection
+pub struct Trace {
+    dir: PathBuf,
+    file: Mutex<TraceFile>,
+    next_connection: AtomicU64,
+}
+
+struct TraceFile {
+    file: File,
+    len: u64,
+}
+
+impl Trace {
+    /// Open (or create) the trace file in the `traces` directory beside
+    /// `config_path`
+    pub fn open(config_path: &Path) -> io::Result<Arc<Self>> {
+        let dir = config_path.parent().unwrap_or(Path::new(".")).join(TRACE_DIR);
+        Self::open_in(dir)
+    }
+
+    fn open_in(dir: PathBuf) -> io::Result<Arc<Self>> {
+        fs::create_dir_all(&dir)?;
+        let file = TraceFile::open(&dir.join(FILE_NAME))?;
+        Ok(Arc::new(Self {
+            dir,
+            file: Mutex::new(file),
+            next_connection: AtomicU64::new(1),
+        }))
+    }
+
+    /// The file records are written to
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(FILE_NAME)
+    }
+
+    /// Trace for a new connection, numbered in the order they are opened
+    pub fn connection(self: &Arc<Self>) -> ConnectionTrace {
+        ConnectionTrace {
+            trace: self.clone(),
+            connection: self.next_connection.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    fn write(&self, record: &Value) -> io::Result<()> {
+        let mut line = record.to_string();
+        line.push('\n');
+        let mut file = self.file.lock().unwrap();
+        if file.len > 0 && file.len + line.len() as u64 > MAX_FILE_BYTES {
+            drop(std::mem::replace(&mut *file, self.rotate()?));
+        }
+        file.file.write_all(line.as_bytes())?;
+        file.len += line.len() as u64;
+        Ok(())
+    }
+
+    /// Shift the rotated files up by one, dropping the oldest, and start a
+    /// new file
+    fn rotate(&self) -> io::Result<TraceFile> {
+        let rotated = |n: usize| self.dir.join(format!("{}.{}", FILE_NAME, n));
+        let _ = fs::remove_file(rotated(KEPT_FILES));
+        for n in (1..KEPT_FILES).rev() {
+            let _ = fs::rename(rotated(n), rotated(n + 1));
+        }
+        fs::rename(self.path(), rotated(1))?;
+        TraceFile::open(&self.path())
+    }
+}
+
+impl TraceFile {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { file, len })
+    }
+}
+
+/// One connection's view of the trace
+pub struct ConnectionTrace {
+    trace: Arc<Trace>,
+    connection: u64,
+}
+
+impl ConnectionTrace {
+    /// Append a message, given as the JSON text that went over the wire,
+    /// with credentials and any occurrence of `token` redacted
+    pub fn record(&self, direction: Direction, message: &str, token: Option<&str>) {
+        let mut message = serde_json::from_str(message).unwrap_or_else(|_| json!(message));
+        redact(&mut message, token.filter(|t| !t.is_empty()));
+        let correlation_id = match message.get("id") {
+            Some(Value::String(id)) => Some(format!("{}:{}", self.connection, id)),
+            Some(id) if !id.is_null() => Some(format!("{}:{}", self.connection, id)),
+            _ => None,
+        };
+        let record = json!({
+            "time": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            "connection": self.connection,
+            "correlation_id": correlation_id,
+            "direction": direction.as_str(),
+            "message": message,
+        });
+        if let Err(e) = self.trace.write(&record) {
+            // Not logged through tracing, which clients may be tracing
+            eprintln!("Could not write trace record: {}", e);
+        }
+    }
+}
+
+/// Replace secret-named fields and occurrences of `token` in strings
+fn redact(value: &mut Value, token: Option<&str>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if SECRET_KEYS.contains(&key.to_ascii_lowercase().as_str()) && !value.is_null() {
+                    *value = json!(REDACTED);
+                } else {
+                    redact(value, token);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| redact(item, token)),
+        Value::String(s) => {
+            if let Some(token) = token.filter(|token| s.contains(token)) {
+                *s = s.replace(token, REDACTED);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records(path: &Path) -> Vec<Value> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_records_and_redacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace = Trace::open(&dir.path().join("config.json")).unwrap();
+        let first = trace.connection();
+        let second = trace.connection();
+
+        let request = r#"{"jsonrpc":"2.0","id":"a","method":"tools/call","params":{"arguments":{"api_token":"x","q":"fe_123"}}}"#;
+        first.record(Direction::Received, request, Some("fe_123"));
+        second.record(Direction::Sent, r#"{"jsonrpc":"2.0","id":7,"result":{}}"#, None);
+        second.record(Direction::Received, "{not json", None);
+
+        let written = records(&trace.path());
+        assert_eq!(written.len(), 3);
+        assert_eq!(written[0]["correlation_id"], "1:a");
+        assert_eq!(written[0]["direction"], "received");
+        assert_eq!(written[0]["message"]["params"]["arguments"], json!({ "api_token": REDACTED, "q": REDACTED }));
+        assert_eq!(written[1]["correlation_id"], "2:7");
+        assert_eq!(written[1]["direction"], "sent");
+        assert_eq!(written[2]["correlation_id"], Value::Null);
+        assert_eq!(written[2]["message"], "{not json");
+    }
+
+    #[test]
+    fn test_rotates() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace = Trace::open_in(dir.path().to_path_buf()).unwrap();
+        trace.file.lock().unwrap().len = MAX_FILE_BYTES;
+        let connection = trace.connection();
+        for _ in 0..KEPT_FILES + 2 {
+            connection.record(Direction::Sent, "{}", None);
+            trace.file.lock().unwrap().len = MAX_FILE_BYTES;
+        }
+
+        let rotated = |n: usize| dir.path().join(format!("{}.{}", FILE_NAME, n));
+        assert_eq!(records(&trace.path()).len(), 1);
+        assert!(rotated(KEPT_FILES).exists());
+        assert!(!rotated(KEPT_FILES + 1).exists());
+    }
+}
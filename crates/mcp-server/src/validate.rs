@@ -0,0 +1,233 @@
+//! Argument validation.
+//!
+//! After coercion, a call's arguments are checked against the tool's input
+//! schema, so a bad call fails naming the parameter at fault before any
+//! upstream request is made. Only the keywords the registry's schemas use
+//! are checked: `type`, `required`, `properties`, `items`, `enum`,
+//! `minimum`, `maximum`, `minItems`, and `maxItems`. Arguments the schema
+//! doesn't declare are allowed, and null is treated as an omitted argument.
+//!
+//! Debug builds also check structured tool results against their tools'
+//! output schemas the same way, so a change in the shape of what the API
+//! returns shows up as a warning instead of going unnoticed.
+
+use crate::error::ToolError;
+use serde_json::{json, Map, Value};
+
+/// Where a value departs from its schema
+enum Violation {
+    /// A required field is missing or null
+    Missing(String),
+    /// A field has the wrong type or value
+    Invalid(String, String),
+}
+
+/// Check `args` against `schema`, failing on the first violation
+pub fn validate_args(schema: &Value, args: &Value) -> Result<(), ToolError> {
+    let Some(args) = args.as_object() else {
+        return Err(ToolError::invalid_param("Tool arguments must be an object"));
+    };
+    check_object(schema, args, "").map_err(|violation| match violation {
+        Violation::Missing(path) => ToolError::missing_param(&path),
+        Violation::Invalid(path, problem) => {
+            ToolError::invalid_param(format!("Invalid parameter '{}': {}", path, problem))
+                .with_details(json!({ "parameter": path }))
+        }
+    })
+}
+
+/// Check a tool's structured result against its output schema, describing
+/// the first violation
+pub fn validate_result(schema: &Value, result: &Value) -> Result<(), String> {
+    check(schema, result, "").map_err(|violation| match violation {
+        Violation::Missing(path) => format!("'{}' is missing", path),
+        Violation::Invalid(path, problem) if path.is_empty() => format!("result: {}", problem),
+        Violation::Invalid(path, problem) => format!("'{}': {}", path, problem),
+    })
+}
+
+fn check_object(schema: &Value, object: &Map<String, Value>, path: &str) -> Result<(), Violation> {
+    let required = schema.get("required").and_then(|r| r.as_array()).into_iter().flatten();
+    for name in required.filter_map(|name| name.as_str()) {
+        if object.get(name).is_none_or(Value::is_null) {
+            return Err(Violation::Missing(join(path, name)));
+        }
+    }
+
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return Ok(());
+    };
+    for (name, value) in object {
+        if let (Some(property), false) = (properties.get(name), value.is_null()) {
+            check(property, value, &join(path, name))?;
+        }
+    }
+    Ok(())
+}
+
+fn check(schema: &Value, value: &Value, path: &str) -> Result<(), Violation> {
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+        return Err(invalid(path, format!("expected {}, got {}", types.join(" or "), describe(value))));
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !allowed.contains(value) {
+            let names: Vec<String> = allowed
+                .iter()
+                .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+                .collect();
+            return Err(invalid(path, format!("must be one of: {}", names.join(", "))));
+        }
+    }
+
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(|m| m.as_f64()).filter(|min| n < *min) {
+            return Err(invalid(path, format!("must be at least {}", min)));
+        }
+        if let Some(max) = schema.get("maximum").and_then(|m| m.as_f64()).filter(|max| n > *max) {
+            return Err(invalid(path, format!("must be at most {}", max)));
+        }
+    }
+
+    if let Some(items) = value.as_array() {
+        if let Some(min) = schema.get("minItems").and_then(|m| m.as_u64()).filter(|min| (items.len() as u64) < *min) {
+            return Err(invalid(path, format!("needs at least {} items", min)));
+        }
+        if let Some(max) = schema.get("maxItems").and_then(|m| m.as_u64()).filter(|max| (items.len() as u64) > *max) {
+            return Err(invalid(path, format!("allows at most {} items", max)));
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                check(item_schema, item, &format!("{}[{}]", path, i))?;
+            }
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        check_object(schema, object, path)?;
+    }
+    Ok(())
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// A value's JSON type, for error messages
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+fn invalid(path: &str, problem: String) -> Violation {
+    Violation::Invalid(path.to_string(), problem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "cik": { "type": "string" },
+                "limit": { "type": "integer", "minimum": 1, "maximum": 50 },
+                "period": { "type": "string", "enum": ["annual", "quarterly"] },
+                "end_date": { "type": ["string", "null"] },
+                "ciks": { "type": "array", "items": { "type": "string" }, "maxItems": 2 },
+                "filter": {
+                    "type": "object",
+                    "properties": { "form": { "type": "string" } },
+                    "required": ["form"]
+                }
+            },
+            "required": ["cik"]
+        })
+    }
+
+    fn error(args: Value) -> ToolError {
+        validate_args(&schema(), &args).unwrap_err()
+    }
+
+    #[test]
+    fn test_accepts_valid_args() {
+        let args = json!({
+            "cik": "0000320193",
+            "limit": 10,
+            "period": "annual",
+            "end_date": null,
+            "ciks": ["1"],
+            "filter": { "form": "10-K" },
+            "undeclared": true
+        });
+        assert!(validate_args(&schema(), &args).is_ok());
+        // Null stands in for an omitted optional argument
+        assert!(validate_args(&schema(), &json!({ "cik": "1", "limit": null })).is_ok());
+    }
+
+    #[test]
+    fn test_reports_the_parameter_at_fault() {
+        let missing = error(json!({ "limit": 5 }));
+        assert_eq!(missing.code, ErrorCode::MissingParam);
+        assert_eq!(missing.details.unwrap()["parameter"], "cik");
+        assert_eq!(error(json!({ "cik": null })).code, ErrorCode::MissingParam);
+
+        let wrong_type = error(json!({ "cik": "1", "limit": "ten" }));
+        assert_eq!(wrong_type.code, ErrorCode::InvalidParam);
+        assert_eq!(wrong_type.message, "Invalid parameter 'limit': expected integer, got string");
+
+        assert!(error(json!({ "cik": "1", "limit": 51 })).message.contains("at most 50"));
+        assert!(error(json!({ "cik": "1", "period": "monthly" })).message.contains("annual, quarterly"));
+        assert!(error(json!({ "cik": "1", "ciks": ["1", "2", "3"] })).message.contains("at most 2 items"));
+        assert!(error(json!({ "cik": "1", "ciks": [1] })).message.contains("'ciks[0]'"));
+        assert_eq!(error(json!({ "cik": "1", "filter": {} })).details.unwrap()["parameter"], "filter.form");
+        assert!(validate_args(&schema(), &json!("AAPL")).is_err());
+    }
+
+    #[test]
+    fn test_validate_result() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "buys": { "type": "integer" }, "window": { "type": "object" } },
+            "required": ["buys"]
+        });
+        assert!(validate_result(&schema, &json!({ "buys": 3, "extra": "ok" })).is_ok());
+        assert_eq!(validate_result(&schema, &json!({ "sells": 3 })).unwrap_err(), "'buys' is missing");
+        assert_eq!(
+            validate_result(&schema, &json!({ "buys": "3" })).unwrap_err(),
+            "'buys': expected integer, got string"
+        );
+        assert_eq!(validate_result(&schema, &json!([])).unwrap_err(), "result: expected object, got array");
+    }
+}
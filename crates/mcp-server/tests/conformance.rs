@@ -0,0 +1,1014 @@
+//! MCP protocol conformance tests.
+//!
+//! Each test spawns the `mcp-server` binary over stdio with an isolated
+//! home directory, points it at a mock FilingExplorer API serving recorded
+//! fixtures, and drives it the way an MCP client would.
+
+use serde_json::{json, Value};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Longest wait for any one message from the server
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(20);
+
+fn fixture(name: &str) -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// A running server and the client end of its stdio
+struct Session {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: Lines<BufReader<ChildStdout>>,
+    /// Messages read while waiting for a different one
+    unclaimed: Vec<Value>,
+    home: TempDir,
+}
+
+impl Session {
+    async fn start(api: &MockServer) -> Self {
+        Self::start_with(api, &[]).await
+    }
+
+    /// Start the server with extra command line arguments
+    async fn start_with(api: &MockServer, args: &[&str]) -> Self {
+        Self::start_with_env(api, args, &[]).await
+    }
+
+    /// Start the server with extra command line arguments and environment
+    /// variables
+    async fn start_with_env(api: &MockServer, args: &[&str], env: &[(&str, &str)]) -> Self {
+        Self::start_in(TempDir::new().unwrap(), api, args, env).await
+    }
+
+    /// Start the server with a `plugins.json` beside its config file
+    async fn start_with_plugins(api: &MockServer, plugins: Value) -> Self {
+        let home = TempDir::new().unwrap();
+        std::fs::write(home.path().join("plugins.json"), plugins.to_string()).unwrap();
+        Self::start_in(home, api, &[], &[]).await
+    }
+
+    /// Start the server with its config file in `home`
+    async fn start_in(home: TempDir, api: &MockServer, args: &[&str], env: &[(&str, &str)]) -> Self {
+        let config = home.path().join("config.json");
+        std::fs::write(&config, json!({ "version": 1, "api_token": "fe_test" }).to_string()).unwrap();
+
+        let mut command = Command::new(env!("CARGO_BIN_EXE_mcp-server"));
+        command
+            .arg("--config")
+            .arg(&config)
+            .args(["--api-base-url", &api.uri(), "--log-level", "error"])
+            .args(args)
+            .env("HOME", home.path())
+            .env("XDG_CONFIG_HOME", home.path().join("config"))
+            .env("XDG_CACHE_HOME", home.path().join("cache"))
+            .env_remove("RUST_LOG")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+        for (name, _) in std::env::vars() {
+            if name.starts_with("FILINGEXPLORER_") {
+                command.env_remove(name);
+            }
+        }
+        command.envs(env.iter().copied());
+        let mut child = command.spawn().unwrap();
+
+        Self {
+            stdin: child.stdin.take(),
+            stdout: BufReader::new(child.stdout.take().unwrap()).lines(),
+            child,
+            unclaimed: Vec::new(),
+            home,
+        }
+    }
+
+    async fn send_raw(&mut self, line: &str) {
+        let stdin = self.stdin.as_mut().expect("stdin is open");
+        stdin.write_all(line.as_bytes()).await.unwrap();
+        stdin.write_all(b"\n").await.unwrap();
+        stdin.flush().await.unwrap();
+    }
+
+    async fn send(&mut self, message: Value) {
+        self.send_raw(&message.to_string()).await;
+    }
+
+    async fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params })).await;
+    }
+
+    /// Next message from the server other than a log notification, or None
+    /// once stdout closes
+    async fn next_message(&mut self) -> Option<Value> {
+        loop {
+            let line = tokio::time::timeout(RESPONSE_TIMEOUT, self.stdout.next_line())
+                .await
+                .expect("timed out waiting for the server")
+                .unwrap()?;
+            let message: Value = serde_json::from_str(&line).expect("server wrote invalid JSON");
+            if message["method"] == "notifications/message" {
+                assert_eq!(message["jsonrpc"], "2.0", "{}", message);
+                assert!(message["params"]["level"].is_string(), "{}", message);
+                continue;
+            }
+            // Other notifications, such as tools/list_changed, go to the test
+            if message.get("id").is_none() && message["method"].as_str().is_some_and(|m| m.starts_with("notifications/")) {
+                assert_eq!(message["jsonrpc"], "2.0", "{}", message);
+                return Some(message);
+            }
+            assert_conformant(&message);
+            return Some(message);
+        }
+    }
+
+    /// The response with `id`, holding on to any others that arrive first
+    async fn response(&mut self, id: &Value) -> Value {
+        if let Some(i) = self.unclaimed.iter().position(|m| &m["id"] == id) {
+            return self.unclaimed.remove(i);
+        }
+        loop {
+            let message = self.next_message().await.expect("server closed stdout");
+            if &message["id"] == id {
+                return message;
+            }
+            self.unclaimed.push(message);
+        }
+    }
+
+    async fn request(&mut self, id: Value, method: &str, params: Value) -> Value {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })).await;
+        self.response(&id).await
+    }
+
+    async fn call_tool(&mut self, id: Value, name: &str, arguments: Value) -> Value {
+        let response = self.request(id, "tools/call", json!({ "name": name, "arguments": arguments })).await;
+        response["result"].clone()
+    }
+
+    /// Close stdin and collect everything the server writes before exiting
+    async fn shutdown(mut self) -> Vec<Value> {
+        drop(self.stdin.take());
+        let mut rest = std::mem::take(&mut self.unclaimed);
+        while let Some(message) = self.next_message().await {
+            rest.push(message);
+        }
+        let status = tokio::time::timeout(RESPONSE_TIMEOUT, self.child.wait())
+            .await
+            .expect("server did not exit after stdin closed")
+            .unwrap();
+        assert!(status.success(), "server exited with {}", status);
+        rest
+    }
+}
+
+/// JSON-RPC 2.0 response shape: version, an id, and exactly one of result
+/// and error, with an integer code and a message on errors
+fn assert_conformant(message: &Value) {
+    assert_eq!(message["jsonrpc"], "2.0", "{}", message);
+    assert!(message.get("id").is_some(), "response without id: {}", message);
+    assert!(message.get("method").is_none(), "unexpected server request: {}", message);
+    match (message.get("result"), message.get("error")) {
+        (Some(_), None) => {}
+        (None, Some(error)) => {
+            assert!(error["code"].is_i64(), "{}", message);
+            assert!(error["message"].is_string(), "{}", message);
+        }
+        _ => panic!("response needs exactly one of result and error: {}", message),
+    }
+}
+
+fn tool_text(result: &Value) -> &str {
+    result["content"][0]["text"].as_str().expect("text content")
+}
+
+#[tokio::test]
+async fn test_lifecycle() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("q", "AAPL"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("search_aapl.json")))
+        .expect(1)
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    let init = session
+        .request(json!(1), "initialize", json!({
+            "protocolVersion": "2024-11-05",
+            "capabilities": {},
+            "clientInfo": { "name": "conformance", "version": "0" }
+        }))
+        .await;
+    let result = &init["result"];
+    assert!(result["protocolVersion"].is_string());
+    assert!(result["capabilities"]["tools"].is_object());
+    assert!(result["capabilities"]["logging"].is_object());
+    assert!(result["capabilities"]["completions"].is_object());
+    assert_eq!(result["serverInfo"]["name"], "filing-explorer");
+
+    // Notifications get no response, so the next message answers the ping
+    session.notify("notifications/initialized", json!({})).await;
+    session.send(json!({ "jsonrpc": "2.0", "id": "ping-1", "method": "ping" })).await;
+    let pong = session.next_message().await.unwrap();
+    assert_eq!(pong["id"], "ping-1");
+    assert_eq!(pong["result"], json!({}));
+
+    let set_level = session.request(json!("log-1"), "logging/setLevel", json!({ "level": "info" })).await;
+    assert_eq!(set_level["result"], json!({}));
+    let bad_level = session.request(json!("log-2"), "logging/setLevel", json!({ "level": "loud" })).await;
+    assert_eq!(bad_level["error"]["code"], -32602);
+
+    let completion = session
+        .request(json!("complete-1"), "completion/complete", json!({
+            "ref": { "type": "ref/tool", "name": "search_tools" },
+            "argument": { "name": "category", "value": "sec" }
+        }))
+        .await;
+    assert_eq!(completion["result"]["completion"]["values"], json!(["sec_documents"]));
+    let unknown_prompt = session
+        .request(json!("complete-2"), "completion/complete", json!({
+            "ref": { "type": "ref/prompt", "name": "nope" },
+            "argument": { "name": "category", "value": "" }
+        }))
+        .await;
+    assert_eq!(unknown_prompt["error"]["code"], -32602);
+
+    let list = session.request(json!(2), "tools/list", json!({})).await;
+    let tools = list["result"]["tools"].as_array().unwrap();
+    assert!(!tools.is_empty());
+    let mut names = std::collections::HashSet::new();
+    for tool in tools {
+        let name = tool["name"].as_str().expect("tool name");
+        assert!(names.insert(name), "duplicate tool {}", name);
+        assert!(tool["description"].is_string(), "{} has no description", name);
+        assert_eq!(tool["inputSchema"]["type"], "object", "{} input schema", name);
+    }
+    assert!(names.contains("search"));
+    for tool in tools.iter().filter(|t| t.get("outputSchema").is_some()) {
+        assert_eq!(tool["outputSchema"]["type"], "object", "{} output schema", tool["name"]);
+    }
+    assert!(list["result"].get("nextCursor").is_none());
+
+    let page = session.call_tool(json!("page-1"), "search_tools", json!({ "query": "filings", "limit": 1 })).await;
+    assert_eq!(page["structuredContent"]["matches"].as_array().unwrap().len(), 1);
+    let cursor = page["structuredContent"]["next_cursor"].clone();
+    assert!(cursor.is_string(), "{}", page);
+    let next = session.call_tool(json!("page-2"), "search_tools", json!({ "query": "filings", "cursor": cursor })).await;
+    assert_ne!(next["structuredContent"]["matches"][0]["name"], page["structuredContent"]["matches"][0]["name"]);
+
+    let described = session.call_tool(json!("describe"), "describe_tool", json!({ "tool_name": "get_red_flags" })).await;
+    assert_ne!(described["isError"], true, "{}", described);
+    assert_eq!(described["structuredContent"]["inputSchema"]["type"], "object");
+    assert!(described["structuredContent"]["examples"].is_array());
+    let unknown = session.call_tool(json!("describe-unknown"), "describe_tool", json!({ "tool_name": "get_weather" })).await;
+    assert_eq!(unknown["isError"], true);
+    assert!(tool_text(&unknown).contains("UNKNOWN_TOOL"), "{}", tool_text(&unknown));
+
+    let result = session.call_tool(json!(3), "search", json!({ "q": "AAPL" })).await;
+    assert_ne!(result["isError"], true, "{}", result);
+    assert_eq!(result["content"][0]["type"], "text");
+    assert!(tool_text(&result).contains("Apple Inc."));
+    assert_eq!(result["structuredContent"]["companies"][0]["ticker"], "AAPL");
+
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_expose_all_tools() {
+    let api = MockServer::start().await;
+    let mut session = Session::start_with(&api, &["--expose-all-tools"]).await;
+
+    let list = session.request(json!(1), "tools/list", json!({})).await;
+    let tools = list["result"]["tools"].as_array().unwrap();
+    let names: Vec<&str> = tools.iter().filter_map(|t| t["name"].as_str()).collect();
+    assert!(names.contains(&"search"));
+    assert!(names.contains(&"get_red_flags"));
+    assert!(!names.contains(&"execute_tool"), "{:?}", names);
+    let red_flags = tools.iter().find(|t| t["name"] == "get_red_flags").unwrap();
+    assert_eq!(red_flags["inputSchema"]["type"], "object");
+
+    // Registry tools are called by name; this one rejects its arguments
+    // rather than failing as an unknown tool
+    let result = session.call_tool(json!(2), "get_red_flags", json!({})).await;
+    assert_eq!(result["isError"], true);
+    assert!(tool_text(&result).contains("INVALID_PARAM"), "{}", tool_text(&result));
+
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_tool_list_changes_with_config() {
+    let api = MockServer::start().await;
+    let mut session = Session::start(&api).await;
+    let initialized = session
+        .request(json!(1), "initialize", json!({ "protocolVersion": "2025-06-18", "capabilities": {} }))
+        .await;
+    assert_eq!(initialized["result"]["capabilities"]["tools"]["listChanged"], true);
+
+    let config = json!({ "version": 1, "api_token": "fe_test", "expose_all_tools": true });
+    std::fs::write(session.home.path().join("config.json"), config.to_string()).unwrap();
+    let notification = session.next_message().await.expect("server closed stdout");
+    assert_eq!(notification["method"], "notifications/tools/list_changed");
+    assert!(notification.get("id").is_none());
+
+    let list = session.request(json!(2), "tools/list", json!({})).await;
+    let names: Vec<&str> = list["result"]["tools"].as_array().unwrap().iter().filter_map(|t| t["name"].as_str()).collect();
+    assert!(names.contains(&"get_red_flags"), "{:?}", names);
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_trace_file() {
+    let api = MockServer::start().await;
+    let mut session = Session::start_with(&api, &["--trace"]).await;
+
+    let response = session.request(json!("p1"), "ping", json!({ "note": "sent fe_test by mistake" })).await;
+    assert_eq!(response["result"], json!({}));
+
+    let trace = std::fs::read_to_string(session.home.path().join("traces/trace.jsonl")).unwrap();
+    assert!(!trace.contains("fe_test"), "{}", trace);
+    let records: Vec<Value> = trace.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 2, "{}", trace);
+    assert_eq!(records[0]["direction"], "received");
+    assert_eq!(records[0]["message"]["params"]["note"], "sent [REDACTED] by mistake");
+    assert_eq!(records[1]["direction"], "sent");
+    assert_eq!(records[0]["correlation_id"], records[1]["correlation_id"]);
+
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_tool_timeout() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [] })).set_delay(Duration::from_secs(5)))
+        .mount(&api)
+        .await;
+    let mut session = Session::start_with_env(&api, &[], &[("FILINGEXPLORER_TOOL_TIMEOUT_SECS", "1")]).await;
+
+    let started = std::time::Instant::now();
+    let arguments = json!({ "tool_name": "get_form_adv_firms", "arguments": { "search": "capital" } });
+    let result = session.call_tool(json!(1), "execute_tool", arguments).await;
+    assert!(started.elapsed() < Duration::from_secs(4));
+    assert_eq!(result["isError"], true);
+    let error: Value = serde_json::from_str(tool_text(&result)).unwrap();
+    assert_eq!(error["error"]["code"], "TIMEOUT");
+    assert_eq!(error["error"]["details"]["timeout_seconds"], 1);
+    assert!(error["error"]["details"]["elapsed_ms"].as_u64().unwrap() >= 1000);
+}
+
+#[tokio::test]
+async fn test_tool_timeout_covers_retries() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [] })).set_delay(Duration::from_secs(10)))
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/owners"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "5"))
+        .mount(&api)
+        .await;
+    let mut session = Session::start_with_env(&api, &[], &[("FILINGEXPLORER_TOOL_TIMEOUT_SECS", "2")]).await;
+
+    // The wait before the retry counts against the budget
+    let started = std::time::Instant::now();
+    let arguments = json!({ "tool_name": "get_form_adv_firms", "arguments": { "search": "capital" } });
+    let result = session.call_tool(json!(1), "execute_tool", arguments).await;
+    assert!(started.elapsed() < Duration::from_millis(2900));
+    let error: Value = serde_json::from_str(tool_text(&result)).unwrap();
+    assert_eq!(error["error"]["code"], "TIMEOUT");
+
+    // A retry that couldn't start within the budget isn't waited for
+    let started = std::time::Instant::now();
+    let arguments = json!({ "tool_name": "get_form_adv_owners", "arguments": { "search": "capital" } });
+    let result = session.call_tool(json!(2), "execute_tool", arguments).await;
+    assert!(started.elapsed() < Duration::from_secs(2));
+    let error: Value = serde_json::from_str(tool_text(&result)).unwrap();
+    assert_eq!(error["error"]["code"], "UPSTREAM_RATE_LIMITED");
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_sigterm_drains_in_flight_calls() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [] })).set_delay(Duration::from_secs(1)))
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    let arguments = json!({ "tool_name": "get_form_adv_firms", "arguments": { "search": "capital" } });
+    session
+        .send(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": "execute_tool", "arguments": arguments }
+        }))
+        .await;
+    // Messages are read in order, so the call is running once this answers
+    session.request(json!(2), "ping", json!({})).await;
+
+    let pid = session.child.id().unwrap().to_string();
+    let kill = Command::new("kill").args(["-TERM", &pid]).status().await.unwrap();
+    assert!(kill.success());
+
+    // The running call is still answered before the server exits
+    let response = session.response(&json!(1)).await;
+    assert_ne!(response["result"]["isError"], true, "{}", response);
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_content_length_framing() {
+    let api = MockServer::start().await;
+    let Session { mut child, stdin, stdout, .. } = Session::start(&api).await;
+    let mut stdin = stdin.unwrap();
+    let mut stdout = stdout.into_inner();
+
+    // Auto-detected from the first message, and answered in kind
+    for id in 1..=2 {
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": "ping" }).to_string();
+        let framed = format!("Content-Length: {}\r\nContent-Type: application/json\r\n\r\n{}", request.len(), request);
+        stdin.write_all(framed.as_bytes()).await.unwrap();
+        stdin.flush().await.unwrap();
+
+        let mut header = String::new();
+        tokio::time::timeout(RESPONSE_TIMEOUT, stdout.read_line(&mut header)).await.unwrap().unwrap();
+        let length: usize = header
+            .strip_prefix("Content-Length: ")
+            .and_then(|n| n.trim_end().parse().ok())
+            .unwrap_or_else(|| panic!("expected a Content-Length header, got {:?}", header));
+        let mut blank = String::new();
+        stdout.read_line(&mut blank).await.unwrap();
+        assert_eq!(blank, "\r\n");
+        let mut body = vec![0; length];
+        stdout.read_exact(&mut body).await.unwrap();
+        let response: Value = serde_json::from_slice(&body).unwrap();
+        assert_conformant(&response);
+        assert_eq!(response["id"], id);
+    }
+
+    drop(stdin);
+    let status = tokio::time::timeout(RESPONSE_TIMEOUT, child.wait()).await.unwrap().unwrap();
+    assert!(status.success(), "server exited with {}", status);
+}
+
+#[tokio::test]
+async fn test_socket_transport() {
+    use tokio::net::UnixStream;
+
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("search_aapl.json")))
+        .mount(&api)
+        .await;
+    let home = TempDir::new().unwrap();
+    let socket = home.path().join("fe.sock");
+    let mut session = Session::start_with(&api, &["--transport", "socket", "--path", socket.to_str().unwrap()]).await;
+
+    let connect = || async {
+        for _ in 0..100 {
+            if let Ok(stream) = UnixStream::connect(&socket).await {
+                return stream;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("server never listened on {}", socket.display());
+    };
+    let (first, mut second) = (connect().await, connect().await);
+
+    // Each connection is its own session
+    second.write_all(b"{\"jsonrpc\":\"2.0\",\"id\":\"b\",\"method\":\"ping\"}\n").await.unwrap();
+    let (read, mut write) = first.into_split();
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": "a",
+        "method": "tools/call",
+        "params": { "name": "search", "arguments": { "q": "AAPL" } }
+    });
+    write.write_all(format!("{}\n", request).as_bytes()).await.unwrap();
+    let mut lines = BufReader::new(read).lines();
+    let line = tokio::time::timeout(RESPONSE_TIMEOUT, lines.next_line()).await.unwrap().unwrap().unwrap();
+    let response: Value = serde_json::from_str(&line).unwrap();
+    assert_conformant(&response);
+    assert_eq!(response["id"], "a");
+    assert!(tool_text(&response["result"]).contains("Apple Inc."));
+
+    let mut lines = BufReader::new(second).lines();
+    let line = tokio::time::timeout(RESPONSE_TIMEOUT, lines.next_line()).await.unwrap().unwrap().unwrap();
+    assert_eq!(serde_json::from_str::<Value>(&line).unwrap()["id"], "b");
+
+    let pid = session.child.id().unwrap().to_string();
+    assert!(Command::new("kill").args(["-TERM", &pid]).status().await.unwrap().success());
+    let status = tokio::time::timeout(RESPONSE_TIMEOUT, session.child.wait()).await.unwrap().unwrap();
+    assert!(status.success(), "server exited with {}", status);
+    assert!(!socket.exists());
+}
+
+#[tokio::test]
+async fn test_invalid_arguments_fail_before_upstream_calls() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [] })))
+        .expect(0)
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    let arguments = json!({ "tool_name": "get_form_adv_firms", "arguments": { "page_size": "lots" } });
+    let result = session.call_tool(json!(1), "execute_tool", arguments).await;
+    assert_eq!(result["isError"], true);
+    let error: Value = serde_json::from_str(tool_text(&result)).unwrap();
+    assert_eq!(error["error"]["code"], "INVALID_PARAM");
+    assert_eq!(error["error"]["details"]["parameter"], "page_size");
+
+    let missing = session.call_tool(json!(2), "search", json!({})).await;
+    let error: Value = serde_json::from_str(tool_text(&missing)).unwrap();
+    assert_eq!(error["error"]["code"], "MISSING_PARAM");
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_dry_run_sends_no_changes() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/lists/Tech"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "7", "name": "Tech", "items": [] })))
+        .mount(&api)
+        .await;
+    for verb in ["POST", "PATCH", "DELETE"] {
+        Mock::given(method(verb))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .expect(0)
+            .mount(&api)
+            .await;
+    }
+    let mut session = Session::start(&api).await;
+
+    let arguments = json!({ "tool_name": "delete_list", "arguments": { "id_or_name": "Tech", "dry_run": true } });
+    let result = session.call_tool(json!(1), "execute_tool", arguments).await;
+    assert_ne!(result["isError"], true, "{}", result);
+    assert_eq!(result["structuredContent"]["dry_run"], true);
+    assert_eq!(result["structuredContent"]["request"]["method"], "DELETE");
+    assert!(session.shutdown().await.is_empty());
+
+    // The global switch covers calls that don't ask for a dry run
+    let mut session = Session::start_with(&api, &["--dry-run"]).await;
+    let arguments = json!({ "tool_name": "create_list", "arguments": { "name": "Energy" } });
+    let result = session.call_tool(json!(1), "execute_tool", arguments).await;
+    assert_eq!(result["structuredContent"]["request"]["body"], json!({ "name": "Energy" }));
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_batch_calls_succeed_or_fail_independently() {
+    let api = MockServer::start().await;
+    for (id, name) in [("7", "Tech"), ("8", "Energy")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/lists/{}", name)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": id, "name": name, "items": [] })))
+            .expect(1)
+            .mount(&api)
+            .await;
+    }
+    let mut session = Session::start(&api).await;
+
+    let calls = json!([
+        { "tool_name": "get_list", "arguments": { "id_or_name": "Tech" } },
+        { "tool_name": "get_red_flags", "arguments": {} },
+        { "tool_name": "get_list", "arguments": { "id_or_name": "Energy" } }
+    ]);
+    let result = session.call_tool(json!(1), "execute_tools_batch", json!({ "calls": calls })).await;
+    assert_ne!(result["isError"], true, "{}", result);
+    let batch = &result["structuredContent"];
+    assert_eq!((batch["succeeded"].as_u64(), batch["failed"].as_u64()), (Some(2), Some(1)));
+    assert_eq!(batch["results"][0]["result"]["name"], "Tech");
+    assert_eq!(batch["results"][1]["error"]["code"], "INVALID_PARAM");
+    assert_eq!(batch["results"][2]["index"], 2);
+    assert_eq!(batch["results"][2]["result"]["name"], "Energy");
+
+    let empty = session.call_tool(json!(2), "execute_tools_batch", json!({ "calls": [] })).await;
+    assert_eq!(empty["isError"], true);
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_batch_calls_share_the_result_cache() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [{ "name": "Acme Capital" }] })))
+        .expect(1)
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    let call = json!({ "tool_name": "get_form_adv_firms", "arguments": { "search": "capital" } });
+    let first = session.call_tool(json!(1), "execute_tool", call.clone()).await;
+    assert_ne!(first["isError"], true, "{}", first);
+
+    let mut markdown = call.clone();
+    markdown["format"] = json!("markdown");
+    let result = session.call_tool(json!(2), "execute_tools_batch", json!({ "calls": [call, markdown] })).await;
+    assert_ne!(result["isError"], true, "{}", result);
+    let batch = &result["structuredContent"];
+    assert_eq!(batch["results"][0]["cache"], "hit");
+    assert_eq!(batch["results"][0]["result"]["data"][0]["name"], "Acme Capital");
+    assert_eq!(batch["results"][1]["cache"], "hit");
+    assert!(batch["results"][1]["result"].as_str().is_some_and(|text| text.contains("Acme Capital")));
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_form_adv_firm_sub_resources() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms/105958/private_funds"))
+        .and(query_param("fund_type", "hedge fund"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "data": [{ "fund_name": "Pure Alpha Fund", "fund_type": "hedge fund" }],
+            "meta": { "total": 1 }
+        })))
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms/105958/aum_history"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [{ "year": 2024, "aum": 1 }] })))
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    let arguments = json!({
+        "tool_name": "get_form_adv_firm_private_funds",
+        "arguments": { "crd": "105958", "fund_type": "hedge fund" }
+    });
+    let result = session.call_tool(json!(1), "execute_tool", arguments).await;
+    assert_ne!(result["isError"], true, "{}", result);
+    assert_eq!(result["structuredContent"]["data"][0]["fund_name"], "Pure Alpha Fund");
+
+    let arguments = json!({ "tool_name": "get_form_adv_firm_aum_history", "arguments": { "crd": "105958" } });
+    let result = session.call_tool(json!(2), "execute_tool", arguments).await;
+    assert_eq!(result["structuredContent"]["data"][0]["year"], 2024);
+
+    let arguments = json!({ "tool_name": "get_form_adv_firm_disclosures", "arguments": {} });
+    let missing = session.call_tool(json!(3), "execute_tool", arguments).await;
+    assert_eq!(missing["isError"], true);
+    assert!(tool_text(&missing).contains("crd"));
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_all_pages_follows_pages() {
+    let api = MockServer::start().await;
+    let firms = |names: &[&str]| json!({ "data": names.iter().map(|n| json!({ "name": n })).collect::<Vec<_>>() });
+    for (offset, body) in [("0", firms(&["Alpha", "Beta"])), ("2", firms(&["Gamma"]))] {
+        Mock::given(method("GET"))
+            .and(path("/forms/adv/firms"))
+            .and(query_param("page[offset]", offset))
+            .and(query_param("state", "CA"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .expect(1)
+            .mount(&api)
+            .await;
+    }
+    let mut session = Session::start(&api).await;
+
+    let arguments = json!({
+        "tool_name": "get_form_adv_firms",
+        "arguments": { "state": "CA", "page_size": 2, "all_pages": true }
+    });
+    let result = session.call_tool(json!(1), "execute_tool", arguments).await;
+    assert_ne!(result["isError"], true, "{}", result);
+    let firms = &result["structuredContent"];
+    assert_eq!(firms["count"], 3);
+    assert_eq!(firms["truncated"], false);
+    assert_eq!(firms["data"][2]["name"], "Gamma");
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_plugin_tools() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/companies/320193/peers"))
+        .and(query_param("limit", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [{ "cik": "789019" }] })))
+        .mount(&api)
+        .await;
+    let plugins = json!({ "tools": [{
+        "name": "get_company_peers",
+        "description": "Companies in the same industry as a company",
+        "category": "company_data",
+        "keywords": ["peers", "competitors"],
+        "input_schema": {
+            "type": "object",
+            "properties": { "cik": { "type": "string" }, "limit": { "type": "integer" } },
+            "required": ["cik"]
+        },
+        "endpoint": "companies/{cik}/peers"
+    }] });
+    let mut session = Session::start_with_plugins(&api, plugins).await;
+
+    let found = session.call_tool(json!(1), "search_tools", json!({ "query": "competitors" })).await;
+    assert_eq!(found["structuredContent"]["matches"][0]["name"], "get_company_peers", "{}", found);
+
+    let arguments = json!({ "tool_name": "get_company_peers", "arguments": { "cik": "320193", "limit": "2" } });
+    let result = session.call_tool(json!(2), "execute_tool", arguments).await;
+    assert_ne!(result["isError"], true, "{}", result);
+    assert_eq!(result["structuredContent"]["data"][0]["cik"], "789019");
+
+    // Checked against the plugin's schema like any other tool
+    let arguments = json!({ "tool_name": "get_company_peers", "arguments": {} });
+    let missing = session.call_tool(json!(3), "execute_tool", arguments).await;
+    assert_eq!(missing["isError"], true);
+    assert!(tool_text(&missing).contains("cik"));
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_markdown_format() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/lists/Tech"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "7",
+            "name": "Tech",
+            "items": [{ "ticker": "AAPL", "notes": "Core" }, { "ticker": "MSFT", "notes": null }]
+        })))
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    let arguments = json!({ "tool_name": "get_list", "arguments": { "id_or_name": "Tech", "format": "markdown" } });
+    let result = session.call_tool(json!(1), "execute_tool", arguments).await;
+    assert_ne!(result["isError"], true, "{}", result);
+    let text = tool_text(&result);
+    assert!(text.contains("- **name:** Tech\n"), "{}", text);
+    assert!(text.contains("| notes | ticker |\n|---|---|\n| Core | AAPL |\n|  | MSFT |"), "{}", text);
+    assert_eq!(result["structuredContent"]["name"], "Tech");
+
+    let arguments = json!({ "tool_name": "get_list", "arguments": { "id_or_name": "Tech" }, "format": "xml" });
+    let invalid = session.call_tool(json!(2), "execute_tool", arguments).await;
+    assert_eq!(invalid["isError"], true);
+    assert!(tool_text(&invalid).contains("INVALID_PARAM"));
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_repeated_reads_are_cached() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [{ "name": "Acme Capital" }] })))
+        .expect(2)
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    let mut cache_status = Vec::new();
+    for id in 1..=4 {
+        if id == 3 {
+            let cleared = session.call_tool(json!("clear"), "clear_cache", json!({})).await;
+            assert_ne!(cleared["isError"], true, "{}", cleared);
+        }
+        let arguments = json!({ "tool_name": "get_form_adv_firms", "arguments": { "search": "capital" } });
+        let result = session.call_tool(json!(id), "execute_tool", arguments).await;
+        let value: Value = serde_json::from_str(tool_text(&result)).unwrap();
+        assert_eq!(value["data"][0]["name"], "Acme Capital");
+        cache_status.push(value["_meta"]["cache"].clone());
+    }
+    assert_eq!(cache_status, vec![json!("miss"), json!("hit"), json!("miss"), json!("hit")]);
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_saved_results_are_served_offline() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/lists/Tech"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "7", "name": "Tech", "items": [] })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/lists/Tech"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/lists/Energy"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    let mut metas = Vec::new();
+    for id in 1..=2 {
+        let arguments = json!({ "tool_name": "get_list", "arguments": { "id_or_name": "Tech" } });
+        let result = session.call_tool(json!(id), "execute_tool", arguments).await;
+        assert_ne!(result["isError"], true, "{}", result);
+        let value: Value = serde_json::from_str(tool_text(&result)).unwrap();
+        assert_eq!(value["name"], "Tech");
+        metas.push(value["_meta"].clone());
+    }
+    assert!(metas[0].get("cache").is_none(), "{}", metas[0]);
+    assert_eq!(metas[1]["cache"], "offline");
+    assert!(metas[1]["cached_at"].is_string());
+    assert!(metas[1]["warnings"][0].as_str().unwrap().starts_with("Offline: "));
+
+    // Nothing saved to fall back on
+    let arguments = json!({ "tool_name": "get_list", "arguments": { "id_or_name": "Energy" } });
+    let failed = session.call_tool(json!(3), "execute_tool", arguments).await;
+    assert_eq!(failed["isError"], true);
+    assert!(tool_text(&failed).contains("UPSTREAM_ERROR"));
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_rate_limited_calls_are_retried() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/forms/adv/firms"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [] })))
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    let arguments = json!({ "tool_name": "get_form_adv_firms", "arguments": { "search": "capital" } });
+    let result = session.call_tool(json!(1), "execute_tool", arguments).await;
+    assert_ne!(result["isError"], true, "{}", result);
+    let value: Value = serde_json::from_str(tool_text(&result)).unwrap();
+    assert_eq!(value["_meta"]["retries"], 1);
+    assert!(value["_meta"]["retry_wait_ms"].as_u64().unwrap() >= 1000);
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_protocol_errors() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(fixture("not_found.json")))
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    session.send_raw("{not json").await;
+    let parse_error = session.next_message().await.unwrap();
+    assert_eq!(parse_error["id"], Value::Null);
+    assert_eq!(parse_error["error"]["code"], -32700);
+
+    session.send(json!({ "jsonrpc": "2.0", "id": 1 })).await;
+    let invalid = session.next_message().await.unwrap();
+    assert_eq!(invalid["error"]["code"], -32600);
+
+    let unknown = session.request(json!(2), "no/such/method", json!({})).await;
+    assert_eq!(unknown["error"]["code"], -32601);
+    let bad_cursor = session.request(json!("list"), "tools/list", json!({ "cursor": "nope" })).await;
+    assert_eq!(bad_cursor["error"]["code"], -32602);
+
+    let missing_name = session.request(json!(3), "tools/call", json!({ "arguments": {} })).await;
+    assert_eq!(missing_name["error"]["code"], -32602);
+
+    // Tool failures are results flagged isError, with a structured envelope
+    let unknown_tool = session.call_tool(json!(4), "no_such_tool", json!({})).await;
+    assert_eq!(unknown_tool["isError"], true);
+
+    let missing_param = session.call_tool(json!(5), "search", json!({})).await;
+    assert_eq!(missing_param["isError"], true);
+    let envelope: Value = serde_json::from_str(tool_text(&missing_param)).unwrap();
+    assert_eq!(envelope["error"]["code"], "MISSING_PARAM");
+
+    let upstream = session.call_tool(json!(6), "search", json!({ "q": "nothing" })).await;
+    assert_eq!(upstream["isError"], true);
+    let envelope: Value = serde_json::from_str(tool_text(&upstream)).unwrap();
+    assert_eq!(envelope["error"]["code"], "NOT_FOUND");
+    assert_eq!(envelope["error"]["is_retryable"], false);
+
+    // String and numeric ids are echoed back unchanged
+    let ping = session.request(json!("abc"), "ping", json!({})).await;
+    assert_eq!(ping["id"], "abc");
+
+    assert!(session.shutdown().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_cancelled_call_gets_no_response() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture("search_aapl.json"))
+                .set_delay(Duration::from_secs(60)),
+        )
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    session
+        .send(json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "tools/call",
+            "params": { "name": "search", "arguments": { "q": "AAPL" } }
+        }))
+        .await;
+    session.notify("notifications/cancelled", json!({ "requestId": 7, "reason": "user" })).await;
+
+    // The server keeps serving after the cancellation
+    let pong = session.request(json!(8), "ping", json!({})).await;
+    assert_eq!(pong["result"], json!({}));
+
+    // Shutting down doesn't wait out the cancelled upstream call, and the
+    // call is never answered
+    let started = std::time::Instant::now();
+    let rest = session.shutdown().await;
+    assert!(started.elapsed() < Duration::from_secs(30));
+    assert!(rest.iter().all(|m| m["id"] != 7), "{:?}", rest);
+}
+
+#[tokio::test]
+async fn test_cancel_reaches_a_saturated_connection() {
+    let api = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .and(query_param("q", "FAST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture("search_aapl.json")))
+        .mount(&api)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/search"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(fixture("search_aapl.json"))
+                .set_delay(Duration::from_secs(60)),
+        )
+        .mount(&api)
+        .await;
+    let mut session = Session::start(&api).await;
+
+    // Eight slow calls take every request slot, so the ninth has to wait
+    for id in 1..=8 {
+        let arguments = json!({ "q": format!("SLOW{}", id) });
+        session
+            .send(json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "tools/call",
+                "params": { "name": "search", "arguments": arguments }
+            }))
+            .await;
+    }
+    session
+        .send(json!({
+            "jsonrpc": "2.0",
+            "id": 9,
+            "method": "tools/call",
+            "params": { "name": "search", "arguments": { "q": "FAST" } }
+        }))
+        .await;
+
+    // The cancellation still gets through, and frees a slot for the ninth
+    session.notify("notifications/cancelled", json!({ "requestId": 1, "reason": "user" })).await;
+    let started = std::time::Instant::now();
+    let response = session.response(&json!(9)).await;
+    assert!(started.elapsed() < Duration::from_secs(10));
+    assert_ne!(response["result"]["isError"], true, "{}", response);
+
+    let rest = session.shutdown().await;
+    assert!(rest.iter().all(|m| m["id"] != 1), "{:?}", rest);
+}
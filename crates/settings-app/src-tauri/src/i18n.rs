@@ -0,0 +1,91 @@
+//! Localized strings for the settings UI and command messages.
+//!
+//! Each locale is a flat JSON catalog in `locales/` mapping message ids to
+//! text with `{name}` placeholders. Ids missing from a catalog fall back to
+//! English.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Locale used when none is configured or the requested one isn't shipped
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Shipped locales: (code, native name, catalog)
+const CATALOGS: &[(&str, &str, &str)] = &[
+    ("en", "English", include_str!("../locales/en.json")),
+    ("es", "Español", include_str!("../locales/es.json")),
+    ("fr", "Français", include_str!("../locales/fr.json")),
+    ("de", "Deutsch", include_str!("../locales/de.json")),
+];
+
+/// A locale the UI can offer
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleInfo {
+    pub code: &'static str,
+    pub name: &'static str,
+}
+
+pub fn available_locales() -> Vec<LocaleInfo> {
+    CATALOGS
+        .iter()
+        .map(|(code, name, _)| LocaleInfo { code, name })
+        .collect()
+}
+
+/// The shipped locale for a requested one: exact match first, then the
+/// language without its region ("es-MX" -> "es")
+pub fn supported_locale(requested: &str) -> Option<&'static str> {
+    let requested = requested.trim().replace('_', "-").to_lowercase();
+    let language = requested.split('-').next().unwrap_or_default();
+    CATALOGS
+        .iter()
+        .map(|(code, _, _)| *code)
+        .find(|code| *code == requested)
+        .or_else(|| CATALOGS.iter().map(|(code, _, _)| *code).find(|code| *code == language))
+}
+
+/// Like [`supported_locale`], falling back to English
+pub fn resolve_locale(requested: Option<&str>) -> &'static str {
+    requested.and_then(supported_locale).unwrap_or(DEFAULT_LOCALE)
+}
+
+fn catalogs() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    static PARSED: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .map(|(code, _, json)| {
+                let messages = serde_json::from_str(json).expect("locale catalogs are valid JSON");
+                (*code, messages)
+            })
+            .collect()
+    })
+}
+
+/// Every message for a locale, with English filling any gaps
+pub fn strings(locale: &str) -> BTreeMap<String, String> {
+    let catalogs = catalogs();
+    let mut strings: BTreeMap<String, String> = catalogs[DEFAULT_LOCALE].clone().into_iter().collect();
+    if let Some(messages) = catalogs.get(resolve_locale(Some(locale))) {
+        strings.extend(messages.clone());
+    }
+    strings
+}
+
+/// Look up a message and fill in its `{name}` placeholders. Unknown ids are
+/// returned as-is so a missing translation is visible rather than blank.
+pub fn t(locale: &str, id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let catalogs = catalogs();
+    let template = catalogs
+        .get(resolve_locale(Some(locale)))
+        .and_then(|messages| messages.get(id))
+        .or_else(|| catalogs[DEFAULT_LOCALE].get(id));
+    let Some(template) = template else {
+        return id.to_string();
+    };
+    args.iter().fold(template.clone(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
@@ -2,9 +2,18 @@
 //!
 //! Tauri commands for managing configuration.
 
-use filing_explorer_core::config::Config;
-use filing_explorer_core::tools::registry::{self, DetailLevel};
+mod i18n;
+
+use filing_explorer_core::config::{Config, DEFAULT_ACCOUNT_LABEL};
+use filing_explorer_core::migration::{ClientInstall, StateArchive};
+use filing_explorer_core::sec_client::{RateProbe, SecClient, SecConfigIssue, SecError};
+use filing_explorer_core::text_cache::{TextCache, TextCacheStats};
+use filing_explorer_core::tools::prompts::{self, ExamplePrompt};
+use filing_explorer_core::tools::registry::{self, Category, DetailLevel};
+use filing_explorer_core::usage::UsageStore;
+use i18n::{t, LocaleInfo};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use tauri::Manager;
 
 /// Response for config operations
@@ -15,6 +24,14 @@ pub struct ConfigResponse {
     pub sec_user_agent_email: Option<String>,
 }
 
+/// A stored account, with its token masked for display
+#[derive(Serialize, Deserialize)]
+pub struct AccountInfo {
+    pub label: String,
+    pub token_preview: String,
+    pub active: bool,
+}
+
 /// Response for validation operations
 #[derive(Serialize, Deserialize)]
 pub struct ValidationResponse {
@@ -22,6 +39,14 @@ pub struct ValidationResponse {
     pub message: String,
 }
 
+/// Result of the SEC fair access test
+#[derive(Serialize)]
+pub struct SecComplianceResponse {
+    pub compliant: bool,
+    pub message: String,
+    pub probe: Option<RateProbe>,
+}
+
 /// Status check response
 #[derive(Serialize, Deserialize)]
 pub struct StatusResponse {
@@ -47,6 +72,39 @@ pub struct ClaudeConfigInfo {
     pub mcp_server_valid: bool,
 }
 
+/// Outcome of repairing one Claude config
+#[derive(Serialize)]
+pub struct RepairResult {
+    pub config_type: String,
+    pub label: String,
+    pub path: String,
+    /// "repaired", "ok", "not_installed", "reinstalled", or "failed"
+    pub status: String,
+    /// The server command before repair, if it changed
+    pub previous_command: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Result of repairing every detected Claude config
+#[derive(Serialize)]
+pub struct RepairResponse {
+    pub server_path: String,
+    pub repaired: usize,
+    pub message: String,
+    pub results: Vec<RepairResult>,
+}
+
+/// Result of exporting or importing app state
+#[derive(Serialize)]
+pub struct MigrationResponse {
+    pub path: String,
+    pub message: String,
+    pub files: usize,
+    pub accounts: usize,
+    /// Claude configs the server was reinstalled into on import
+    pub clients: Vec<RepairResult>,
+}
+
 /// Tool category info for the UI
 #[derive(Serialize, Deserialize)]
 pub struct ToolCategoryInfo {
@@ -57,6 +115,21 @@ pub struct ToolCategoryInfo {
     pub tools: Vec<ToolInfo>,
 }
 
+/// Example prompts, and the same prompts as a Markdown playbook when asked
+#[derive(Serialize)]
+pub struct ExamplePromptsResponse {
+    pub prompts: Vec<ExamplePrompt>,
+    pub playbook: Option<String>,
+}
+
+/// Localized UI strings
+#[derive(Serialize)]
+pub struct StringsResponse {
+    pub locale: String,
+    pub available: Vec<LocaleInfo>,
+    pub strings: BTreeMap<String, String>,
+}
+
 /// Individual tool info for the UI
 #[derive(Serialize, Deserialize)]
 pub struct ToolInfo {
@@ -83,7 +156,7 @@ async fn save_config(
     sec_user_agent_email: Option<String>,
 ) -> Result<(), String> {
     let mut config = Config::load().unwrap_or_default();
-    config.api_token = api_token;
+    config.set_api_token(api_token);
     config.sec_user_agent_name = sec_user_agent_name;
     config.sec_user_agent_email = sec_user_agent_email;
     config.save().map_err(|e| e.to_string())
@@ -92,6 +165,88 @@ async fn save_config(
 /// Validate the API token by making a test request
 #[tauri::command]
 async fn validate_token(api_token: String) -> Result<ValidationResponse, String> {
+    check_token(&api_token).await
+}
+
+/// List stored accounts. A token saved before accounts existed is shown as
+/// the default account.
+#[tauri::command]
+async fn list_accounts() -> Result<Vec<AccountInfo>, String> {
+    let config = Config::load().unwrap_or_default();
+    if config.accounts.is_empty() {
+        return Ok(config
+            .api_token
+            .filter(|t| !t.is_empty())
+            .map(|token| AccountInfo {
+                label: DEFAULT_ACCOUNT_LABEL.to_string(),
+                token_preview: mask_token(&token),
+                active: true,
+            })
+            .into_iter()
+            .collect());
+    }
+    Ok(config
+        .accounts
+        .iter()
+        .map(|account| AccountInfo {
+            label: account.label.clone(),
+            token_preview: mask_token(&account.api_token),
+            active: config.active_account.as_deref() == Some(account.label.as_str()),
+        })
+        .collect())
+}
+
+/// Validate a token and store it under `label`. Invalid tokens are not
+/// saved.
+#[tauri::command]
+async fn add_account(label: String, api_token: String) -> Result<ValidationResponse, String> {
+    let validation = check_token(&api_token).await?;
+    if !validation.success {
+        return Ok(validation);
+    }
+
+    let mut config = Config::load().unwrap_or_default();
+    config
+        .add_account(&label, &api_token)
+        .map_err(|e| e.to_string())?;
+    config.save().map_err(|e| e.to_string())?;
+    Ok(ValidationResponse {
+        success: true,
+        message: t(&current_locale(), "account.saved", &[("label", &label.trim())]),
+    })
+}
+
+/// Re-check the token of a stored account
+#[tauri::command]
+async fn validate_account(label: String) -> Result<ValidationResponse, String> {
+    let config = Config::load().map_err(|e| e.to_string())?;
+    let account = config
+        .accounts
+        .iter()
+        .find(|a| a.label == label)
+        .ok_or_else(|| t(&config_locale(&config), "account.not_found", &[("label", &label)]))?;
+    check_token(&account.api_token).await
+}
+
+/// Remove a stored account
+#[tauri::command]
+async fn remove_account(label: String) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.remove_account(&label).map_err(|e| e.to_string())?;
+    config.save().map_err(|e| e.to_string())
+}
+
+/// Make a stored account the one the MCP server uses. A running server
+/// switches within a few seconds, when it next checks the config file.
+#[tauri::command]
+async fn switch_account(label: String) -> Result<(), String> {
+    let mut config = Config::load().map_err(|e| e.to_string())?;
+    config.switch_account(&label).map_err(|e| e.to_string())?;
+    config.save().map_err(|e| e.to_string())
+}
+
+/// Check a token against the API
+async fn check_token(api_token: &str) -> Result<ValidationResponse, String> {
     let client = reqwest::Client::new();
 
     let response = client
@@ -102,24 +257,91 @@ async fn validate_token(api_token: String) -> Result<ValidationResponse, String>
         .await
         .map_err(|e| e.to_string())?;
 
+    let locale = current_locale();
     if response.status().is_success() {
         Ok(ValidationResponse {
             success: true,
-            message: "API token is valid".to_string(),
+            message: t(&locale, "token.valid", &[]),
         })
     } else if response.status() == 401 {
         Ok(ValidationResponse {
             success: false,
-            message: "Invalid API token".to_string(),
+            message: t(&locale, "token.invalid", &[]),
         })
     } else {
         Ok(ValidationResponse {
             success: false,
-            message: format!("Unexpected response: {}", response.status()),
+            message: t(&locale, "token.unexpected_response", &[("status", &response.status())]),
         })
     }
 }
 
+/// Show only the last four characters of a token
+fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() <= 4 {
+        return "****".to_string();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("****{}", tail)
+}
+
+/// Requests sent by the SEC fair access test; enough to get past the first
+/// second of the burst
+const SEC_PROBE_REQUESTS: usize = 20;
+
+/// Send a short burst of requests to SEC EDGAR through `SecClient` and
+/// report whether the rate limiter and User-Agent comply with SEC's fair
+/// access policy
+#[tauri::command]
+async fn test_sec_compliance() -> Result<SecComplianceResponse, String> {
+    let config = Config::load().unwrap_or_default();
+    let locale = config_locale(&config);
+    let client = match SecClient::from_config(&config) {
+        Ok(client) => client,
+        Err(SecError::NotConfigured(issue)) => {
+            let key = match issue {
+                SecConfigIssue::InvalidName(_) => "sec.name_invalid",
+                SecConfigIssue::InvalidEmail(_) => "sec.email_invalid",
+                _ => "sec.identity_missing",
+            };
+            return Ok(SecComplianceResponse {
+                compliant: false,
+                message: t(&locale, key, &[]),
+                probe: None,
+            });
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let probe = client
+        .probe_rate(SEC_PROBE_REQUESTS)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let rate: [(&str, &dyn std::fmt::Display); 2] = [
+        ("peak", &probe.peak_requests_per_second),
+        ("limit", &probe.limit_per_second),
+    ];
+    let message = if probe.is_compliant() {
+        t(&locale, "sec.compliant", &rate)
+    } else if probe.statuses.contains_key(&403) {
+        t(&locale, "sec.user_agent_rejected", &[])
+    } else if probe.statuses.contains_key(&429) {
+        t(&locale, "sec.throttled", &[])
+    } else if probe.failures > 0 {
+        t(&locale, "sec.requests_failed", &[("failures", &probe.failures), ("requests", &probe.requests)])
+    } else {
+        t(&locale, "sec.rate_exceeded", &rate)
+    };
+
+    Ok(SecComplianceResponse {
+        compliant: probe.is_compliant(),
+        message,
+        probe: Some(probe),
+    })
+}
+
 /// Check the current setup status
 #[tauri::command]
 async fn check_status() -> Result<StatusResponse, String> {
@@ -233,10 +455,55 @@ async fn install_mcp_to_config(
     match config_type.as_str() {
         "desktop" => configure_claude_desktop().await,
         "code_global" => configure_claude_code().await,
-        _ => Err(format!("Unknown config type: {}", config_type)),
+        _ => Err(t(&current_locale(), "error.unknown_config_type", &[("config_type", &config_type)])),
     }
 }
 
+/// Point every detected Claude config that has the FilingExplorer server
+/// installed at the current server binary. App updates move the bundled
+/// binary, which leaves existing configs pointing at a path that no longer
+/// exists.
+#[tauri::command]
+async fn repair_all_configs() -> Result<RepairResponse, String> {
+    let server_path = find_mcp_server_path()?.to_string_lossy().to_string();
+
+    let results: Vec<RepairResult> = client_config_locations()
+        .into_iter()
+        .map(|(config_type, label, path)| {
+            let (status, previous_command, error) = match repair_config(&path, &server_path) {
+                Ok(None) if check_mcp_in_config(&path).0 => ("ok", None, None),
+                Ok(None) => ("not_installed", None, None),
+                Ok(Some(previous)) => ("repaired", Some(previous), None),
+                Err(e) => ("failed", None, Some(e)),
+            };
+            RepairResult {
+                config_type: config_type.to_string(),
+                label: label.to_string(),
+                path: path.to_string_lossy().to_string(),
+                status: status.to_string(),
+                previous_command,
+                error,
+            }
+        })
+        .collect();
+
+    let repaired = results.iter().filter(|r| r.status == "repaired").count();
+    let installed = results.iter().filter(|r| r.status != "not_installed").count();
+    let locale = current_locale();
+    let message = if repaired == 0 {
+        t(&locale, "repair.none_needed", &[])
+    } else {
+        t(&locale, "repair.summary", &[("repaired", &repaired), ("installed", &installed)])
+    };
+
+    Ok(RepairResponse {
+        server_path,
+        repaired,
+        message,
+        results,
+    })
+}
+
 /// Get the MCP config JSON snippet for manual installation / clipboard
 #[tauri::command]
 async fn get_mcp_config_snippet(config_type: String) -> Result<String, String> {
@@ -263,7 +530,7 @@ async fn get_mcp_config_snippet(config_type: String) -> Result<String, String> {
             }))
             .map_err(|e| e.to_string())?
         }
-        _ => return Err(format!("Unknown config type: {}", config_type)),
+        _ => return Err(t(&current_locale(), "error.unknown_config_type", &[("config_type", &config_type)])),
     };
 
     Ok(snippet)
@@ -291,7 +558,7 @@ async fn get_tool_categories() -> Result<Vec<ToolCategoryInfo>, String> {
         let tool_count = cat["tool_count"].as_u64().unwrap_or(0);
 
         // Get tools for this category
-        let tools_json = registry::list_tools_by_category(&id, DetailLevel::WithDescriptions);
+        let tools_json = registry::list_tools_by_category(&id, None, DetailLevel::WithDescriptions);
         let tools_arr = tools_json["tools"].as_array();
 
         let tools = tools_arr
@@ -321,10 +588,175 @@ async fn get_tool_categories() -> Result<Vec<ToolCategoryInfo>, String> {
     Ok(result)
 }
 
+/// Get copyable example prompts, optionally for one category and with a
+/// starter playbook
+#[tauri::command]
+async fn get_example_prompts(
+    category: Option<String>,
+    playbook: Option<bool>,
+) -> Result<ExamplePromptsResponse, String> {
+    let category = category.map(|c| c.parse::<Category>()).transpose()?;
+    let prompts = prompts::example_prompts(category);
+    let playbook = playbook
+        .unwrap_or(false)
+        .then(|| prompts::format_playbook(&prompts));
+    Ok(ExamplePromptsResponse { prompts, playbook })
+}
+
+/// Size of the extracted document text cache
+#[tauri::command]
+async fn get_text_cache_stats() -> Result<TextCacheStats, String> {
+    let cache = TextCache::open_default().map_err(|e| e.to_string())?;
+    Ok(cache.stats())
+}
+
+/// Clear cached document text for one filing, or all of it when no
+/// accession number is given. Returns the number of documents removed.
+#[tauri::command]
+async fn clear_text_cache(accession_number: Option<String>) -> Result<usize, String> {
+    let cache = TextCache::open_default().map_err(|e| e.to_string())?;
+    cache
+        .clear(accession_number.as_deref().filter(|a| !a.trim().is_empty()))
+        .map_err(|e| e.to_string())
+}
+
+/// Which tools the MCP server has run most and which fail most, as the
+/// server's get_usage_stats tool reports it
+#[tauri::command]
+async fn get_usage_stats(limit: Option<usize>) -> Result<serde_json::Value, String> {
+    let store = UsageStore::open_default().map_err(|e| e.to_string())?;
+    Ok(store.load().report(limit.unwrap_or(10)))
+}
+
+/// Forget the recorded tool usage
+#[tauri::command]
+async fn clear_usage_stats() -> Result<(), String> {
+    let store = UsageStore::open_default().map_err(|e| e.to_string())?;
+    store.reset().map_err(|e| e.to_string())
+}
+
+/// Write the config, accounts, watchlist snapshots, cache sizes, and Claude
+/// config installation state to one file for moving to another machine
+#[tauri::command]
+async fn export_app_state(path: String) -> Result<MigrationResponse, String> {
+    let config = Config::load().unwrap_or_default();
+    let locale = config_locale(&config);
+    let config_dir = Config::config_dir().map_err(|e| e.to_string())?;
+    let cache_dir = Config::cache_dir().map_err(|e| e.to_string())?;
+    let mut archive = StateArchive::collect(config, &config_dir, &cache_dir).map_err(|e| e.to_string())?;
+    archive.clients = client_config_locations()
+        .into_iter()
+        .map(|(config_type, _, config_path)| {
+            let (installed, server_command, _) = check_mcp_in_config(&config_path);
+            ClientInstall {
+                config_type: config_type.to_string(),
+                installed,
+                server_command,
+            }
+        })
+        .collect();
+
+    let json = archive.to_json().map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())?;
+
+    let files = archive.files.len();
+    let accounts = archive.config.accounts.len();
+    Ok(MigrationResponse {
+        message: t(&locale, "migration.exported", &[("accounts", &accounts), ("files", &files), ("path", &path)]),
+        path,
+        files,
+        accounts,
+        clients: Vec::new(),
+    })
+}
+
+/// Restore an exported state file, then install the server into the Claude
+/// configs that had it, pointing them at this machine's server binary
+#[tauri::command]
+async fn import_app_state(path: String) -> Result<MigrationResponse, String> {
+    let json = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let archive = StateArchive::from_json(&json).map_err(|e| e.to_string())?;
+    let config_dir = Config::config_dir().map_err(|e| e.to_string())?;
+    let summary = archive.restore(&config_dir).map_err(|e| e.to_string())?;
+    let locale = config_locale(&archive.config);
+
+    let locations = client_config_locations();
+    let mut clients = Vec::new();
+    for client in archive.installed_clients() {
+        let Some((config_type, label, config_path)) =
+            locations.iter().find(|(config_type, _, _)| *config_type == client.config_type)
+        else {
+            continue;
+        };
+        let (status, error) = match install_mcp_to_config(config_type.to_string()).await {
+            Ok(_) => ("reinstalled", None),
+            Err(e) => ("failed", Some(e)),
+        };
+        clients.push(RepairResult {
+            config_type: config_type.to_string(),
+            label: label.to_string(),
+            path: config_path.to_string_lossy().to_string(),
+            status: status.to_string(),
+            previous_command: client.server_command.clone(),
+            error,
+        });
+    }
+
+    let reinstalled = clients.iter().filter(|c| c.status == "reinstalled").count();
+    Ok(MigrationResponse {
+        message: t(
+            &locale,
+            "migration.imported",
+            &[
+                ("accounts", &summary.accounts),
+                ("files", &summary.files_restored),
+                ("clients", &reinstalled),
+            ],
+        ),
+        path,
+        files: summary.files_restored,
+        accounts: summary.accounts,
+        clients,
+    })
+}
+
+/// Get the UI strings for a locale, defaulting to the saved one
+#[tauri::command]
+async fn get_strings(locale: Option<String>) -> Result<StringsResponse, String> {
+    let locale = match locale {
+        Some(requested) => i18n::resolve_locale(Some(&requested)).to_string(),
+        None => current_locale(),
+    };
+    Ok(StringsResponse {
+        strings: i18n::strings(&locale),
+        available: i18n::available_locales(),
+        locale,
+    })
+}
+
+/// Save the UI language
+#[tauri::command]
+async fn set_locale(locale: String) -> Result<(), String> {
+    let mut config = Config::load().unwrap_or_default();
+    let supported = i18n::supported_locale(&locale)
+        .ok_or_else(|| t(&config_locale(&config), "error.unknown_locale", &[("locale", &locale)]))?;
+    config.locale = Some(supported.to_string());
+    config.save().map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Helper functions
 // ============================================================================
 
+/// The saved UI language
+fn current_locale() -> String {
+    config_locale(&Config::load().unwrap_or_default())
+}
+
+fn config_locale(config: &Config) -> String {
+    i18n::resolve_locale(config.locale.as_deref()).to_string()
+}
+
 /// Check if a config file has MCP server configured, return (installed, server_path, server_exists)
 fn check_mcp_in_config(
     path: &std::path::Path,
@@ -357,6 +789,46 @@ fn check_mcp_in_config(
     }
 }
 
+/// Rewrite a config's FilingExplorer server command to `server_path`,
+/// keeping its other settings. Returns the previous command if it changed.
+fn repair_config(path: &std::path::Path, server_path: &str) -> Result<Option<String>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut config: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let Some(server) = config
+        .get_mut("mcpServers")
+        .and_then(|s| s.get_mut("filing-explorer"))
+        .and_then(|s| s.as_object_mut())
+    else {
+        return Ok(None);
+    };
+    let previous = server.get("command").and_then(|c| c.as_str()).map(str::to_string);
+    if previous.as_deref() == Some(server_path) {
+        return Ok(None);
+    }
+    server.insert("command".to_string(), serde_json::json!(server_path));
+
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(path, content).map_err(|e| e.to_string())?;
+    Ok(Some(previous.unwrap_or_default()))
+}
+
+/// Every Claude config location on this system, as (config type, label,
+/// path)
+fn client_config_locations() -> Vec<(&'static str, &'static str, std::path::PathBuf)> {
+    let mut locations = Vec::new();
+    if let Some(path) = get_claude_desktop_config_path() {
+        locations.push(("desktop", "Claude Desktop", path));
+    }
+    if let Some(path) = get_claude_code_config_path() {
+        locations.push(("code_global", "Claude Code (Global)", path));
+    }
+    locations
+}
+
 /// Get the path to Claude Desktop config file
 fn get_claude_desktop_config_path() -> Option<std::path::PathBuf> {
     #[cfg(target_os = "macos")]
@@ -388,7 +860,7 @@ fn find_mcp_server_path() -> Result<std::path::PathBuf, String> {
     let current_exe = std::env::current_exe().map_err(|e| e.to_string())?;
     let exe_dir = current_exe
         .parent()
-        .ok_or_else(|| "Could not get parent directory".to_string())?;
+        .ok_or_else(|| t(&current_locale(), "error.no_exe_dir", &[]))?;
 
     // Tauri bundles external binaries with target triple suffix
     let target_triple = if cfg!(target_os = "macos") {
@@ -443,14 +915,14 @@ fn find_mcp_server_path() -> Result<std::path::PathBuf, String> {
         }
     }
 
-    Err("Could not find mcp-server binary. The app bundle may be corrupted.".to_string())
+    Err(t(&current_locale(), "error.server_not_found", &[]))
 }
 
 /// Configure Claude Desktop to use the MCP server
 #[tauri::command]
 async fn configure_claude_desktop() -> Result<ValidationResponse, String> {
     let config_path = get_claude_desktop_config_path()
-        .ok_or_else(|| "Could not determine Claude Desktop config path".to_string())?;
+        .ok_or_else(|| t(&current_locale(), "error.desktop_config_path", &[]))?;
 
     // Read existing config or create new one
     let mut config: serde_json::Value = if config_path.exists() {
@@ -484,10 +956,7 @@ async fn configure_claude_desktop() -> Result<ValidationResponse, String> {
 
     Ok(ValidationResponse {
         success: true,
-        message: format!(
-            "Claude Desktop configured. Restart Claude Desktop to apply changes.\nConfig path: {}",
-            config_path.display()
-        ),
+        message: t(&current_locale(), "install.desktop_configured", &[("path", &config_path.display())]),
     })
 }
 
@@ -496,7 +965,7 @@ async fn configure_claude_desktop() -> Result<ValidationResponse, String> {
 #[tauri::command]
 async fn configure_claude_code() -> Result<ValidationResponse, String> {
     let config_path = get_claude_code_config_path()
-        .ok_or_else(|| "Could not determine Claude Code config path".to_string())?;
+        .ok_or_else(|| t(&current_locale(), "error.code_config_path", &[]))?;
 
     // Read existing config or create new one
     let mut config: serde_json::Value = if config_path.exists() {
@@ -526,10 +995,7 @@ async fn configure_claude_code() -> Result<ValidationResponse, String> {
 
     Ok(ValidationResponse {
         success: true,
-        message: format!(
-            "Claude Code configured. The MCP server will be available in new Claude Code sessions.\nConfig path: {}",
-            config_path.display()
-        ),
+        message: t(&current_locale(), "install.code_configured", &[("path", &config_path.display())]),
     })
 }
 
@@ -538,20 +1004,21 @@ async fn configure_claude_code() -> Result<ValidationResponse, String> {
 async fn configure_both() -> Result<ValidationResponse, String> {
     let desktop_result = configure_claude_desktop().await;
     let code_result = configure_claude_code().await;
+    let locale = current_locale();
 
     match (desktop_result, code_result) {
         (Ok(_), Ok(_)) => Ok(ValidationResponse {
             success: true,
-            message: "Both Claude Desktop and Claude Code configured. Restart Claude Desktop and start new Claude Code sessions to apply changes.".to_string(),
+            message: t(&locale, "install.both_configured", &[]),
         }),
-        (Err(e1), Err(e2)) => Err(format!("Both configurations failed:\nDesktop: {}\nCode: {}", e1, e2)),
+        (Err(e1), Err(e2)) => Err(t(&locale, "install.both_failed", &[("desktop", &e1), ("code", &e2)])),
         (Err(e), Ok(_)) => Ok(ValidationResponse {
             success: true,
-            message: format!("Claude Code configured, but Claude Desktop failed: {}", e),
+            message: t(&locale, "install.desktop_failed", &[("error", &e)]),
         }),
         (Ok(_), Err(e)) => Ok(ValidationResponse {
             success: true,
-            message: format!("Claude Desktop configured, but Claude Code failed: {}", e),
+            message: t(&locale, "install.code_failed", &[("error", &e)]),
         }),
     }
 }
@@ -566,6 +1033,12 @@ pub fn run() {
             load_config,
             save_config,
             validate_token,
+            list_accounts,
+            add_account,
+            validate_account,
+            remove_account,
+            switch_account,
+            test_sec_compliance,
             configure_claude_desktop,
             configure_claude_code,
             configure_both,
@@ -573,7 +1046,17 @@ pub fn run() {
             get_all_claude_configs,
             install_mcp_to_config,
             get_mcp_config_snippet,
+            repair_all_configs,
             get_tool_categories,
+            get_example_prompts,
+            get_text_cache_stats,
+            clear_text_cache,
+            get_usage_stats,
+            clear_usage_stats,
+            export_app_state,
+            import_app_state,
+            get_strings,
+            set_locale,
         ])
         .setup(|app| {
             #[cfg(debug_assertions)]
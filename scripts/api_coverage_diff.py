@@ -9,6 +9,7 @@ Usage:
     python scripts/api_coverage_diff.py
 """
 
+import json
 import re
 import yaml
 from pathlib import Path
@@ -165,19 +166,9 @@ def parse_mcp_implementation(main_rs_path: Path) -> tuple[set[str], list[McpTool
 
 
 def parse_registry(registry_path: Path) -> dict[str, str]:
-    """Parse registry.rs to get tool metadata (name -> category)."""
-    content = registry_path.read_text()
-
-    tools = {}
-
-    # Find all m.insert("tool_name", Tool { ... category: Category::X, ... })
-    pattern = r'm\.insert\("(\w+)", Tool \{[^}]+category: Category::(\w+),'
-    matches = re.findall(pattern, content)
-
-    for name, category in matches:
-        tools[name] = category
-
-    return tools
+    """Parse registry.json to get tool metadata (name -> category)."""
+    registry = json.loads(registry_path.read_text())
+    return {tool["name"]: tool["category"] for tool in registry["tools"]}
 
 
 def map_swagger_to_mcp() -> dict[str, tuple[str, ...]]:
@@ -253,7 +244,7 @@ def main():
     root = Path(__file__).parent.parent
     swagger_path = root / "swagger.yaml"
     main_rs_path = root / "crates" / "mcp-server" / "src" / "main.rs"
-    registry_path = root / "crates" / "core" / "src" / "tools" / "registry.rs"
+    registry_path = root / "crates" / "core" / "src" / "tools" / "registry.json"
 
     print("=" * 80)
     print("FilingExplorer API Coverage Report")